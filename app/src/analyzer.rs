@@ -1,72 +1,183 @@
 // In app/src/analyzer.rs
 
+use analytics::scoring::{rank_reports, ScoringWeights};
 use anyhow::Result;
 use database::{Db, FullReport};
-use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 
-const MINIMUM_TRADES_THRESHOLD: u32 = 30;
-
-#[derive(Debug, Serialize)]
-pub struct RankedReport {
-    pub score: f64,
-    pub report: FullReport,
-}
+/// A job's backtest report together with its score.
+pub type RankedReport = analytics::scoring::RankedReport<FullReport>;
 
 /// Analyzes and ranks the results of an optimization job.
-pub async fn analyze_and_rank_results(db: &Db, job_id: i64) -> Result<Vec<RankedReport>> {
+pub async fn analyze_and_rank_results(
+    db: &Db,
+    job_id: i64,
+    weights: &ScoringWeights,
+) -> Result<Vec<RankedReport>> {
     tracing::info!(job_id, "Fetching and analyzing reports for optimization job...");
 
     let reports = db.get_reports_for_job(job_id).await?;
     let total_reports = reports.len();
 
-    let mut ranked_reports: Vec<RankedReport> = reports
-        .into_iter()
-        .filter_map(|full_report| {
-            // 1. Filter out runs with too few trades
-            if full_report.report.total_trades < MINIMUM_TRADES_THRESHOLD {
-                return None;
-            }
-
-            // 2. Calculate the score
-            let score = calculate_score(&full_report.report);
+    let ranked_reports = rank_reports(reports, weights);
 
-            Some(RankedReport {
-                score,
-                report: full_report,
-            })
-        })
-        .collect();
-    
     tracing::info!(
         total_reports,
         passing_reports = ranked_reports.len(),
         "Finished scoring reports."
     );
 
-    // 3. Sort by score in descending order (higher is better)
-    ranked_reports.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-
     Ok(ranked_reports)
 }
 
-/// The multi-objective scoring function.
-/// Higher scores are better.
-fn calculate_score(report: &analytics::types::PerformanceReport) -> f64 {
-    // Define weights for each metric
-    const PROFIT_FACTOR_WEIGHT: f64 = 40.0;
-    const SHARPE_RATIO_WEIGHT: f64 = 30.0;
-    const MAX_DRAWDOWN_WEIGHT: f64 = -35.0; // Negative weight penalizes drawdown
-    const CALMAR_RATIO_WEIGHT: f64 = 15.0;
-
-    // Normalize or cap values to prevent extreme outliers from dominating the score
-    let capped_profit_factor = report.profit_factor.min(5.0); // Cap at 5.0
-    let capped_sharpe = report.sharpe_ratio.min(5.0);
-    let normalized_drawdown = report.max_drawdown_percentage / 100.0; // Convert to 0-1 scale
-
-    let score = (capped_profit_factor * PROFIT_FACTOR_WEIGHT)
-        + (capped_sharpe * SHARPE_RATIO_WEIGHT)
-        + (normalized_drawdown * MAX_DRAWDOWN_WEIGHT)
-        + (report.calmar_ratio * CALMAR_RATIO_WEIGHT);
-
-    score
-}
\ No newline at end of file
+/// The report metrics written as columns of a CSV leaderboard, after the parameters.
+const LEADERBOARD_METRICS: [&str; 9] = [
+    "net_pnl_absolute",
+    "net_pnl_percentage",
+    "max_drawdown_percentage",
+    "sharpe_ratio",
+    "sortino_ratio",
+    "calmar_ratio",
+    "profit_factor",
+    "win_rate",
+    "total_trades",
+];
+
+/// Writes the full ranked leaderboard to `path`, as CSV if it ends in `.csv` and as JSON
+/// otherwise.
+pub fn write_leaderboard(results: &[RankedReport], path: &Path) -> Result<()> {
+    let is_csv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let document = if is_csv {
+        leaderboard_csv(results)?
+    } else {
+        serde_json::to_string_pretty(results)?
+    };
+    std::fs::write(path, document)?;
+    Ok(())
+}
+
+/// Renders the leaderboard as CSV: one row per ranked report, with each (possibly nested)
+/// parameter flattened into its own dotted column.
+fn leaderboard_csv(results: &[RankedReport]) -> Result<String> {
+    let rows = results
+        .iter()
+        .map(|ranked| {
+            let mut parameters = BTreeMap::new();
+            flatten_json("", &ranked.report.parameters, &mut parameters);
+            let metrics = serde_json::to_value(&ranked.report.report)?;
+            Ok((parameters, metrics))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let parameter_columns: BTreeSet<&String> = rows.iter().flat_map(|(p, _)| p.keys()).collect();
+
+    let mut header = vec!["rank".to_string(), "score".to_string(), "run_id".to_string()];
+    header.extend(parameter_columns.iter().map(|c| c.to_string()));
+    header.extend(LEADERBOARD_METRICS.iter().map(|m| m.to_string()));
+    let mut csv = csv_line(&header);
+
+    for (i, (ranked, (parameters, metrics))) in results.iter().zip(&rows).enumerate() {
+        let mut line = vec![(i + 1).to_string(), ranked.score.to_string(), ranked.report.run_id.to_string()];
+        line.extend(parameter_columns.iter().map(|c| parameters.get(*c).cloned().unwrap_or_default()));
+        line.extend(LEADERBOARD_METRICS.iter().map(|m| json_cell(&metrics[*m])));
+        csv.push_str(&csv_line(&line));
+    }
+    Ok(csv)
+}
+
+/// Collects the leaves of `value` into `out`, keyed by their dotted path below `prefix`.
+fn flatten_json(prefix: &str, value: &JsonValue, out: &mut BTreeMap<String, String>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_json(&path, value, out);
+            }
+        }
+        _ => {
+            let key = if prefix.is_empty() { "parameters" } else { prefix };
+            out.insert(key.to_string(), json_cell(value));
+        }
+    }
+}
+
+/// Renders a JSON leaf as a CSV cell; strings lose their quotes and `null` becomes empty.
+fn json_cell(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Joins `fields` into a CSV line, quoting those containing a delimiter, quote or newline.
+fn csv_line(fields: &[String]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analytics::types::PerformanceReport;
+    use chrono::{TimeZone, Utc};
+    use core_types::Symbol;
+    use database::BacktestRunMeta;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn leaderboard_export_has_a_row_per_passing_run(pool: PgPool) {
+        let db = Db::from(pool);
+        let job_id = db.create_optimization_job("leaderboard").await.unwrap();
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        // The run with 5 trades falls below the minimum and is left out.
+        for (fast_period, total_trades, net_pnl_percentage) in [(5, 20, 4.0), (10, 15, 9.5), (15, 5, 30.0), (20, 12, -2.0)] {
+            let parameters = json!({ "fast_period": fast_period, "risk": { "stop_loss": 1.5 } });
+            let meta = BacktestRunMeta {
+                strategy_name: "MACrossover",
+                symbol: &symbol,
+                interval: "1m",
+                start_date: Utc.timestamp_millis_opt(0).unwrap(),
+                end_date: Utc.timestamp_millis_opt(600_000).unwrap(),
+                parameters: &parameters,
+                config: None,
+            };
+            let report = PerformanceReport { total_trades, net_pnl_percentage, ..PerformanceReport::new() };
+            db.save_full_backtest(Some(job_id), &meta, &report, &[], &[]).await.unwrap();
+        }
+        let weights = ScoringWeights { minimum_trades: 10, ..ScoringWeights::default() };
+        let results = analyze_and_rank_results(&db, job_id, &weights).await.unwrap();
+        assert_eq!(results.len(), 3);
+
+        let dir = std::env::temp_dir().join(format!("atlas-leaderboard-{}-{}", std::process::id(), job_id));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv_path = dir.join("leaderboard.csv");
+        let json_path = dir.join("leaderboard.json");
+        write_leaderboard(&results, &csv_path).unwrap();
+        write_leaderboard(&results, &json_path).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let exported: JsonValue = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(&header[..5], ["rank", "score", "run_id", "fast_period", "risk.stop_loss"]);
+        assert_eq!(lines.count(), results.len());
+        assert_eq!(exported.as_array().unwrap().len(), results.len());
+    }
+}
@@ -1,35 +1,38 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use chrono::{TimeZone, Utc};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::Mutex as StdMutex;
 use tokio::sync::Mutex as TokioMutex;
-use execution::Portfolio;
+use api_client::ApiClient;
 use core_types::Symbol;
 use risk::simple_manager::SimpleRiskManager;
 use risk::RiskManager;
-use strategies::ma_crossover::MACrossover;
-use strategies::Strategy;
-use std::time::Duration;
 mod optimizer;
-use tokio::time::sleep;
-use execution::simulated::SimulatedExecutor;
 use execution::Executor;
-use rust_decimal_macros::dec; // For our test portfolio
+use rust_decimal::prelude::FromPrimitive;
+use analytics::types::{EquityPoint, PerformanceReport, Trade};
 use backtester::Backtester;
+use backtester::types::{BacktestConfig, RunConfigSnapshot};
 mod analyzer;
+mod sensitivity;
 use crate::analyzer::RankedReport;
 use crate::optimizer::{generate_generic_parameter_sets, load_optimizer_config, run_optimization};
 use std::time::Instant;
-use serde_json;
 use tokio::task;
 use tracing_subscriber::prelude::*;
-use events::WsMessage;
+use tracing_subscriber::EnvFilter;
+use tracing::level_filters::LevelFilter;
+use events::{ReplayCache, WsMessage};
 use self::tracing_layer::WsBroadcastLayer;
 use tokio::sync::broadcast;
-use std::collections::VecDeque;
 mod tracing_layer;
+#[cfg(test)]
+mod test_support;
 use engine::Engine; // Import our new Engine
+use app_config::types::TradingMode;
+use engine::equity_snapshotter::EquitySnapshotter;
+use engine::hot_reload::{ConfigWatcher, HotReloadHandle};
 use engine::reconciler::StateReconciler; // Import the Reconciler
 
 // --- Command-Line Interface Definition ---
@@ -59,6 +62,10 @@ enum Commands {
         /// Optional start date for backfilling in YYYY-MM-DD format.
         #[arg(long)]
         start_date: Option<String>,
+
+        /// Optional end date (inclusive) in YYYY-MM-DD format. Defaults to now.
+        #[arg(long)]
+        end_date: Option<String>,
     },
 
     // Add this new subcommand
@@ -79,10 +86,165 @@ enum Commands {
         /// The end date for the backtest in YYYY-MM-DD format.
         #[arg(long)]
         end_date: String,
+
+        /// Seed for the simulation's random number generator, for reproducible runs.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Backtests the configured strategy on recent klines fetched straight from Binance,
+    /// bypassing the database, and prints the report without saving it.
+    ValidateStrategy {
+        /// The trading symbol to fetch (e.g., "BTCUSDT").
+        #[arg(short, long)]
+        symbol: String,
+
+        /// The interval of the klines (e.g., "5m", "1h").
+        #[arg(short, long)]
+        interval: String,
+
+        /// The number of most recent klines to fetch (at most 1500).
+        #[arg(long, default_value_t = 1000)]
+        limit: u16,
+
+        /// Seed for the simulation's random number generator, for reproducible runs.
+        #[arg(long)]
+        seed: Option<u64>,
     },
 
     /// Runs a full parameter optimization job.
-    Optimize,
+    Optimize {
+        /// Also writes the full ranked leaderboard to this file (CSV if it ends in `.csv`,
+        /// JSON otherwise).
+        #[arg(long)]
+        export: Option<PathBuf>,
+    },
+
+    /// Checks stored kline data for gaps and optionally re-fetches the missing ranges.
+    VerifyData {
+        /// The trading symbol to verify (e.g., "BTCUSDT").
+        #[arg(short, long)]
+        symbol: String,
+
+        /// The interval of the klines (e.g., "5m", "1h").
+        #[arg(short, long)]
+        interval: String,
+
+        /// The start date of the range to verify in YYYY-MM-DD format.
+        #[arg(long)]
+        start_date: String,
+
+        /// The end date of the range to verify in YYYY-MM-DD format.
+        #[arg(long)]
+        end_date: String,
+
+        /// Re-fetch the missing ranges from Binance and insert them.
+        #[arg(long)]
+        repair: bool,
+    },
+
+    /// Immediately closes every open position on the exchange with reduce-only market orders.
+    Flatten,
+
+    /// Deletes stored klines older than a given date.
+    PruneData {
+        /// The trading symbol to prune (e.g., "BTCUSDT").
+        #[arg(short, long)]
+        symbol: String,
+
+        /// The interval of the klines to prune (e.g., "1m").
+        #[arg(short, long)]
+        interval: String,
+
+        /// Klines opening before this date (YYYY-MM-DD) are deleted.
+        #[arg(long)]
+        before: String,
+    },
+
+    /// Lists and inspects stored backtest runs.
+    Runs {
+        #[command(subcommand)]
+        action: RunsAction,
+
+        /// Emit machine-readable JSON instead of formatted text.
+        #[arg(long, global = true)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RunsAction {
+    /// Lists backtest runs with their key metrics, newest first.
+    List {
+        /// The page to show, starting at 1.
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+
+        /// The number of runs per page.
+        #[arg(long, default_value_t = 20)]
+        page_size: u32,
+
+        /// Only list runs belonging to this optimization job.
+        #[arg(long)]
+        job_id: Option<i64>,
+    },
+
+    /// Shows the full performance report and a trade summary of one run.
+    Show {
+        /// The ID of the backtest run.
+        run_id: i64,
+    },
+
+    /// Exports a run with its report, trades and equity curve as a JSON document.
+    Export {
+        /// The ID of the backtest run.
+        run_id: i64,
+
+        /// The file to write to. Defaults to stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Imports a JSON document written by `runs export` as a new run.
+    Import {
+        /// The exported JSON file.
+        file: PathBuf,
+    },
+
+    /// Re-runs a backtest under scaled fee and slippage assumptions, without saving the
+    /// results, and shows how its net P&L and Sharpe ratio degrade.
+    Sensitivity {
+        /// The ID of the backtest run.
+        run_id: i64,
+
+        /// Comma-separated multipliers of the configured maker and taker fees.
+        #[arg(long, value_delimiter = ',', default_values_t = sensitivity::DEFAULT_COST_MULTIPLIERS)]
+        fee_multipliers: Vec<f64>,
+
+        /// Comma-separated multipliers of the configured slippage.
+        #[arg(long, value_delimiter = ',', default_values_t = sensitivity::DEFAULT_COST_MULTIPLIERS)]
+        slippage_multipliers: Vec<f64>,
+    },
+}
+
+/// Builds the global log filter from `log_level` (an `EnvFilter` directive string), with
+/// `sqlx::query` quietened to WARN and any `RUST_LOG` directives applied on top, so single
+/// targets can be overridden (e.g. `RUST_LOG=engine=trace,sqlx::query=info`).
+fn build_log_filter(log_level: &str) -> EnvFilter {
+    let mut filter = EnvFilter::try_new(log_level).unwrap_or_else(|e| {
+        eprintln!("Invalid app.log_level '{}': {}. Falling back to 'info'.", log_level, e);
+        EnvFilter::new("info")
+    });
+    filter = filter.add_directive("sqlx::query=warn".parse().expect("valid directive"));
+    if let Ok(overrides) = std::env::var(EnvFilter::DEFAULT_ENV) {
+        for directive in overrides.split(',').filter(|d| !d.trim().is_empty()) {
+            match directive.trim().parse() {
+                Ok(directive) => filter = filter.add_directive(directive),
+                Err(e) => eprintln!("Ignoring invalid {} directive '{}': {}", EnvFilter::DEFAULT_ENV, directive, e),
+            }
+        }
+    }
+    filter
 }
 
 // --- Main Application Entry Point ---
@@ -94,15 +256,30 @@ async fn main() -> Result<()> {
 
     // --- WebSocket and Tracing Setup ---
     let (ws_tx, _) = broadcast::channel::<WsMessage>(1024);
-    // Create the cache here
-    let ws_cache = Arc::new(StdMutex::new(VecDeque::with_capacity(200)));
+    // The log levels and cache size come from the settings, which are loaded again (and
+    // reported on) by each command; fall back to defaults here so a broken config still gets logged.
+    let early_settings = app_config::load_settings().ok();
+    let app_settings = early_settings.as_ref().map(|settings| &settings.app);
+    let log_level = app_settings.map_or("info", |app| app.log_level.as_str());
+    let ws_log_level = app_settings.and_then(|app| app.ws_log_level.as_deref()).unwrap_or("info");
+    // The replay cache is shared by the tracing layer (which fills it) and the web server.
+    let ws_cache = ReplayCache::new(
+        early_settings
+            .as_ref()
+            .map_or(app_config::types::DEFAULT_WS_CACHE_SIZE, |settings| settings.server.ws_cache_size),
+    );
+    let ws_min_level = ws_log_level.parse::<LevelFilter>().unwrap_or(LevelFilter::INFO);
     // Pass both to the layer
-    let ws_layer = WsBroadcastLayer::new(ws_tx.clone(), ws_cache.clone());
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_filter(tracing_subscriber::filter::Targets::new()
-            .with_target("sqlx::query", tracing::Level::WARN) // Disable sqlx query debug logs
-            .with_default(tracing::Level::INFO));
-    tracing_subscriber::registry().with(fmt_layer).with(ws_layer).init();
+    let ws_layer = WsBroadcastLayer::new(ws_tx.clone(), ws_cache.clone(), ws_min_level);
+    let fmt_layer = tracing_subscriber::fmt::layer();
+    tracing_subscriber::registry()
+        .with(build_log_filter(log_level))
+        .with(fmt_layer)
+        .with(ws_layer)
+        .init();
+    if ws_log_level.parse::<LevelFilter>().is_err() {
+        tracing::warn!(ws_log_level, "Invalid app.ws_log_level; broadcasting INFO and above.");
+    }
 
     // Parse command-line arguments.
     let cli = Cli::parse();
@@ -112,14 +289,15 @@ async fn main() -> Result<()> {
     // Match on the parsed command and call the appropriate handler.
     match cli.command {
         Commands::Run => {
-            run_app().await?;
+            run_app(ws_tx.clone(), ws_cache.clone()).await?;
         }
         Commands::Backfill {
             symbol,
             interval,
             start_date,
+            end_date,
         } => {
-            handle_backfill(symbol, interval, start_date).await?;
+            handle_backfill(symbol, interval, start_date, end_date).await?;
         }
 
         Commands::Backtest {
@@ -127,11 +305,42 @@ async fn main() -> Result<()> {
             interval,
             start_date,
             end_date,
+            seed,
         } => {
-            handle_backtest(symbol, interval, start_date, end_date, ws_tx.clone()).await?;
+            handle_backtest(symbol, interval, start_date, end_date, seed, ws_tx.clone()).await?;
         }
-        Commands::Optimize => {
-            handle_optimize().await?;
+        Commands::ValidateStrategy {
+            symbol,
+            interval,
+            limit,
+            seed,
+        } => {
+            handle_validate_strategy(symbol, interval, limit, seed, ws_tx.clone()).await?;
+        }
+        Commands::Optimize { export } => {
+            handle_optimize(export).await?;
+        }
+        Commands::VerifyData {
+            symbol,
+            interval,
+            start_date,
+            end_date,
+            repair,
+        } => {
+            handle_verify_data(symbol, interval, start_date, end_date, repair).await?;
+        }
+        Commands::PruneData {
+            symbol,
+            interval,
+            before,
+        } => {
+            handle_prune_data(symbol, interval, before).await?;
+        }
+        Commands::Runs { action, json } => {
+            handle_runs(action, json).await?;
+        }
+        Commands::Flatten => {
+            handle_flatten().await?;
         }
     }
 
@@ -145,74 +354,66 @@ async fn main() -> Result<()> {
 /// The primary logic for the `run` command.
 /// This function initializes all core components and starts the web server.
 /// It will run indefinitely until terminated.
-async fn run_app() -> Result<()> {
+async fn run_app(ws_tx: broadcast::Sender<WsMessage>, ws_cache: ReplayCache) -> Result<()> {
     // --- 1. Initialization ---
     let settings = app_config::load_settings()?;
     tracing::info!("Application settings loaded successfully.");
 
-    let _db_pool = database::connect(&settings.database).await?;
-    tracing::info!("Database connection established and migrations are up-to-date.");
-
-    // Create a broadcast channel for WebSocket messages.
-    let (_ws_tx, _) = broadcast::channel::<WsMessage>(1024); // Adjust buffer size as needed
-
     // Initialize the database connection pool.
-    let db_pool = database::connect(&settings.database).await?;
+    let db_pool = connect_db(&settings.database).await?;
+    tracing::info!("Database connection established and migrations are up-to-date.");
 
     // --- 2. Create Shared State ---
-    let initial_capital = dec!(10_000);
-    let portfolio = Arc::new(TokioMutex::new(execution::types::Portfolio::new(initial_capital) ));
-    tracing::info!("Portfolio initialized with initial capital.");
-
-    // The WebSocket broadcaster is a central piece of state.
-    let (ws_tx, _) = broadcast::channel::<events::WsMessage>(1024);
+    let portfolio = initial_portfolio(&settings.app)?;
+    tracing::info!(initial_capital = %portfolio.initial_capital, "Portfolio initialized with initial capital.");
+    let portfolio = Arc::new(TokioMutex::new(portfolio));
 
     // --- 3. Component Instantiation ---
-    // Use a hardcoded SimulationSettings for now, as in backtest
-    let dummy_settings = execution::types::SimulationSettings {
-        maker_fee: 0.0,
-        taker_fee: 0.0,
-        slippage_percent: 0.0,
-    };
+    let simulation_settings = simulation_settings_or_default(&settings);
     let api_client = api_client::new(&settings.binance)?;
 
-    // Conditionally instantiate the executor based on the config flag
-    let executor: Box<dyn Executor + Send> = if settings.app.live_trading_enabled {
-        tracing::warn!("LIVE TRADING IS ENABLED. REAL ORDERS WILL BE PLACED.");
-        Box::new(execution::live::LiveExecutor::new(
-            api_client.clone(),
-            ws_tx.clone(),
-        ))
-    } else {
-        Box::new(execution::simulated::SimulatedExecutor::new(
-            dummy_settings.clone(),
-            ws_tx.clone(),
-        ))
-    };
+    // Instantiate the executor for the configured trading mode. Both consume the same
+    // live kline stream and broadcast the same WebSocket events.
+    let trading_mode = settings.app.effective_trading_mode();
+    let allow_pyramiding = simulation_settings.allow_pyramiding;
+    let executor = build_executor(trading_mode, &api_client, simulation_settings, &ws_tx);
 
     // Instantiate Risk Manager
-    let risk_manager = Box::new(SimpleRiskManager::new(
-        settings.simple_risk_manager.clone().unwrap(),
-    ));
+    // The live executor leaves netting to the exchange, so only paper trading adds to or
+    // reverses open positions.
+    let risk_manager = Box::new(
+        SimpleRiskManager::new(settings.simple_risk_manager.clone().unwrap())
+            .with_pyramiding(trading_mode == TradingMode::Paper && allow_pyramiding)
+            .with_reversals(trading_mode == TradingMode::Paper),
+    );
 
     // Instantiate Strategy (explicit, as in backtest)
-    let _strategy: Box<dyn Strategy + Send> = if let Some(settings) = settings.strategies.ma_crossover.as_ref() {
-        Box::new(strategies::ma_crossover::MACrossover::new(settings.clone()))
-    } else if let Some(settings) = settings.strategies.supertrend.as_ref() {
-        Box::new(strategies::supertrend::SuperTrend::new(settings.clone()))
-    } else if let Some(settings) = settings.strategies.prob_reversion.as_ref() {
-        Box::new(strategies::prob_reversion::ProbReversion::new(settings.clone()))
-    } else {
-        anyhow::bail!("Cannot run: No strategies are configured in settings.");
-    };
+    let _strategy = settings.strategies.first_configured()
+        .ok_or_else(|| anyhow::anyhow!("Cannot run: No strategies are configured in settings."))?
+        .build();
 
     // --- 4. Launch Concurrent Tasks ---
     let live_config = app_config::load_live_config()?;
 
-    // Create the State Reconciler instance
-    let reconciler = StateReconciler::new(
+    // Create the State Reconciler instance. A paper portfolio has no exchange-side state
+    // to reconcile against.
+    let reconciler_enabled = trading_mode == TradingMode::Live && settings.app.reconciler_enabled;
+    let reconciler = reconciler_enabled.then(|| StateReconciler::new(
         api_client.clone(),
         Arc::clone(&portfolio), // Give it a pointer to the shared portfolio
+        ws_tx.clone(),
+        std::time::Duration::from_secs(settings.app.reconcile_interval_secs),
+    ));
+
+    // Share the strategy settings and bot config with a watcher that hot-reloads them on change
+    let hot_reload = HotReloadHandle::new(settings.strategies.clone(), live_config.clone());
+    let config_watcher = ConfigWatcher::new(
+        hot_reload.clone(),
+        vec![
+            PathBuf::from("config/base.toml"),
+            PathBuf::from(format!("config/{}.toml", settings.app.environment)),
+            PathBuf::from("config/live.toml"),
+        ],
     );
 
     // Create the Trading Engine instance
@@ -224,6 +425,15 @@ async fn run_app() -> Result<()> {
         risk_manager,
         executor,
         ws_tx.clone(),
+        Arc::clone(&portfolio),
+    )
+    .with_hot_reload(hot_reload);
+
+    // Persist the live portfolio's equity periodically (trades also snapshot it).
+    let equity_snapshotter = EquitySnapshotter::new(
+        db_pool.clone(),
+        Arc::clone(&portfolio),
+        std::time::Duration::from_secs(settings.app.equity_snapshot_interval_secs),
     );
     
     // --- 4. Launch Concurrent Tasks ---
@@ -234,12 +444,23 @@ async fn run_app() -> Result<()> {
         trading_engine.run().await
     });
 
+    let server_portfolio = Arc::clone(&portfolio);
+    let server_api_client = (trading_mode == TradingMode::Live).then(|| api_client.clone());
     let server_handle = tokio::spawn(async move {
-        web_server::run(settings.server, db_pool, ws_tx).await
+        web_server::run(settings.server, db_pool, ws_tx, ws_cache, server_portfolio, server_api_client).await
+    });
+
+    let reconciler_handle = match reconciler {
+        Some(reconciler) => tokio::spawn(async move { reconciler.run().await }),
+        None => tokio::spawn(std::future::pending()),
+    };
+
+    let config_watcher_handle = tokio::spawn(async move {
+        config_watcher.run().await
     });
 
-    let reconciler_handle = tokio::spawn(async move {
-        reconciler.run().await
+    let equity_snapshotter_handle = tokio::spawn(async move {
+        equity_snapshotter.run().await
     });
 
     // --- 5. Supervise Tasks ---
@@ -253,6 +474,12 @@ async fn run_app() -> Result<()> {
         reconciler_result = reconciler_handle => {
             tracing::error!(?reconciler_result, "State reconciler has terminated unexpectedly.");
         }
+        config_watcher_result = config_watcher_handle => {
+            tracing::error!(?config_watcher_result, "Config watcher has terminated unexpectedly.");
+        }
+        equity_snapshotter_result = equity_snapshotter_handle => {
+            tracing::error!(?equity_snapshotter_result, "Equity snapshotter has terminated unexpectedly.");
+        }
     }
 
     anyhow::bail!("A critical task terminated. Shutting down.");
@@ -260,61 +487,461 @@ async fn run_app() -> Result<()> {
 
 // --- "Backfill" Subcommand Logic ---
 
+/// Connects to the database and brings its schema up to date.
+async fn connect_db(settings: &app_config::types::DatabaseSettings) -> Result<database::Db> {
+    let db = database::connect(settings).await?;
+    db.migrate().await?;
+    Ok(db)
+}
+
+/// The fraction of missing klines above which a backtest's results are flagged as unreliable.
+const MAX_MISSING_KLINE_FRACTION: f64 = 0.01;
+
+/// Logs the gaps in a backtest's kline data: a warning if more than
+/// `MAX_MISSING_KLINE_FRACTION` of the klines are missing, otherwise an info line if any are.
+fn warn_on_kline_gaps(symbol: &Symbol, interval: &str, summary: &database::KlineGapSummary) {
+    if summary.gap_count == 0 {
+        return;
+    }
+    if summary.missing_fraction() > MAX_MISSING_KLINE_FRACTION {
+        tracing::warn!(
+            symbol = %symbol.0,
+            interval,
+            gaps = summary.gap_count,
+            missing = summary.missing_bars,
+            largest_gap = summary.largest_gap_bars,
+            "{:.1}% of the klines in the backtest range are missing; results may be misleading. Run `backfill` to fill the gaps.",
+            summary.missing_fraction() * 100.0
+        );
+    } else {
+        tracing::info!(
+            symbol = %symbol.0,
+            interval,
+            gaps = summary.gap_count,
+            missing = summary.missing_bars,
+            largest_gap = summary.largest_gap_bars,
+            "The backtest range has a few missing klines."
+        );
+    }
+}
+
+/// Builds the executor for `trading_mode`. Both consume the same live kline stream and
+/// broadcast the same WebSocket events, but only the live one places orders through
+/// `api_client`.
+fn build_executor(
+    trading_mode: TradingMode,
+    api_client: &ApiClient,
+    simulation_settings: core_types::SimulationSettings,
+    ws_tx: &broadcast::Sender<WsMessage>,
+) -> Box<dyn Executor + Send> {
+    match trading_mode {
+        TradingMode::Live => {
+            tracing::warn!("LIVE TRADING IS ENABLED. REAL ORDERS WILL BE PLACED.");
+            Box::new(execution::live::LiveExecutor::new(
+                api_client.clone(),
+                ws_tx.clone(),
+                simulation_settings.taker_fee,
+            ))
+        }
+        TradingMode::Paper => {
+            tracing::info!("Paper trading: orders are simulated against live prices.");
+            Box::new(execution::simulated::SimulatedExecutor::new(
+                simulation_settings,
+                ws_tx.clone(),
+            ))
+        }
+    }
+}
+
+/// Creates the `run` portfolio holding `app.initial_capital` in cash. In live mode the
+/// reconciler later replaces it with the exchange's wallet balance.
+fn initial_portfolio(app_settings: &app_config::types::AppSettings) -> anyhow::Result<execution::types::Portfolio> {
+    let initial_capital = rust_decimal::Decimal::from_f64(app_settings.initial_capital)
+        .ok_or_else(|| anyhow::anyhow!("app.initial_capital is not a valid amount"))?;
+    Ok(execution::types::Portfolio::new(initial_capital))
+}
+
+/// Returns the configured `[simulation]` settings, or fee- and slippage-free defaults
+/// (with a warning) if the table is missing.
+fn simulation_settings_or_default(settings: &app_config::Settings) -> core_types::SimulationSettings {
+    settings.simulation.clone().unwrap_or_else(|| {
+        tracing::warn!("No [simulation] settings configured; simulating without fees or slippage.");
+        core_types::SimulationSettings::default()
+    })
+}
+
 /// Handles the logic for the `backfill` subcommand.
 async fn handle_backfill(
     symbol_str: String,
     interval: String,
     start_date: Option<String>,
+    end_date: Option<String>,
 ) -> Result<()> {
     // --- 1. Initialization ---
     let settings = app_config::load_settings()?;
-    let db = database::connect(&settings.database).await?;
+    let db = connect_db(&settings.database).await?;
     let api_client = api_client::new(&settings.binance)?;
-    let symbol = Symbol(symbol_str);
+    let symbol = Symbol::new(&symbol_str)?;
+    let interval_ms = core_types::interval_to_millis(&interval)
+        .ok_or_else(|| anyhow::anyhow!("Invalid interval: {}", interval))?;
 
     tracing::info!(symbol = %symbol.0, interval, "Starting backfill process.");
 
-    // --- 2. Determine Start Time ---
-    let mut current_start_time = match start_date {
+    // --- 2. Determine the Range ---
+    let end_time = match &end_date {
+        Some(date_str) => parse_cli_date(date_str, "23:59:59")?.min(Utc::now()),
+        None => Utc::now(),
+    };
+    let start_time = match &start_date {
         Some(date_str) => {
-            let naive = chrono::NaiveDateTime::parse_from_str(&format!("{} 00:00:00", date_str), "%Y-%m-%d %H:%M:%S")
-                .map_err(|e| anyhow::anyhow!("Failed to parse start date: {}", e))?;
-            let dt = chrono::Utc.from_utc_datetime(&naive);
+            let dt = parse_cli_date(date_str, "00:00:00")?;
             tracing::info!("Using provided start date: {}", dt);
-            Some(dt.timestamp_millis())
-        }
-        None => {
-            tracing::info!("No start date provided. Resuming from the last saved kline.");
-            None
+            dt
         }
+        None => match db.get_latest_klines(&symbol, &interval, 1).await?.last() {
+            Some(last) => {
+                tracing::info!("No start date provided. Resuming from the last saved kline.");
+                Utc.timestamp_millis_opt(last.open_time + interval_ms).unwrap()
+            }
+            None => {
+                let fallback = end_time - chrono::Duration::milliseconds(interval_ms * 1000);
+                tracing::info!(%fallback, "No start date provided and no klines stored. Fetching the most recent 1000 klines.");
+                fallback
+            }
+        },
     };
+    if start_time > end_time {
+        anyhow::bail!("The start date {} is after the end date {}", start_time, end_time);
+    }
 
-    // --- 3. The Fetch-and-Save Loop ---
-    loop {
-        tracing::info!(?current_start_time, "Fetching batch of klines...");
-        let klines = api_client
-            .get_historical_klines(&symbol, &interval, current_start_time, Some(1000))
-            .await?;
+    // --- 3. Fetch What Is Missing ---
+    let fetched = backfill_missing(&db, &api_client, &symbol, &interval, start_time, end_time).await?;
 
-        if klines.is_empty() {
-            tracing::info!("Reached the end of the historical data. Backfill complete.");
-            break;
+    println!("Backfill complete. Inserted {} {} {} klines.", fetched, symbol.0, interval);
+    Ok(())
+}
+
+/// Fetches and stores the klines of `symbol` missing between `start_time` and `end_time`
+/// (inclusive), and returns how many were inserted.
+///
+/// Only the missing ranges are fetched, so re-running a backfill is cheap. Pacing between
+/// requests is handled by the API client's rate limiter.
+async fn backfill_missing(
+    db: &database::Db,
+    api_client: &api_client::types::ApiClient,
+    symbol: &Symbol,
+    interval: &str,
+    start_time: chrono::DateTime<Utc>,
+    end_time: chrono::DateTime<Utc>,
+) -> Result<i64> {
+    let interval_ms = core_types::interval_to_millis(interval)
+        .ok_or_else(|| anyhow::anyhow!("Invalid interval: {}", interval))?;
+    let gaps = db.find_kline_gaps(symbol, interval, start_time, end_time).await?;
+    let total_missing: i64 = gaps.iter().map(|g| g.missing_bars(interval_ms)).sum();
+    if gaps.is_empty() {
+        println!("All {} {} klines between {} and {} are already stored.", symbol.0, interval, start_time, end_time);
+        return Ok(0);
+    }
+    tracing::info!(gaps = gaps.len(), missing_bars = total_missing, "Found missing ranges to backfill.");
+
+    let mut fetched: i64 = 0;
+    for gap in &gaps {
+        let mut cursor = gap.start;
+        while cursor <= gap.end {
+            let klines = api_client
+                .get_historical_klines(symbol, interval, Some(cursor), Some(gap.end), Some(1000))
+                .await?;
+            let Some(last) = klines.last() else {
+                break; // The exchange has no data for the rest of this gap.
+            };
+            cursor = last.open_time + 1;
+            let current_time = Utc.timestamp_millis_opt(last.open_time).unwrap();
+
+            db.insert_klines(symbol, interval, &klines).await?;
+            fetched += klines.len() as i64;
+
+            tracing::info!(
+                fetched,
+                total = total_missing,
+                current = %current_time,
+                "Backfill progress: {:.1}%",
+                (fetched as f64 / total_missing.max(1) as f64 * 100.0).min(100.0)
+            );
         }
+    }
+    Ok(fetched)
+}
 
-        let kline_count = klines.len();
-        let first_kline_time = Utc.timestamp_millis_opt(klines.first().unwrap().open_time).unwrap();
-        let last_kline_time = Utc.timestamp_millis_opt(klines.last().unwrap().open_time).unwrap();
+/// Parses a `YYYY-MM-DD` date from the command line into a UTC timestamp at the given time of day.
+fn parse_cli_date(date_str: &str, time_of_day: &str) -> Result<chrono::DateTime<Utc>> {
+    let naive = chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date_str, time_of_day), "%Y-%m-%d %H:%M:%S")
+        .map_err(|e| anyhow::anyhow!("Failed to parse date '{}': {}", date_str, e))?;
+    Ok(Utc.from_utc_datetime(&naive))
+}
 
-        tracing::info!(
-            count = kline_count,
-            from = %first_kline_time,
-            to = %last_kline_time,
-            "Received klines. Inserting into database."
+// --- "VerifyData" Subcommand Logic ---
+
+/// Handles the logic for the `verify-data` subcommand.
+async fn handle_verify_data(
+    symbol_str: String,
+    interval: String,
+    start_date: String,
+    end_date: String,
+    repair: bool,
+) -> Result<()> {
+    // --- 1. Initialization ---
+    let settings = app_config::load_settings()?;
+    let db = connect_db(&settings.database).await?;
+    let symbol = Symbol::new(&symbol_str)?;
+    let start_dt = parse_cli_date(&start_date, "00:00:00")?;
+    let end_dt = parse_cli_date(&end_date, "23:59:59")?;
+    let interval_ms = core_types::interval_to_millis(&interval)
+        .ok_or_else(|| anyhow::anyhow!("Unsupported interval '{}'", interval))?;
+
+    // --- 2. Detect Gaps ---
+    tracing::info!(symbol = %symbol.0, interval, %start_dt, %end_dt, "Verifying kline continuity...");
+    let gaps = db.find_kline_gaps(&symbol, &interval, start_dt, end_dt).await?;
+
+    if gaps.is_empty() {
+        println!("No gaps found for {} {} between {} and {}.", symbol.0, interval, start_date, end_date);
+        return Ok(());
+    }
+
+    let total_missing: i64 = gaps.iter().map(|g| g.missing_bars(interval_ms)).sum();
+    println!("\n--- Kline Gaps for {} {} ---", symbol.0, interval);
+    for gap in &gaps {
+        println!(
+            "  - {} -> {} ({} missing bars)",
+            Utc.timestamp_millis_opt(gap.start).unwrap(),
+            Utc.timestamp_millis_opt(gap.end).unwrap(),
+            gap.missing_bars(interval_ms)
         );
+    }
+    println!("Found {} gaps, {} missing bars in total.", gaps.len(), total_missing);
+
+    if !repair {
+        return Ok(());
+    }
 
+    // --- 3. Repair: Re-fetch Only the Missing Ranges ---
+    let api_client = api_client::new(&settings.binance)?;
+    for gap in &gaps {
+        tracing::info!(from = gap.start, to = gap.end, "Repairing gap...");
+        let klines = api_client.get_klines_range(&symbol, &interval, gap.start, gap.end).await?;
+        if klines.is_empty() {
+            tracing::warn!(from = gap.start, to = gap.end, "Exchange returned no data for this range. It may be a genuine outage.");
+            continue;
+        }
         db.insert_klines(&symbol, &interval, &klines).await?;
-        current_start_time = Some(klines.last().unwrap().open_time + 1);
-        sleep(Duration::from_millis(500)).await;
+    }
+
+    let remaining = db.find_kline_gaps(&symbol, &interval, start_dt, end_dt).await?;
+    println!("Repair finished. {} gaps remain.", remaining.len());
+
+    Ok(())
+}
+
+// --- "Flatten" Subcommand Logic ---
+
+/// Handles the logic for the `flatten` subcommand.
+/// Fails, naming the symbols, if any position could not be closed.
+async fn handle_flatten() -> Result<()> {
+    let settings = app_config::load_settings()?;
+    let api_client = api_client::new(&settings.binance)?;
+
+    tracing::warn!("Flattening all open positions on the exchange.");
+    let report = api_client.flatten_positions().await?;
+    for symbol in &report.closed {
+        println!("Closed {}", symbol);
+    }
+    if report.closed.is_empty() && report.failed.is_empty() {
+        println!("No open positions.");
+    }
+    if !report.failed.is_empty() {
+        for failure in &report.failed {
+            eprintln!("Failed to close {}: {}", failure.symbol, failure.error);
+        }
+        let symbols: Vec<&str> = report.failed.iter().map(|f| f.symbol.as_str()).collect();
+        anyhow::bail!("Could not flatten {}", symbols.join(", "));
+    }
+    Ok(())
+}
+
+// --- "PruneData" Subcommand Logic ---
+
+/// Handles the logic for the `prune-data` subcommand.
+async fn handle_prune_data(symbol_str: String, interval: String, before: String) -> Result<()> {
+    let settings = app_config::load_settings()?;
+    let db = connect_db(&settings.database).await?;
+    let symbol = Symbol::new(&symbol_str)?;
+    let cutoff = parse_cli_date(&before, "00:00:00")?;
+
+    tracing::info!(symbol = %symbol.0, interval, %cutoff, "Pruning klines older than cutoff...");
+    let deleted = db.prune_klines(&symbol, &interval, cutoff).await?;
+    println!("Deleted {} {} {} klines opened before {}.", deleted, symbol.0, interval, before);
+
+    Ok(())
+}
+
+/// The page size used when fetching all trades of a run for `runs show`.
+const RUN_TRADES_PAGE_SIZE: u32 = 500;
+
+/// Handles the logic for the `runs` subcommand.
+async fn handle_runs(action: RunsAction, json: bool) -> Result<()> {
+    if let RunsAction::List { page, page_size, .. } = &action
+        && (*page == 0 || *page_size == 0)
+    {
+        anyhow::bail!("--page and --page-size must be at least 1");
+    }
+    if let RunsAction::Sensitivity { fee_multipliers, slippage_multipliers, .. } = &action {
+        let negative = fee_multipliers.iter().chain(slippage_multipliers).any(|m| *m < 0.0);
+        if fee_multipliers.is_empty() || slippage_multipliers.is_empty() || negative {
+            anyhow::bail!("--fee-multipliers and --slippage-multipliers must be non-empty and non-negative");
+        }
+    }
+
+    let settings = app_config::load_settings()?;
+    let db = connect_db(&settings.database).await?;
+
+    match action {
+        RunsAction::List { page, page_size, job_id } => {
+            let (runs, total) = db.get_backtest_runs_paginated(page, page_size, job_id).await?;
+            if json {
+                let output = serde_json::json!({
+                    "page": page,
+                    "page_size": page_size,
+                    "total_items": total,
+                    "runs": runs,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            let fmt_metric = |value: Option<f64>, precision: usize| {
+                value.map_or_else(|| "-".to_string(), |v| format!("{:.*}", precision, v))
+            };
+            println!(
+                "{:<8} {:<20} {:<10} {:<6} {:<12} {:<12} {:>9} {:>7} {:>8} {:>8}",
+                "ID", "Strategy", "Symbol", "Intvl", "Start", "End", "PnL %", "Trades", "Sharpe", "MaxDD %"
+            );
+            println!("{}", "-".repeat(108));
+            for run in &runs {
+                println!(
+                    "{:<8} {:<20} {:<10} {:<6} {:<12} {:<12} {:>9} {:>7} {:>8} {:>8}",
+                    run.id,
+                    run.strategy_name,
+                    run.symbol,
+                    run.interval,
+                    run.start_date.format("%Y-%m-%d"),
+                    run.end_date.format("%Y-%m-%d"),
+                    fmt_metric(run.net_pnl_percentage, 2),
+                    run.total_trades.map_or_else(|| "-".to_string(), |t| t.to_string()),
+                    fmt_metric(run.sharpe_ratio, 3),
+                    fmt_metric(run.max_drawdown_percentage, 2),
+                );
+            }
+            let total_pages = (total as u64).div_ceil(page_size as u64).max(1);
+            println!("\nPage {} of {} ({} runs total)", page, total_pages, total);
+        }
+        RunsAction::Show { run_id } => {
+            let report = db
+                .get_performance_report(run_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No performance report found for run {}", run_id))?;
+
+            let mut trades = Vec::new();
+            let mut page = 1;
+            loop {
+                let (batch, total) = db
+                    .get_trades_for_run_paginated(run_id, page, RUN_TRADES_PAGE_SIZE, &database::TradeFilter::default())
+                    .await?;
+                let batch_len = batch.len();
+                trades.extend(batch);
+                if batch_len == 0 || trades.len() as i64 >= total {
+                    break;
+                }
+                page += 1;
+            }
+
+            let longs = trades.iter().filter(|t| t.side.eq_ignore_ascii_case("long")).count();
+            let shorts = trades.len() - longs;
+            let winners = trades.iter().filter(|t| t.pnl > rust_decimal::Decimal::ZERO).count();
+            let gross_pnl: rust_decimal::Decimal = trades.iter().map(|t| t.pnl).sum();
+            let total_fees: rust_decimal::Decimal = trades.iter().map(|t| t.fees).sum();
+            let best = trades.iter().map(|t| t.pnl).max();
+            let worst = trades.iter().map(|t| t.pnl).min();
+
+            if json {
+                let output = serde_json::json!({
+                    "run_id": run_id,
+                    "report": report,
+                    "trade_summary": {
+                        "total": trades.len(),
+                        "longs": longs,
+                        "shorts": shorts,
+                        "winners": winners,
+                        "gross_pnl": gross_pnl,
+                        "total_fees": total_fees,
+                        "best_trade_pnl": best,
+                        "worst_trade_pnl": worst,
+                    },
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            println!("\nBacktest run #{}", run_id);
+            backtester::print_report(&report);
+            println!("Trade Summary:");
+            println!("  Trades:              {} ({} long / {} short)", trades.len(), longs, shorts);
+            println!("  Winners:             {}", winners);
+            println!("  Gross P&L:           ${:.2}", gross_pnl);
+            println!("  Total Fees:          ${:.2}", total_fees);
+            if let (Some(best), Some(worst)) = (best, worst) {
+                println!("  Best / Worst Trade:  ${:.2} / ${:.2}", best, worst);
+            }
+            println!("-----------------------------------");
+        }
+        RunsAction::Export { run_id, output } => {
+            let export = db
+                .export_backtest(run_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Backtest run {} not found", run_id))?;
+            let document = serde_json::to_string_pretty(&export)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, document)?;
+                    tracing::info!(run_id, path = %path.display(), "Backtest run exported.");
+                }
+                None => println!("{}", document),
+            }
+        }
+        RunsAction::Import { file } => {
+            let document = std::fs::read_to_string(&file)?;
+            let export: database::FullBacktestExport = serde_json::from_str(&document)?;
+            let run_id = db.import_backtest(&export).await?;
+            if json {
+                println!("{}", serde_json::json!({ "run_id": run_id }));
+            } else {
+                println!("Imported {} as backtest run #{}", file.display(), run_id);
+            }
+        }
+        RunsAction::Sensitivity { run_id, fee_multipliers, slippage_multipliers } => {
+            let cells = sensitivity::run_cost_sensitivity(
+                &db,
+                settings.analytics,
+                run_id,
+                &fee_multipliers,
+                &slippage_multipliers,
+            )
+            .await?;
+            if json {
+                let output = serde_json::json!({ "run_id": run_id, "cells": cells });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                sensitivity::print_sensitivity(run_id, &cells, &fee_multipliers, &slippage_multipliers);
+            }
+        }
     }
 
     Ok(())
@@ -326,103 +953,157 @@ async fn handle_backtest(
     interval: String,
     start_date: String,
     end_date: String,
+    seed: Option<u64>,
     ws_tx: broadcast::Sender<WsMessage>,
 ) -> Result<()> {
     // --- 1. Initialization & Configuration ---
     let settings = app_config::load_settings()?;
-    let symbol = Symbol(symbol_str);
+    let symbol = Symbol::new(&symbol_str)?;
 
     // Parse start and end dates
-    let start_dt = chrono::DateTime::parse_from_str(&format!("{} 00:00:00", start_date), "%Y-%m-%d %H:%M:%S")
-        .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|e| anyhow::anyhow!("Failed to parse start date: {}", e))?;
-    let end_dt = chrono::DateTime::parse_from_str(&format!("{} 23:59:59", end_date), "%Y-%m-%d %H:%M:%S")
-        .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|e| anyhow::anyhow!("Failed to parse end date: {}", e))?;
+    let start_dt = parse_cli_date(&start_date, "00:00:00")?;
+    let end_dt = parse_cli_date(&end_date, "23:59:59")?;
 
     // --- 2. Instantiate All Components ---
-    let risk_manager = match settings.simple_risk_manager {
-        Some(risk_settings) => Box::new(SimpleRiskManager::new(risk_settings)) as Box<dyn RiskManager + Send>,
-        None => anyhow::bail!("Cannot run backtest: simple_risk_manager settings are missing."),
+    let (mut backtester, strategy_params, snapshot) = build_backtester(&settings, &symbol, &interval, seed, &ws_tx)?;
+    let strategy_name = strategy_params.kind().key();
+
+    // --- 3. Load Data ---
+    let db = connect_db(&settings.database).await?;
+    tracing::info!("Loading historical data for backtest...");
+    let (klines, gap_summary) = db.get_klines_checked(&symbol, &interval, start_dt, end_dt).await?;
+    tracing::info!("Loaded {} klines for the specified date range.", klines.len());
+    warn_on_kline_gaps(&symbol, &interval, &gap_summary);
+
+    // --- 4. Run the Backtester ---
+    let (report, trades, equity_curve) = backtester.run(klines, Some(ws_tx.clone())).await?;
+
+    // --- 5. Save the Results to the Database ---
+    tracing::info!(trade_count = trades.len(), "Saving backtest report, trades, and equity curve to the database...");
+    let config = serde_json::to_value(&snapshot)?;
+    let meta = database::BacktestRunMeta {
+        strategy_name,
+        symbol: &symbol,
+        interval: &interval,
+        start_date: start_dt,
+        end_date: end_dt,
+        parameters: &strategy_params,
+        config: Some(&config),
     };
+    let run_id = db.save_full_backtest(
+        None, // job_id
+        &meta,
+        &report,
+        &trades,
+        &equity_curve,
+    ).await?;
+    tracing::info!(run_id, "Backtest run and all associated data saved.");
+
+    Ok(())
+}
+
+/// Builds a backtester for the first configured strategy, with the configured risk
+/// settings and fees. A `seed` given on the command line takes precedence over the
+/// configured one. Returns the strategy's parameters and a snapshot of the settings
+/// alongside, for saving the run.
+fn build_backtester(
+    settings: &app_config::Settings,
+    symbol: &Symbol,
+    interval: &str,
+    seed: Option<u64>,
+    ws_tx: &broadcast::Sender<WsMessage>,
+) -> Result<(Backtester, strategies::registry::StrategyParams, RunConfigSnapshot)> {
+    let fees = simulation_settings_or_default(settings);
+    let Some(risk_settings) = settings.simple_risk_manager.clone() else {
+        anyhow::bail!("Cannot run backtest: simple_risk_manager settings are missing.");
+    };
+    let risk_manager = Box::new(
+        SimpleRiskManager::new(risk_settings.clone())
+            .with_pyramiding(fees.allow_pyramiding)
+            .with_reversals(true),
+    ) as Box<dyn RiskManager + Send>;
 
     // Pick the first available strategy from config
-    let (strategy_name, strategy): (String, Box<dyn Strategy + Send>) = if let Some(settings) = settings.strategies.ma_crossover.as_ref() {
-        ("ma_crossover".to_string(), Box::new(MACrossover::new(settings.clone())))
-    } else if let Some(settings) = settings.strategies.supertrend.as_ref() {
-        ("supertrend".to_string(), Box::new(strategies::supertrend::SuperTrend::new(settings.clone())))
-    } else if let Some(settings) = settings.strategies.prob_reversion.as_ref() {
-        ("prob_reversion".to_string(), Box::new(strategies::prob_reversion::ProbReversion::new(settings.clone())))
-    } else {
+    let Some(strategy_params) = settings.strategies.first_configured() else {
         anyhow::bail!("No strategy is configured in the config file.");
     };
 
-    // In handle_backtest, replace settings.simulation usage with a placeholder
-    let dummy_settings = execution::types::SimulationSettings {
-        maker_fee: 0.0,
-        taker_fee: 0.0,
-        slippage_percent: 0.0,
+    let config = BacktestConfig {
+        fees,
+        seed,
+        analytics: settings.analytics,
+        ..BacktestConfig::default()
     };
+    let executor = Box::new(config.simulated_executor(ws_tx.clone())) as Box<dyn Executor + Send + Sync>;
+    let snapshot = RunConfigSnapshot::new(&config, risk_settings);
 
-    // Create a new portfolio with initial capital
-    let initial_capital = dec!(10_000.0);
-    let portfolio = Arc::new(TokioMutex::new(Portfolio::new(initial_capital)));
-
-    let executor = Box::new(SimulatedExecutor::new(
-        dummy_settings,
-        ws_tx.clone()
-    )) as Box<dyn Executor + Send + Sync>;
-
-    // --- 3. Load Data ---
-    let db = database::connect(&settings.database).await?;
-    tracing::info!("Loading historical data for backtest...");
-    let klines = db.get_klines_by_date_range(&symbol, &interval, start_dt, end_dt).await?;
-    tracing::info!("Loaded {} klines for the specified date range.", klines.len());
-
-    // --- 4. Setup and Run the Backtester ---
-    let mut backtester = Backtester::new(
+    let backtester = Backtester::new(
         symbol.clone(),
-        interval.clone(),
-        strategy,
+        interval.to_string(),
+        strategy_params.build(),
         risk_manager,
         executor,
+        config,
     );
+    Ok((backtester, strategy_params, snapshot))
+}
 
-    let (report, trades, equity_curve) = backtester.run(klines).await?;
-
-    // --- 5. Save the Results to the Database ---
-    let strategy_settings_json = match strategy_name.as_str() {
-        "ma_crossover" => settings.strategies.ma_crossover.as_ref().map(|s| serde_json::to_value(s).unwrap()),
-        "supertrend" => settings.strategies.supertrend.as_ref().map(|s| serde_json::to_value(s).unwrap()),
-        "prob_reversion" => settings.strategies.prob_reversion.as_ref().map(|s| serde_json::to_value(s).unwrap()),
-        _ => None,
-    };
-    if let Some(strategy_settings) = strategy_settings_json {
-        tracing::info!("Saving backtest report to the database...");
-        let run_id = db.save_backtest_report(
-            None, // job_id
-            &strategy_name,
-            &symbol,
-            &interval,
-            start_dt,
-            end_dt,
-            &strategy_settings,
-            &report,
-        ).await?;
-        tracing::info!(trade_count = trades.len(), "Saving individual trades to the database...");
-        db.save_trades(run_id, &trades).await?;
-        tracing::info!("Individual trades saved successfully.");
-        db.save_equity_curve(run_id, &equity_curve).await?;
-        tracing::info!(run_id, "Backtest run and all associated data saved.");
-    } else {
-        tracing::warn!("Could not find strategy settings to save with the report.");
+/// Handles the logic for the `validate-strategy` subcommand.
+async fn handle_validate_strategy(
+    symbol_str: String,
+    interval: String,
+    limit: u16,
+    seed: Option<u64>,
+    ws_tx: broadcast::Sender<WsMessage>,
+) -> Result<()> {
+    if limit == 0 || limit > 1500 {
+        anyhow::bail!("--limit must be between 1 and 1500");
     }
+    let settings = app_config::load_settings()?;
+    let symbol = Symbol::new(&symbol_str)?;
+    let api_client = api_client::new(&settings.binance)?;
 
+    // `run` prints the report; nothing is written to the database.
+    validate_strategy(&settings, &api_client, &symbol, &interval, limit, seed, &ws_tx).await?;
     Ok(())
 }
 
+/// Backtests the first configured strategy on the latest `limit` closed klines fetched
+/// through `api_client`, bypassing the database, and returns the unsaved results.
+async fn validate_strategy(
+    settings: &app_config::Settings,
+    api_client: &api_client::types::ApiClient,
+    symbol: &Symbol,
+    interval: &str,
+    limit: u16,
+    seed: Option<u64>,
+    ws_tx: &broadcast::Sender<WsMessage>,
+) -> Result<(PerformanceReport, Vec<Trade>, Vec<EquityPoint>)> {
+    let (mut backtester, strategy_params, _) = build_backtester(settings, symbol, interval, seed, ws_tx)?;
+
+    // Fetch straight from the exchange, so the run is independent of the stored data.
+    let mut klines = api_client
+        .get_historical_klines(symbol, interval, None, None, Some(limit))
+        .await?;
+    // The most recent kline is usually still forming; only closed ones are backtested.
+    let now = Utc::now().timestamp_millis();
+    klines.retain(|kline| kline.close_time < now);
+    let (Some(first), Some(last)) = (klines.first(), klines.last()) else {
+        anyhow::bail!("Binance returned no closed klines for {} {}", symbol.0, interval);
+    };
+    tracing::info!(
+        strategy = strategy_params.kind().key(),
+        kline_count = klines.len(),
+        start = %Utc.timestamp_millis_opt(first.open_time).unwrap(),
+        end = %Utc.timestamp_millis_opt(last.open_time).unwrap(),
+        "Fetched klines from Binance; running an unsaved backtest."
+    );
+
+    backtester.run(klines, None).await
+}
+
 /// Handles the logic for the `optimize` subcommand.
-async fn handle_optimize() -> Result<()> {
+async fn handle_optimize(export: Option<PathBuf>) -> Result<()> {
     // ... load configs and generate param_sets (this is fast) ...
     let start_time = Instant::now();
     tracing::info!("Starting optimization job...");
@@ -437,20 +1118,27 @@ async fn handle_optimize() -> Result<()> {
     tracing::info!("Starting optimization with {} parameter sets", param_sets.len());
 
     // Create the DB connection and job ID in the async context
-    let db = database::connect(&app_config::load_settings()?.database).await?;
+    let db = connect_db(&app_config::load_settings()?.database).await?;
     let job_id = db.create_optimization_job(&optimizer_config.job.name).await?;
+    let weights = optimizer_config.scoring.clone();
     tracing::info!(job_id, "Created parent optimization job.");
 
-    // Now, move the heavy, parallel work to a blocking thread.
+    // Now, move the heavy, parallel work to a blocking thread. The pool is shared by all
+    // runs, which drive their async work on this runtime through `runtime`.
+    let runtime = tokio::runtime::Handle::current();
+    let optimizer_db = db.clone();
     task::spawn_blocking(move || {
-        run_optimization(&app_settings, &optimizer_config.job, param_sets, job_id)
+        run_optimization(&app_settings, &optimizer_config.job, param_sets, job_id, optimizer_db, runtime)
     }).await??;
 
     // 3. Analyze the results (this is fast, can be done on the main thread).
-    let db = database::connect(&app_config::load_settings()?.database).await?;
-    let ranked_results = analyzer::analyze_and_rank_results(&db, job_id).await?;
+    let ranked_results = analyzer::analyze_and_rank_results(&db, job_id, &weights).await?;
 
     print_optimization_report(&ranked_results);
+    if let Some(path) = export {
+        analyzer::write_leaderboard(&ranked_results, &path)?;
+        tracing::info!(rows = ranked_results.len(), path = %path.display(), "Optimization leaderboard exported.");
+    }
 
     tracing::info!(duration = ?start_time.elapsed(), "Optimization job and analysis finished.");
     Ok(())
@@ -485,3 +1173,231 @@ fn print_optimization_report(results: &[RankedReport]) {
         println!("Recommendation: No parameter sets passed the minimum threshold.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{development_settings, zig_zag_close};
+    use api_client::test_support::mock_exchange;
+    use api_client::types::ApiClient;
+    use axum::extract::{Query, State};
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use core_types::Kline;
+    use database::Db;
+    use rust_decimal::Decimal;
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    const MINUTE_MS: i64 = 60_000;
+    /// 2024-01-01T00:00:00Z.
+    const START_MS: i64 = 1_704_067_200_000;
+
+    type Requests = Arc<Mutex<Vec<(i64, i64)>>>;
+
+    /// Serves `/fapi/v1/klines` for a market with a 1m kline every minute, honoring
+    /// `startTime`, `endTime` and `limit`, and records the requested ranges.
+    async fn klines_handler(State(requests): State<Requests>, Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+        let start: i64 = query["startTime"].parse().unwrap();
+        let end: i64 = query.get("endTime").map_or(i64::MAX, |end| end.parse().unwrap());
+        let limit: i64 = query.get("limit").map_or(500, |limit| limit.parse().unwrap());
+        requests.lock().unwrap().push((start, end));
+        let first = (start + MINUTE_MS - 1).div_euclid(MINUTE_MS) * MINUTE_MS;
+        let rows = (0..limit)
+            .map(|i| first + i * MINUTE_MS)
+            .take_while(|open_time| *open_time <= end)
+            .map(|open_time| {
+                json!([open_time, "100", "101", "99", "100.5", "10", open_time + MINUTE_MS - 1, "1005", 42, "5", "502.5", "0"])
+            })
+            .collect();
+        Json(Value::Array(rows))
+    }
+
+    async fn mock_client() -> (ApiClient, Requests) {
+        let requests = Requests::default();
+        let app = Router::new()
+            .route("/fapi/v1/klines", get(klines_handler))
+            .with_state(requests.clone());
+        let client = mock_exchange(app).await;
+        (client, requests)
+    }
+
+    fn minute(index: i64) -> chrono::DateTime<Utc> {
+        Utc.timestamp_millis_opt(START_MS + index * MINUTE_MS).unwrap()
+    }
+
+    fn stored_kline(index: i64) -> Kline {
+        let open_time = START_MS + index * MINUTE_MS;
+        Kline {
+            open_time,
+            open: Decimal::ONE_HUNDRED,
+            high: Decimal::ONE_HUNDRED,
+            low: Decimal::ONE_HUNDRED,
+            close: Decimal::ONE_HUNDRED,
+            volume: Decimal::ONE,
+            close_time: open_time + MINUTE_MS - 1,
+        }
+    }
+
+    /// Serves nothing and records the path of every request it receives.
+    async fn recording_client() -> (ApiClient, Arc<Mutex<Vec<String>>>) {
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let recorded = paths.clone();
+        let app = Router::new().fallback(move |uri: axum::http::Uri| {
+            let recorded = recorded.clone();
+            async move {
+                recorded.lock().unwrap().push(uri.path().to_string());
+                axum::http::StatusCode::SERVICE_UNAVAILABLE
+            }
+        });
+        let client = mock_exchange(app).await;
+        (client, paths)
+    }
+
+    fn market_long() -> core_types::OrderRequest {
+        core_types::OrderRequest {
+            symbol: Symbol::new("BTCUSDT").unwrap(),
+            side: core_types::Side::Long,
+            quantity: Decimal::ONE,
+            order_type: core_types::OrderType::Market,
+            leverage: 1,
+            sl_price: Decimal::ZERO,
+            tp_price: None,
+            originating_signal: core_types::Signal::GoLong { confidence: 1.0, suggested_sl: None, suggested_tp: None },
+        }
+    }
+
+    #[tokio::test]
+    async fn paper_mode_fills_orders_without_calling_the_exchange() {
+        let (client, paths) = recording_client().await;
+        let (ws_tx, _) = broadcast::channel(16);
+        let mut portfolio = execution::types::Portfolio::new(Decimal::from(10_000));
+
+        let mut executor = build_executor(TradingMode::Paper, &client, core_types::SimulationSettings::default(), &ws_tx);
+        let fill = executor.execute(&market_long(), Decimal::ONE_HUNDRED, START_MS, &mut portfolio).await.unwrap();
+
+        assert!(fill.is_some());
+        assert!(portfolio.open_positions.contains_key(&Symbol::new("BTCUSDT").unwrap()));
+        assert!(paths.lock().unwrap().is_empty());
+
+        // The same order in live mode goes to the exchange.
+        let mut executor = build_executor(TradingMode::Live, &client, core_types::SimulationSettings::default(), &ws_tx);
+        let _ = executor.execute(&market_long(), Decimal::ONE_HUNDRED, START_MS, &mut portfolio).await;
+        assert!(!paths.lock().unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn backfill_fetches_only_missing_ranges_up_to_the_end_date(pool: PgPool) {
+        let db = Db::from(pool);
+        let (client, requests) = mock_client().await;
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        // Minutes 20 to 59 are already stored.
+        let stored: Vec<_> = (20..60).map(stored_kline).collect();
+        db.insert_klines(&symbol, "1m", &stored).await.unwrap();
+
+        let fetched = backfill_missing(&db, &client, &symbol, "1m", minute(0), minute(99)).await.unwrap();
+
+        assert_eq!(fetched, 60);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                (minute(0).timestamp_millis(), minute(19).timestamp_millis()),
+                (minute(60).timestamp_millis(), minute(99).timestamp_millis()),
+            ]
+        );
+        let klines = db.get_klines_by_date_range(&symbol, "1m", minute(0), minute(200)).await.unwrap();
+        assert_eq!(klines.len(), 100);
+        assert_eq!(klines.last().unwrap().open_time, minute(99).timestamp_millis());
+        // The stored klines were not overwritten by the mock's prices.
+        assert_eq!(klines[20].close, Decimal::ONE_HUNDRED);
+
+        // Everything is stored now, so a re-run makes no requests.
+        requests.lock().unwrap().clear();
+        let fetched = backfill_missing(&db, &client, &symbol, "1m", minute(0), minute(99)).await.unwrap();
+        assert_eq!(fetched, 0);
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn the_portfolio_starts_with_the_configured_capital() {
+        let app_settings: app_config::types::AppSettings = toml::from_str(
+            r#"
+            environment = "test"
+            log_level = "info"
+            optimizer_cores = 1
+            initial_capital = 2500.0
+            "#,
+        )
+        .unwrap();
+
+        let portfolio = initial_portfolio(&app_settings).unwrap();
+
+        assert_eq!(portfolio.initial_capital, Decimal::from(2_500));
+        assert_eq!(portfolio.cash, Decimal::from(2_500));
+        assert_eq!(portfolio.total_value(), Decimal::from(2_500));
+        assert!(portfolio.open_positions.is_empty());
+
+        let unset: app_config::types::AppSettings =
+            toml::from_str("environment = \"test\"\nlog_level = \"info\"\noptimizer_cores = 1").unwrap();
+        assert_eq!(initial_portfolio(&unset).unwrap().cash, Decimal::from(10_000));
+    }
+
+    /// The settings `load_settings` reads for the development environment.
+    type Queries = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+    /// Answers `/fapi/v1/klines` like Binance does without a `startTime`: the latest `limit`
+    /// 1m klines of a zig-zagging market, the last of which opens now and is still forming.
+    /// Records every query.
+    async fn latest_klines_handler(State(queries): State<Queries>, Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+        let limit: i64 = query["limit"].parse().unwrap();
+        queries.lock().unwrap().push(query);
+        let now = Utc::now().timestamp_millis();
+        let rows = (0..limit)
+            .map(|i| {
+                let open_time = now - (limit - 1 - i) * MINUTE_MS;
+                let close = zig_zag_close(i);
+                json!([open_time, close.to_string(), (close + 1).to_string(), (close - 1).to_string(), close.to_string(),
+                       "10", open_time + MINUTE_MS - 1, "1000", 42, "5", "500", "0"])
+            })
+            .collect();
+        Json(Value::Array(rows))
+    }
+
+    #[tokio::test]
+    async fn validate_strategy_backtests_the_latest_closed_klines_from_the_exchange() {
+        let queries = Queries::default();
+        let app = Router::new()
+            .route("/fapi/v1/klines", get(latest_klines_handler))
+            .with_state(queries.clone());
+        let client = mock_exchange(app).await;
+        let (ws_tx, _) = broadcast::channel(16);
+        let before = Utc::now();
+
+        let (report, trades, equity_curve) = validate_strategy(
+            &development_settings(),
+            &client,
+            &Symbol::new("BTCUSDT").unwrap(),
+            "1m",
+            200,
+            Some(7),
+            &ws_tx,
+        )
+        .await
+        .unwrap();
+
+        let queries = queries.lock().unwrap();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0]["symbol"], "BTCUSDT");
+        assert_eq!(queries[0]["interval"], "1m");
+        assert_eq!(queries[0]["limit"], "200");
+        assert!(!queries[0].contains_key("startTime"));
+        // The run covers every closed kline up to the latest, but not the forming one.
+        let last = equity_curve.last().unwrap();
+        assert!(equity_curve.iter().all(|point| point.timestamp < before));
+        assert!(last.timestamp >= before - chrono::Duration::minutes(1));
+        assert!(!report.aborted);
+        assert_eq!(report.total_trades as usize, trades.len());
+    }
+}
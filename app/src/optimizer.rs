@@ -1,32 +1,43 @@
 // In app/src/optimizer.rs
 
 use serde::Deserialize;
-use strategies::types::{MACrossoverSettings, SuperTrendSettings, ProbReversionSettings};
+use analytics::scoring::ScoringWeights;
+use strategies::registry::{StrategyKind, StrategyParams};
 use std::fs;
 use anyhow::{Context, Result};
 use crate::{ SimpleRiskManager,}; // MACrossover will be imported below
 use app_config::types::AppSettings;
 use backtester::Backtester;
+use backtester::types::{BacktestConfig, RunConfigSnapshot};
 use core_types::Symbol;
-use execution::simulated::SimulatedExecutor;
+use database::{Db, OptimizationJobStatus};
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
-use rust_decimal_macros::dec;
 use std::sync::{
     Arc, 
     // Mutex
 };
-use strategies::ma_crossover::MACrossover;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use chrono::Utc;
 use chrono::TimeZone;
-use std::any::Any;
 use toml::Value;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::runtime::Handle;
+use tokio_util::sync::CancellationToken;
+
+/// How often a running job checks whether its cancellation has been requested.
+const CANCEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
 
 // --- Structs for deserializing optimizer.toml ---
 
 #[derive(Deserialize, Debug)]
 pub struct OptimizerConfig {
     pub job: JobSettings,
+
+    /// The `[scoring]` table used to rank the job's results. Defaults apply if it is absent.
+    #[serde(default)]
+    pub scoring: ScoringWeights,
     
     // Using `flatten` tells serde to collect all other top-level tables
     // from the TOML file into this HashMap. The key will be the table name
@@ -43,13 +54,27 @@ pub struct JobSettings {
     pub start_date: String,
     pub end_date: String,
     pub strategy_to_optimize: String,
+    /// How parameter sets are drawn from the configured ranges.
+    #[serde(default)]
+    pub search: SearchStrategy,
+    /// The number of parameter sets to draw with `SearchStrategy::Random`.
+    pub num_samples: Option<usize>,
+    /// Seed for `SearchStrategy::Random`, for reproducible jobs.
+    pub seed: Option<u64>,
+    /// Stops a run early once its equity has fallen this many percent below the initial
+    /// capital. Aborted runs are saved with their report flagged and are not ranked.
+    pub max_drawdown_abort_percent: Option<f64>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(untagged)] // Allows serde to try parsing as one variant, then the next
-enum ParamValue {
-    Fixed(u32),
-    Range { cle: u32, end: u32, step: Option<u32> },
+/// How `generate_generic_parameter_sets` explores the parameter ranges.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchStrategy {
+    /// Every combination of the range values.
+    #[default]
+    Grid,
+    /// `num_samples` sets, each parameter drawn uniformly from its range.
+    Random,
 }
 
 // #[derive(Deserialize, Debug)]
@@ -69,7 +94,7 @@ pub fn load_optimizer_config() -> Result<OptimizerConfig> {
     toml::from_str(&content).context("Failed to parse optimizer.toml")
 }
 
-pub fn generate_generic_parameter_sets(config: &OptimizerConfig) -> anyhow::Result<Vec<Box<dyn Any + Send + Sync>>> {
+pub fn generate_generic_parameter_sets(config: &OptimizerConfig) -> anyhow::Result<Vec<StrategyParams>> {
     // 1. Dynamically find the correct parameter table to use.
     let strategy_key = format!("{}_params", config.job.strategy_to_optimize);
     
@@ -89,166 +114,172 @@ pub fn generate_generic_parameter_sets(config: &OptimizerConfig) -> anyhow::Resu
 
     // Helper to expand a ParamValue (int or float) into a Vec of numbers
     fn expand_value(value: &Value) -> Vec<Value> {
-        if let Some(table) = value.as_table() {
-            if let (Some(start), Some(end)) = (table.get("start"), table.get("end")) {
-                let step = table.get("step").and_then(|v| {
-                    if let Some(f) = v.as_float() {
-                        Some(f)
-                    } else if let Some(i) = v.as_integer() {
-                        Some(i as f64)
-                    } else {
-                        None
-                    }
-                }).unwrap_or(1.0);
-                
-                tracing::info!("Parsing range: start={:?}, end={:?}, step={:?}", start, end, step);
-                
-                // Handle both integer and float start/end values
-                let start_val = if let Some(f) = start.as_float() {
-                    f
-                } else if let Some(i) = start.as_integer() {
-                    i as f64
-                } else {
-                    return vec![value.clone()]; // Return original value if not numeric
-                };
-                
-                let end_val = if let Some(f) = end.as_float() {
-                    f
-                } else if let Some(i) = end.as_integer() {
-                    i as f64
+        if let Some(table) = value.as_table()
+            && let (Some(start), Some(end)) = (table.get("start"), table.get("end"))
+        {
+            let step = table.get("step")
+                .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+                .unwrap_or(1.0);
+            
+            tracing::info!("Parsing range: start={:?}, end={:?}, step={:?}", start, end, step);
+            
+            // Handle both integer and float start/end values
+            let start_val = if let Some(f) = start.as_float() {
+                f
+            } else if let Some(i) = start.as_integer() {
+                i as f64
+            } else {
+                return vec![value.clone()]; // Return original value if not numeric
+            };
+            
+            let end_val = if let Some(f) = end.as_float() {
+                f
+            } else if let Some(i) = end.as_integer() {
+                i as f64
+            } else {
+                return vec![value.clone()]; // Return original value if not numeric
+            };
+            
+            let mut vals = vec![];
+            let mut v = start_val;
+            while v <= end_val + 1e-8 {
+                // Preserve the original type (integer vs float)
+                if start.as_integer().is_some() && end.as_integer().is_some() && step == step.floor() {
+                    vals.push(Value::Integer(v as i64));
                 } else {
-                    return vec![value.clone()]; // Return original value if not numeric
-                };
-                
-                let mut vals = vec![];
-                let mut v = start_val;
-                while v <= end_val + 1e-8 {
-                    // Preserve the original type (integer vs float)
-                    if start.as_integer().is_some() && end.as_integer().is_some() && step == step.floor() {
-                        vals.push(Value::Integer(v as i64));
-                    } else {
-                        vals.push(Value::Float(v));
-                    }
-                    v += step;
+                    vals.push(Value::Float(v));
                 }
-                tracing::info!("Generated values: {:?}", vals);
-                return vals;
+                v += step;
             }
+            tracing::info!("Generated values: {:?}", vals);
+            return vals;
         }
         vec![value.clone()]
     }
 
-    // Build all combinations
-    let mut keys = vec![];
-    let mut value_lists = vec![];
-    for (k, v) in params_table.iter() {
-        keys.push(k.clone());
-        tracing::info!("Raw parameter {}: {:?}", k, v);
-        let expanded = expand_value(v);
-        tracing::info!("Parameter {}: {} values", k, expanded.len());
-        value_lists.push(expanded);
-    }
-    let mut final_sets = vec![];
-    let mut final_tables = vec![];
-    let mut indices = vec![0; value_lists.len()];
-    loop {
-        let mut table = toml::map::Map::new();
-        for (i, k) in keys.iter().enumerate() {
-            table.insert(k.clone(), value_lists[i][indices[i]].clone());
-        }
-        final_tables.push(table);
-        // Increment indices
-        let mut idx = value_lists.len();
-        while idx > 0 {
-            idx -= 1;
-            indices[idx] += 1;
-            if indices[idx] < value_lists[idx].len() {
-                break;
-            } else {
-                indices[idx] = 0;
+    // Helper to draw one value uniformly from a range (int or float); fixed values are kept as-is
+    fn sample_value(value: &Value, rng: &mut StdRng) -> Value {
+        if let Some(table) = value.as_table()
+            && let (Some(start), Some(end)) = (table.get("start"), table.get("end"))
+        {
+            // Ranges the grid would expand to integers are sampled as integers too.
+            let integral_step = table
+                .get("step")
+                .is_none_or(|s| s.as_integer().is_some() || s.as_float().is_some_and(|f| f == f.floor()));
+            if let (Some(s), Some(e), true) = (start.as_integer(), end.as_integer(), integral_step) {
+                return Value::Integer(rng.gen_range(s.min(e)..=s.max(e)));
+            }
+            let as_f64 = |v: &Value| v.as_float().or_else(|| v.as_integer().map(|i| i as f64));
+            if let (Some(s), Some(e)) = (as_f64(start), as_f64(end)) {
+                return Value::Float(rng.gen_range(s.min(e)..=s.max(e)));
             }
         }
-        if idx == 0 && indices[0] == 0 {
-            break;
-        }
+        value.clone()
     }
-    
-    tracing::info!("Generated {} parameter combinations", final_tables.len());
-    
-    // The key part that makes it generic is the `match` statement at the end:
-    for final_table in final_tables {
-        match config.job.strategy_to_optimize.as_str() {
-            "ma_crossover" => {
-                let settings: MACrossoverSettings = Value::Table(final_table).try_into()?;
-                final_sets.push(Box::new(settings) as Box<dyn Any + Send + Sync>);
-            },
-            "supertrend" => {
-                let settings: SuperTrendSettings = Value::Table(final_table).try_into()?;
-                final_sets.push(Box::new(settings) as Box<dyn Any + Send + Sync>);
-            },
-            "prob_reversion" => {
-                let settings: ProbReversionSettings = Value::Table(final_table).try_into()?;
-                final_sets.push(Box::new(settings) as Box<dyn Any + Send + Sync>);
+
+    let final_tables = match config.job.search {
+        SearchStrategy::Grid => {
+            // Build all combinations
+            let mut keys = vec![];
+            let mut value_lists = vec![];
+            for (k, v) in params_table.iter() {
+                keys.push(k.clone());
+                tracing::info!("Raw parameter {}: {:?}", k, v);
+                let expanded = expand_value(v);
+                tracing::info!("Parameter {}: {} values", k, expanded.len());
+                value_lists.push(expanded);
             }
-            _ => anyhow::bail!("Unknown strategy '{}' in optimizer config", config.job.strategy_to_optimize),
+            let mut final_tables = vec![];
+            let mut indices = vec![0; value_lists.len()];
+            loop {
+                let mut table = toml::map::Map::new();
+                for (i, k) in keys.iter().enumerate() {
+                    table.insert(k.clone(), value_lists[i][indices[i]].clone());
+                }
+                final_tables.push(table);
+                // Increment indices
+                let mut idx = value_lists.len();
+                while idx > 0 {
+                    idx -= 1;
+                    indices[idx] += 1;
+                    if indices[idx] < value_lists[idx].len() {
+                        break;
+                    } else {
+                        indices[idx] = 0;
+                    }
+                }
+                if idx == 0 && indices[0] == 0 {
+                    break;
+                }
+            }
+            final_tables
         }
-    }
+        SearchStrategy::Random => {
+            let num_samples = config.job.num_samples.ok_or_else(|| {
+                anyhow::anyhow!("job.num_samples must be set when job.search = \"random\"")
+            })?;
+            let mut rng = match config.job.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            (0..num_samples)
+                .map(|_| {
+                    params_table
+                        .iter()
+                        .map(|(k, v)| (k.clone(), sample_value(v, &mut rng)))
+                        .collect::<toml::map::Map<_, _>>()
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+    tracing::info!("Generated {} parameter combinations", final_tables.len());
+
+    let kind: StrategyKind = config.job.strategy_to_optimize.parse()?;
+    let final_sets = final_tables
+        .into_iter()
+        .map(|final_table| StrategyParams::from_params(kind, Value::Table(final_table)))
+        .collect::<Result<Vec<_>, _>>()?;
     Ok(final_sets)
 }
 
+/// Runs and saves one parameter set, returning whether the run was aborted early.
+///
+/// A run stopped by `cancel` is saved with its report flagged as cancelled, like an aborted
+/// one, so it is kept for inspection but never ranked.
 fn run_single_backtest_and_save(
     job_id: i64,
     main_settings: &app_config::Settings,
     job_settings: &JobSettings,
-    strategy_name: &str,
-    param: &Box<dyn Any + Send + Sync>,
-) -> Result<()> {
-    let rt = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()?;
-
-    rt.block_on(async {
-        let db = database::connect(&main_settings.database).await?;
-        let symbol = Symbol(job_settings.symbol.clone());
+    param: &StrategyParams,
+    db: &Db,
+    runtime: &Handle,
+    cancel: &CancellationToken,
+) -> Result<bool> {
+    runtime.block_on(async {
+        let symbol = Symbol::new(&job_settings.symbol)?;
         let interval = job_settings.interval.clone();
-        let risk_manager = Box::new(SimpleRiskManager::new(main_settings.simple_risk_manager.clone().unwrap()));
-        let dummy_settings = execution::types::SimulationSettings {
-            maker_fee: 0.0,
-            taker_fee: 0.0,
-            slippage_percent: 0.0,
-        };
+        let fees = main_settings.simulation.clone().unwrap_or_default();
+        let risk_settings = main_settings.simple_risk_manager.clone().unwrap();
+        let risk_manager = Box::new(
+            SimpleRiskManager::new(risk_settings.clone())
+                .with_pyramiding(fees.allow_pyramiding)
+                .with_reversals(true),
+        );
         let (dummy_ws_tx, _) = tokio::sync::broadcast::channel(1);
-        
-        // Create a new portfolio with initial capital
-        let initial_capital = dec!(10_000.0);
-        let _portfolio = std::sync::Arc::new(tokio::sync::Mutex::new(execution::Portfolio::new(initial_capital)));
-        
-        let executor = Box::new(SimulatedExecutor::new(
-            dummy_settings,
-            dummy_ws_tx
-        ));
-
-        // Instantiate the correct strategy based on strategy_name and param type
-        let strategy: Box<dyn strategies::Strategy + Send> = match strategy_name {
-            "ma_crossover" => {
-                let settings = param.downcast_ref::<MACrossoverSettings>().ok_or_else(|| anyhow::anyhow!("Failed to downcast to MACrossoverSettings"))?;
-                Box::new(MACrossover::new(settings.clone()))
-            },
-            "supertrend" => {
-                let settings = param.downcast_ref::<SuperTrendSettings>().ok_or_else(|| anyhow::anyhow!("Failed to downcast to SuperTrendSettings"))?;
-                Box::new(strategies::supertrend::SuperTrend::new(settings.clone()))
-            },
-            "prob_reversion" => {
-                let settings = param.downcast_ref::<ProbReversionSettings>().ok_or_else(|| anyhow::anyhow!("Failed to downcast to ProbReversionSettings"))?;
-                Box::new(strategies::prob_reversion::ProbReversion::new(settings.clone()))
-            },
-            _ => anyhow::bail!("Unknown strategy '{}' in optimizer config", strategy_name),
+        let config = BacktestConfig {
+            fees,
+            max_drawdown_abort_percent: job_settings.max_drawdown_abort_percent,
+            analytics: main_settings.analytics,
+            ..BacktestConfig::default()
         };
+        let executor = Box::new(config.simulated_executor(dummy_ws_tx));
+        let snapshot = serde_json::to_value(RunConfigSnapshot::new(&config, risk_settings))?;
+
+        let strategy = param.build();
 
         let parse_date = |s: &str, is_start: bool| {
-        if let Ok(dt) = chrono::DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
-            .map(|dt| dt.with_timezone(&Utc)) {
-                Ok(dt)
+        if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
+                Ok(Utc.from_utc_datetime(&naive))
             } else if let Ok(date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
                 let time = if is_start { chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap() } else { chrono::NaiveTime::from_hms_opt(23, 59, 59).unwrap() };
                 Ok(Utc.from_utc_datetime(&date.and_time(time)))
@@ -258,7 +289,8 @@ fn run_single_backtest_and_save(
         };
         let start_dt = parse_date(&job_settings.start_date, true)?;
         let end_dt = parse_date(&job_settings.end_date, false)?;
-        let klines = db.get_klines_by_date_range(&symbol, &interval, start_dt, end_dt).await?;
+        let (klines, gap_summary) = db.get_klines_checked(&symbol, &interval, start_dt, end_dt).await?;
+        crate::warn_on_kline_gaps(&symbol, &interval, &gap_summary);
         
         // Check if we have enough data for meaningful backtesting
         if klines.len() < 100 {
@@ -269,7 +301,7 @@ fn run_single_backtest_and_save(
                 "Insufficient data for backtesting. Need at least 100 klines, got {}",
                 klines.len()
             );
-            return Ok(()); // Skip this parameter set
+            return Ok(false); // Skip this parameter set
         }
         
         tracing::info!(
@@ -279,35 +311,16 @@ fn run_single_backtest_and_save(
             "Loaded klines for backtesting"
         );
         
-        let mut backtester = Backtester::new(symbol.clone(), interval.clone(), strategy, risk_manager, executor);
-        if let Ok((report, trades, equity_curve)) = backtester.run(klines).await {
-            // Save the parameters as JSON (downcast to correct type)
-            match strategy_name {
-                "ma_crossover" => {
-                    let settings = param.downcast_ref::<MACrossoverSettings>().unwrap();
-                    let run_id = db.save_backtest_report(Some(job_id), strategy_name, &symbol, &interval, start_dt, end_dt, settings, &report).await?;
-                    db.save_trades(run_id, &trades).await?;
-                    db.save_equity_curve(run_id, &equity_curve).await?;
-                    tracing::info!(run_id, "Saved results.");
-                },
-                "supertrend" => {
-                    let settings = param.downcast_ref::<SuperTrendSettings>().unwrap();
-                    let run_id = db.save_backtest_report(Some(job_id), strategy_name, &symbol, &interval, start_dt, end_dt, settings, &report).await?;
-                    db.save_trades(run_id, &trades).await?;
-                    db.save_equity_curve(run_id, &equity_curve).await?;
-                    tracing::info!(run_id, "Saved results.");
-                },
-                "prob_reversion" => {
-                    let settings = param.downcast_ref::<ProbReversionSettings>().unwrap();
-                    let run_id = db.save_backtest_report(Some(job_id), strategy_name, &symbol, &interval, start_dt, end_dt, settings, &report).await?;
-                    db.save_trades(run_id, &trades).await?;
-                    db.save_equity_curve(run_id, &equity_curve).await?;
-                    tracing::info!(run_id, "Saved results.");
-                },
-                _ => {}
-            }
+        let mut backtester = Backtester::new(symbol.clone(), interval.clone(), strategy, risk_manager, executor, config)
+            .with_cancellation(cancel.clone());
+        let mut aborted = false;
+        if let Ok((report, trades, equity_curve)) = backtester.run(klines, None).await {
+            aborted = report.aborted;
+            let meta = database::BacktestRunMeta { strategy_name: param.kind().key(), symbol: &symbol, interval: &interval, start_date: start_dt, end_date: end_dt, parameters: param, config: Some(&snapshot) };
+            let run_id = db.save_full_backtest(Some(job_id), &meta, &report, &trades, &equity_curve).await?;
+            tracing::info!(run_id, "Saved results.");
         }
-        Ok(())
+        Ok(aborted)
     })
 }
 
@@ -315,8 +328,10 @@ fn run_single_backtest_and_save(
 pub fn run_optimization(
     app_settings: &AppSettings,
     job_settings: &JobSettings,
-    param_sets: Vec<Box<dyn Any + Send + Sync>>,
+    param_sets: Vec<StrategyParams>,
     job_id: i64,
+    db: Db,
+    runtime: Handle,
 ) -> Result<i64> {
     tracing::info!(cores = app_settings.optimizer_cores, total_runs = param_sets.len(), "Configuring Rayon thread pool.");
     ThreadPoolBuilder::new()
@@ -324,23 +339,244 @@ pub fn run_optimization(
         .build_global()
         .context("Failed to build Rayon thread pool")?;
     let shared_settings = Arc::new(app_config::load_settings()?);
-    let strategy_name = job_settings.strategy_to_optimize.clone();
-    
+
+    // Cancelled through `POST /api/backtests/cancel/{jobId}`, which flags the job in the database.
+    let cancel = CancellationToken::new();
+    let cancel_watcher = runtime.spawn(watch_for_cancel(db.clone(), job_id, cancel.clone()));
+    let status = run_job(shared_settings, job_settings, &param_sets, job_id, &db, &runtime, &cancel);
+    cancel_watcher.abort();
+    runtime.block_on(db.finish_optimization_job(job_id, status))?;
+    Ok(job_id)
+}
+
+/// Runs every parameter set of job `job_id` on the Rayon pool and returns how the job ended.
+///
+/// Once `cancel` fires, the in-flight runs stop at their next bar and the remaining
+/// parameter sets are skipped.
+fn run_job(
+    shared_settings: Arc<app_config::Settings>,
+    job_settings: &JobSettings,
+    param_sets: &[StrategyParams],
+    job_id: i64,
+    db: &Db,
+    runtime: &Handle,
+    cancel: &CancellationToken,
+) -> OptimizationJobStatus {
     let total_runs = param_sets.len();
-    let _completed_runs = 0;
-    let completed_runs_mutex = Arc::new(std::sync::Mutex::new(0));
-    
+    let completed_runs_mutex = Arc::new(std::sync::Mutex::new(0usize));
+    let aborted_runs = AtomicUsize::new(0);
+    let skipped_runs = AtomicUsize::new(0);
+
     param_sets.par_iter().for_each_with(shared_settings, |settings, param| {
-        if let Err(e) = run_single_backtest_and_save(job_id, settings, job_settings, &strategy_name, param) {
-            tracing::error!(error = %e, "A single backtest run failed.");
+        if cancel.is_cancelled() {
+            skipped_runs.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        match run_single_backtest_and_save(job_id, settings, job_settings, param, db, runtime, cancel) {
+            Ok(true) => {
+                aborted_runs.fetch_add(1, Ordering::Relaxed);
+                tracing::info!("Run aborted early by the drawdown limit.");
+            }
+            Ok(false) => {}
+            Err(e) => tracing::error!(error = %e, "A single backtest run failed."),
         }
         
         // Update progress
         let mut completed = completed_runs_mutex.lock().unwrap();
         *completed += 1;
-        if *completed % 10 == 0 || *completed == total_runs {
+        if (*completed).is_multiple_of(10) || *completed == total_runs {
             tracing::info!("Progress: {}/{} runs completed ({:.1}%)", *completed, total_runs, (*completed as f64 / total_runs as f64) * 100.0);
         }
     });
-    Ok(job_id)
-}
\ No newline at end of file
+    if cancel.is_cancelled() {
+        tracing::warn!(
+            total_runs,
+            skipped_runs = skipped_runs.load(Ordering::Relaxed),
+            "Optimization job cancelled; the remaining runs were skipped."
+        );
+        return OptimizationJobStatus::Cancelled;
+    }
+    tracing::info!(
+        total_runs,
+        aborted_runs = aborted_runs.load(Ordering::Relaxed),
+        "All optimization runs finished."
+    );
+    OptimizationJobStatus::Completed
+}
+
+/// Cancels `cancel` once cancellation of the job has been requested in the database.
+async fn watch_for_cancel(db: Db, job_id: i64, cancel: CancellationToken) {
+    let mut interval = tokio::time::interval(CANCEL_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        match db.is_optimization_cancel_requested(job_id).await {
+            Ok(true) => {
+                tracing::warn!(job_id, "Cancellation requested, stopping the optimization job.");
+                cancel.cancel();
+                return;
+            }
+            Ok(false) => {}
+            Err(e) => tracing::error!(error = %e, "Failed to check the job for a cancellation request."),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{development_settings, seed_zig_zag_klines};
+
+    const JOB: &str = r#"
+        [job]
+        name = "test"
+        symbol = "BTCUSDT"
+        interval = "1h"
+        start_date = "2024-01-01T00:00:00"
+        end_date = "2024-02-01T00:00:00"
+        strategy_to_optimize = "ma_crossover"
+    "#;
+
+    #[test]
+    fn scoring_table_overrides_the_default_weights() {
+        let config: OptimizerConfig = toml::from_str(&format!(
+            "{}\n[scoring]\nmax_drawdown = -80.0\nminimum_trades = 10\n",
+            JOB
+        ))
+        .unwrap();
+
+        assert_eq!(config.scoring.max_drawdown, -80.0);
+        assert_eq!(config.scoring.minimum_trades, 10);
+        // Keys missing from the table keep their defaults.
+        assert_eq!(config.scoring.profit_factor, ScoringWeights::default().profit_factor);
+        assert!(!config.strategy_params.contains_key("scoring"));
+    }
+
+    #[test]
+    fn missing_scoring_table_uses_the_defaults() {
+        let config: OptimizerConfig = toml::from_str(JOB).unwrap();
+        let defaults = ScoringWeights::default();
+
+        assert_eq!(config.scoring.max_drawdown, defaults.max_drawdown);
+        assert_eq!(config.scoring.minimum_trades, defaults.minimum_trades);
+    }
+
+    /// `JOB` searching randomly with the given extra `[job]` keys, over MA crossover ranges.
+    fn random_search_config(job_keys: &str) -> OptimizerConfig {
+        toml::from_str(&format!(
+            r#"{}
+            search = "random"
+            {}
+
+            [ma_crossover_params]
+            m5_fast_period = {{ start = 5, end = 50, step = 5 }}
+            m5_slow_period = {{ start = 60, end = 120 }}
+            h1_fast_period = 50
+            h1_slow_period = {{ start = 100, end = 500, step = 100 }}
+            confidence = {{ start = 0.5, end = 0.9, step = 0.1 }}
+            "#,
+            JOB, job_keys
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn random_search_draws_num_samples_sets_within_the_ranges() {
+        let config = random_search_config("num_samples = 40\nseed = 7");
+
+        let sets = generate_generic_parameter_sets(&config).unwrap();
+
+        assert_eq!(sets.len(), 40);
+        for set in &sets {
+            let StrategyParams::MaCrossover(params) = set else {
+                panic!("expected MA crossover parameters, got {:?}", set);
+            };
+            assert!((5..=50).contains(&params.m5_fast_period), "{:?}", params);
+            assert!((60..=120).contains(&params.m5_slow_period), "{:?}", params);
+            assert_eq!(params.h1_fast_period, 50);
+            assert!((100..=500).contains(&params.h1_slow_period), "{:?}", params);
+            assert!((0.5..=0.9).contains(&params.confidence), "{:?}", params);
+        }
+        // Not every draw lands on the same values.
+        let first = format!("{:?}", sets[0]);
+        assert!(sets.iter().any(|set| format!("{:?}", set) != first));
+        // The seed makes the draw reproducible.
+        let again = generate_generic_parameter_sets(&config).unwrap();
+        assert_eq!(format!("{:?}", sets), format!("{:?}", again));
+    }
+
+    #[test]
+    fn random_search_requires_num_samples() {
+        let error = generate_generic_parameter_sets(&random_search_config("")).unwrap_err();
+
+        assert!(error.to_string().contains("num_samples"), "{}", error);
+    }
+
+    /// An MA crossover whose history (36 hourly klines) fits well within `seed_zig_zag_klines`.
+    fn ma_crossover(m5_fast_period: u32) -> StrategyParams {
+        let params = serde_json::json!({
+            "h1_fast_period": 2,
+            "h1_slow_period": 3,
+            "m5_fast_period": m5_fast_period,
+            "m5_slow_period": 10,
+            "confidence": 0.8,
+        });
+        StrategyParams::from_params(StrategyKind::MaCrossover, params).unwrap()
+    }
+
+    /// Runs `param_sets` as job `job_id` on a blocking thread, after one run that was already
+    /// in flight when the job was cancelled (if `in_flight` is set), and records the outcome.
+    async fn run_and_finish(
+        db: &Db,
+        job_id: i64,
+        in_flight: Option<StrategyParams>,
+        param_sets: Vec<StrategyParams>,
+        cancel: CancellationToken,
+    ) -> OptimizationJobStatus {
+        let job_settings = toml::from_str::<OptimizerConfig>(JOB).unwrap().job;
+        let (db, runtime) = (db.clone(), Handle::current());
+        tokio::task::spawn_blocking(move || {
+            let settings = Arc::new(development_settings());
+            if let Some(param) = in_flight {
+                run_single_backtest_and_save(job_id, &settings, &job_settings, &param, &db, &runtime, &cancel).unwrap();
+            }
+            let status = run_job(settings, &job_settings, &param_sets, job_id, &db, &runtime, &cancel);
+            runtime.block_on(db.finish_optimization_job(job_id, status)).unwrap();
+            status
+        })
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn a_cancelled_job_stops_and_is_stored_as_cancelled(pool: sqlx::PgPool) {
+        let db = Db::from(pool);
+        seed_zig_zag_klines(&db).await;
+        let job_id = db.create_optimization_job("cancelled").await.unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let status = run_and_finish(&db, job_id, Some(ma_crossover(4)), vec![ma_crossover(5), ma_crossover(6)], cancel).await;
+
+        assert_eq!(status, OptimizationJobStatus::Cancelled);
+        assert_eq!(db.get_optimization_job(job_id).await.unwrap().unwrap().status, "cancelled");
+        // Only the in-flight run is kept, stopped before its first bar; the rest were skipped.
+        let reports = db.get_reports_for_job(job_id).await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].report.cancelled);
+        assert_eq!(reports[0].report.total_trades, 0);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn an_uncancelled_job_runs_every_set_and_is_stored_as_completed(pool: sqlx::PgPool) {
+        let db = Db::from(pool);
+        seed_zig_zag_klines(&db).await;
+        let job_id = db.create_optimization_job("completed").await.unwrap();
+
+        let status = run_and_finish(&db, job_id, None, vec![ma_crossover(5), ma_crossover(6)], CancellationToken::new()).await;
+
+        assert_eq!(status, OptimizationJobStatus::Completed);
+        assert_eq!(db.get_optimization_job(job_id).await.unwrap().unwrap().status, "completed");
+        let reports = db.get_reports_for_job(job_id).await.unwrap();
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|r| !r.report.cancelled));
+    }
+}
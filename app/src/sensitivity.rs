@@ -0,0 +1,221 @@
+// In app/src/sensitivity.rs
+
+use analytics::types::AnalyticsConfig;
+use anyhow::Result;
+use backtester::types::{BacktestConfig, RunConfigSnapshot};
+use backtester::Backtester;
+use core_types::Symbol;
+use database::Db;
+use risk::simple_manager::SimpleRiskManager;
+use serde::Serialize;
+use strategies::registry::{StrategyKind, StrategyParams};
+
+/// The fee and slippage multipliers used when none are given on the command line.
+pub const DEFAULT_COST_MULTIPLIERS: [f64; 4] = [0.5, 1.0, 2.0, 3.0];
+
+/// The outcome of one backtest re-run under scaled cost assumptions.
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityCell {
+    /// The factor the configured maker and taker fees were multiplied by.
+    pub fee_multiplier: f64,
+    /// The factor the configured slippage was multiplied by.
+    pub slippage_multiplier: f64,
+    pub net_pnl_percentage: f64,
+    pub sharpe_ratio: f64,
+    pub total_trades: u32,
+}
+
+/// Re-runs a stored backtest once per (fee, slippage) multiplier pair, without saving the
+/// results, to show how much of its performance survives higher trading costs.
+///
+/// The run's strategy, parameters, symbol and period, as well as the risk settings, base
+/// fees and seed it was produced with, all come from the database, so the 1x/1x cell
+/// reproduces the stored run. Runs saved without a config snapshot are rejected.
+pub async fn run_cost_sensitivity(
+    db: &Db,
+    analytics: AnalyticsConfig,
+    run_id: i64,
+    fee_multipliers: &[f64],
+    slippage_multipliers: &[f64],
+) -> Result<Vec<SensitivityCell>> {
+    let export = db
+        .export_backtest(run_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Backtest run {} not found", run_id))?;
+    let run = export.run;
+    let kind: StrategyKind = run.strategy_name.parse()?;
+    let params = StrategyParams::from_params(kind, &run.parameters)?;
+    let Some(snapshot) = run.config else {
+        anyhow::bail!("Backtest run {} was saved without a config snapshot and cannot be re-run.", run_id);
+    };
+    let snapshot: RunConfigSnapshot = serde_json::from_value(snapshot)?;
+
+    let symbol = Symbol::new(&run.symbol)?;
+    let (klines, _) = db.get_klines_checked(&symbol, &run.interval, run.start_date, run.end_date).await?;
+
+    let mut cells = Vec::with_capacity(fee_multipliers.len() * slippage_multipliers.len());
+    for &fee_multiplier in fee_multipliers {
+        for &slippage_multiplier in slippage_multipliers {
+            let mut fees = snapshot.fees.clone();
+            fees.maker_fee *= fee_multiplier;
+            fees.taker_fee *= fee_multiplier;
+            fees.slippage_percent *= slippage_multiplier;
+            let config = BacktestConfig {
+                fees,
+                seed: snapshot.seed,
+                analytics,
+                ..BacktestConfig::default()
+            };
+            // Nobody listens to a re-run's executor events.
+            let (ws_tx, _) = tokio::sync::broadcast::channel(1);
+            let executor = Box::new(config.simulated_executor(ws_tx));
+
+            let mut backtester = Backtester::new(
+                symbol.clone(),
+                run.interval.clone(),
+                params.build(),
+                Box::new(
+                    SimpleRiskManager::new(snapshot.risk.clone())
+                        .with_pyramiding(config.fees.allow_pyramiding)
+                        .with_reversals(true),
+                ),
+                executor,
+                config,
+            );
+            let (report, _, _) = backtester.run(klines.clone(), None).await?;
+            cells.push(SensitivityCell {
+                fee_multiplier,
+                slippage_multiplier,
+                net_pnl_percentage: report.net_pnl_percentage,
+                sharpe_ratio: report.sharpe_ratio,
+                total_trades: report.total_trades,
+            });
+        }
+    }
+    Ok(cells)
+}
+
+/// Prints the net P&L and Sharpe ratio of every cell as two matrices, with one row per fee
+/// multiplier and one column per slippage multiplier.
+pub fn print_sensitivity(run_id: i64, cells: &[SensitivityCell], fee_multipliers: &[f64], slippage_multipliers: &[f64]) {
+    let print_matrix = |title: &str, value: fn(&SensitivityCell) -> f64| {
+        println!("\n{}", title);
+        print!("{:<14}", "fee \\ slip");
+        for slippage in slippage_multipliers {
+            print!("{:>10}", format!("{}x", slippage));
+        }
+        println!();
+        for (row, fee) in fee_multipliers.iter().enumerate() {
+            print!("{:<14}", format!("{}x", fee));
+            let row_cells = &cells[row * slippage_multipliers.len()..(row + 1) * slippage_multipliers.len()];
+            for cell in row_cells {
+                print!("{:>10.2}", value(cell));
+            }
+            println!();
+        }
+    };
+
+    println!("\n--- Cost Sensitivity of Backtest Run #{} ---", run_id);
+    print_matrix("Net P&L (%)", |cell| cell.net_pnl_percentage);
+    print_matrix("Sharpe Ratio", |cell| cell.sharpe_ratio);
+    println!("-----------------------------------");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::seed_zig_zag_klines;
+    use analytics::types::PerformanceReport;
+    use chrono::{TimeZone, Utc};
+    use core_types::SimulationSettings;
+    use database::BacktestRunMeta;
+    use risk::types::SimpleRiskSettings;
+    use sqlx::PgPool;
+
+    const MULTIPLIERS: [f64; 3] = [0.5, 1.0, 2.0];
+
+    /// Stores the zig-zag market and a run of an MA crossover over it, with `config` as its
+    /// snapshot. Returns the run's ID.
+    async fn seed_run(db: &Db, config: Option<&serde_json::Value>) -> i64 {
+        seed_zig_zag_klines(db).await;
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let parameters = serde_json::json!({
+            "h1_fast_period": 2,
+            "h1_slow_period": 3,
+            "m5_fast_period": 5,
+            "m5_slow_period": 10,
+            "confidence": 0.8,
+        });
+        let meta = BacktestRunMeta {
+            strategy_name: StrategyKind::MaCrossover.key(),
+            symbol: &symbol,
+            interval: "1h",
+            start_date: start,
+            end_date: start + chrono::Duration::hours(150),
+            parameters: &parameters,
+            config,
+        };
+        db.save_full_backtest(None, &meta, &PerformanceReport::new(), &[], &[]).await.unwrap()
+    }
+
+    fn snapshot() -> RunConfigSnapshot {
+        RunConfigSnapshot {
+            fees: SimulationSettings {
+                maker_fee: 0.0002,
+                taker_fee: 0.0004,
+                slippage_percent: 0.0005,
+                ..SimulationSettings::default()
+            },
+            risk: SimpleRiskSettings {
+                risk_per_trade_percent: 0.01,
+                stop_loss_percent: 0.05,
+                minimum_confidence_threshold: 0.5,
+                leverage: 5,
+                cooldown_bars_after_loss: 0,
+                min_position_notional: None,
+                max_position_notional: None,
+                correlation_groups: Vec::new(),
+                max_group_exposure: None,
+                volatility_leverage: None,
+                symbol_overrides: Default::default(),
+            },
+            seed: Some(7),
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn costs_rise_monotonically_across_the_grid(pool: PgPool) {
+        let db = Db::from(pool);
+        let run_id = seed_run(&db, Some(&serde_json::to_value(snapshot()).unwrap())).await;
+
+        let cells = run_cost_sensitivity(&db, AnalyticsConfig::default(), run_id, &MULTIPLIERS, &MULTIPLIERS)
+            .await
+            .unwrap();
+
+        assert_eq!(cells.len(), MULTIPLIERS.len() * MULTIPLIERS.len());
+        // The same trades are taken in every cell; only what they cost changes.
+        assert!(cells[0].total_trades > 0);
+        assert!(cells.iter().all(|cell| cell.total_trades == cells[0].total_trades), "{:?}", cells);
+        let pnl = |fee: usize, slippage: usize| cells[fee * MULTIPLIERS.len() + slippage].net_pnl_percentage;
+        for i in 0..MULTIPLIERS.len() {
+            for j in 1..MULTIPLIERS.len() {
+                assert!(pnl(j, i) < pnl(j - 1, i), "higher fees must cost more: {:?}", cells);
+                assert!(pnl(i, j) < pnl(i, j - 1), "higher slippage must cost more: {:?}", cells);
+            }
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn runs_without_a_config_snapshot_are_rejected(pool: PgPool) {
+        let db = Db::from(pool);
+        let run_id = seed_run(&db, None).await;
+
+        let error = run_cost_sensitivity(&db, AnalyticsConfig::default(), run_id, &MULTIPLIERS, &MULTIPLIERS)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("config snapshot"), "{}", error);
+    }
+}
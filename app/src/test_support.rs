@@ -0,0 +1,47 @@
+// In app/src/test_support.rs
+
+//! Settings and market data shared by the app's tests.
+
+use chrono::{TimeZone, Utc};
+use core_types::{Kline, Symbol};
+use database::Db;
+use rust_decimal::Decimal;
+
+const HOUR_MS: i64 = 3_600_000;
+
+/// The settings `load_settings` reads for the development environment.
+pub fn development_settings() -> app_config::Settings {
+    config::Config::builder()
+        .add_source(config::File::from_str(include_str!("../../config/base.toml"), config::FileFormat::Toml))
+        .add_source(config::File::from_str(include_str!("../../config/development.toml"), config::FileFormat::Toml))
+        .build()
+        .unwrap()
+        .try_deserialize()
+        .unwrap()
+}
+
+/// The close of bar `i` of a market zig-zagging between 100 and 110 every 10 bars.
+pub fn zig_zag_close(i: i64) -> i64 {
+    100 + (i % 20 - 10).abs()
+}
+
+/// Stores 150 hourly BTCUSDT klines of the zig-zag market, starting at 2024-01-01.
+pub async fn seed_zig_zag_klines(db: &Db) {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap().timestamp_millis();
+    let klines: Vec<Kline> = (0..150i64)
+        .map(|i| {
+            let close = Decimal::from(zig_zag_close(i));
+            let open_time = start + i * HOUR_MS;
+            Kline {
+                open_time,
+                open: close,
+                high: close + Decimal::ONE,
+                low: close - Decimal::ONE,
+                close,
+                volume: Decimal::ONE_HUNDRED,
+                close_time: open_time + HOUR_MS - 1,
+            }
+        })
+        .collect();
+    db.insert_klines(&Symbol::new("BTCUSDT").unwrap(), "1h", &klines).await.unwrap();
+}
@@ -3,18 +3,20 @@
 use chrono::Utc;
 use tokio::sync::broadcast;
 use tracing::{Event, Subscriber};
+use tracing::level_filters::LevelFilter;
 use tracing_subscriber::Layer;
-use events::{WsLogMessage, WsMessage};
-type WsCache = std::sync::Arc<std::sync::Mutex<std::collections::VecDeque<events::WsMessage>>>;
+use events::{ReplayCache, WsLogMessage, WsMessage};
 
 pub struct WsBroadcastLayer {
     tx: broadcast::Sender<WsMessage>,
-    cache: WsCache, // <-- Add this
+    cache: ReplayCache,
+    /// Events more verbose than this are not broadcast.
+    min_level: LevelFilter,
 }
 
 impl WsBroadcastLayer {
-    pub fn new(tx: broadcast::Sender<WsMessage>, cache: WsCache) -> Self { // <-- Update signature
-        Self { tx, cache }
+    pub fn new(tx: broadcast::Sender<WsMessage>, cache: ReplayCache, min_level: LevelFilter) -> Self {
+        Self { tx, cache, min_level }
     }
 }
 
@@ -23,39 +25,156 @@ where
     S: Subscriber,
 {
     fn on_event(&self, event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        if *event.metadata().level() > self.min_level {
+            return;
+        }
         // Create a visitor to extract the message from the event's fields.
         let mut visitor = LogMessageVisitor::new();
         event.record(&mut visitor);
         let log_message = WsLogMessage {
             timestamp: Utc::now(),
             level: event.metadata().level().to_string(),
-            message: visitor.message,
+            message: visitor.finish(),
         };
         let msg = WsMessage::Log(log_message);
         // Send to live clients
         let _ = self.tx.send(msg.clone());
         // Also add to the replay cache
-        let mut cache = self.cache.lock().unwrap();
-        if cache.len() >= 200 { // WS_CACHE_SIZE
-            cache.pop_front();
-        }
-        cache.push_back(msg);
+        self.cache.push(msg);
     }
 }
 
-// A simple visitor to capture the `message` field of a log event.
+/// Assembles an event's fields into a readable line: the `message` field first, followed
+/// by every other field as `key=value`.
 struct LogMessageVisitor {
     message: String,
+    fields: Vec<String>,
 }
 
 impl LogMessageVisitor {
     fn new() -> Self {
-        Self { message: String::new() }
+        Self { message: String::new(), fields: Vec::new() }
+    }
+
+    fn record_field(&mut self, field: &tracing::field::Field, value: String) {
+        if field.name() == "message" {
+            self.message = value;
+        } else {
+            self.fields.push(format!("{}={}", field.name(), value));
+        }
+    }
+
+    /// The final broadcast message.
+    fn finish(self) -> String {
+        match (self.message.is_empty(), self.fields.is_empty()) {
+            (_, true) => self.message,
+            (true, false) => self.fields.join(" "),
+            (false, false) => format!("{} {}", self.message, self.fields.join(" ")),
+        }
     }
 }
 
 impl tracing::field::Visit for LogMessageVisitor {
-    fn record_debug(&mut self, _field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
-        self.message = format!("{:?}", value);
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.record_field(field, format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.record_field(field, value.to_string());
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.record_field(field, value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.record_field(field, value.to_string());
+    }
+
+    fn record_i128(&mut self, field: &tracing::field::Field, value: i128) {
+        self.record_field(field, value.to_string());
+    }
+
+    fn record_u128(&mut self, field: &tracing::field::Field, value: u128) {
+        self.record_field(field, value.to_string());
+    }
+
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        self.record_field(field, value.to_string());
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.record_field(field, value.to_string());
+    }
+
+    fn record_error(&mut self, field: &tracing::field::Field, value: &(dyn std::error::Error + 'static)) {
+        self.record_field(field, value.to_string());
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn events_below_the_minimum_level_are_not_broadcast() {
+        let (tx, mut rx) = broadcast::channel(16);
+        let cache = ReplayCache::new(16);
+        let subscriber = tracing_subscriber::registry()
+            .with(WsBroadcastLayer::new(tx, cache.clone(), LevelFilter::INFO));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("too verbose");
+            tracing::info!(symbol = "BTCUSDT", "order placed");
+            tracing::trace!("far too verbose");
+            tracing::warn!("attention");
+        });
+
+        let mut broadcast = Vec::new();
+        while let Ok(WsMessage::Log(log)) = rx.try_recv() {
+            broadcast.push((log.level, log.message));
+        }
+        assert_eq!(
+            broadcast,
+            vec![
+                ("INFO".to_string(), "order placed symbol=BTCUSDT".to_string()),
+                ("WARN".to_string(), "attention".to_string()),
+            ]
+        );
+        assert_eq!(cache.snapshot().len(), 2);
+    }
+
+    /// Emits events through a layer broadcasting at every level and returns the messages.
+    fn broadcast_messages(emit: impl FnOnce()) -> Vec<String> {
+        let (tx, mut rx) = broadcast::channel(16);
+        let subscriber = tracing_subscriber::registry()
+            .with(WsBroadcastLayer::new(tx, ReplayCache::new(16), LevelFilter::TRACE));
+        tracing::subscriber::with_default(subscriber, emit);
+
+        let mut messages = Vec::new();
+        while let Ok(WsMessage::Log(log)) = rx.try_recv() {
+            messages.push(log.message);
+        }
+        messages
+    }
+
+    #[test]
+    fn string_and_integer_fields_follow_the_message() {
+        let messages = broadcast_messages(|| {
+            let symbol = "ETHUSDT";
+            tracing::info!(symbol, retries = 3_i64, "reconnecting");
+        });
+
+        assert_eq!(messages, vec!["reconnecting symbol=ETHUSDT retries=3".to_string()]);
+    }
+
+    #[test]
+    fn events_without_a_message_still_carry_their_fields() {
+        let messages = broadcast_messages(|| {
+            tracing::warn!(filled = true, fee = 0.5, count = 7_u64);
+        });
+
+        assert_eq!(messages, vec!["filled=true fee=0.5 count=7".to_string()]);
+    }
+}
@@ -0,0 +1,65 @@
+// In crates/analytics/benches/calculate.rs
+
+use analytics::engine::AnalyticsEngine;
+use analytics::types::{EntryReason, EquityPoint, ExitReason, Trade};
+use chrono::{Duration, TimeZone, Utc};
+use core_types::{Side, Symbol};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Builds `count` synthetic trades with a deterministic mix of winners and losers, and the
+/// matching equity curve (one point per trade close).
+fn synthetic_run(count: usize) -> (Vec<Trade>, Vec<EquityPoint>) {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut equity = dec!(10_000);
+    let mut trades = Vec::with_capacity(count);
+    let mut equity_curve = Vec::with_capacity(count);
+    for i in 0..count {
+        let entry_time = start + Duration::minutes(i as i64 * 15);
+        let exit_time = entry_time + Duration::minutes(10);
+        // A repeating pattern of three winners and two losers of varying size.
+        let pnl = match i % 5 {
+            0 => dec!(12.5),
+            1 => dec!(-8.25),
+            2 => dec!(4.0),
+            3 => dec!(-15.75),
+            _ => dec!(21.0),
+        } * Decimal::from(1 + i % 3);
+        equity += pnl;
+        trades.push(Trade {
+            symbol: Symbol::new("BTCUSDT").unwrap(),
+            side: if i % 2 == 0 { Side::Long } else { Side::Short },
+            entry_time,
+            exit_time,
+            entry_price: dec!(50_000),
+            exit_price: dec!(50_100),
+            quantity: dec!(0.01),
+            pnl,
+            fees: dec!(0.4),
+            signal_confidence: (i % 50) as f64 / 100.0 + 0.5,
+            leverage: 10,
+            max_adverse_excursion: dec!(60),
+            max_favorable_excursion: dec!(140),
+            entry_reason: EntryReason::LongSignal,
+            exit_reason: ExitReason::Signal,
+        });
+        equity_curve.push(EquityPoint { timestamp: exit_time, value: equity });
+    }
+    (trades, equity_curve)
+}
+
+fn bench_calculate(c: &mut Criterion) {
+    let engine = AnalyticsEngine::new();
+    let mut group = c.benchmark_group("AnalyticsEngine::calculate");
+    for count in [1_000, 10_000, 100_000] {
+        let (trades, equity_curve) = synthetic_run(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| engine.calculate(black_box(dec!(10_000)), black_box(&trades), black_box(&equity_curve)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_calculate);
+criterion_main!(benches);
@@ -0,0 +1,151 @@
+// In crates/analytics/src/attribution.rs
+
+use crate::types::EquityPoint;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// The part of a closed trade that attribution looks at.
+#[derive(Debug, Clone)]
+pub struct TradeOutcome {
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    pub pnl: Decimal,
+}
+
+/// One trade's contribution to the equity curve.
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeAttribution {
+    /// The trade's position among all trades of the run, ordered by entry time.
+    pub trade_index: usize,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    pub pnl: Decimal,
+    /// The equity at the last curve point at or before the entry.
+    pub equity_at_entry: Decimal,
+    /// `pnl` as a percentage of `equity_at_entry`.
+    pub impact_percent: f64,
+    /// The summed P&L of all trades that closed up to and including this one.
+    pub cumulative_pnl: Decimal,
+}
+
+/// The trades that moved the equity curve the most in either direction.
+#[derive(Debug, Clone, Serialize)]
+pub struct AttributionReport {
+    pub total_trades: usize,
+    /// Winning trades, largest P&L first.
+    pub contributors: Vec<TradeAttribution>,
+    /// Losing trades, largest loss first.
+    pub detractors: Vec<TradeAttribution>,
+}
+
+/// Attributes the equity swings of a run to its trades and keeps the `top_n` largest
+/// contributors and detractors.
+///
+/// Ties in P&L are broken by the earlier entry time, so the ranking does not depend on the
+/// order the trades are passed in. Trades with zero P&L are in neither list.
+pub fn attribute_trades(trades: &[TradeOutcome], equity_curve: &[EquityPoint], top_n: usize) -> AttributionReport {
+    let mut by_entry: Vec<&TradeOutcome> = trades.iter().collect();
+    by_entry.sort_by_key(|t| (t.entry_time, t.exit_time));
+
+    // Running P&L in the order the trades were closed.
+    let mut by_exit: Vec<usize> = (0..by_entry.len()).collect();
+    by_exit.sort_by_key(|&i| (by_entry[i].exit_time, i));
+    let mut cumulative = vec![Decimal::ZERO; by_entry.len()];
+    let mut running = Decimal::ZERO;
+    for i in by_exit {
+        running += by_entry[i].pnl;
+        cumulative[i] = running;
+    }
+
+    let attributions: Vec<TradeAttribution> = by_entry
+        .iter()
+        .enumerate()
+        .map(|(index, trade)| {
+            let equity_at_entry = equity_at(equity_curve, trade.entry_time);
+            let impact_percent = if equity_at_entry.is_zero() {
+                0.0
+            } else {
+                (trade.pnl / equity_at_entry * Decimal::from(100)).to_f64().unwrap_or(0.0)
+            };
+            TradeAttribution {
+                trade_index: index,
+                entry_time: trade.entry_time,
+                exit_time: trade.exit_time,
+                pnl: trade.pnl,
+                equity_at_entry,
+                impact_percent,
+                cumulative_pnl: cumulative[index],
+            }
+        })
+        .collect();
+
+    let ranked = |keep: fn(&Decimal) -> bool, by_pnl: fn(&Decimal, &Decimal) -> Ordering| {
+        let mut ranked: Vec<TradeAttribution> = attributions.iter().filter(|a| keep(&a.pnl)).cloned().collect();
+        // `attributions` is in entry order and the sort is stable, which breaks ties by entry time.
+        ranked.sort_by(|a, b| by_pnl(&a.pnl, &b.pnl));
+        ranked.truncate(top_n);
+        ranked
+    };
+
+    AttributionReport {
+        total_trades: trades.len(),
+        contributors: ranked(|pnl| *pnl > Decimal::ZERO, |a, b| b.cmp(a)),
+        detractors: ranked(|pnl| *pnl < Decimal::ZERO, |a, b| a.cmp(b)),
+    }
+}
+
+/// Returns the equity at the last point at or before `time`, falling back to the first point
+/// for trades entered before the curve starts.
+fn equity_at(equity_curve: &[EquityPoint], time: DateTime<Utc>) -> Decimal {
+    let index = equity_curve.partition_point(|p| p.timestamp <= time);
+    equity_curve
+        .get(index.saturating_sub(1))
+        .map(|p| p.value)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn at(hour: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(hour * 3_600, 0).unwrap()
+    }
+
+    /// A trade entered at `entry_hour` and closed an hour later.
+    fn trade(entry_hour: i64, pnl: Decimal) -> TradeOutcome {
+        TradeOutcome { entry_time: at(entry_hour), exit_time: at(entry_hour + 1), pnl }
+    }
+
+    #[test]
+    fn trades_are_ranked_by_their_pnl() {
+        // Passed out of entry order; the 50s tie and is decided by the earlier entry.
+        let trades = vec![
+            trade(6, dec!(50)),
+            trade(0, dec!(-20)),
+            trade(2, dec!(200)),
+            trade(4, dec!(50)),
+            trade(8, dec!(-80)),
+        ];
+        let equity_curve: Vec<EquityPoint> = [(0, dec!(1_000)), (2, dec!(980)), (4, dec!(1_180))]
+            .into_iter()
+            .map(|(hour, value)| EquityPoint { timestamp: at(hour), value })
+            .collect();
+
+        let report = attribute_trades(&trades, &equity_curve, 2);
+
+        assert_eq!(report.total_trades, 5);
+        let contributors: Vec<_> = report.contributors.iter().map(|a| (a.trade_index, a.pnl)).collect();
+        assert_eq!(contributors, vec![(1, dec!(200)), (2, dec!(50))]);
+        let detractors: Vec<_> = report.detractors.iter().map(|a| (a.trade_index, a.pnl)).collect();
+        assert_eq!(detractors, vec![(4, dec!(-80)), (0, dec!(-20))]);
+
+        let largest = &report.contributors[0];
+        assert_eq!(largest.equity_at_entry, dec!(980));
+        assert_eq!(largest.cumulative_pnl, dec!(180));
+        assert!((largest.impact_percent - 200.0 / 9.8).abs() < 1e-9);
+    }
+}
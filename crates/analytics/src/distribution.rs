@@ -0,0 +1,85 @@
+// In crates/analytics/src/distribution.rs
+
+use serde::Serialize;
+
+/// One bucket of a histogram, covering `[lower, upper)` (the last bucket also includes `upper`).
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: u32,
+}
+
+/// Splits the range of `values` into `bucket_count` equal-width buckets and counts the values
+/// falling into each one.
+///
+/// Returns no buckets for empty input or `bucket_count == 0`. If all values are equal, a single
+/// bucket holds all of them.
+pub fn histogram(values: &[f64], bucket_count: usize) -> Vec<HistogramBucket> {
+    let finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if finite.is_empty() || bucket_count == 0 {
+        return Vec::new();
+    }
+
+    let min = finite.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = finite.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min == max {
+        return vec![HistogramBucket { lower: min, upper: max, count: finite.len() as u32 }];
+    }
+
+    let width = (max - min) / bucket_count as f64;
+    let mut buckets: Vec<HistogramBucket> = (0..bucket_count)
+        .map(|i| HistogramBucket {
+            lower: min + width * i as f64,
+            upper: if i + 1 == bucket_count { max } else { min + width * (i + 1) as f64 },
+            count: 0,
+        })
+        .collect();
+
+    for value in finite {
+        let index = (((value - min) / width) as usize).min(bucket_count - 1);
+        buckets[index].count += 1;
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The P&L of a known set of trades.
+    const TRADE_PNLS: [f64; 12] = [-40.0, -25.5, -10.0, -2.0, 0.0, 3.5, 8.0, 12.0, 15.0, 22.0, 35.0, 60.0];
+
+    #[test]
+    fn bucket_counts_sum_to_the_trade_count() {
+        for bucket_count in [1, 4, 5, 20] {
+            let buckets = histogram(&TRADE_PNLS, bucket_count);
+            assert_eq!(buckets.len(), bucket_count);
+            assert_eq!(buckets.iter().map(|b| b.count).sum::<u32>(), TRADE_PNLS.len() as u32);
+        }
+    }
+
+    #[test]
+    fn buckets_cover_the_range_in_equal_widths() {
+        let buckets = histogram(&TRADE_PNLS, 4);
+
+        // The range -40..60 splits into four buckets of 25.
+        let bounds: Vec<_> = buckets.iter().map(|b| (b.lower, b.upper)).collect();
+        assert_eq!(bounds, vec![(-40.0, -15.0), (-15.0, 10.0), (10.0, 35.0), (35.0, 60.0)]);
+        // The maximum falls into the last bucket rather than past it.
+        let counts: Vec<_> = buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 5, 3, 2]);
+    }
+
+    #[test]
+    fn empty_and_degenerate_inputs() {
+        assert!(histogram(&[], 10).is_empty());
+        assert!(histogram(&TRADE_PNLS, 0).is_empty());
+        let flat = histogram(&[5.0, 5.0, 5.0], 10);
+        assert_eq!(flat.len(), 1);
+        assert_eq!(flat[0].count, 3);
+        // Non-finite values are ignored.
+        let with_nan = histogram(&[1.0, f64::NAN, 3.0, f64::INFINITY], 2);
+        assert_eq!(with_nan.iter().map(|b| b.count).sum::<u32>(), 2);
+    }
+}
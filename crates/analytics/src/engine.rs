@@ -1,17 +1,50 @@
-use crate::types::{EquityPoint, PerformanceReport, Trade};
+use crate::types::{
+    AnalyticsConfig, EquityPoint, ExitReason, MonteCarloReport, PerformanceReport, Percentiles, RollingMetric, RollingPoint, Trade,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use rust_decimal::prelude::*;
 
+/// The seed for Monte Carlo resampling when the caller gives none, so repeated requests
+/// return the same result.
+pub const DEFAULT_MONTE_CARLO_SEED: u64 = 42;
+
+/// The per-period return separating gains from losses in the Omega ratio.
+const OMEGA_THRESHOLD_RETURN: f64 = 0.0;
+
+/// The number of seconds in a year, used to convert annual rates to per-period ones.
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
 /// The engine responsible for calculating performance metrics from trade data.
 #[derive(Default)]
-pub struct AnalyticsEngine;
+pub struct AnalyticsEngine {
+    config: AnalyticsConfig,
+}
 
 impl AnalyticsEngine {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Creates an engine that calculates its metrics with the given `config`.
+    pub fn with_config(config: AnalyticsConfig) -> Self {
+        Self { config }
+    }
+
+    /// Converts the annual risk-free rate to the rate of one period of `equity_curve`,
+    /// assuming its points are evenly spaced.
+    fn per_period_risk_free_rate(&self, equity_curve: &[EquityPoint]) -> f64 {
+        if self.config.risk_free_rate == 0.0 || equity_curve.len() < 2 {
+            return 0.0;
+        }
+        let span = equity_curve[equity_curve.len() - 1].timestamp - equity_curve[0].timestamp;
+        let period_secs = span.num_seconds() as f64
+            / (equity_curve.len() - 1) as f64;
+        (1.0 + self.config.risk_free_rate).powf(period_secs / SECONDS_PER_YEAR) - 1.0
+    }
+
     /// Calculates a full performance report from a set of trades and an equity curve.
     pub fn calculate(
         &self,
@@ -24,13 +57,62 @@ impl AnalyticsEngine {
             return report; // Return a default report if there are no trades.
         }
 
+        // --- Trade Pass ---
+        // Every trade-based metric is accumulated in a single pass over the trades.
+        let mut net_pnl = dec!(0);
+        let mut winning_count = 0usize;
+        let mut gross_profit = dec!(0);
+        let mut gross_loss = dec!(0);
+        let mut total_duration_secs: i64 = 0;
+        let mut leverage_sum = 0.0;
+        let mut margin_sum = dec!(0);
+        let mut confidence_map: std::collections::HashMap<&'static str, (u32, Decimal, usize)> =
+            std::collections::HashMap::new();
+        let mut exit_reason_map: std::collections::HashMap<ExitReason, (u32, Decimal, usize)> =
+            std::collections::HashMap::new();
+        for trade in trades {
+            net_pnl += trade.pnl;
+            if trade.pnl > dec!(0) {
+                winning_count += 1;
+                gross_profit += trade.pnl;
+            } else if trade.pnl < dec!(0) {
+                gross_loss += trade.pnl;
+            }
+            total_duration_secs += (trade.exit_time - trade.entry_time).num_seconds();
+            leverage_sum += trade.leverage as f64;
+            margin_sum += (trade.entry_price * trade.quantity) / Decimal::from(trade.leverage);
+
+            let bucket = match (trade.signal_confidence * 100.0) as u32 {
+                0..=59 => "0-59%",
+                60..=69 => "60-69%",
+                70..=79 => "70-79%",
+                80..=89 => "80-89%",
+                90..=100 => "90-100%",
+                _ => "Other",
+            };
+            let (count, bucket_pnl, wins) = confidence_map.entry(bucket).or_insert((0, dec!(0), 0));
+            *count += 1;
+            *bucket_pnl += trade.pnl;
+            if trade.pnl > dec!(0) {
+                *wins += 1;
+            }
+
+            let (count, reason_pnl, wins) = exit_reason_map.entry(trade.exit_reason).or_insert((0, dec!(0), 0));
+            *count += 1;
+            *reason_pnl += trade.pnl;
+            if trade.pnl > dec!(0) {
+                *wins += 1;
+            }
+        }
+        let gross_loss = gross_loss.abs();
+
         // --- Tier 1 Calculations ---
 
         // 1. Total Trades
         report.total_trades = trades.len() as u32;
 
         // 2. Net P&L (Absolute & Percentage)
-        report.net_pnl_absolute = trades.iter().map(|t| t.pnl).sum();
+        report.net_pnl_absolute = net_pnl;
         if initial_capital > dec!(0) {
             report.net_pnl_percentage = (report.net_pnl_absolute / initial_capital)
                 .to_f64()
@@ -38,77 +120,125 @@ impl AnalyticsEngine {
         }
 
         // 3. Win Rate & Profit Factor
-        let winning_trades: Vec<&Trade> = trades.iter().filter(|t| t.pnl > dec!(0)).collect();
-        let losing_trades: Vec<&Trade> = trades.iter().filter(|t| t.pnl < dec!(0)).collect();
-        report.win_rate = (winning_trades.len() as f64 / report.total_trades as f64) * 100.0;
-
-        let gross_profit: Decimal = winning_trades.iter().map(|t| t.pnl).sum();
-        let gross_loss: Decimal = losing_trades.iter().map(|t| t.pnl).sum::<Decimal>().abs();
+        report.win_rate = (winning_count as f64 / report.total_trades as f64) * 100.0;
         report.profit_factor = if gross_loss > dec!(0) {
             (gross_profit / gross_loss).to_f64().unwrap_or(0.0)
         } else {
             f64::INFINITY // Pure profit
         };
 
-        // 4. Max Drawdown (Absolute & Percentage)
+        // 4. Max Drawdown (Absolute & Percentage) and 13. Drawdown Duration, in one pass
         let mut peak_equity = initial_capital;
         let mut max_drawdown = dec!(0);
+        let mut in_drawdown = false;
+        let mut drawdown_start_time = None;
+        let mut max_drawdown_duration = chrono::Duration::zero();
         for point in equity_curve {
-            peak_equity = peak_equity.max(point.value);
-            let drawdown = peak_equity - point.value;
-            max_drawdown = max_drawdown.max(drawdown);
+            if point.value >= peak_equity {
+                // We've reached a new peak or recovered from a drawdown
+                if in_drawdown {
+                    let duration = point.timestamp - drawdown_start_time.unwrap();
+                    if duration > max_drawdown_duration {
+                        max_drawdown_duration = duration;
+                    }
+                    in_drawdown = false;
+                }
+                peak_equity = point.value;
+            } else if !in_drawdown {
+                // We are now in a drawdown
+                in_drawdown = true;
+                drawdown_start_time = Some(point.timestamp);
+            }
+            max_drawdown = max_drawdown.max(peak_equity - point.value);
         }
         report.max_drawdown_absolute = max_drawdown;
         if peak_equity > dec!(0) {
             report.max_drawdown_percentage = (max_drawdown / peak_equity).to_f64().unwrap_or(0.0) * 100.0;
         }
 
-        // 5. Sharpe Ratio (Simplified)
+        // 5. Sharpe Ratio (Simplified), 6. Sortino Ratio, 14. Omega Ratio, 15. Tail Ratio and
+        // 16. Value at Risk / Conditional VaR
+        // The returns are computed once and shared; the total and downside variances and the
+        // Omega gains/losses are accumulated in the same pass. Sharpe and Sortino use the
+        // returns in excess of the per-period risk-free rate.
         if equity_curve.len() > 1 {
-            let returns: Vec<f64> = equity_curve
+            let mut returns: Vec<f64> = equity_curve
                 .windows(2)
                 .map(|w| (w[1].value / w[0].value - dec!(1)).to_f64().unwrap_or(0.0))
                 .collect();
             let mean_return = returns.iter().sum::<f64>() / returns.len() as f64;
-            let std_dev = {
-                let variance = returns.iter().map(|r| (*r - mean_return).powi(2)).sum::<f64>() / returns.len() as f64;
-                variance.sqrt()
-            };
+            let risk_free_rate = self.per_period_risk_free_rate(equity_curve);
+            let mean_excess_return = mean_return - risk_free_rate;
+
+            let mut squared_deviations = 0.0;
+            let mut squared_downside = 0.0;
+            let mut negative_count = 0usize;
+            let mut omega_gains = 0.0;
+            let mut omega_losses = 0.0;
+            for r in &returns {
+                squared_deviations += (*r - mean_return).powi(2);
+                let excess = *r - risk_free_rate;
+                if excess < 0.0 {
+                    squared_downside += excess.powi(2);
+                    negative_count += 1;
+                }
+                if *r > OMEGA_THRESHOLD_RETURN {
+                    omega_gains += *r - OMEGA_THRESHOLD_RETURN;
+                } else {
+                    omega_losses += OMEGA_THRESHOLD_RETURN - *r;
+                }
+            }
+
+            let std_dev = (squared_deviations / returns.len() as f64).sqrt();
             report.sharpe_ratio = if std_dev > 0.0 {
-                mean_return / std_dev
+                mean_excess_return / std_dev
             } else {
                 0.0 // Or f64::INFINITY if mean_return > 0
             };
             // Note: This is a periodic Sharpe. To annualize, multiply by sqrt(periods per year).
-        }
 
-        // --- Tier 2 Calculations ---
-
-        // 6. Sortino Ratio (Measures return against downside deviation)
-        if equity_curve.len() > 1 {
-            let returns: Vec<f64> = equity_curve
-                .windows(2)
-                .map(|w| (w[1].value / w[0].value - dec!(1)).to_f64().unwrap_or(0.0))
-                .collect();
-            
-            let mean_return = returns.iter().sum::<f64>() / returns.len() as f64;
-            
-            // Calculate downside deviation (standard deviation of negative returns only)
-            let negative_returns: Vec<f64> = returns.iter().cloned().filter(|r| *r < 0.0).collect();
-            let downside_deviation = if !negative_returns.is_empty() {
-                let variance = negative_returns.iter().map(|r| (*r - 0.0).powi(2)).sum::<f64>() / negative_returns.len() as f64;
-                variance.sqrt()
+            // Downside deviation: the standard deviation of negative excess returns only, around 0.
+            let downside_deviation = if negative_count > 0 {
+                (squared_downside / negative_count as f64).sqrt()
             } else {
                 0.0
             };
-
             report.sortino_ratio = if downside_deviation > 0.0 {
-                mean_return / downside_deviation
+                mean_excess_return / downside_deviation
             } else {
                 f64::INFINITY // No downside risk
             };
+
+            // Omega: the sum of returns above the threshold over the sum of shortfalls below it.
+            report.omega_ratio = if omega_losses > 0.0 {
+                omega_gains / omega_losses
+            } else {
+                f64::INFINITY // No losing periods
+            };
+
+            // Tail ratio: the 95th percentile return over the magnitude of the 5th percentile one.
+            returns.sort_by(f64::total_cmp);
+            let at = |pct: usize| returns[(returns.len() - 1) * pct / 100];
+            let left_tail = at(5).abs();
+            report.tail_ratio = if left_tail > 0.0 {
+                at(95) / left_tail
+            } else {
+                f64::INFINITY // No left tail
+            };
+
+            // VaR: the 5th percentile return. CVaR: the mean of the returns below it, which
+            // falls back to the VaR itself when no return is lower. Both are per-period.
+            report.value_at_risk_95 = at(5);
+            let below_var = returns.partition_point(|r| *r < report.value_at_risk_95);
+            report.conditional_var_95 = if below_var > 0 {
+                returns[..below_var].iter().sum::<f64>() / below_var as f64
+            } else {
+                report.value_at_risk_95
+            };
         }
 
+        // --- Tier 2 Calculations ---
+
         // 7. Calmar Ratio (Annualized Return / Max Drawdown)
         // Note: Proper annualization needs the full backtest duration.
         // We will approximate for now.
@@ -119,58 +249,41 @@ impl AnalyticsEngine {
         }
 
         // 8. Average Trade Duration
-        if !trades.is_empty() {
-            let total_duration_secs: i64 = trades.iter().map(|t| (t.exit_time - t.entry_time).num_seconds()).sum();
-            report.avg_trade_duration_secs = total_duration_secs as f64 / trades.len() as f64;
-        }
+        report.avg_trade_duration_secs = total_duration_secs as f64 / trades.len() as f64;
 
         // 9. Expectancy (Average P&L per trade)
-        if !trades.is_empty() {
-            report.expectancy = report.net_pnl_absolute / Decimal::from(trades.len());
-        }
+        report.expectancy = report.net_pnl_absolute / Decimal::from(trades.len());
 
         // --- Tier 3 ("Atlas") Calculations ---
 
         // 10. Confidence-Weighted Performance Analysis
-        let mut confidence_map: std::collections::HashMap<String, Vec<&Trade>> = std::collections::HashMap::new();
-        for trade in trades {
-            let bucket = match (trade.signal_confidence * 100.0) as u32 {
-                0..=59 => "0-59%",
-                60..=69 => "60-69%",
-                70..=79 => "70-79%",
-                80..=89 => "80-89%",
-                90..=100 => "90-100%",
-                _ => "Other",
-            };
-            confidence_map.entry(bucket.to_string()).or_default().push(trade);
+        // We can't get an equity curve per bucket, so each bucket gets a simplified sub-report.
+        for (bucket_name, (count, bucket_pnl, wins)) in confidence_map {
+            let mut sub_report = PerformanceReport::new();
+            sub_report.total_trades = count;
+            sub_report.net_pnl_absolute = bucket_pnl;
+            sub_report.win_rate = (wins as f64 / count as f64) * 100.0;
+            report.confidence_performance.insert(bucket_name.to_string(), sub_report);
         }
 
-        for (bucket_name, bucket_trades) in confidence_map {
-            // We can't get an equity curve per bucket, so we'll calculate simpler metrics.
-            // A more advanced version might generate sub-reports.
-            // For now, let's just create a simplified sub-report.
+        // 10b. Exit-Reason Breakdown, with the same simplified sub-reports.
+        for (reason, (count, reason_pnl, wins)) in exit_reason_map {
             let mut sub_report = PerformanceReport::new();
-            if !bucket_trades.is_empty() {
-                sub_report.total_trades = bucket_trades.len() as u32;
-                sub_report.net_pnl_absolute = bucket_trades.iter().map(|t| t.pnl).sum();
-                let wins = bucket_trades.iter().filter(|t| t.pnl > dec!(0)).count();
-                sub_report.win_rate = (wins as f64 / sub_report.total_trades as f64) * 100.0;
-            }
-            report.confidence_performance.insert(bucket_name, sub_report);
+            sub_report.total_trades = count;
+            sub_report.net_pnl_absolute = reason_pnl;
+            sub_report.win_rate = (wins as f64 / count as f64) * 100.0;
+            report.by_exit_reason.insert(reason.to_string(), sub_report);
         }
 
         // 11. Leverage-Adjusted Return on Margin (LAROM)
         // This requires knowing margin used, which is complex. We will approximate it.
         // Approximation: Margin Used = Position Value / Leverage
-        if !trades.is_empty() {
-            let avg_leverage: f64 = trades.iter().map(|t| t.leverage as f64).sum::<f64>() / trades.len() as f64;
-            let avg_margin_used: Decimal = trades.iter().map(|t| (t.entry_price * t.quantity) / Decimal::from(t.leverage)).sum::<Decimal>() / Decimal::from(trades.len());
-            
-            if avg_margin_used > dec!(0) && avg_leverage > 0.0 {
-                report.larom = (report.net_pnl_absolute / (avg_margin_used * Decimal::from_f64(avg_leverage).unwrap_or(dec!(1))))
-                    .to_f64()
-                    .unwrap_or(0.0);
-            }
+        let avg_leverage = leverage_sum / trades.len() as f64;
+        let avg_margin_used = margin_sum / Decimal::from(trades.len());
+        if avg_margin_used > dec!(0) && avg_leverage > 0.0 {
+            report.larom = (report.net_pnl_absolute / (avg_margin_used * Decimal::from_f64(avg_leverage).unwrap_or(dec!(1))))
+                .to_f64()
+                .unwrap_or(0.0);
         }
         
         // 12. Funding Rate Impact (Placeholder)
@@ -178,33 +291,355 @@ impl AnalyticsEngine {
         // We will assume it's zero for now and build the structure.
         report.funding_pnl = dec!(0); // Placeholder
 
-        // 13. Drawdown Duration
-        let mut in_drawdown = false;
-        let mut drawdown_start_time = None;
-        let mut max_drawdown_duration = chrono::Duration::zero();
-        let mut peak_equity_for_duration = initial_capital;
+        report.drawdown_duration_secs = max_drawdown_duration.num_seconds();
 
-        for point in equity_curve {
-            if point.value >= peak_equity_for_duration {
-                // We've reached a new peak or recovered from a drawdown
-                if in_drawdown {
-                    let duration = point.timestamp - drawdown_start_time.unwrap();
-                    if duration > max_drawdown_duration {
-                        max_drawdown_duration = duration;
+        report
+    }
+
+    /// Bootstraps the trade P&L sequence (sampling with replacement) `iterations` times and
+    /// returns the distribution of final equity and max drawdown over the synthetic curves.
+    ///
+    /// The same `seed` always draws the same samples; `None` uses `DEFAULT_MONTE_CARLO_SEED`.
+    pub fn monte_carlo(&self, trade_pnls: &[Decimal], iterations: usize, seed: Option<u64>) -> MonteCarloReport {
+        let seed = seed.unwrap_or(DEFAULT_MONTE_CARLO_SEED);
+        let mut report = MonteCarloReport { iterations, seed, ..Default::default() };
+        if trade_pnls.is_empty() || iterations == 0 {
+            return report;
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut final_equities = Vec::with_capacity(iterations);
+        let mut max_drawdowns = Vec::with_capacity(iterations);
+
+        for _ in 0..iterations {
+            let mut equity = dec!(0);
+            let mut peak = dec!(0);
+            let mut max_drawdown = dec!(0);
+            for _ in 0..trade_pnls.len() {
+                equity += trade_pnls[rng.gen_range(0..trade_pnls.len())];
+                peak = peak.max(equity);
+                max_drawdown = max_drawdown.max(peak - equity);
+            }
+            final_equities.push(equity);
+            max_drawdowns.push(max_drawdown);
+        }
+
+        report.final_equity = percentiles(&mut final_equities);
+        report.max_drawdown = percentiles(&mut max_drawdowns);
+        report
+    }
+
+    /// Computes `metric` over every window of `window` consecutive equity points.
+    ///
+    /// Each point of the result is stamped with the last timestamp of its window, so the series
+    /// has `equity_curve.len() - window + 1` points. It is empty if `window` is smaller than 2
+    /// (a single point has no returns) or larger than the curve.
+    pub fn rolling(&self, equity_curve: &[EquityPoint], metric: RollingMetric, window: usize) -> Vec<RollingPoint> {
+        if window < 2 || window > equity_curve.len() {
+            return Vec::new();
+        }
+
+        match metric {
+            RollingMetric::Return => equity_curve
+                .windows(window)
+                .map(|w| {
+                    let (first, last) = (w[0].value, w[window - 1].value);
+                    let value = if first.is_zero() {
+                        0.0
+                    } else {
+                        (last / first - dec!(1)).to_f64().unwrap_or(0.0) * 100.0
+                    };
+                    RollingPoint { timestamp: w[window - 1].timestamp, value }
+                })
+                .collect(),
+            RollingMetric::Sharpe => {
+                let returns: Vec<f64> = equity_curve
+                    .windows(2)
+                    .map(|w| (w[1].value / w[0].value - dec!(1)).to_f64().unwrap_or(0.0))
+                    .collect();
+                let risk_free_rate = self.per_period_risk_free_rate(equity_curve);
+
+                // A window of `window` points holds `window - 1` returns; the sums are kept
+                // running so each step is O(1).
+                let n = (window - 1) as f64;
+                let mut sum: f64 = returns[..window - 1].iter().sum();
+                let mut sum_sq: f64 = returns[..window - 1].iter().map(|r| r * r).sum();
+                let mut series = Vec::with_capacity(equity_curve.len() - window + 1);
+                for end in window - 1..equity_curve.len() {
+                    if end >= window {
+                        let (added, removed) = (returns[end - 1], returns[end - window]);
+                        sum += added - removed;
+                        sum_sq += added * added - removed * removed;
                     }
-                    in_drawdown = false;
-                }
-                peak_equity_for_duration = point.value;
-            } else {
-                // We are currently in a drawdown
-                if !in_drawdown {
-                    in_drawdown = true;
-                    drawdown_start_time = Some(point.timestamp);
+                    let mean = sum / n;
+                    let std_dev = (sum_sq / n - mean * mean).max(0.0).sqrt();
+                    let value = if std_dev > 0.0 { (mean - risk_free_rate) / std_dev } else { 0.0 };
+                    series.push(RollingPoint { timestamp: equity_curve[end].timestamp, value });
                 }
+                series
             }
         }
-        report.drawdown_duration_secs = max_drawdown_duration.num_seconds();
+    }
+}
 
-        report
+/// Sorts `values` and picks the 5th, 50th and 95th percentiles (nearest rank).
+fn percentiles(values: &mut [Decimal]) -> Percentiles {
+    values.sort();
+    let at = |pct: usize| values[(values.len() - 1) * pct / 100];
+    Percentiles { p5: at(5), p50: at(50), p95: at(95) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EntryReason, ExitReason};
+    use chrono::{Duration, TimeZone, Utc};
+    use core_types::{Side, Symbol};
+
+    /// A fixed run of 60 trades with uneven winners, losers and a flat trade, and the
+    /// equity curve they produce (one point per trade close).
+    fn fixed_run() -> (Vec<Trade>, Vec<EquityPoint>) {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut equity = dec!(10_000);
+        let mut trades = Vec::new();
+        let mut equity_curve = Vec::new();
+        for i in 0..60u32 {
+            let entry_time = start + Duration::minutes(i as i64 * 30);
+            let exit_time = entry_time + Duration::minutes(5 + (i as i64 * 7) % 40);
+            let pnl = match i % 7 {
+                0 => dec!(37.125),
+                1 => dec!(-12.5),
+                2 => dec!(0),
+                3 => dec!(-41.75),
+                4 => dec!(8.3),
+                5 => dec!(-3.05),
+                _ => dec!(66.6),
+            } * Decimal::from(1 + i % 4);
+            equity += pnl;
+            trades.push(Trade {
+                symbol: Symbol::new("BTCUSDT").unwrap(),
+                side: if i % 2 == 0 { Side::Long } else { Side::Short },
+                entry_time,
+                exit_time,
+                entry_price: dec!(50_000) + Decimal::from(i * 13),
+                exit_price: dec!(50_100),
+                quantity: dec!(0.015),
+                pnl,
+                fees: dec!(0.3),
+                signal_confidence: 0.45 + (i % 11) as f64 * 0.05,
+                leverage: 5 + (i % 3) as u8 * 5,
+                max_adverse_excursion: dec!(45),
+                max_favorable_excursion: dec!(120),
+                entry_reason: EntryReason::LongSignal,
+                exit_reason: ExitReason::Signal,
+            });
+            equity_curve.push(EquityPoint { timestamp: exit_time, value: equity });
+        }
+        (trades, equity_curve)
+    }
+
+    /// Sharpe as computed before `calculate` was folded into a single pass.
+    fn reference_sharpe(equity_curve: &[EquityPoint]) -> f64 {
+        let returns: Vec<f64> = equity_curve
+            .windows(2)
+            .map(|w| (w[1].value / w[0].value - dec!(1)).to_f64().unwrap_or(0.0))
+            .collect();
+        let mean_return = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (*r - mean_return).powi(2)).sum::<f64>() / returns.len() as f64;
+        let std_dev = variance.sqrt();
+        if std_dev > 0.0 { mean_return / std_dev } else { 0.0 }
+    }
+
+    /// Sortino as computed before `calculate` was folded into a single pass.
+    fn reference_sortino(equity_curve: &[EquityPoint]) -> f64 {
+        let returns: Vec<f64> = equity_curve
+            .windows(2)
+            .map(|w| (w[1].value / w[0].value - dec!(1)).to_f64().unwrap_or(0.0))
+            .collect();
+        let mean_return = returns.iter().sum::<f64>() / returns.len() as f64;
+        let negative_returns: Vec<f64> = returns.iter().cloned().filter(|r| *r < 0.0).collect();
+        let downside_deviation = if !negative_returns.is_empty() {
+            let variance = negative_returns.iter().map(|r| (*r - 0.0).powi(2)).sum::<f64>() / negative_returns.len() as f64;
+            variance.sqrt()
+        } else {
+            0.0
+        };
+        if downside_deviation > 0.0 { mean_return / downside_deviation } else { f64::INFINITY }
+    }
+
+    #[test]
+    fn single_pass_matches_multi_pass_metrics_bit_for_bit() {
+        let (trades, equity_curve) = fixed_run();
+        let initial_capital = dec!(10_000);
+        let report = AnalyticsEngine::new().calculate(initial_capital, &trades, &equity_curve);
+
+        let net_pnl: Decimal = trades.iter().map(|t| t.pnl).sum();
+        let winners: Vec<&Trade> = trades.iter().filter(|t| t.pnl > dec!(0)).collect();
+        let gross_profit: Decimal = winners.iter().map(|t| t.pnl).sum();
+        let gross_loss: Decimal = trades.iter().filter(|t| t.pnl < dec!(0)).map(|t| t.pnl).sum::<Decimal>().abs();
+        let total_duration_secs: i64 = trades.iter().map(|t| (t.exit_time - t.entry_time).num_seconds()).sum();
+
+        assert_eq!(report.sharpe_ratio.to_bits(), reference_sharpe(&equity_curve).to_bits());
+        assert_eq!(report.sortino_ratio.to_bits(), reference_sortino(&equity_curve).to_bits());
+        assert_eq!(report.expectancy, net_pnl / Decimal::from(trades.len()));
+        assert_eq!(report.net_pnl_absolute, net_pnl);
+        assert_eq!(
+            report.win_rate.to_bits(),
+            ((winners.len() as f64 / trades.len() as f64) * 100.0).to_bits()
+        );
+        assert_eq!(
+            report.profit_factor.to_bits(),
+            (gross_profit / gross_loss).to_f64().unwrap_or(0.0).to_bits()
+        );
+        assert_eq!(
+            report.avg_trade_duration_secs.to_bits(),
+            (total_duration_secs as f64 / trades.len() as f64).to_bits()
+        );
+    }
+
+    #[test]
+    fn monte_carlo_median_final_equity_is_close_to_the_actual_net_pnl() {
+        let (trades, _) = fixed_run();
+        let pnls: Vec<Decimal> = trades.iter().map(|t| t.pnl).collect();
+        let net_pnl: Decimal = pnls.iter().sum();
+
+        let report = AnalyticsEngine::new().monte_carlo(&pnls, 2_000, None);
+
+        // Resampling keeps the expected sum, so the median lands near the actual P&L,
+        // well inside the 5-95% spread.
+        let spread = report.final_equity.p95 - report.final_equity.p5;
+        assert!(spread > Decimal::ZERO);
+        assert!(
+            (report.final_equity.p50 - net_pnl).abs() < spread / dec!(10),
+            "median {} vs net P&L {} (spread {})",
+            report.final_equity.p50,
+            net_pnl,
+            spread
+        );
+        assert!(report.final_equity.p5 < net_pnl && net_pnl < report.final_equity.p95);
+        assert!(report.max_drawdown.p5 >= Decimal::ZERO);
+    }
+
+    #[test]
+    fn monte_carlo_seed_is_overridable_and_reproducible() {
+        let (trades, _) = fixed_run();
+        let pnls: Vec<Decimal> = trades.iter().map(|t| t.pnl).collect();
+        let engine = AnalyticsEngine::new();
+
+        let default_seed = engine.monte_carlo(&pnls, 200, None);
+        let same_seed = engine.monte_carlo(&pnls, 200, Some(DEFAULT_MONTE_CARLO_SEED));
+        let other_seed = engine.monte_carlo(&pnls, 200, Some(7));
+
+        assert_eq!(default_seed.seed, DEFAULT_MONTE_CARLO_SEED);
+        assert_eq!(other_seed.seed, 7);
+        assert_eq!(format!("{:?}", default_seed), format!("{:?}", same_seed));
+        assert_ne!(format!("{:?}", default_seed.final_equity), format!("{:?}", other_seed.final_equity));
+    }
+
+    /// An equity curve starting at 10,000 whose period returns are exactly `returns`.
+    fn curve_from_returns(returns: &[Decimal]) -> Vec<EquityPoint> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut value = dec!(10_000);
+        let mut curve = vec![EquityPoint { timestamp: start, value }];
+        for (i, r) in returns.iter().enumerate() {
+            value *= dec!(1) + r;
+            curve.push(EquityPoint { timestamp: start + Duration::hours(i as i64 + 1), value });
+        }
+        curve
+    }
+
+    #[test]
+    fn omega_and_tail_ratio_reward_a_right_skewed_series() {
+        // 21 returns: small losses, many small gains and a few large ones.
+        let mut returns = vec![dec!(-0.02)];
+        returns.extend([dec!(-0.01); 4]);
+        returns.extend([dec!(0.005); 12]);
+        returns.extend([dec!(0.03), dec!(0.05), dec!(0.08), dec!(0.12)]);
+        let equity_curve = curve_from_returns(&returns);
+        // The ratios only read the equity curve, but a report needs trades to be computed.
+        let (trades, _) = fixed_run();
+
+        let report = AnalyticsEngine::new().calculate(dec!(10_000), &trades, &equity_curve);
+
+        // Gains 12 x 0.005 + 0.03 + 0.05 + 0.08 + 0.12 = 0.34 over losses 0.02 + 4 x 0.01 = 0.06.
+        assert!(report.omega_ratio > 1.0);
+        assert!((report.omega_ratio - 0.34 / 0.06).abs() < 1e-9, "omega {}", report.omega_ratio);
+        // Sorted, the 5th percentile is index 20 * 5 / 100 = 1 (-0.01) and the 95th is
+        // index 20 * 95 / 100 = 19 (0.08).
+        assert!((report.tail_ratio - 8.0).abs() < 1e-9, "tail ratio {}", report.tail_ratio);
+    }
+
+    #[test]
+    fn a_nonzero_risk_free_rate_lowers_the_sharpe_ratio() {
+        let (trades, equity_curve) = fixed_run();
+        let baseline = AnalyticsEngine::new().calculate(dec!(10_000), &trades, &equity_curve);
+        let zero_rate = AnalyticsEngine::with_config(AnalyticsConfig { risk_free_rate: 0.0 })
+            .calculate(dec!(10_000), &trades, &equity_curve);
+        let high_rate = AnalyticsEngine::with_config(AnalyticsConfig { risk_free_rate: 0.05 })
+            .calculate(dec!(10_000), &trades, &equity_curve);
+
+        assert_eq!(zero_rate.sharpe_ratio.to_bits(), baseline.sharpe_ratio.to_bits());
+        assert!(high_rate.sharpe_ratio < baseline.sharpe_ratio);
+        // Omega is measured against its own threshold, not the risk-free rate.
+        assert_eq!(high_rate.omega_ratio.to_bits(), baseline.omega_ratio.to_bits());
+        assert_eq!(high_rate.net_pnl_absolute, baseline.net_pnl_absolute);
+    }
+
+    #[test]
+    fn value_at_risk_is_the_fifth_percentile_return() {
+        // 41 returns, so the 5th percentile is index 40 * 5 / 100 = 2 once sorted.
+        let mut returns = vec![dec!(0.01); 38];
+        returns.extend([dec!(-0.04), dec!(-0.03), dec!(-0.06)]);
+        let equity_curve = curve_from_returns(&returns);
+        let (trades, _) = fixed_run();
+
+        let report = AnalyticsEngine::new().calculate(dec!(10_000), &trades, &equity_curve);
+
+        assert!((report.value_at_risk_95 + 0.03).abs() < 1e-9, "VaR {}", report.value_at_risk_95);
+        // The mean of the two returns below it.
+        assert!((report.conditional_var_95 + 0.05).abs() < 1e-9, "CVaR {}", report.conditional_var_95);
+    }
+
+    #[test]
+    fn rolling_series_has_one_point_per_full_window() {
+        let returns = [0.01, -0.02, 0.03, 0.01, -0.01, 0.02, 0.0, 0.015, -0.005, 0.01];
+        let curve = curve_from_returns(&returns.map(|r| Decimal::from_f64(r).unwrap()));
+        let engine = AnalyticsEngine::new();
+
+        for metric in [RollingMetric::Return, RollingMetric::Sharpe] {
+            let series = engine.rolling(&curve, metric, 4);
+            assert_eq!(series.len(), curve.len() - 4 + 1);
+            assert_eq!(series[0].timestamp, curve[3].timestamp);
+            assert_eq!(series.last().unwrap().timestamp, curve.last().unwrap().timestamp);
+            assert!(engine.rolling(&curve, metric, curve.len() + 1).is_empty());
+        }
+
+        // The first window compounds +1%, -2% and +3%.
+        let first_return = engine.rolling(&curve, RollingMetric::Return, 4)[0].value;
+        assert!((first_return - (1.01 * 0.98 * 1.03 - 1.0) * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn trades_are_broken_down_by_exit_reason() {
+        let (mut trades, equity_curve) = fixed_run();
+        for trade in &mut trades {
+            trade.exit_reason = match trade.pnl.cmp(&Decimal::ZERO) {
+                std::cmp::Ordering::Less => ExitReason::StopLoss,
+                std::cmp::Ordering::Greater => ExitReason::TakeProfit,
+                std::cmp::Ordering::Equal => ExitReason::Signal,
+            };
+        }
+
+        let report = AnalyticsEngine::new().calculate(dec!(10_000), &trades, &equity_curve);
+
+        assert_eq!(report.by_exit_reason.len(), 3);
+        for reason in [ExitReason::StopLoss, ExitReason::TakeProfit, ExitReason::Signal] {
+            let bucket: Vec<&Trade> = trades.iter().filter(|t| t.exit_reason == reason).collect();
+            let sub_report = &report.by_exit_reason[reason.as_str()];
+            assert_eq!(sub_report.total_trades, bucket.len() as u32, "{reason}");
+            assert_eq!(sub_report.net_pnl_absolute, bucket.iter().map(|t| t.pnl).sum::<Decimal>(), "{reason}");
+        }
+        assert!(report.by_exit_reason["stop_loss"].net_pnl_absolute < Decimal::ZERO);
+        assert_eq!(report.by_exit_reason["signal"].net_pnl_absolute, Decimal::ZERO);
     }
-} 
\ No newline at end of file
+}
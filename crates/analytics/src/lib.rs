@@ -15,3 +15,6 @@ mod tests {
 
 pub mod types;
 pub mod engine;
+pub mod scoring;
+pub mod distribution;
+pub mod attribution;
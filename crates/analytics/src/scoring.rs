@@ -0,0 +1,187 @@
+// In crates/analytics/src/scoring.rs
+
+use crate::types::PerformanceReport;
+use serde::{Deserialize, Serialize};
+
+/// Runs with fewer trades than this are considered statistically meaningless and are not ranked.
+pub const MINIMUM_TRADES_THRESHOLD: u32 = 30;
+
+/// The weights of the multi-objective scoring function.
+///
+/// Missing fields fall back to the defaults, so a partial config table is valid.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    pub profit_factor: f64,
+    pub sharpe_ratio: f64,
+    /// Applied to the max drawdown on a 0-1 scale; negative values penalize drawdown.
+    pub max_drawdown: f64,
+    pub calmar_ratio: f64,
+    /// Runs with fewer trades than this are excluded from the ranking.
+    pub minimum_trades: u32,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            profit_factor: 40.0,
+            sharpe_ratio: 30.0,
+            max_drawdown: -35.0, // Negative weight penalizes drawdown
+            calmar_ratio: 15.0,
+            minimum_trades: MINIMUM_TRADES_THRESHOLD,
+        }
+    }
+}
+
+/// A report together with its score.
+#[derive(Debug, Serialize)]
+pub struct RankedReport<T> {
+    pub score: f64,
+    pub report: T,
+}
+
+/// The multi-objective scoring function.
+/// Higher scores are better.
+pub fn calculate_score(report: &PerformanceReport, weights: &ScoringWeights) -> f64 {
+    // Normalize or cap values to prevent extreme outliers from dominating the score
+    let capped_profit_factor = report.profit_factor.min(5.0); // Cap at 5.0
+    let capped_sharpe = report.sharpe_ratio.min(5.0);
+    let normalized_drawdown = report.max_drawdown_percentage / 100.0; // Convert to 0-1 scale
+
+    (capped_profit_factor * weights.profit_factor)
+        + (capped_sharpe * weights.sharpe_ratio)
+        + (normalized_drawdown * weights.max_drawdown)
+        + (report.calmar_ratio * weights.calmar_ratio)
+}
+
+/// Scores a set of reports, drops aborted and cancelled ones and those below the minimum
+/// trade count, and sorts the rest by score in descending order (higher is better).
+pub fn rank_reports<T: AsRef<PerformanceReport>>(
+    reports: Vec<T>,
+    weights: &ScoringWeights,
+) -> Vec<RankedReport<T>> {
+    let mut ranked: Vec<RankedReport<T>> = reports
+        .into_iter()
+        .filter(|r| {
+            let report = r.as_ref();
+            !report.aborted && !report.cancelled && report.total_trades >= weights.minimum_trades
+        })
+        .map(|r| RankedReport {
+            score: calculate_score(r.as_ref(), weights),
+            report: r,
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A report tagged with the run it came from, like the database's `FullReport`.
+    struct Run {
+        id: i64,
+        report: PerformanceReport,
+    }
+
+    impl AsRef<PerformanceReport> for Run {
+        fn as_ref(&self) -> &PerformanceReport {
+            &self.report
+        }
+    }
+
+    fn run(id: i64, profit_factor: f64, sharpe_ratio: f64, total_trades: u32) -> Run {
+        Run {
+            id,
+            report: PerformanceReport {
+                profit_factor,
+                sharpe_ratio,
+                max_drawdown_percentage: 10.0,
+                calmar_ratio: 1.0,
+                total_trades,
+                ..PerformanceReport::default()
+            },
+        }
+    }
+
+    #[test]
+    fn leaderboard_is_sorted_by_score_descending() {
+        let runs = vec![run(1, 1.2, 0.5, 40), run(2, 2.5, 1.8, 40), run(3, 0.8, -0.2, 40), run(4, 1.9, 1.1, 40)];
+
+        let ranked = rank_reports(runs, &ScoringWeights::default());
+
+        let ids: Vec<_> = ranked.iter().map(|r| r.report.id).collect();
+        assert_eq!(ids, vec![2, 4, 1, 3]);
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        // 2.5 x 40 + 1.8 x 30 - 0.1 x 35 + 1.0 x 15
+        assert!((ranked[0].score - 165.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn thin_aborted_and_cancelled_runs_are_not_ranked() {
+        let mut aborted = run(3, 5.0, 5.0, 100);
+        aborted.report.aborted = true;
+        let mut cancelled = run(4, 5.0, 5.0, 100);
+        cancelled.report.cancelled = true;
+        let runs = vec![run(1, 1.5, 1.0, 40), run(2, 5.0, 5.0, MINIMUM_TRADES_THRESHOLD - 1), aborted, cancelled];
+
+        let ranked = rank_reports(runs, &ScoringWeights::default());
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].report.id, 1);
+    }
+
+    #[test]
+    fn score_applies_custom_weights_and_caps() {
+        let weights = ScoringWeights {
+            profit_factor: 10.0,
+            sharpe_ratio: 5.0,
+            max_drawdown: -100.0,
+            calmar_ratio: 2.0,
+            minimum_trades: 0,
+        };
+        let report = PerformanceReport {
+            profit_factor: 2.0,
+            sharpe_ratio: 1.5,
+            max_drawdown_percentage: 20.0,
+            calmar_ratio: 3.0,
+            ..PerformanceReport::default()
+        };
+        // 2.0 x 10 + 1.5 x 5 - 0.2 x 100 + 3.0 x 2
+        assert!((calculate_score(&report, &weights) - 13.5).abs() < 1e-9);
+
+        // Profit factor and Sharpe ratio count for at most 5 each.
+        let outlier = PerformanceReport { profit_factor: 50.0, sharpe_ratio: 9.0, ..report };
+        assert!((calculate_score(&outlier, &weights) - (50.0 + 25.0 - 20.0 + 6.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn minimum_trades_is_configurable() {
+        let weights = ScoringWeights { minimum_trades: 5, ..ScoringWeights::default() };
+        let runs = vec![run(1, 1.5, 1.0, 5), run(2, 1.5, 1.0, 4)];
+
+        let ranked = rank_reports(runs, &weights);
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].report.id, 1);
+    }
+
+    #[test]
+    fn drawdown_weight_reorders_otherwise_equal_runs() {
+        let with_drawdown = |id, max_drawdown_percentage| {
+            let mut run = run(id, 1.5, 1.0, 40);
+            run.report.max_drawdown_percentage = max_drawdown_percentage;
+            run
+        };
+
+        let penalizing = ScoringWeights::default();
+        let ranked = rank_reports(vec![with_drawdown(1, 30.0), with_drawdown(2, 10.0)], &penalizing);
+        assert_eq!(ranked.iter().map(|r| r.report.id).collect::<Vec<_>>(), vec![2, 1]);
+
+        let rewarding = ScoringWeights { max_drawdown: 35.0, ..ScoringWeights::default() };
+        let ranked = rank_reports(vec![with_drawdown(1, 30.0), with_drawdown(2, 10.0)], &rewarding);
+        assert_eq!(ranked.iter().map(|r| r.report.id).collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
@@ -1,11 +1,14 @@
 // In crates/analytics/src/types.rs
 
 use chrono::{DateTime, Utc};
-use core_types::{Side, Symbol};
+use core_types::{Side, Signal, Symbol};
 use rust_decimal::Decimal;
 use serde::Serialize;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
 
 /// A comprehensive record of a single closed trade, from entry to exit.
 #[derive(Debug, Clone, Serialize)]
@@ -21,10 +24,125 @@ pub struct Trade {
     pub fees: Decimal,
     pub signal_confidence: f64,
     pub leverage: u8,
+    /// The furthest the price moved against the position while it was open, per unit.
+    pub max_adverse_excursion: Decimal,
+    /// The furthest the price moved in favour of the position while it was open, per unit.
+    pub max_favorable_excursion: Decimal,
+    /// What opened the position.
+    pub entry_reason: EntryReason,
+    /// What closed the position.
+    pub exit_reason: ExitReason,
+}
+
+/// What opened a trade's position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryReason {
+    /// A strategy `GoLong` signal.
+    LongSignal,
+    /// A strategy `GoShort` signal.
+    ShortSignal,
+    /// Not recorded, e.g. for trades saved before entry reasons were tracked.
+    #[default]
+    Unknown,
+}
+
+impl EntryReason {
+    /// Returns the entry reason of a position opened by `signal`.
+    pub fn from_signal(signal: &Signal) -> Self {
+        match signal {
+            Signal::GoLong { .. } => EntryReason::LongSignal,
+            Signal::GoShort { .. } => EntryReason::ShortSignal,
+            _ => EntryReason::Unknown,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryReason::LongSignal => "long_signal",
+            EntryReason::ShortSignal => "short_signal",
+            EntryReason::Unknown => "unknown",
+        }
+    }
+}
+
+/// What closed a trade's position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    StopLoss,
+    TakeProfit,
+    Liquidation,
+    /// A strategy signal, either an explicit close or an order on the opposite side.
+    Signal,
+    /// Not recorded, e.g. for trades saved before exit reasons were tracked.
+    #[default]
+    Unknown,
+}
+
+impl ExitReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::StopLoss => "stop_loss",
+            ExitReason::TakeProfit => "take_profit",
+            ExitReason::Liquidation => "liquidation",
+            ExitReason::Signal => "signal",
+            ExitReason::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for EntryReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EntryReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [EntryReason::LongSignal, EntryReason::ShortSignal, EntryReason::Unknown]
+            .into_iter()
+            .find(|reason| reason.as_str() == s)
+            .ok_or_else(|| format!("unknown entry reason '{}'", s))
+    }
+}
+
+impl FromStr for ExitReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            ExitReason::StopLoss,
+            ExitReason::TakeProfit,
+            ExitReason::Liquidation,
+            ExitReason::Signal,
+            ExitReason::Unknown,
+        ]
+        .into_iter()
+        .find(|reason| reason.as_str() == s)
+        .ok_or_else(|| format!("unknown exit reason '{}'", s))
+    }
+}
+
+/// Settings for the metrics calculated by the `AnalyticsEngine`.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct AnalyticsConfig {
+    /// The annual risk-free rate (e.g. `0.05` for 5%) subtracted from returns in the
+    /// Sharpe and Sortino ratios.
+    #[serde(default)]
+    pub risk_free_rate: f64,
 }
 
 /// A struct to hold a point in the portfolio's equity curve.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct EquityPoint {
     pub timestamp: DateTime<Utc>,
     pub value: Decimal,
@@ -34,7 +152,7 @@ pub struct EquityPoint {
 pub type ConfidenceBucketPerformance = HashMap<String, PerformanceReport>;
 
 /// A comprehensive report of a strategy's performance over a backtest period.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct PerformanceReport {
     pub run_id: i64, // Add this
     // Tier 1 Metrics
@@ -50,15 +168,40 @@ pub struct PerformanceReport {
     // Tier 2 Metrics
     pub sortino_ratio: f64,
     pub calmar_ratio: f64,
+    /// Gains over losses of the per-period returns, relative to a zero threshold return.
+    #[serde(default)]
+    pub omega_ratio: f64,
+    /// The 95th percentile per-period return over the magnitude of the 5th percentile one.
+    #[serde(default)]
+    pub tail_ratio: f64,
+    /// The 5th percentile per-period return (not annualized); negative when it is a loss.
+    #[serde(default)]
+    pub value_at_risk_95: f64,
+    /// The mean per-period return below `value_at_risk_95` (not annualized).
+    #[serde(default)]
+    pub conditional_var_95: f64,
     pub avg_trade_duration_secs: f64,
     pub expectancy: Decimal,
 
     // Tier 3 Metrics
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schema(value_type = Object, no_recursion)] // A map of bucket name to `PerformanceReport`
     pub confidence_performance: ConfidenceBucketPerformance,
+    /// Simplified sub-reports of the trades grouped by `ExitReason`, keyed by its name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schema(value_type = Object, no_recursion)] // A map of exit reason to `PerformanceReport`
+    pub by_exit_reason: HashMap<String, PerformanceReport>,
     pub larom: f64, // Leverage-Adjusted Return on Margin
     pub funding_pnl: Decimal,
     pub drawdown_duration_secs: i64,
+    /// Whether the backtest was stopped early by its drawdown abort, in which case the
+    /// metrics only cover the bars processed until then.
+    #[serde(default)]
+    pub aborted: bool,
+    /// Whether the backtest was cancelled before reaching the last bar, in which case the
+    /// metrics only cover the bars processed until then.
+    #[serde(default)]
+    pub cancelled: bool,
 }
 
 impl PerformanceReport {
@@ -66,4 +209,43 @@ impl PerformanceReport {
     pub fn new() -> Self {
         Self::default()
     }
-}
\ No newline at end of file
+}
+
+/// The 5th, 50th and 95th percentiles of a distribution.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct Percentiles {
+    pub p5: Decimal,
+    pub p50: Decimal,
+    pub p95: Decimal,
+}
+
+/// The outcome of a Monte Carlo resampling of a trade sequence.
+///
+/// Final equity is expressed as the cumulative P&L of the synthetic sequence, so it is
+/// directly comparable to `PerformanceReport::net_pnl_absolute`.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct MonteCarloReport {
+    pub iterations: usize,
+    /// The seed the samples were drawn with.
+    pub seed: u64,
+    pub final_equity: Percentiles,
+    pub max_drawdown: Percentiles,
+}
+
+/// A metric that can be computed over a sliding window of the equity curve.
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RollingMetric {
+    /// The periodic Sharpe ratio of the window's returns, as in `PerformanceReport::sharpe_ratio`.
+    #[default]
+    Sharpe,
+    /// The equity change across the window, in percent.
+    Return,
+}
+
+/// The value of a rolling metric for the window ending at `timestamp`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RollingPoint {
+    pub timestamp: DateTime<Utc>,
+    pub value: f64,
+}
@@ -1,7 +1,7 @@
 // In crates/api-client/src/live_connector.rs
 
 use crate::Result;
-use crate::types::WsKlineEvent;
+use crate::types::{MarkPriceUpdate, WsKlineEvent};
 use async_stream::stream;
 use core_types::{Kline, Symbol};
 use futures::Stream;
@@ -14,6 +14,12 @@ const BINANCE_WS_BASE_URL: &str = "wss://fstream.binancefuture.com/ws";
 #[derive(Clone)]
 pub struct LiveConnector;
 
+impl Default for LiveConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LiveConnector {
     pub fn new() -> Self {
         Self
@@ -26,7 +32,7 @@ impl LiveConnector {
         &self,
         symbol: &Symbol,
         interval: &str,
-    ) -> impl Stream<Item = Result<Kline>> {
+    ) -> impl Stream<Item = Result<Kline>> + use<> {
         let stream_name = format!("{}@kline_{}", symbol.0.to_lowercase(), interval);
         let url = format!("{}/{}", BINANCE_WS_BASE_URL, stream_name);
 
@@ -49,22 +55,21 @@ impl LiveConnector {
                     match message {
                         Ok(msg) => {
                             // tracing::info!(?msg, "Raw WebSocket message received");
-                            if let Ok(text) = msg.to_text() {
-                                if let Ok(event) = serde_json::from_str::<WsKlineEvent>(text) {
-                                    // Only yield the kline if it's the final update for that bar.
-                                    if event.kline.is_closed {
-                                        tracing::info!("Closed kline received: {:?}", event.kline);
-                                        yield Ok(Kline {
-                                            open_time: event.kline.open_time,
-                                            open: event.kline.open,
-                                            high: event.kline.high,
-                                            low: event.kline.low,
-                                            close: event.kline.close,
-                                            volume: event.kline.volume,
-                                            close_time: event.kline.close_time,
-                                        });
-                                    }
-                                }
+                            // Only yield the kline if it's the final update for that bar.
+                            if let Ok(text) = msg.to_text()
+                                && let Ok(event) = serde_json::from_str::<WsKlineEvent>(text)
+                                && event.kline.is_closed
+                            {
+                                tracing::info!("Closed kline received: {:?}", event.kline);
+                                yield Ok(Kline {
+                                    open_time: event.kline.open_time,
+                                    open: event.kline.open,
+                                    high: event.kline.high,
+                                    low: event.kline.low,
+                                    close: event.kline.close,
+                                    volume: event.kline.volume,
+                                    close_time: event.kline.close_time,
+                                });
                             }
                         }
                         Err(e) => {
@@ -85,7 +90,7 @@ impl LiveConnector {
         &self,
         stream_names: Vec<String>,
         base_url: &str,
-    ) -> impl Stream<Item = Result<WsKlineEvent>> {
+    ) -> impl Stream<Item = Result<WsKlineEvent>> + use<> {
         let streams_param = stream_names.join("/");
         let url = format!("{}/stream?streams={}", base_url, streams_param);
 
@@ -107,18 +112,15 @@ impl LiveConnector {
                 while let Some(message) = read.next().await {
                     match message {
                         Ok(msg) => {
-                            if let Ok(text) = msg.to_text() {
-                                // The new payload has a "stream" name and "data"
-                                if let Ok(mut wrapper) = serde_json::from_str::<serde_json::Value>(text) {
-                                    if let Some(data) = wrapper.get_mut("data") {
-                                        if let Ok(event) = serde_json::from_value::<WsKlineEvent>(data.take()) {
-                                            // Only yield closed klines
-                                            if event.kline.is_closed {
-                                                yield Ok(event); // Yield the full event
-                                            }
-                                        }
-                                    }
-                                }
+                            // The new payload has a "stream" name and "data"; only closed klines
+                            // are yielded.
+                            if let Ok(text) = msg.to_text()
+                                && let Ok(mut wrapper) = serde_json::from_str::<serde_json::Value>(text)
+                                && let Some(data) = wrapper.get_mut("data")
+                                && let Ok(event) = serde_json::from_value::<WsKlineEvent>(data.take())
+                                && event.kline.is_closed
+                            {
+                                yield Ok(event); // Yield the full event
                             }
                         }
                         Err(e) => {
@@ -131,4 +133,58 @@ impl LiveConnector {
             }
         }
     }
+
+    /// Subscribes to the mark-price streams of several symbols and returns an asynchronous
+    /// stream of `MarkPriceUpdate` data.
+    ///
+    /// Updates are pushed by Binance every 3 seconds per symbol.
+    pub fn subscribe_to_mark_price(
+        &self,
+        symbols: &[Symbol],
+        base_url: &str,
+    ) -> impl Stream<Item = Result<MarkPriceUpdate>> + use<> {
+        let streams_param = symbols
+            .iter()
+            .map(|s| format!("{}@markPrice", s.0.to_lowercase()))
+            .collect::<Vec<_>>()
+            .join("/");
+        let url = format!("{}/stream?streams={}", base_url, streams_param);
+
+        stream! {
+            loop {
+                tracing::info!(url = %url, "Connecting to mark-price WebSocket...");
+                let (ws_stream, _) = match connect_async(&url).await {
+                    Ok(s) => s,
+                    Err(e) => {
+                        tracing::error!(error = %e, "WebSocket connection failed. Retrying in 5s...");
+                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                        continue;
+                    }
+                };
+                tracing::info!("Mark-price WebSocket connection successful.");
+
+                let mut read = ws_stream.fuse();
+
+                while let Some(message) = read.next().await {
+                    match message {
+                        Ok(msg) => {
+                            // Combined streams wrap each event in a "stream"/"data" envelope
+                            if let Ok(text) = msg.to_text()
+                                && let Ok(mut wrapper) = serde_json::from_str::<serde_json::Value>(text)
+                                && let Some(data) = wrapper.get_mut("data")
+                                && let Ok(update) = serde_json::from_value::<MarkPriceUpdate>(data.take())
+                            {
+                                yield Ok(update);
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Error reading from mark-price WebSocket. Reconnecting...");
+                            // Break the inner loop to trigger a reconnection.
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
\ No newline at end of file
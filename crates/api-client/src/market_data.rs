@@ -0,0 +1,213 @@
+// In crates/api-client/src/market_data.rs
+
+use crate::error::{Error, Result};
+use crate::types::{ApiClient, OrderBook, RawKline};
+use core_types::{Kline, Symbol};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use std::str::FromStr;
+
+/// The maximum number of klines Binance returns for a single request.
+pub const MAX_KLINES_PER_REQUEST: u16 = 1500;
+
+/// Converts a raw kline array from the REST API into our core `Kline` type.
+fn parse_raw_kline(raw: RawKline) -> Result<Kline> {
+    let parse = |value: &str| {
+        Decimal::from_str(value)
+            .map_err(|e| Error::CustomError(format!("Invalid decimal '{}' in kline: {}", value, e)))
+    };
+    Ok(Kline {
+        open_time: raw.0,
+        open: parse(&raw.1)?,
+        high: parse(&raw.2)?,
+        low: parse(&raw.3)?,
+        close: parse(&raw.4)?,
+        volume: parse(&raw.5)?,
+        close_time: raw.6,
+    })
+}
+
+impl ApiClient {
+    /// Sends an unsigned `GET` request to a public market-data endpoint.
+    ///
+    /// Binance reports failures as a JSON body of the form `{"code": -1121, "msg": "..."}`,
+    /// which is detected here and surfaced as `Error::ApiError` before deserializing `T`.
+    pub(crate) async fn send_public_request<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        self.rate_limiter.acquire().await;
+        let response = self.http_client.get(&url).query(query).send().await?;
+        self.rate_limiter.record(response.headers());
+        let body = response.text().await?;
+
+        let value: serde_json::Value = serde_json::from_str(&body)?;
+        if let (Some(code), Some(msg)) = (value.get("code").and_then(|c| c.as_i64()), value.get("msg")) {
+            return Err(Error::ApiError {
+                code,
+                msg: msg.as_str().unwrap_or_default().to_string(),
+            });
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Fetches a single batch of historical klines.
+    ///
+    /// # Arguments
+    /// * `symbol` - The trading pair (e.g., "BTCUSDT").
+    /// * `interval` - The kline interval (e.g., "1m", "5m").
+    /// * `start_time` - The inclusive start of the window in milliseconds. If `None`, Binance
+    ///   returns the most recent klines.
+    /// * `end_time` - The inclusive end of the window in milliseconds.
+    /// * `limit` - The maximum number of klines to return (capped at 1500 by Binance).
+    pub async fn get_historical_klines(
+        &self,
+        symbol: &Symbol,
+        interval: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        limit: Option<u16>,
+    ) -> Result<Vec<Kline>> {
+        let mut query = vec![("symbol", symbol.0.clone()), ("interval", interval.to_string())];
+        if let Some(start_time) = start_time {
+            query.push(("startTime", start_time.to_string()));
+        }
+        if let Some(end_time) = end_time {
+            query.push(("endTime", end_time.to_string()));
+        }
+        if let Some(limit) = limit {
+            query.push(("limit", limit.min(MAX_KLINES_PER_REQUEST).to_string()));
+        }
+
+        let raw_klines: Vec<RawKline> = self.send_public_request("/fapi/v1/klines", &query).await?;
+        raw_klines.into_iter().map(parse_raw_kline).collect()
+    }
+
+    /// Fetches every kline whose open time falls within `[start, end]`, paginating internally.
+    ///
+    /// Requests are issued in batches of up to 1500 klines, paced by the client's rate limiter,
+    /// and no kline past `end` is ever returned.
+    pub async fn get_klines_range(
+        &self,
+        symbol: &Symbol,
+        interval: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Kline>> {
+        let mut klines = Vec::new();
+        let mut cursor = start;
+
+        while cursor <= end {
+            let batch = self
+                .get_historical_klines(symbol, interval, Some(cursor), Some(end), Some(MAX_KLINES_PER_REQUEST))
+                .await?;
+            let Some(last) = batch.last() else {
+                break; // No more data in this window.
+            };
+            cursor = last.open_time + 1;
+            let is_last_page = batch.len() < MAX_KLINES_PER_REQUEST as usize;
+
+            klines.extend(batch.into_iter().filter(|k| k.open_time <= end));
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(klines)
+    }
+
+    /// Fetches the current order book depth for a symbol.
+    ///
+    /// # Arguments
+    /// * `symbol` - The trading pair (e.g., "BTCUSDT").
+    /// * `limit` - The number of levels per side. Binance accepts 5, 10, 20, 50, 100, 500 or 1000.
+    pub async fn get_order_book(&self, symbol: &Symbol, limit: u16) -> Result<OrderBook> {
+        self.send_public_request(
+            "/fapi/v1/depth",
+            &[("symbol", symbol.0.clone()), ("limit", limit.to_string())],
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::mock_exchange;
+    use axum::extract::{Query, State};
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const MINUTE_MS: i64 = 60_000;
+
+    /// Answers `/fapi/v1/klines` like Binance would for a market with one 1m kline every
+    /// minute since the epoch: klines from `startTime` to `endTime`, at most `limit` of them.
+    async fn klines_handler(
+        State(requests): State<Arc<AtomicUsize>>,
+        Query(query): Query<HashMap<String, String>>,
+    ) -> Json<Value> {
+        requests.fetch_add(1, Ordering::SeqCst);
+        let start: i64 = query["startTime"].parse().unwrap();
+        let end: i64 = query.get("endTime").map_or(i64::MAX, |end| end.parse().unwrap());
+        let limit: i64 = query.get("limit").map_or(500, |limit| limit.parse().unwrap());
+        let first = (start + MINUTE_MS - 1).div_euclid(MINUTE_MS) * MINUTE_MS;
+        let rows = (0..limit)
+            .map(|i| first + i * MINUTE_MS)
+            .take_while(|open_time| *open_time <= end)
+            .map(|open_time| {
+                json!([open_time, "100.0", "101.0", "99.0", "100.5", "10.0", open_time + MINUTE_MS - 1,
+                       "1005.0", 42, "5.0", "502.5", "0"])
+            })
+            .collect();
+        Json(Value::Array(rows))
+    }
+
+    /// Starts the mock exchange and returns a client pointed at it, along with the number of
+    /// requests it has served.
+    async fn mock_client() -> (ApiClient, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let app = Router::new()
+            .route("/fapi/v1/klines", get(klines_handler))
+            .with_state(requests.clone());
+        let client = mock_exchange(app).await;
+        (client, requests)
+    }
+
+    #[tokio::test]
+    async fn get_klines_range_paginates_and_stops_at_end_time() {
+        let (client, requests) = mock_client().await;
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let start = 1_000 * MINUTE_MS;
+        // 3,200 klines: two full pages of 1,500 and a partial one.
+        let end = start + 3_199 * MINUTE_MS;
+
+        let klines = client.get_klines_range(&symbol, "1m", start, end).await.unwrap();
+
+        assert_eq!(klines.len(), 3_200);
+        assert_eq!(klines.first().unwrap().open_time, start);
+        assert_eq!(klines.last().unwrap().open_time, end);
+        assert!(klines.windows(2).all(|pair| pair[1].open_time - pair[0].open_time == MINUTE_MS));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn get_historical_klines_forwards_end_time() {
+        let (client, _) = mock_client().await;
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+
+        let klines = client
+            .get_historical_klines(&symbol, "1m", Some(0), Some(9 * MINUTE_MS), Some(1_000))
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 10);
+        assert_eq!(klines[9].close, Decimal::from_str("100.5").unwrap());
+    }
+}
@@ -0,0 +1,70 @@
+// In crates/api-client/src/rate_limiter.rs
+
+use reqwest::header::HeaderMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Binance's default request-weight budget per IP and minute.
+pub const DEFAULT_WEIGHT_LIMIT_PER_MINUTE: u32 = 2400;
+/// The share of the budget above which requests are held back until the next minute.
+const THROTTLE_THRESHOLD: f64 = 0.8;
+/// The response header carrying the weight used in the current minute.
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+
+/// Tracks the request weight Binance reports as used and pauses callers before the limit is hit.
+///
+/// Cloning shares the underlying counter, so every clone of an `ApiClient` throttles together.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    used_weight: Arc<AtomicU32>,
+    limit_per_minute: u32,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_WEIGHT_LIMIT_PER_MINUTE)
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limit_per_minute: u32) -> Self {
+        Self {
+            used_weight: Arc::new(AtomicU32::new(0)),
+            limit_per_minute,
+        }
+    }
+
+    /// Waits, if needed, until a request can be sent without approaching the weight limit.
+    ///
+    /// Binance resets the counter at the start of every minute, so once the threshold is
+    /// reached we sleep until then.
+    pub async fn acquire(&self) {
+        let threshold = (self.limit_per_minute as f64 * THROTTLE_THRESHOLD) as u32;
+        if self.used_weight.load(Ordering::Relaxed) < threshold {
+            return;
+        }
+
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let until_next_minute = Duration::from_millis((60_000 - now_ms.rem_euclid(60_000)) as u64);
+        tracing::warn!(
+            used_weight = self.used_weight.load(Ordering::Relaxed),
+            limit = self.limit_per_minute,
+            wait = ?until_next_minute,
+            "Approaching the request-weight limit. Pausing until the next minute."
+        );
+        tokio::time::sleep(until_next_minute).await;
+        self.used_weight.store(0, Ordering::Relaxed);
+    }
+
+    /// Updates the used weight from a response's `X-MBX-USED-WEIGHT-1M` header, if present.
+    pub fn record(&self, headers: &HeaderMap) {
+        if let Some(used) = headers
+            .get(USED_WEIGHT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u32>().ok())
+        {
+            self.used_weight.store(used, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,14 @@
+// In crates/api-client/src/test_support.rs
+
+//! Helpers for tests that need a Binance-like server to talk to.
+
+use crate::types::ApiClient;
+use axum::Router;
+
+/// Serves `router` on a local port and returns a client pointed at it.
+pub async fn mock_exchange(router: Router) -> ApiClient {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    ApiClient::with_base_url(format!("http://{}", address))
+}
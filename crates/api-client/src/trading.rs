@@ -0,0 +1,319 @@
+// In crates/api-client/src/trading.rs
+
+use crate::error::{Error, Result};
+use crate::types::{AccountState, ApiClient, FlattenFailure, FlattenReport, NewOrderResponse, PositionInfo, PositionMode};
+use core_types::{Side, Symbol};
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use sha2::Sha256;
+
+/// The response of the `positionSide/dual` endpoint.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DualSidePositionResponse {
+    dual_side_position: bool,
+}
+
+/// Builds the query parameters for a market order.
+///
+/// In one-way mode every order uses `positionSide=BOTH`, and closing orders carry
+/// `reduceOnly=true` so they can never flip the position. In hedge mode the position side
+/// must name the position being traded: an entry uses its own side, while a close targets
+/// the opposite side. Binance rejects `reduceOnly` in hedge mode, so it is omitted there.
+pub fn market_order_params(
+    symbol: &Symbol,
+    side: &Side,
+    quantity: Decimal,
+    position_mode: PositionMode,
+    reduce_only: bool,
+) -> Vec<(&'static str, String)> {
+    let order_side = match side {
+        Side::Long => "BUY",
+        Side::Short => "SELL",
+    };
+    let position_side = match position_mode {
+        PositionMode::OneWay => "BOTH",
+        PositionMode::Hedge => match (side, reduce_only) {
+            (Side::Long, false) | (Side::Short, true) => "LONG",
+            (Side::Short, false) | (Side::Long, true) => "SHORT",
+        },
+    };
+
+    let mut params = vec![
+        ("symbol", symbol.0.clone()),
+        ("side", order_side.to_string()),
+        ("positionSide", position_side.to_string()),
+        ("type", "MARKET".to_string()),
+        ("quantity", quantity.normalize().to_string()),
+        ("newOrderRespType", "RESULT".to_string()),
+    ];
+    if reduce_only && position_mode == PositionMode::OneWay {
+        params.push(("reduceOnly", "true".to_string()));
+    }
+    params
+}
+
+/// Returns the order (symbol, order side, quantity) that closes each non-zero position.
+///
+/// A long position (positive amount) is closed with a `Short` order and a short one with a
+/// `Long` order, for the full absolute amount.
+pub fn closing_orders(positions: &[PositionInfo]) -> Vec<(Symbol, Side, Decimal)> {
+    positions
+        .iter()
+        .filter(|p| !p.position_amt.is_zero())
+        .map(|p| {
+            let side = if p.position_amt > Decimal::ZERO { Side::Short } else { Side::Long };
+            (Symbol(p.symbol.clone()), side, p.position_amt.abs())
+        })
+        .collect()
+}
+
+impl ApiClient {
+    /// Sends a request to a `USER_DATA` or `TRADE` endpoint, signed with the secret key.
+    ///
+    /// A `timestamp` parameter is appended and the whole query string is signed with
+    /// HMAC-SHA256. Error bodies are surfaced as `Error::ApiError`.
+    pub(crate) async fn send_signed_request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<T> {
+        let mut query: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        query.push(format!("timestamp={}", chrono::Utc::now().timestamp_millis()));
+        let query = query.join("&");
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes())
+            .map_err(|e| Error::ClientBuildError(e.to_string()))?;
+        mac.update(query.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let url = format!("{}{}?{}&signature={}", self.base_url, path, query, signature);
+        self.rate_limiter.acquire().await;
+        let response = self
+            .http_client
+            .request(method, &url)
+            .header("X-MBX-APIKEY", &self.api_key)
+            .send()
+            .await?;
+        self.rate_limiter.record(response.headers());
+        let body = response.text().await?;
+
+        let value: serde_json::Value = serde_json::from_str(&body)?;
+        if let (Some(code), Some(msg)) = (value.get("code").and_then(|c| c.as_i64()), value.get("msg")) {
+            // The position-mode and leverage endpoints report success as code 200.
+            if code != 200 {
+                return Err(Error::ApiError {
+                    code,
+                    msg: msg.as_str().unwrap_or_default().to_string(),
+                });
+            }
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Fetches the futures account's balances and open positions.
+    pub async fn get_account_balance(&self) -> Result<AccountState> {
+        self.send_signed_request(Method::GET, "/fapi/v2/account", &[]).await
+    }
+
+    /// Queries whether the account trades in one-way or hedge position mode.
+    pub async fn get_position_mode(&self) -> Result<PositionMode> {
+        let response: DualSidePositionResponse = self
+            .send_signed_request(Method::GET, "/fapi/v1/positionSide/dual", &[])
+            .await?;
+        Ok(if response.dual_side_position { PositionMode::Hedge } else { PositionMode::OneWay })
+    }
+
+    /// Sets the leverage used for new positions on `symbol`.
+    pub async fn set_leverage(&self, symbol: &Symbol, leverage: u8) -> Result<()> {
+        let params = [("symbol", symbol.0.clone()), ("leverage", leverage.to_string())];
+        let _: serde_json::Value = self.send_signed_request(Method::POST, "/fapi/v1/leverage", &params).await?;
+        Ok(())
+    }
+
+    /// Places a market order.
+    ///
+    /// # Arguments
+    /// * `symbol` - The trading pair (e.g., "BTCUSDT").
+    /// * `side` - The side of the order itself (a long position is closed with a `Short` order).
+    /// * `quantity` - The order quantity in the base asset.
+    /// * `position_mode` - The account's position mode, which decides the `positionSide` sent.
+    /// * `reduce_only` - Whether the order may only reduce an existing position.
+    pub async fn place_market_order(
+        &self,
+        symbol: &Symbol,
+        side: &Side,
+        quantity: Decimal,
+        position_mode: PositionMode,
+        reduce_only: bool,
+    ) -> Result<NewOrderResponse> {
+        let params = market_order_params(symbol, side, quantity, position_mode, reduce_only);
+        self.send_signed_request(Method::POST, "/fapi/v1/order", &params).await
+    }
+
+    /// Closes every open position on the account with reduce-only market orders.
+    ///
+    /// A failed close does not stop the others; the report lists which symbols were
+    /// closed and which failed. Only fetching the account state itself is an error.
+    pub async fn flatten_positions(&self) -> Result<FlattenReport> {
+        let account_state = self.get_account_balance().await?;
+        let position_mode = self.get_position_mode().await?;
+
+        let mut report = FlattenReport::default();
+        for (symbol, side, quantity) in closing_orders(&account_state.positions) {
+            match self.place_market_order(&symbol, &side, quantity, position_mode, true).await {
+                Ok(_) => {
+                    tracing::warn!(symbol = %symbol.0, %quantity, "Position flattened.");
+                    report.closed.push(symbol.0);
+                }
+                Err(e) => {
+                    tracing::error!(symbol = %symbol.0, error = %e, "Failed to flatten position.");
+                    report.failed.push(FlattenFailure { symbol: symbol.0, error: e.to_string() });
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn query(symbol: &str, side: Side, position_mode: PositionMode, reduce_only: bool) -> String {
+        market_order_params(&Symbol::new(symbol).unwrap(), &side, dec!(0.0100), position_mode, reduce_only)
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    #[test]
+    fn one_way_mode_sends_both_and_reduce_only_on_closes() {
+        assert_eq!(
+            query("BTCUSDT", Side::Long, PositionMode::OneWay, false),
+            "symbol=BTCUSDT&side=BUY&positionSide=BOTH&type=MARKET&quantity=0.01&newOrderRespType=RESULT"
+        );
+        assert_eq!(
+            query("BTCUSDT", Side::Short, PositionMode::OneWay, true),
+            "symbol=BTCUSDT&side=SELL&positionSide=BOTH&type=MARKET&quantity=0.01&newOrderRespType=RESULT&reduceOnly=true"
+        );
+    }
+
+    #[test]
+    fn hedge_mode_names_the_position_and_omits_reduce_only() {
+        // Entries trade the position on their own side.
+        assert_eq!(
+            query("ETHUSDT", Side::Long, PositionMode::Hedge, false),
+            "symbol=ETHUSDT&side=BUY&positionSide=LONG&type=MARKET&quantity=0.01&newOrderRespType=RESULT"
+        );
+        assert_eq!(
+            query("ETHUSDT", Side::Short, PositionMode::Hedge, false),
+            "symbol=ETHUSDT&side=SELL&positionSide=SHORT&type=MARKET&quantity=0.01&newOrderRespType=RESULT"
+        );
+        // Closes target the opposite position: selling closes the long, buying the short.
+        assert_eq!(
+            query("ETHUSDT", Side::Short, PositionMode::Hedge, true),
+            "symbol=ETHUSDT&side=SELL&positionSide=LONG&type=MARKET&quantity=0.01&newOrderRespType=RESULT"
+        );
+        assert_eq!(
+            query("ETHUSDT", Side::Long, PositionMode::Hedge, true),
+            "symbol=ETHUSDT&side=BUY&positionSide=SHORT&type=MARKET&quantity=0.01&newOrderRespType=RESULT"
+        );
+    }
+
+    /// The JSON of an open one-way position, as listed by `/fapi/v2/account`.
+    fn position_json(symbol: &str, amount: &str) -> serde_json::Value {
+        serde_json::json!({
+            "symbol": symbol,
+            "positionAmt": amount,
+            "entryPrice": "100",
+            "markPrice": "100",
+            "unrealizedProfit": "0",
+            "leverage": "10",
+            "positionSide": "BOTH"
+        })
+    }
+
+    #[tokio::test]
+    async fn flatten_sends_a_reduce_only_close_per_open_position() {
+        use crate::test_support::mock_exchange;
+        use axum::extract::{Query, State};
+        use axum::routing::{get, post};
+        use axum::{Json, Router};
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+
+        type Orders = Arc<Mutex<Vec<HashMap<String, String>>>>;
+        // Records every order; the ETH close is rejected by the exchange.
+        async fn order_handler(
+            State(orders): State<Orders>,
+            Query(query): Query<HashMap<String, String>>,
+        ) -> Json<serde_json::Value> {
+            let symbol = query["symbol"].clone();
+            orders.lock().unwrap().push(query.clone());
+            if symbol == "ETHUSDT" {
+                return Json(serde_json::json!({ "code": -2022, "msg": "ReduceOnly Order is rejected." }));
+            }
+            Json(serde_json::json!({
+                "orderId": 1,
+                "symbol": symbol,
+                "side": query["side"],
+                "type": "MARKET",
+                "avgPrice": "100",
+                "executedQty": query["quantity"],
+                "cumQuote": "100"
+            }))
+        }
+
+        let account = serde_json::json!({
+            "assets": [],
+            "positions": [
+                position_json("BTCUSDT", "0.5"),
+                position_json("ETHUSDT", "-2"),
+                position_json("SOLUSDT", "0"),
+            ],
+            "totalWalletBalance": "10000",
+            "totalUnrealizedProfit": "0",
+            "totalMarginBalance": "10000"
+        });
+        let orders = Orders::default();
+        let app = Router::new()
+            .route("/fapi/v2/account", get(move || async move { Json(account) }))
+            .route(
+                "/fapi/v1/positionSide/dual",
+                get(|| async { Json(serde_json::json!({ "dualSidePosition": false })) }),
+            )
+            .route("/fapi/v1/order", post(order_handler))
+            .with_state(orders.clone());
+        let client = mock_exchange(app).await;
+
+        let report = client.flatten_positions().await.unwrap();
+
+        let mut sent: Vec<_> = orders
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|q| (q["symbol"].clone(), q["side"].clone(), q["quantity"].clone(), q["reduceOnly"].clone()))
+            .collect();
+        sent.sort();
+        // One close per non-zero position, opposite to its side; none for the flat SOL.
+        assert_eq!(
+            sent,
+            vec![
+                ("BTCUSDT".to_string(), "SELL".to_string(), "0.5".to_string(), "true".to_string()),
+                ("ETHUSDT".to_string(), "BUY".to_string(), "2".to_string(), "true".to_string()),
+            ]
+        );
+        assert_eq!(report.closed, vec!["BTCUSDT".to_string()]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].symbol, "ETHUSDT");
+        assert!(report.failed[0].error.contains("-2022"), "{}", report.failed[0].error);
+    }
+}
@@ -1,8 +1,10 @@
 // In crates/api-client/src/types.rs
 
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use rust_decimal::Decimal;
+use core_types::Symbol;
+use crate::rate_limiter::RateLimiter;
 
 /// The main client for interacting with the Binance Futures API.
 #[derive(Debug, Clone)]
@@ -15,10 +17,34 @@ pub struct ApiClient {
     pub secret_key: String,
     /// The base URL for the Binance Futures API.
     pub base_url: String,
+    /// Paces requests against Binance's request-weight limit.
+    pub rate_limiter: RateLimiter,
+}
+
+impl ApiClient {
+    /// Creates a client with no API keys for the REST API at `base_url`.
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self {
+            http_client: Client::new(),
+            api_key: String::new(),
+            secret_key: String::new(),
+            base_url: base_url.into(),
+            rate_limiter: RateLimiter::default(),
+        }
+    }
+}
+
+/// The position mode of a futures account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionMode {
+    /// A single net position per symbol (`positionSide=BOTH`).
+    OneWay,
+    /// Separate long and short positions per symbol (`positionSide=LONG/SHORT`).
+    Hedge,
 }
 
 /// Represents a single asset's balance in the futures account.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FuturesAsset {
     /// The asset's symbol (e.g., "USDT").
@@ -34,7 +60,7 @@ pub struct FuturesAsset {
 }
 
 /// Represents a single open position as returned by the account endpoint.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PositionInfo {
     /// The trading pair symbol (e.g., "BTCUSDT").
@@ -55,7 +81,7 @@ pub struct PositionInfo {
 }
 
 /// Represents the overall futures account state.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountState {
     /// A list of assets in the futures account.
@@ -72,6 +98,22 @@ pub struct AccountState {
     pub total_available_balance: Option<Decimal>,
 }
 
+/// The outcome of `ApiClient::flatten_positions`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FlattenReport {
+    /// The symbols whose positions were closed.
+    pub closed: Vec<String>,
+    /// The symbols whose closing order failed.
+    pub failed: Vec<FlattenFailure>,
+}
+
+/// A position that `ApiClient::flatten_positions` could not close.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlattenFailure {
+    pub symbol: String,
+    pub error: String,
+}
+
 // Keep the old type for backward compatibility
 #[deprecated(note = "Use AccountState instead")]
 pub type FuturesAccountInfo = AccountState;
@@ -134,9 +176,26 @@ pub struct WsKline {
 }
 
 
+/// Represents a mark-price update from the `<symbol>@markPrice` WebSocket stream.
+#[derive(Debug, Deserialize, Clone)]
+pub struct MarkPriceUpdate {
+    #[serde(rename = "s")]
+    pub symbol: Symbol,
+    #[serde(rename = "p")]
+    pub mark_price: Decimal,
+    /// The current funding rate (e.g., 0.0001 for 0.01%).
+    #[serde(rename = "r")]
+    pub funding_rate: Decimal,
+    /// The time of the next funding payment, as a Unix timestamp in milliseconds.
+    #[serde(rename = "T")]
+    pub next_funding_time: i64,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct NewOrderResponse {
+    #[serde(default)]
+    pub order_id: Option<i64>,
     pub symbol: String,
     pub side: String, // "BUY" or "SELL"
     pub r#type: String, // "MARKET", "LIMIT", etc.
@@ -144,3 +203,119 @@ pub struct NewOrderResponse {
     pub executed_qty: Decimal, // The actual filled quantity
     pub cum_quote: Decimal, // The cumulative quote asset transacted
 }
+
+/// A snapshot of the order book, as returned by the depth endpoint.
+///
+/// Each level is a `(price, quantity)` pair. Bids are sorted from best (highest) to
+/// worst, and asks from best (lowest) to worst.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBook {
+    /// The update ID of this snapshot, used to sync with the diff-depth stream.
+    pub last_update_id: i64,
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
+}
+
+impl OrderBook {
+    /// Returns the midpoint between the best bid and the best ask, if both sides have liquidity.
+    pub fn mid_price(&self) -> Option<Decimal> {
+        let (best_bid, _) = self.bids.first()?;
+        let (best_ask, _) = self.asks.first()?;
+        Some((best_bid + best_ask) / Decimal::from(2))
+    }
+
+    /// Estimates the average fill price of a market order of `quantity` by walking the book.
+    ///
+    /// A buy consumes the asks and a sell consumes the bids. Returns `None` if the visible
+    /// depth is not enough to fill the full quantity.
+    pub fn estimate_fill_price(&self, is_buy: bool, quantity: Decimal) -> Option<Decimal> {
+        if quantity <= Decimal::ZERO {
+            return None;
+        }
+        let levels = if is_buy { &self.asks } else { &self.bids };
+
+        let mut remaining = quantity;
+        let mut cost = Decimal::ZERO;
+        for (price, level_qty) in levels {
+            let filled = remaining.min(*level_qty);
+            cost += filled * price;
+            remaining -= filled;
+            if remaining.is_zero() {
+                return Some(cost / quantity);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// A trimmed response of `GET /fapi/v1/depth`.
+    const DEPTH_RESPONSE: &str = r#"{
+        "lastUpdateId": 1027024,
+        "E": 1589436922972,
+        "T": 1589436922959,
+        "bids": [["67000.10", "1.500"], ["66999.90", "2.000"]],
+        "asks": [["67000.30", "0.500"], ["67000.50", "3.000"]]
+    }"#;
+
+    fn order_book() -> OrderBook {
+        serde_json::from_str(DEPTH_RESPONSE).unwrap()
+    }
+
+    #[test]
+    fn deserializes_a_depth_response() {
+        let book = order_book();
+
+        assert_eq!(book.last_update_id, 1027024);
+        assert_eq!(book.bids, vec![(dec!(67000.10), dec!(1.5)), (dec!(66999.90), dec!(2))]);
+        assert_eq!(book.asks, vec![(dec!(67000.30), dec!(0.5)), (dec!(67000.50), dec!(3))]);
+    }
+
+    #[test]
+    fn mid_price_is_between_the_best_bid_and_ask() {
+        assert_eq!(order_book().mid_price(), Some(dec!(67000.20)));
+
+        let one_sided = OrderBook { last_update_id: 1, bids: Vec::new(), asks: order_book().asks };
+        assert_eq!(one_sided.mid_price(), None);
+    }
+
+    #[test]
+    fn fill_estimate_walks_the_book() {
+        let book = order_book();
+
+        // 0.5 at 67000.30 and 0.5 at 67000.50.
+        assert_eq!(book.estimate_fill_price(true, dec!(1)), Some(dec!(67000.40)));
+        assert_eq!(book.estimate_fill_price(false, dec!(1)), Some(dec!(67000.10)));
+        assert_eq!(book.estimate_fill_price(true, dec!(10)), None);
+    }
+
+    #[test]
+    fn deserializes_a_mark_price_event_from_its_stream_envelope() {
+        let message = r#"{
+            "stream": "btcusdt@markPrice",
+            "data": {
+                "e": "markPriceUpdate",
+                "E": 1562305380000,
+                "s": "BTCUSDT",
+                "p": "11794.15000000",
+                "i": "11784.62659091",
+                "P": "11784.25641265",
+                "r": "0.00038167",
+                "T": 1562306400000
+            }
+        }"#;
+        let envelope: serde_json::Value = serde_json::from_str(message).unwrap();
+
+        let update: MarkPriceUpdate = serde_json::from_value(envelope["data"].clone()).unwrap();
+
+        assert_eq!(update.symbol, Symbol::new("BTCUSDT").unwrap());
+        assert_eq!(update.mark_price, dec!(11794.15));
+        assert_eq!(update.funding_rate, dec!(0.00038167));
+        assert_eq!(update.next_funding_time, 1562306400000);
+    }
+}
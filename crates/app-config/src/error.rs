@@ -13,6 +13,9 @@ pub enum Error {
     
     #[error("Failed to parse TOML: {0}")]
     TomlError(#[from] toml::de::Error),
+
+    #[error("Invalid configuration: {0}")]
+    Invalid(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
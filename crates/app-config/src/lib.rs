@@ -32,6 +32,9 @@ pub fn load_settings() -> Result<Settings> {
     // Deserialize the configuration into our `Settings` struct.
     let settings: Settings = settings.try_deserialize()?;
 
+    // 4. Reject invalid values now rather than as confusing failures at runtime.
+    settings.validate()?;
+
     Ok(settings)
 }
 
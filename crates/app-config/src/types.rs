@@ -2,14 +2,34 @@
 
 use serde::Deserialize;
 // Import the settings struct from our strategies crate
-use strategies::types::{MACrossoverSettings, ProbReversionSettings, SuperTrendSettings};
+use strategies::registry::{StrategyKind, StrategyParams};
+use strategies::types::{
+    EnsembleSettings, KeltnerSettings, MACrossoverSettings, ProbReversionSettings, RsiDivergenceSettings,
+    StochasticSettings, SuperTrendSettings,
+};
 use risk::types::SimpleRiskSettings;
-// use execution::types::SimulationSettings; // Removed to break cyclic dependency
+use core_types::SimulationSettings;
+use analytics::types::AnalyticsConfig;
+use crate::error::{Error, Result};
 
 #[derive(Deserialize, Debug)]
 pub struct ServerSettings {
     pub host: String,
     pub port: u16,
+    /// The number of recent WebSocket messages replayed to newly connected clients.
+    #[serde(default = "default_ws_cache_size")]
+    pub ws_cache_size: usize,
+    /// The bearer token required by privileged endpoints such as `POST /api/live/flatten`.
+    /// Those endpoints are disabled while it is unset.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+}
+
+/// The default number of recent WebSocket messages kept for replay.
+pub const DEFAULT_WS_CACHE_SIZE: usize = 200;
+
+fn default_ws_cache_size() -> usize {
+    DEFAULT_WS_CACHE_SIZE
 }
 
 #[derive(Deserialize, Debug)]
@@ -25,23 +45,236 @@ pub struct Settings {
     #[serde(default)]
     pub strategies: StrategySettings,
 
-    // pub simulation: Option<SimulationSettings>, // Removed to break cyclic dependency
+    /// Fee and slippage settings for simulated execution (backtests and paper trading).
+    pub simulation: Option<SimulationSettings>,
 
     pub simple_risk_manager: Option<SimpleRiskSettings>,
+
+    /// Settings for the performance metrics of backtest reports.
+    #[serde(default)]
+    pub analytics: AnalyticsConfig,
+}
+
+impl Settings {
+    /// Checks that the loaded values are within their valid ranges and that required
+    /// fields are present, returning `Error::Invalid` describing the first problem found.
+    pub fn validate(&self) -> Result<()> {
+        ensure(!self.binance.rest_base_url.trim().is_empty(), "binance.rest_base_url must not be empty")?;
+        ensure(!self.binance.ws_base_url.trim().is_empty(), "binance.ws_base_url must not be empty")?;
+        ensure(!self.database.url.trim().is_empty(), "database.url must not be empty")?;
+        ensure(self.database.max_connections != Some(0), "database.max_connections must be at least 1")?;
+        ensure(self.server.port != 0, "server.port must not be 0")?;
+        ensure(self.app.initial_capital > 0.0, "app.initial_capital must be positive")?;
+        if let Ok(available) = std::thread::available_parallelism() {
+            ensure(
+                self.app.optimizer_cores as usize <= available.get(),
+                &format!("app.optimizer_cores must be 0 (all cores) or at most {}", available),
+            )?;
+        }
+        ensure(
+            self.app.equity_snapshot_interval_secs > 0,
+            "app.equity_snapshot_interval_secs must be at least 1",
+        )?;
+        ensure(
+            self.app.reconcile_interval_secs >= MIN_RECONCILE_INTERVAL_SECS,
+            &format!("app.reconcile_interval_secs must be at least {}", MIN_RECONCILE_INTERVAL_SECS),
+        )?;
+
+        if let Some(sim) = &self.simulation {
+            ensure(sim.maker_fee >= 0.0, "simulation.maker_fee must not be negative")?;
+            ensure(sim.taker_fee >= 0.0, "simulation.taker_fee must not be negative")?;
+            ensure(sim.slippage_percent >= 0.0, "simulation.slippage_percent must not be negative")?;
+        }
+
+        ensure(self.analytics.risk_free_rate > -1.0, "analytics.risk_free_rate must be greater than -1")?;
+
+        if let Some(risk) = &self.simple_risk_manager {
+            ensure(
+                risk.risk_per_trade_percent > 0.0 && risk.risk_per_trade_percent <= 1.0,
+                "simple_risk_manager.risk_per_trade_percent must be in (0, 1]",
+            )?;
+            ensure(
+                risk.stop_loss_percent > 0.0 && risk.stop_loss_percent < 1.0,
+                "simple_risk_manager.stop_loss_percent must be in (0, 1)",
+            )?;
+            ensure(
+                (0.0..=1.0).contains(&risk.minimum_confidence_threshold),
+                "simple_risk_manager.minimum_confidence_threshold must be in [0, 1]",
+            )?;
+            ensure(risk.leverage >= 1, "simple_risk_manager.leverage must be at least 1")?;
+            if let (Some(min), Some(max)) = (risk.min_position_notional, risk.max_position_notional) {
+                ensure(
+                    min <= max,
+                    "simple_risk_manager.min_position_notional must not exceed max_position_notional",
+                )?;
+            }
+            if let Some(vol) = &risk.volatility_leverage {
+                ensure(
+                    vol.min_leverage >= 1 && vol.min_leverage <= vol.max_leverage,
+                    "simple_risk_manager.volatility_leverage needs 1 <= min_leverage <= max_leverage",
+                )?;
+                ensure(vol.lookback >= 1, "simple_risk_manager.volatility_leverage.lookback must be at least 1")?;
+            }
+        }
+
+        self.strategies.validate()
+    }
+}
+
+impl StrategySettings {
+    /// Returns the configured settings of `kind`, if any.
+    pub fn params(&self, kind: StrategyKind) -> Option<StrategyParams> {
+        match kind {
+            StrategyKind::MaCrossover => self.ma_crossover.clone().map(StrategyParams::MaCrossover),
+            StrategyKind::SuperTrend => self.supertrend.clone().map(StrategyParams::SuperTrend),
+            StrategyKind::ProbReversion => self.prob_reversion.clone().map(StrategyParams::ProbReversion),
+            StrategyKind::RsiDivergence => self.rsi_divergence.clone().map(StrategyParams::RsiDivergence),
+            StrategyKind::Stochastic => self.stochastic.clone().map(StrategyParams::Stochastic),
+            StrategyKind::Keltner => self.keltner.clone().map(StrategyParams::Keltner),
+            StrategyKind::Ensemble => self.ensemble.clone().map(StrategyParams::Ensemble),
+        }
+    }
+
+    /// Returns the settings of the first configured strategy, in `StrategyKind::ALL` order.
+    pub fn first_configured(&self) -> Option<StrategyParams> {
+        StrategyKind::ALL.into_iter().find_map(|kind| self.params(kind))
+    }
+
+    /// Checks the strategy parameters for invalid periods and cross-field inconsistencies.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(s) = &self.ma_crossover {
+            ensure(
+                s.h1_fast_period > 0 && s.h1_fast_period < s.h1_slow_period,
+                "strategies.ma_crossover needs 0 < h1_fast_period < h1_slow_period",
+            )?;
+            ensure(
+                s.m5_fast_period > 0 && s.m5_fast_period < s.m5_slow_period,
+                "strategies.ma_crossover needs 0 < m5_fast_period < m5_slow_period",
+            )?;
+            ensure_confidence("ma_crossover", s.confidence)?;
+        }
+        if let Some(s) = &self.supertrend {
+            ensure(s.period > 0, "strategies.supertrend.period must be at least 1")?;
+            ensure(s.multiplier > 0.0, "strategies.supertrend.multiplier must be positive")?;
+            ensure(s.exit_multiplier > 0.0, "strategies.supertrend.exit_multiplier must be positive")?;
+            ensure_confidence("supertrend", s.confidence)?;
+        }
+        if let Some(s) = &self.prob_reversion {
+            ensure(s.bband_period > 1, "strategies.prob_reversion.bband_period must be at least 2")?;
+            ensure(s.adx_period > 0, "strategies.prob_reversion.adx_period must be at least 1")?;
+            ensure(s.rsi_period > 0, "strategies.prob_reversion.rsi_period must be at least 1")?;
+            ensure(
+                (0.0..=100.0).contains(&s.rsi_oversold),
+                "strategies.prob_reversion.rsi_oversold must be in [0, 100]",
+            )?;
+            ensure_confidence("prob_reversion", s.confidence)?;
+        }
+        if let Some(s) = &self.rsi_divergence {
+            ensure(s.rsi_period > 0, "strategies.rsi_divergence.rsi_period must be at least 1")?;
+            ensure(s.lookback_swings > 0, "strategies.rsi_divergence.lookback_swings must be at least 1")?;
+            ensure_confidence("rsi_divergence", s.confidence)?;
+        }
+        if let Some(s) = &self.stochastic {
+            ensure(s.k_period > 0, "strategies.stochastic.k_period must be at least 1")?;
+            ensure(s.d_period > 0, "strategies.stochastic.d_period must be at least 1")?;
+            ensure(s.smoothing > 0, "strategies.stochastic.smoothing must be at least 1")?;
+            ensure(
+                0.0 <= s.oversold && s.oversold < s.overbought && s.overbought <= 100.0,
+                "strategies.stochastic needs 0 <= oversold < overbought <= 100",
+            )?;
+            ensure_confidence("stochastic", s.confidence)?;
+        }
+        if let Some(s) = &self.keltner {
+            ensure(s.ema_period > 0, "strategies.keltner.ema_period must be at least 1")?;
+            ensure(s.atr_period > 0, "strategies.keltner.atr_period must be at least 1")?;
+            ensure(s.multiplier > 0.0, "strategies.keltner.multiplier must be positive")?;
+            ensure_confidence("keltner", s.confidence)?;
+        }
+        if let Some(s) = &self.ensemble {
+            ensure(!s.members.is_empty(), "strategies.ensemble needs at least one member")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `Error::Invalid(message)` unless `condition` holds.
+fn ensure(condition: bool, message: &str) -> Result<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(Error::Invalid(message.to_string()))
+    }
+}
+
+/// Checks that a strategy's `confidence` is in `[0, 1]`.
+fn ensure_confidence(strategy: &str, confidence: f64) -> Result<()> {
+    ensure(
+        (0.0..=1.0).contains(&confidence),
+        &format!("strategies.{}.confidence must be in [0, 1]", strategy),
+    )
 }
 
 #[derive(Deserialize, Debug)]
 pub struct AppSettings {
     /// The environment the application is running in (e.g., "development", "production").
     pub environment: String,
-    /// The log level for the application.
+    /// The log level for the application, as an `EnvFilter` directive string
+    /// (e.g. `"info"` or `"info,engine=debug"`). `RUST_LOG` directives are applied on top.
     pub log_level: String,
+    /// The least severe level of log events broadcast to WebSocket clients. Defaults to `"info"`.
+    #[serde(default)]
+    pub ws_log_level: Option<String>,
 
+    /// The number of threads the optimizer runs backtests on. `0` uses all available cores;
+    /// larger values than the machine has are rejected by `Settings::validate`.
     pub optimizer_cores: u32,
+    /// Whether `run` places real orders or paper-trades on live data. Takes precedence
+    /// over `live_trading_enabled`.
+    #[serde(default)]
+    pub trading_mode: Option<TradingMode>,
+    /// The starting cash of the `run` portfolio. Defaults to 10,000. In live mode it is
+    /// replaced by the exchange's wallet balance on the first reconciliation.
+    #[serde(default = "default_initial_capital")]
+    pub initial_capital: f64,
+    /// How often the live portfolio's equity is persisted, in seconds. Defaults to 60.
+    #[serde(default = "default_equity_snapshot_interval_secs")]
+    pub equity_snapshot_interval_secs: u64,
+    /// Whether live trading periodically reconciles the portfolio with the exchange.
+    /// Defaults to `true`; disable it when relying on the user-data stream alone.
+    #[serde(default = "default_as_true")]
+    pub reconciler_enabled: bool,
+    /// How often the reconciler polls the exchange, in seconds. Defaults to 60 and must be
+    /// at least `MIN_RECONCILE_INTERVAL_SECS`.
+    #[serde(default = "default_reconcile_interval_secs")]
+    pub reconcile_interval_secs: u64,
+    /// Deprecated: use `trading_mode` instead. Only consulted if `trading_mode` is unset.
     #[serde(default)] // This makes the field optional, defaulting to `false`
     pub live_trading_enabled: bool,
 }
 
+impl AppSettings {
+    /// The trading mode to run in, falling back to `live_trading_enabled` and then to
+    /// `TradingMode::Paper`.
+    pub fn effective_trading_mode(&self) -> TradingMode {
+        self.trading_mode.unwrap_or(if self.live_trading_enabled {
+            TradingMode::Live
+        } else {
+            TradingMode::Paper
+        })
+    }
+}
+
+/// How the live engine executes orders.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TradingMode {
+    /// Consumes live market data but fills orders with the `SimulatedExecutor`; no
+    /// orders reach the exchange.
+    Paper,
+    /// Places real orders on the exchange.
+    Live,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct BinanceSettings {
     /// The API key for Binance.
@@ -56,10 +289,19 @@ pub struct BinanceSettings {
 pub struct DatabaseSettings {
     /// The connection URL for the PostgreSQL database.
     pub url: String,
+    /// The maximum number of pooled connections. Defaults to 10.
+    #[serde(default)]
+    pub max_connections: Option<u32>,
+    /// How long to wait for a free connection before failing, in seconds. Defaults to 30.
+    #[serde(default)]
+    pub acquire_timeout_secs: Option<u64>,
+    /// How long an unused connection may stay idle before it is closed, in seconds. Defaults to 600.
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
 }
 
 // Define the container for all strategy settings
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Debug, Default, Clone)]
 pub struct StrategySettings {
     // Each strategy will have its own optional settings block
     pub ma_crossover: Option<MACrossoverSettings>,
@@ -67,6 +309,10 @@ pub struct StrategySettings {
     // pub rsi_reversal: Option<RSIReversalSettings>,
     pub supertrend: Option<SuperTrendSettings>, 
     pub prob_reversion: Option<ProbReversionSettings>,
+    pub rsi_divergence: Option<RsiDivergenceSettings>,
+    pub stochastic: Option<StochasticSettings>,
+    pub keltner: Option<KeltnerSettings>,
+    pub ensemble: Option<EnsembleSettings>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -82,12 +328,206 @@ pub struct BotConfig {
     #[serde(default = "default_as_true")]
     pub enabled: bool,
     pub symbol: String,
+    /// The interval the strategy is assessed and trades on.
     pub interval: String,
+    /// Further intervals of the same symbol whose klines are buffered and handed to the
+    /// strategy as context (e.g. `["1h"]` for a 5m strategy with an hourly filter).
+    #[serde(default)]
+    pub context_intervals: Vec<String>,
     pub strategy_name: String,
     pub strategy_params: String, // The key to look up in StrategySettings
 }
 
+fn default_initial_capital() -> f64 {
+    10_000.0
+}
+
+fn default_equity_snapshot_interval_secs() -> u64 {
+    60
+}
+
+/// The shortest reconciliation interval allowed, to stay clear of the exchange's rate limits.
+pub const MIN_RECONCILE_INTERVAL_SECS: u64 = 5;
+
+fn default_reconcile_interval_secs() -> u64 {
+    60
+}
+
 // Helper for serde to default `enabled` to true if missing.
 fn default_as_true() -> bool {
     true
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::{Config, File, FileFormat};
+
+    /// Deserializes `toml` the same way `load_settings` does.
+    fn parse<T: serde::de::DeserializeOwned>(toml: &str) -> T {
+        Config::builder()
+            .add_source(File::from_str(toml, FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn ensemble_strategy_is_loaded_from_config() {
+        let strategies: StrategySettings = parse(
+            r#"
+            [ensemble]
+            rule = "unanimous"
+            [[ensemble.members]]
+            strategy = "keltner"
+            params = { ema_period = 20, atr_period = 10, multiplier = 2.0, confidence = 1.0 }
+            [[ensemble.members]]
+            strategy = "rsi_divergence"
+            params = { rsi_period = 14, lookback_swings = 3, confidence = 1.0 }
+            "#,
+        );
+
+        strategies.validate().unwrap();
+        let params = strategies.first_configured().unwrap();
+        assert_eq!(params.kind(), StrategyKind::Ensemble);
+        assert_eq!(params.build().name(), "Ensemble");
+    }
+
+    #[test]
+    fn ensemble_without_members_is_rejected() {
+        let strategies: StrategySettings = parse(
+            r#"
+            [ensemble]
+            rule = "majority_vote"
+            members = []
+            "#,
+        );
+
+        assert!(strategies.validate().is_err());
+    }
+
+    /// The `[simulation]` table of a config file, ignoring everything else.
+    #[derive(Deserialize)]
+    struct SimulationTable {
+        simulation: SimulationSettings,
+    }
+
+    #[test]
+    fn simulation_table_is_loaded_from_config() {
+        let wrapper: SimulationTable = parse(
+            r#"
+            [simulation]
+            maker_fee = 0.0001
+            taker_fee = 0.0005
+            slippage_percent = 0.001
+            "#,
+        );
+
+        assert_eq!(wrapper.simulation.maker_fee, 0.0001);
+        assert_eq!(wrapper.simulation.taker_fee, 0.0005);
+        assert_eq!(wrapper.simulation.slippage_percent, 0.001);
+        assert!(!wrapper.simulation.allow_pyramiding);
+    }
+
+    #[test]
+    fn base_config_charges_fees_by_default() {
+        let SimulationTable { simulation } = parse(include_str!("../../../config/base.toml"));
+
+        assert!(simulation.taker_fee > 0.0);
+        assert!(simulation.maker_fee > 0.0);
+    }
+
+    /// The base config plus the environment-specific `[binance]` table.
+    fn valid_settings() -> Settings {
+        parse(&format!(
+            "{}\n{}",
+            include_str!("../../../config/base.toml"),
+            r#"
+            [binance]
+            api_key = "key"
+            secret_key = "secret"
+            rest_base_url = "https://testnet.binancefuture.com"
+            ws_base_url = "wss://fstream.binancefuture.com"
+            "#
+        ))
+    }
+
+    /// Asserts that `settings` fail validation with a message mentioning `field`.
+    fn assert_invalid(settings: Settings, field: &str) {
+        match settings.validate() {
+            Err(Error::Invalid(message)) => assert!(message.contains(field), "{} should mention {}", message, field),
+            other => panic!("expected {} to be rejected, got {:?}", field, other),
+        }
+    }
+
+    #[test]
+    fn base_config_is_valid() {
+        valid_settings().validate().unwrap();
+    }
+
+    #[test]
+    fn zero_optimizer_cores_means_all_cores() {
+        let mut settings = valid_settings();
+        settings.app.optimizer_cores = 0;
+        settings.validate().unwrap();
+
+        settings.app.optimizer_cores = u32::MAX;
+        assert_invalid(settings, "app.optimizer_cores");
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected_with_the_offending_field() {
+        let mut settings = valid_settings();
+        settings.binance.rest_base_url = " ".to_string();
+        assert_invalid(settings, "binance.rest_base_url");
+
+        let mut settings = valid_settings();
+        settings.simulation.as_mut().unwrap().taker_fee = -0.001;
+        assert_invalid(settings, "simulation.taker_fee");
+
+        let mut settings = valid_settings();
+        settings.app.initial_capital = 0.0;
+        assert_invalid(settings, "app.initial_capital");
+
+        let mut settings = valid_settings();
+        settings.app.reconcile_interval_secs = MIN_RECONCILE_INTERVAL_SECS - 1;
+        assert_invalid(settings, "app.reconcile_interval_secs");
+
+        let mut settings = valid_settings();
+        settings.database.max_connections = Some(0);
+        assert_invalid(settings, "database.max_connections");
+    }
+
+    #[test]
+    fn ma_crossover_fast_period_must_be_below_slow_period() {
+        let strategies: StrategySettings = parse(
+            r#"
+            [ma_crossover]
+            h1_fast_period = 50
+            h1_slow_period = 20
+            m5_fast_period = 5
+            m5_slow_period = 10
+            confidence = 0.8
+            "#,
+        );
+
+        match strategies.validate() {
+            Err(Error::Invalid(message)) => assert!(message.contains("h1_fast_period < h1_slow_period")),
+            other => panic!("expected the periods to be rejected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn trading_mode_defaults_to_paper_and_overrides_the_legacy_flag() {
+        let mut app = valid_settings().app;
+        app.trading_mode = None;
+        app.live_trading_enabled = false;
+        assert_eq!(app.effective_trading_mode(), TradingMode::Paper);
+
+        app.live_trading_enabled = true;
+        assert_eq!(app.effective_trading_mode(), TradingMode::Live);
+
+        app.trading_mode = Some(TradingMode::Paper);
+        assert_eq!(app.effective_trading_mode(), TradingMode::Paper);
+    }
+}
@@ -1,18 +1,22 @@
 pub mod error;
+pub mod multi;
 pub mod types;
 
 use std::collections::HashMap;
 
 use analytics::engine::AnalyticsEngine;
-use analytics::types::{EquityPoint, PerformanceReport, Trade};
+use analytics::types::{EntryReason, EquityPoint, ExitReason, PerformanceReport, Trade};
 use chrono::{DateTime, TimeZone, Utc};
-use core_types::{Kline, OrderRequest, Side, Signal};
+use core_types::{Kline, OrderRequest, OrderType, Position, Side, Signal, Symbol};
+use events::WsMessage;
 use execution::{Executor, Portfolio};
 use rust_decimal::prelude::*;
 use rust_decimal_macros::dec;
 use risk::RiskManager;
 use strategies::Strategy;
-use tracing::{error, info, warn};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use types::BacktestConfig;
 
 // Define a simple logger for backtesting
 #[derive(Debug)]
@@ -20,6 +24,10 @@ pub struct BacktestLogger {
     pub trades: Vec<Trade>,
     pub equity_points: Vec<EquityPoint>,
     initial_equity: Decimal,
+    /// The (adverse, favorable) excursions seen so far for each open position.
+    excursions: HashMap<Symbol, (Decimal, Decimal)>,
+    /// Why each open position was opened.
+    entry_reasons: HashMap<Symbol, EntryReason>,
 }
 
 impl BacktestLogger {
@@ -28,9 +36,45 @@ impl BacktestLogger {
             trades: Vec::new(),
             equity_points: Vec::new(),
             initial_equity,
+            excursions: HashMap::new(),
+            entry_reasons: HashMap::new(),
         }
     }
 
+    /// Remembers why the position on `symbol` was opened.
+    pub fn track_entry(&mut self, symbol: &Symbol, reason: EntryReason) {
+        self.entry_reasons.insert(symbol.clone(), reason);
+    }
+
+    /// Returns why `position` was opened. The reason is kept while part of the position
+    /// remains open, so later partial closes report it too.
+    pub fn entry_reason(&self, position: &Position) -> EntryReason {
+        self.entry_reasons.get(&position.symbol).copied().unwrap_or_default()
+    }
+
+    /// Widens the excursions of `position` with a price range it traded through.
+    pub fn track_excursion(&mut self, position: &Position, low: Decimal, high: Decimal) {
+        let (adverse, favorable) = match position.side {
+            Side::Long => (position.entry_price - low, high - position.entry_price),
+            Side::Short => (high - position.entry_price, position.entry_price - low),
+        };
+        let excursion = self
+            .excursions
+            .entry(position.symbol.clone())
+            .or_insert((Decimal::ZERO, Decimal::ZERO));
+        excursion.0 = excursion.0.max(adverse);
+        excursion.1 = excursion.1.max(favorable);
+    }
+
+    /// Returns the final (adverse, favorable) excursions of `position`, closed at `exit_price`,
+    /// and stops tracking it.
+    pub fn take_excursion(&mut self, position: &Position, exit_price: Decimal) -> (Decimal, Decimal) {
+        self.track_excursion(position, exit_price, exit_price);
+        self.excursions
+            .remove(&position.symbol)
+            .unwrap_or((Decimal::ZERO, Decimal::ZERO))
+    }
+
     pub fn record_trade(&mut self, trade: &Trade, _execution: &core_types::Execution, timestamp: i64) {
         self.trades.push(trade.clone());
         self.record_equity(Utc.timestamp_millis_opt(timestamp).unwrap(), self.current_equity());
@@ -63,17 +107,27 @@ pub struct Backtester {
     pub executor: Box<dyn Executor>,
     logger: BacktestLogger,
     portfolio: Portfolio,
+    config: BacktestConfig,
+    cancel: Option<CancellationToken>,
 }
 
-const KLINE_HISTORY_SIZE: usize = 100;
+/// The number of processed bars between two `BacktestProgress` events.
+const PROGRESS_INTERVAL_BARS: usize = 10_000;
+/// The maintenance margin rate used to compute liquidation prices (Binance's lowest tier).
+const MAINTENANCE_MARGIN_RATE: Decimal = dec!(0.004);
 
 impl Backtester {
+    /// Creates a backtester with the tunables in `config`.
+    ///
+    /// `executor` is taken as-is; `config.simulated_executor` builds the usual one from the
+    /// same config's fees and seed.
     pub fn new(
         symbol: core_types::Symbol,
         interval: String,
         strategy: Box<dyn Strategy + Send>,
         risk_manager: Box<dyn RiskManager + Send + Sync>,
         executor: Box<dyn Executor>,
+        config: BacktestConfig,
     ) -> Self {
         Self {
             symbol,
@@ -81,154 +135,126 @@ impl Backtester {
             strategy,
             risk_manager,
             executor,
-            logger: BacktestLogger::new(dec!(10_000)),
-            portfolio: Portfolio::new(dec!(10_000)), // Default initial capital for backtesting
+            logger: BacktestLogger::new(config.initial_capital),
+            portfolio: Portfolio::new(config.initial_capital),
+            config,
+            cancel: None,
         }
     }
 
-    // Change the return type from anyhow::Result<()> to anyhow::Result<PerformanceReport>
-    pub async fn run(&mut self, klines: Vec<Kline>) -> anyhow::Result<(PerformanceReport, Vec<Trade>, Vec<EquityPoint>)> {
-        for i in KLINE_HISTORY_SIZE..klines.len() {
-            let current_kline = &klines[i];
-            let history_slice = &klines[(i - KLINE_HISTORY_SIZE)..i];
+    /// Makes `run` stop at the next bar once `token` is cancelled. The report then covers the
+    /// bars processed so far and has `cancelled` set.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
 
-            // --- At the beginning of the loop ---
-            self.logger.record_equity(
-                Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
-                self.portfolio.cash
-            );
+    /// Runs the backtest over `klines`.
+    ///
+    /// If `progress_tx` is given, a `WsMessage::BacktestProgress` event is sent every
+    /// `PROGRESS_INTERVAL_BARS` bars, plus a final one once all bars are processed.
+    ///
+    /// With `BacktestConfig::max_drawdown_abort_percent` set, the run stops early once the
+    /// mark-to-market equity falls below the threshold. A run stopped through `with_cancellation` likewise returns the
+    /// partial report.
+    pub async fn run(
+        &mut self,
+        klines: Vec<Kline>,
+        progress_tx: Option<broadcast::Sender<WsMessage>>,
+    ) -> anyhow::Result<(PerformanceReport, Vec<Trade>, Vec<EquityPoint>)> {
+        // The strategy and the order fills need at least the previous kline.
+        let history_size = self
+            .config
+            .history_size
+            .unwrap_or_else(|| self.strategy.required_history())
+            .max(1);
+        let total_bars = klines.len().saturating_sub(history_size);
+        let send_progress = |processed_bars: usize, open_time: i64| {
+            if let Some(tx) = &progress_tx {
+                let _ = tx.send(WsMessage::BacktestProgress {
+                    processed_bars,
+                    total_bars,
+                    current_time: Utc.timestamp_millis_opt(open_time).unwrap(),
+                });
+            }
+        };
 
-            // --- 1. Check for Stop-Loss Trigger ---
-            let position_to_check = self.portfolio.open_positions.get(&self.symbol).cloned();
-            if let Some(open_position) = position_to_check {
-                let stop_triggered = if open_position.side == Side::Long {
-                    current_kline.low <= open_position.sl_price
-                } else {
-                    current_kline.high >= open_position.sl_price
-                };
+        // Warm up the strategy with every kline before the last one of the first history slice,
+        // which is the first kline the strategy will assess.
+        if klines.len() > history_size {
+            self.strategy.warm_up(&klines[..history_size - 1]);
+        }
 
-                if stop_triggered {
-                    tracing::info!(
-                        time = %Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
-                        sl_price = open_position.sl_price.to_f64().unwrap_or(0.0),
-                        trigger_price = if open_position.side == Side::Long { current_kline.low.to_f64().unwrap_or(0.0) } else { current_kline.high.to_f64().unwrap_or(0.0) },
-                        "Stop-loss triggered!"
-                    );
-
-                    let close_order = OrderRequest {
-                        symbol: open_position.symbol.clone(),
-                        side: if open_position.side == Side::Long { Side::Short } else { Side::Long },
-                        quantity: open_position.quantity,
-                        leverage: open_position.leverage,
-                        sl_price: dec!(0),
-                        originating_signal: Signal::Close,
-                    };
-
-                    let execution_result = self.executor.execute(
-                        &close_order,
-                        open_position.sl_price,
-                        current_kline.open_time,
-                        &mut self.portfolio
-                    ).await;
-                    if let Ok((execution, Some(closed_pos))) = execution_result {
-                        // Convert Position to Trade for logging
-                        let trade = Trade {
-                            symbol: closed_pos.symbol.clone(),
-                            side: execution.side,
-                            entry_time: Utc.timestamp_millis_opt(closed_pos.entry_time).unwrap(),
-                            exit_time: Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
-                            entry_price: closed_pos.entry_price,
-                            exit_price: execution.price,
-                            quantity: execution.quantity,
-                            pnl: Decimal::ZERO, // Will be calculated by analytics
-                            fees: execution.fee,
-                            signal_confidence: 0.0, // TODO: Get from signal if available
-                            leverage: closed_pos.leverage,
-                        };
-                        self.logger.record_trade(&trade, &execution, current_kline.open_time);
-                        tracing::info!(?execution, "Stop-loss order executed.");
-                    } else if let Ok((execution, None)) = execution_result {
-                        tracing::warn!(?execution, "Stop-loss order executed but no closed position returned.");
-                    } else if let Err(e) = execution_result {
-                        tracing::error!(error = %e, "Failed to execute stop-loss order.");
-                    }
-                    continue;
-                }
+        let abort_equity = self.config.max_drawdown_abort_percent.map(|percent| {
+            self.portfolio.initial_capital
+                * (Decimal::ONE - Decimal::from_f64(percent / 100.0).unwrap_or(Decimal::ZERO))
+        });
+        let mut aborted = false;
+        let mut cancelled = false;
+
+        for i in history_size..klines.len() {
+            let current_kline = &klines[i];
+            if self.cancel.as_ref().is_some_and(|token| token.is_cancelled()) {
+                tracing::warn!(
+                    time = %Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
+                    "Backtest cancelled."
+                );
+                cancelled = true;
+                break;
             }
+            let history_slice = &klines[(i - history_size)..i];
 
-            // --- 2. Assess Strategy for New Signals (if no SL was hit) ---
-            let signal = self.strategy.assess(history_slice);
-            if matches!(signal, Signal::Hold) {
-                continue;
+            let processed_bars = i - history_size;
+            if processed_bars > 0 && processed_bars.is_multiple_of(PROGRESS_INTERVAL_BARS) {
+                send_progress(processed_bars, current_kline.open_time);
             }
 
-            // --- 3. Evaluate Signal with Risk Manager ---
-            let portfolio_value = self.portfolio.cash;
-            let open_position = self.portfolio.open_positions.get(&self.symbol);
-            let calculation_kline = &klines[i - 1];
-            let order_request_result = self.risk_manager.evaluate(
-                &signal,
+            // --- 1-4. Protective Exits, Strategy, Risk and Execution ---
+            process_bar(
                 &self.symbol,
-                portfolio_value,
-                calculation_kline,
-                open_position,
-            );
+                self.strategy.as_mut(),
+                self.risk_manager.as_ref(),
+                self.executor.as_mut(),
+                &mut self.portfolio,
+                &mut self.logger,
+                history_slice,
+                current_kline,
+            ).await;
 
-            // --- 4. Execute Approved Order ---
-            match order_request_result {
-                Ok(Some(order_request)) => {
-                    let execution_result = self.executor.execute(
-                        &order_request, 
-                        calculation_kline.close, 
-                        calculation_kline.open_time,
-                        &mut self.portfolio
-                    ).await;
-                    match execution_result {
-                        Ok((execution, Some(closed_pos))) => {
-                            // Convert Position to Trade for logging
-                    let trade = Trade {
-                        symbol: closed_pos.symbol.clone(),
-                        side: execution.side,
-                        entry_time: Utc.timestamp_millis_opt(closed_pos.entry_time).unwrap(),
-                        exit_time: Utc.timestamp_millis_opt(calculation_kline.open_time).unwrap(),
-                        entry_price: closed_pos.entry_price,
-                        exit_price: execution.price,
-                        quantity: execution.quantity,
-                        pnl: Decimal::ZERO, // Will be calculated by analytics
-                        fees: execution.fee,
-                        signal_confidence: 0.0, // TODO: Get from signal if available
-                        leverage: closed_pos.leverage,
-                    };
-                    self.logger.record_trade(&trade, &execution, calculation_kline.open_time);
-                            tracing::info!(?execution, "Order executed and trade logged.");
-                        }
-                        Ok((execution, None)) => {
-                            tracing::info!(?execution, "Order executed (entry or no position closed).");
-                        }
-                        Err(e) => {
-                            tracing::error!(error = %e, "Order execution failed.");
-                        }
-                    }
-                }
-                Ok(None) => {
-                    // No action needed
-                }
-                Err(e) => {
-                    tracing::warn!(error = %e, "Risk manager vetoed the signal.");
-                }
+            // Mark the open position to the bar's close, so that both the equity curve and
+            // the drawdown check include its unrealized P&L.
+            self.portfolio.update_mark_price(self.symbol.clone(), current_kline.close);
+            let equity = self.portfolio.total_value();
+            self.logger.record_equity(Utc.timestamp_millis_opt(current_kline.open_time).unwrap(), equity);
+
+            if let Some(abort_equity) = abort_equity
+                && equity < abort_equity
+            {
+                tracing::warn!(
+                    time = %Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
+                    equity = equity.to_f64().unwrap_or(0.0),
+                    "Equity fell below the drawdown abort threshold. Aborting backtest."
+                );
+                aborted = true;
+                break;
             }
         }
+        if let Some(last_kline) = klines.last() {
+            send_progress(total_bars, last_kline.open_time);
+        }
         tracing::info!(trades = ?self.logger.trades, "--- Logged Trades ---");
         tracing::info!(portfolio = ?self.portfolio, "Backtest finished. Final portfolio state:");
 
         // --- Analytics Calculation & Reporting ---
         let initial_capital = self.portfolio.initial_capital;
-        let analytics_engine = AnalyticsEngine::new();
-        let report = analytics_engine.calculate(
+        let analytics_engine = AnalyticsEngine::with_config(self.config.analytics);
+        let mut report = analytics_engine.calculate(
             initial_capital,
             &self.logger.trades,
             &self.logger.equity_points,
         );
+        report.aborted = aborted;
+        report.cancelled = cancelled;
 
         print_report(&report);
 
@@ -237,10 +263,244 @@ impl Backtester {
     }
 }
 
+/// The P&L of closing `position` with `execution`, net of its entry and exit fees.
+fn realized_pnl(position: &Position, execution: &core_types::Execution) -> Decimal {
+    let direction = if position.side == Side::Long { Decimal::ONE } else { Decimal::NEGATIVE_ONE };
+    (execution.price - position.entry_price) * execution.quantity * direction
+        - position.entry_fee
+        - execution.fee
+}
+
+/// Runs the full per-bar pipeline for one symbol: protective exits (liquidation, stop-loss,
+/// take-profit), then strategy assessment, risk evaluation and execution.
+///
+/// `history_slice` holds the klines preceding `current_kline`; orders are filled at the
+/// close of its last kline.
+#[allow(clippy::too_many_arguments)]
+async fn process_bar(
+    symbol: &core_types::Symbol,
+    strategy: &mut (dyn Strategy + Send),
+    risk_manager: &(dyn RiskManager + Send + Sync),
+    executor: &mut dyn Executor,
+    portfolio: &mut Portfolio,
+    logger: &mut BacktestLogger,
+    history_slice: &[Kline],
+    current_kline: &Kline,
+) {
+    // --- 1. Check for Liquidation / Stop-Loss / Take-Profit Trigger ---
+    let position_to_check = portfolio.open_positions.get(symbol).cloned();
+    if let Some(open_position) = position_to_check {
+        let stop_triggered = if open_position.side == Side::Long {
+            current_kline.low <= open_position.sl_price
+        } else {
+            current_kline.high >= open_position.sl_price
+        };
+        let liquidation_price = open_position.liquidation_price(MAINTENANCE_MARGIN_RATE);
+        let liquidation_triggered = if open_position.side == Side::Long {
+            current_kline.low <= liquidation_price
+        } else {
+            current_kline.high >= liquidation_price
+        };
+        // Whichever adverse level is closer to the entry is hit first.
+        let liquidated_before_stop = !stop_triggered
+            || (open_position.side == Side::Long && liquidation_price > open_position.sl_price)
+            || (open_position.side == Side::Short && liquidation_price < open_position.sl_price);
+        let tp_triggered = match open_position.tp_price {
+            Some(tp) if open_position.side == Side::Long => current_kline.high >= tp,
+            Some(tp) => current_kline.low <= tp,
+            None => false,
+        };
+
+        // If both levels fall inside the same bar we cannot know which was hit
+        // first, so we conservatively assume the stop-loss.
+        let exit = if liquidation_triggered && liquidated_before_stop {
+            tracing::warn!(
+                time = %Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
+                liquidation_price = liquidation_price.to_f64().unwrap_or(0.0),
+                leverage = open_position.leverage,
+                "Position liquidated!"
+            );
+            Some((liquidation_price, ExitReason::Liquidation))
+        } else if stop_triggered {
+            tracing::info!(
+                time = %Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
+                sl_price = open_position.sl_price.to_f64().unwrap_or(0.0),
+                trigger_price = if open_position.side == Side::Long { current_kline.low.to_f64().unwrap_or(0.0) } else { current_kline.high.to_f64().unwrap_or(0.0) },
+                "Stop-loss triggered!"
+            );
+            Some((open_position.sl_price, ExitReason::StopLoss))
+        } else if tp_triggered {
+            tracing::info!(
+                time = %Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
+                tp_price = open_position.tp_price.and_then(|tp| tp.to_f64()).unwrap_or(0.0),
+                "Take-profit triggered!"
+            );
+            open_position.tp_price.map(|tp| (tp, ExitReason::TakeProfit))
+        } else {
+            None
+        };
+
+        // On a protective exit the bar is only known to have reached the exit price, which
+        // `take_excursion` accounts for; otherwise the whole bar was traded through.
+        if exit.is_none() {
+            logger.track_excursion(&open_position, current_kline.low, current_kline.high);
+        }
+
+        if let Some((exit_price, exit_reason)) = exit {
+            let close_order = OrderRequest {
+                symbol: open_position.symbol.clone(),
+                side: if open_position.side == Side::Long { Side::Short } else { Side::Long },
+                quantity: open_position.quantity,
+                order_type: OrderType::Market,
+                leverage: open_position.leverage,
+                sl_price: dec!(0),
+                tp_price: None,
+                originating_signal: Signal::Close,
+            };
+
+            let execution_result = executor.execute(
+                &close_order,
+                exit_price,
+                current_kline.open_time,
+                portfolio
+            ).await;
+            if let Ok(Some((execution, Some(closed_pos)))) = execution_result {
+                risk_manager.on_position_closed(&closed_pos, &execution, current_kline.open_time);
+                let (max_adverse_excursion, max_favorable_excursion) =
+                    logger.take_excursion(&closed_pos, execution.price);
+                // Convert Position to Trade for logging
+                let trade = Trade {
+                    symbol: closed_pos.symbol.clone(),
+                    side: closed_pos.side,
+                    entry_time: Utc.timestamp_millis_opt(closed_pos.entry_time).unwrap(),
+                    exit_time: Utc.timestamp_millis_opt(current_kline.open_time).unwrap(),
+                    entry_price: closed_pos.entry_price,
+                    exit_price: execution.price,
+                    quantity: execution.quantity,
+                    pnl: realized_pnl(&closed_pos, &execution),
+                    fees: closed_pos.entry_fee + execution.fee,
+                    signal_confidence: 0.0, // TODO: Get from signal if available
+                    leverage: closed_pos.leverage,
+                    max_adverse_excursion,
+                    max_favorable_excursion,
+                    entry_reason: logger.entry_reason(&closed_pos),
+                    exit_reason,
+                };
+                logger.record_trade(&trade, &execution, current_kline.open_time);
+                tracing::info!(?execution, "Protective exit order executed.");
+            } else if let Ok(Some((execution, None))) = execution_result {
+                tracing::warn!(?execution, "Protective exit order executed but no closed position returned.");
+            } else if let Ok(None) = execution_result {
+                tracing::warn!("Protective exit order was not filled.");
+            } else if let Err(e) = execution_result {
+                tracing::error!(error = %e, "Failed to execute protective exit order.");
+            }
+            return;
+        }
+    }
+
+    // --- 2. Assess Strategy for New Signals (if no SL/TP was hit) ---
+    let signal = strategy.assess(history_slice);
+    if matches!(signal, Signal::Hold) {
+        return;
+    }
+
+    // --- 3. Evaluate Signal with Risk Manager ---
+    let portfolio_value = portfolio.cash;
+    let open_position = portfolio.open_positions.get(symbol);
+    let had_position = open_position.is_some();
+    let calculation_kline = history_slice.last().unwrap();
+    let order_request_result = risk_manager.evaluate(
+        &signal,
+        symbol,
+        portfolio_value,
+        history_slice,
+        open_position,
+        &portfolio.open_positions,
+    );
+
+    // --- 4. Execute Approved Order ---
+    match order_request_result {
+        Ok(Some(order_request)) => {
+            let execution_result = executor.execute(
+                &order_request, 
+                calculation_kline.close, 
+                calculation_kline.open_time,
+                portfolio
+            ).await;
+            let fill = match execution_result {
+                Ok(fill) => fill,
+                Err(execution::Error::ReversalFailed { closed, reason }) => {
+                    tracing::warn!(%reason, "Position closed but the reversing entry failed.");
+                    Some(*closed)
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Order execution failed.");
+                    None
+                }
+            };
+            match fill {
+                Some((execution, Some(closed_pos))) => {
+                    risk_manager.on_position_closed(&closed_pos, &execution, calculation_kline.open_time);
+                    let (max_adverse_excursion, max_favorable_excursion) =
+                        logger.take_excursion(&closed_pos, execution.price);
+                    // Convert Position to Trade for logging
+            let trade = Trade {
+                symbol: closed_pos.symbol.clone(),
+                side: closed_pos.side,
+                entry_time: Utc.timestamp_millis_opt(closed_pos.entry_time).unwrap(),
+                exit_time: Utc.timestamp_millis_opt(calculation_kline.open_time).unwrap(),
+                entry_price: closed_pos.entry_price,
+                exit_price: execution.price,
+                quantity: execution.quantity,
+                pnl: realized_pnl(&closed_pos, &execution),
+                fees: closed_pos.entry_fee + execution.fee,
+                signal_confidence: 0.0, // TODO: Get from signal if available
+                leverage: closed_pos.leverage,
+                max_adverse_excursion,
+                max_favorable_excursion,
+                entry_reason: logger.entry_reason(&closed_pos),
+                exit_reason: ExitReason::Signal,
+            };
+            logger.record_trade(&trade, &execution, calculation_kline.open_time);
+                    // A reversal closes the old position and opens an opposite one in the same fill.
+                    if portfolio.open_positions.get(symbol).is_some_and(|pos| pos.side != closed_pos.side) {
+                        logger.track_entry(symbol, EntryReason::from_signal(&signal));
+                    }
+                    tracing::info!(?execution, "Order executed and trade logged.");
+                }
+                Some((execution, None)) => {
+                    if !had_position && portfolio.open_positions.contains_key(symbol) {
+                        logger.track_entry(symbol, EntryReason::from_signal(&signal));
+                    }
+                    tracing::info!(?execution, "Order executed (entry or no position closed).");
+                }
+                None => {
+                    tracing::debug!("Order was not filled.");
+                }
+            }
+        }
+        Ok(None) => {
+            // No action needed
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Risk manager vetoed the signal.");
+        }
+    }
+}
+
 /// Helper function to print the performance report in a readable format.
-fn print_report(report: &PerformanceReport) {
+pub fn print_report(report: &PerformanceReport) {
     println!("\n--- Backtest Performance Report ---");
     println!("-----------------------------------");
+    if report.aborted {
+        println!("ABORTED: drawdown limit hit, metrics cover the bars processed until then.");
+        println!("-----------------------------------");
+    }
+    if report.cancelled {
+        println!("CANCELLED: metrics cover the bars processed until then.");
+        println!("-----------------------------------");
+    }
     // Tier 1
     println!("Net P&L:               ${:.2} ({:.2}%)", report.net_pnl_absolute, report.net_pnl_percentage);
     println!("Max Drawdown:          ${:.2} ({:.2}%)", report.max_drawdown_absolute, report.max_drawdown_percentage);
@@ -251,6 +511,10 @@ fn print_report(report: &PerformanceReport) {
     println!("-----------------------------------");
     // Tier 2
     println!("Sortino Ratio:         {:.3}", report.sortino_ratio);
+    println!("Omega Ratio:           {:.3}", report.omega_ratio);
+    println!("Tail Ratio:            {:.3}", report.tail_ratio);
+    println!("VaR (95%, per bar):    {:.4}%", report.value_at_risk_95 * 100.0);
+    println!("CVaR (95%, per bar):   {:.4}%", report.conditional_var_95 * 100.0);
     println!("Calmar Ratio:          {:.3}", report.calmar_ratio);
     println!("Avg. Trade Duration:   {:.1}s", report.avg_trade_duration_secs);
     println!("Expectancy:            ${:.2}", report.expectancy);
@@ -278,4 +542,439 @@ fn print_report(report: &PerformanceReport) {
         }
         println!("-----------------------------------");
     }
-}
\ No newline at end of file
+
+    // Exit-Reason Breakdown
+    if !report.by_exit_reason.is_empty() {
+        println!("Performance by Exit Reason:");
+        let mut sorted_reasons: Vec<_> = report.by_exit_reason.iter().collect();
+        sorted_reasons.sort_by_key(|(k, _)| *k);
+
+        for (reason, sub_report) in sorted_reasons {
+            println!(
+                "  - {}: Trades = {}, Win Rate = {:.1}%, P&L = ${:.2}",
+                reason,
+                sub_report.total_trades,
+                sub_report.win_rate,
+                sub_report.net_pnl_absolute
+            );
+        }
+        println!("-----------------------------------");
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Emits a fixed sequence of signals, one per assessed bar, then holds.
+    pub(crate) struct Scripted(pub(crate) VecDeque<Signal>);
+
+    impl Strategy for Scripted {
+        fn name(&self) -> &'static str {
+            "Scripted"
+        }
+
+        fn required_history(&self) -> usize {
+            1
+        }
+
+        fn assess(&mut self, _klines: &[Kline]) -> Signal {
+            self.0.pop_front().unwrap_or(Signal::Hold)
+        }
+    }
+
+    /// Approves every entry with a fixed quantity and leverage and a stop-loss far outside
+    /// the tested moves, and closes the whole position on `Close`.
+    pub(crate) struct FixedSize {
+        pub(crate) quantity: Decimal,
+        pub(crate) leverage: u8,
+    }
+
+    impl RiskManager for FixedSize {
+        fn name(&self) -> &'static str {
+            "FixedSize"
+        }
+
+        fn evaluate(
+            &self,
+            signal: &Signal,
+            symbol: &Symbol,
+            _portfolio_value: Decimal,
+            klines: &[Kline],
+            open_position: Option<&Position>,
+            _open_positions: &HashMap<Symbol, Position>,
+        ) -> risk::Result<Option<OrderRequest>> {
+            let price = klines.last().unwrap().close;
+            let (side, quantity, sl_price) = match (signal, open_position) {
+                (Signal::GoLong { .. }, _) => (Side::Long, self.quantity, price / dec!(2)),
+                (Signal::GoShort { .. }, _) => (Side::Short, self.quantity, price * dec!(2)),
+                (Signal::Close, Some(position)) => {
+                    let side = if position.side == Side::Long { Side::Short } else { Side::Long };
+                    (side, position.quantity, Decimal::ZERO)
+                }
+                _ => return Ok(None),
+            };
+            Ok(Some(OrderRequest {
+                symbol: symbol.clone(),
+                side,
+                quantity,
+                order_type: OrderType::Market,
+                leverage: self.leverage,
+                sl_price,
+                tp_price: None,
+                originating_signal: *signal,
+            }))
+        }
+    }
+
+    pub(crate) fn go_long() -> Signal {
+        Signal::GoLong { confidence: 1.0, suggested_sl: None, suggested_tp: None }
+    }
+
+    pub(crate) fn kline(index: i64, open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> Kline {
+        Kline {
+            open_time: index * 60_000,
+            open,
+            high,
+            low,
+            close,
+            volume: Decimal::ONE_HUNDRED,
+            close_time: index * 60_000 + 59_999,
+        }
+    }
+
+    /// Builds a fee-free backtester on 10,000 of capital that trades `signals` through
+    /// `risk_manager`, assessing one kline per bar.
+    fn backtester(signals: Vec<Signal>, risk_manager: impl RiskManager + Send + 'static, config: BacktestConfig) -> Backtester {
+        let (ws_tx, _) = broadcast::channel(16);
+        let config = BacktestConfig { history_size: Some(1), ..config };
+        Backtester::new(
+            Symbol::new("BTCUSDT").unwrap(),
+            "1m".to_string(),
+            Box::new(Scripted(signals.into())),
+            Box::new(risk_manager),
+            Box::new(config.simulated_executor(ws_tx)),
+            config,
+        )
+    }
+
+    #[tokio::test]
+    async fn leveraged_long_is_liquidated_on_a_sharp_drop() {
+        // 10 BTC at 100 with 20x leverage lock 50 of margin and are liquidated once price
+        // falls 1/20 - 0.4% below the entry, at 95.4, well above the stop-loss at 50.
+        let mut backtester = backtester(
+            vec![go_long()],
+            FixedSize { quantity: dec!(10), leverage: 20 },
+            BacktestConfig::default(),
+        );
+        let klines = vec![
+            kline(0, dec!(100), dec!(100), dec!(100), dec!(100)),
+            kline(1, dec!(100), dec!(101), dec!(99), dec!(100)),
+            kline(2, dec!(100), dec!(100), dec!(90), dec!(92)),
+        ];
+
+        let (_, trades, _) = backtester.run(klines, None).await.unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_reason, ExitReason::Liquidation);
+        assert_eq!(trades[0].entry_price, dec!(100));
+        assert_eq!(trades[0].exit_price, dec!(95.4));
+        assert!(backtester.portfolio.open_positions.is_empty());
+        assert_eq!(backtester.portfolio.cash, dec!(10_000) - dec!(46));
+    }
+
+    #[tokio::test]
+    async fn entry_exceeding_available_margin_is_rejected() {
+        // 3,000 BTC at 100 with 20x leverage need 15,000 of margin against 10,000 of cash.
+        let mut backtester = backtester(
+            vec![go_long()],
+            FixedSize { quantity: dec!(3_000), leverage: 20 },
+            BacktestConfig::default(),
+        );
+        let klines = vec![
+            kline(0, dec!(100), dec!(100), dec!(100), dec!(100)),
+            kline(1, dec!(100), dec!(101), dec!(99), dec!(100)),
+        ];
+
+        let (_, trades, _) = backtester.run(klines, None).await.unwrap();
+
+        assert!(trades.is_empty());
+        assert!(backtester.portfolio.open_positions.is_empty());
+        assert_eq!(backtester.portfolio.cash, dec!(10_000));
+    }
+
+    #[tokio::test]
+    async fn trade_fees_are_the_actual_entry_and_exit_fees() {
+        let config = BacktestConfig {
+            fees: core_types::SimulationSettings { taker_fee: 0.001, ..Default::default() },
+            ..BacktestConfig::default()
+        };
+        let mut backtester = backtester(
+            vec![go_long(), Signal::Close],
+            FixedSize { quantity: dec!(10), leverage: 1 },
+            config,
+        );
+        let klines = vec![
+            kline(0, dec!(100), dec!(100), dec!(100), dec!(100)),
+            kline(1, dec!(100), dec!(110), dec!(100), dec!(110)),
+            kline(2, dec!(110), dec!(110), dec!(110), dec!(110)),
+        ];
+
+        let (_, trades, _) = backtester.run(klines, None).await.unwrap();
+
+        // Entry: 10 x 100 x 0.1% = 1. Exit: 10 x 110 x 0.1% = 1.1.
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_reason, ExitReason::Signal);
+        assert_eq!(trades[0].fees, dec!(2.1));
+        assert_eq!(backtester.portfolio.cash, dec!(10_000) + dec!(100) - trades[0].fees);
+    }
+
+    #[tokio::test]
+    async fn taker_fee_reduces_the_reported_net_pnl() {
+        let run_with_fee = |taker_fee: f64| async move {
+            let config = BacktestConfig {
+                fees: core_types::SimulationSettings { taker_fee, ..Default::default() },
+                ..BacktestConfig::default()
+            };
+            let mut backtester = backtester(
+                vec![go_long(), Signal::Close],
+                FixedSize { quantity: dec!(10), leverage: 1 },
+                config,
+            );
+            let klines = vec![
+                kline(0, dec!(100), dec!(100), dec!(100), dec!(100)),
+                kline(1, dec!(100), dec!(110), dec!(100), dec!(110)),
+                kline(2, dec!(110), dec!(110), dec!(110), dec!(110)),
+            ];
+            let (report, _, _) = backtester.run(klines, None).await.unwrap();
+            report.net_pnl_absolute
+        };
+
+        let fee_free = run_with_fee(0.0).await;
+        let with_fees = run_with_fee(0.001).await;
+
+        assert_eq!(fee_free, dec!(100));
+        assert_eq!(with_fees, dec!(100) - dec!(2.1));
+    }
+
+    #[tokio::test]
+    async fn progress_is_reported_every_interval_and_at_the_end() {
+        let mut backtester = backtester(vec![], FixedSize { quantity: dec!(1), leverage: 1 }, BacktestConfig::default());
+        let total_bars = 2 * PROGRESS_INTERVAL_BARS + PROGRESS_INTERVAL_BARS / 2;
+        let klines = (0..=total_bars as i64)
+            .map(|i| kline(i, dec!(100), dec!(100), dec!(100), dec!(100)))
+            .collect();
+        let (progress_tx, mut progress_rx) = broadcast::channel(16);
+
+        backtester.run(klines, Some(progress_tx)).await.unwrap();
+
+        let mut reported = Vec::new();
+        while let Ok(message) = progress_rx.try_recv() {
+            let WsMessage::BacktestProgress { processed_bars, total_bars: reported_total, .. } = message else {
+                panic!("unexpected message {:?}", message);
+            };
+            assert_eq!(reported_total, total_bars);
+            reported.push(processed_bars);
+        }
+        assert_eq!(reported, vec![PROGRESS_INTERVAL_BARS, 2 * PROGRESS_INTERVAL_BARS, total_bars]);
+    }
+
+    #[tokio::test]
+    async fn same_seed_reproduces_the_same_report() {
+        let run_once = || async {
+            let config = BacktestConfig {
+                fees: core_types::SimulationSettings { taker_fee: 0.0004, slippage_percent: 0.0005, ..Default::default() },
+                seed: Some(7),
+                ..BacktestConfig::default()
+            };
+            let short = Signal::GoShort { confidence: 0.6, suggested_sl: None, suggested_tp: None };
+            let signals = vec![go_long(), Signal::Hold, Signal::Close, short, Signal::Hold, Signal::Close, go_long()];
+            let mut backtester = backtester(signals, FixedSize { quantity: dec!(3), leverage: 2 }, config);
+            let klines = [100, 103, 101, 98, 95, 97, 99, 104, 102]
+                .iter()
+                .enumerate()
+                .map(|(i, &close)| {
+                    let close = Decimal::from(close);
+                    kline(i as i64, close, close + dec!(1), close - dec!(1), close)
+                })
+                .collect();
+            backtester.run(klines, None).await.unwrap()
+        };
+
+        let (first_report, first_trades, first_equity) = run_once().await;
+        let (second_report, second_trades, second_equity) = run_once().await;
+
+        assert_eq!(first_report.total_trades, 2);
+        assert_eq!(first_report.total_trades, second_report.total_trades);
+        assert_eq!(first_report.net_pnl_absolute, second_report.net_pnl_absolute);
+        assert_eq!(format!("{:?}", first_report), format!("{:?}", second_report));
+        assert_eq!(format!("{:?}", first_trades), format!("{:?}", second_trades));
+        assert_eq!(format!("{:?}", first_equity), format!("{:?}", second_equity));
+    }
+
+    #[tokio::test]
+    async fn excursions_capture_a_dip_before_the_recovery() {
+        let mut backtester = backtester(
+            vec![go_long(), Signal::Hold, Signal::Hold, Signal::Close],
+            FixedSize { quantity: dec!(1), leverage: 1 },
+            BacktestConfig::default(),
+        );
+        // Orders fill at the close of the assessed kline, so the long enters at 100 (kline 0)
+        // and exits at 106 (kline 3); the excursions cover klines 2 to 4.
+        let klines = vec![
+            kline(0, dec!(100), dec!(100), dec!(100), dec!(100)),
+            kline(1, dec!(100), dec!(100), dec!(100), dec!(100)),
+            // Dips to 92 after the entry...
+            kline(2, dec!(100), dec!(101), dec!(92), dec!(94)),
+            // ...then recovers through 112 before the exit.
+            kline(3, dec!(94), dec!(112), dec!(94), dec!(106)),
+            kline(4, dec!(106), dec!(107), dec!(105), dec!(106)),
+        ];
+
+        let (_, trades, _) = backtester.run(klines, None).await.unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].entry_price, trades[0].exit_price), (dec!(100), dec!(106)));
+        assert_eq!(trades[0].max_adverse_excursion, dec!(8));
+        assert_eq!(trades[0].max_favorable_excursion, dec!(12));
+    }
+
+    #[tokio::test]
+    async fn falling_equity_aborts_the_run_at_the_drawdown_threshold() {
+        // 50 BTC bought at 100 without leverage: the stop-loss at 50 stays far away, and the
+        // cash never changes, so only the unrealized loss can trip the 5% limit at 9,500.
+        let config = BacktestConfig { max_drawdown_abort_percent: Some(5.0), ..BacktestConfig::default() };
+        let mut backtester = backtester(vec![go_long()], FixedSize { quantity: dec!(50), leverage: 1 }, config);
+        let klines = vec![
+            kline(0, dec!(100), dec!(100), dec!(100), dec!(100)),
+            kline(1, dec!(100), dec!(100), dec!(97), dec!(98)),
+            kline(2, dec!(98), dec!(98), dec!(88), dec!(88)),
+            kline(3, dec!(88), dec!(120), dec!(88), dec!(120)),
+            kline(4, dec!(120), dec!(120), dec!(120), dec!(120)),
+        ];
+
+        let (report, trades, equity_curve) = backtester.run(klines, None).await.unwrap();
+
+        assert!(report.aborted);
+        assert!(trades.is_empty());
+        assert_eq!(backtester.portfolio.cash, dec!(10_000));
+        // Marked at 98 (-100) and then at 88 (-600), where the run stops before the rebound.
+        let equity: Vec<Decimal> = equity_curve.iter().map(|point| point.value).collect();
+        assert_eq!(equity, vec![dec!(9_900), dec!(9_400)]);
+    }
+
+    /// Records the length of every history slice it is asked to assess.
+    struct HistoryLengths(std::sync::Arc<std::sync::Mutex<Vec<usize>>>);
+
+    impl Strategy for HistoryLengths {
+        fn name(&self) -> &'static str {
+            "HistoryLengths"
+        }
+
+        fn required_history(&self) -> usize {
+            1
+        }
+
+        fn assess(&mut self, klines: &[Kline]) -> Signal {
+            self.0.lock().unwrap().push(klines.len());
+            Signal::Hold
+        }
+    }
+
+    #[tokio::test]
+    async fn the_config_sets_the_capital_and_the_history_size() {
+        let lengths = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let config = BacktestConfig {
+            initial_capital: dec!(5_000),
+            history_size: Some(3),
+            ..BacktestConfig::default()
+        };
+        let (ws_tx, _) = broadcast::channel(16);
+        let mut backtester = Backtester::new(
+            Symbol::new("BTCUSDT").unwrap(),
+            "1m".to_string(),
+            Box::new(HistoryLengths(lengths.clone())),
+            Box::new(FixedSize { quantity: dec!(1), leverage: 1 }),
+            Box::new(config.simulated_executor(ws_tx)),
+            config,
+        );
+        let klines: Vec<Kline> = (0..5).map(|i| kline(i, dec!(100), dec!(100), dec!(100), dec!(100))).collect();
+
+        let (_, trades, equity_curve) = backtester.run(klines, None).await.unwrap();
+
+        // The configured history overrides the strategy's own `required_history` of 1.
+        assert_eq!(*lengths.lock().unwrap(), vec![3, 3]);
+        assert!(trades.is_empty());
+        assert_eq!(backtester.portfolio.initial_capital, dec!(5_000));
+        let equity: Vec<Decimal> = equity_curve.iter().map(|point| point.value).collect();
+        assert_eq!(equity, vec![dec!(5_000), dec!(5_000)]);
+    }
+
+    #[tokio::test]
+    async fn stop_loss_close_records_its_exit_reason() {
+        // 10 BTC bought at 100 without leverage with the stop-loss at 50; the next bar
+        // trades down to 40.
+        let mut backtester = backtester(vec![go_long()], FixedSize { quantity: dec!(10), leverage: 1 }, BacktestConfig::default());
+        let klines = vec![
+            kline(0, dec!(100), dec!(100), dec!(100), dec!(100)),
+            kline(1, dec!(100), dec!(101), dec!(99), dec!(100)),
+            kline(2, dec!(100), dec!(100), dec!(40), dec!(45)),
+        ];
+
+        let (_, trades, _) = backtester.run(klines, None).await.unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_reason, ExitReason::StopLoss);
+        assert_eq!(trades[0].entry_reason, EntryReason::LongSignal);
+        assert_eq!(trades[0].exit_price, dec!(50));
+    }
+
+    /// Holds, and cancels `token` while assessing its `bars`-th bar.
+    struct CancelAfter {
+        token: CancellationToken,
+        bars: usize,
+    }
+
+    impl Strategy for CancelAfter {
+        fn name(&self) -> &'static str {
+            "CancelAfter"
+        }
+
+        fn required_history(&self) -> usize {
+            1
+        }
+
+        fn assess(&mut self, _klines: &[Kline]) -> Signal {
+            self.bars -= 1;
+            if self.bars == 0 {
+                self.token.cancel();
+            }
+            Signal::Hold
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_mid_run_returns_the_partial_report() {
+        let token = CancellationToken::new();
+        let config = BacktestConfig { history_size: Some(1), ..BacktestConfig::default() };
+        let (ws_tx, _) = broadcast::channel(16);
+        let mut backtester = Backtester::new(
+            Symbol::new("BTCUSDT").unwrap(),
+            "1m".to_string(),
+            Box::new(CancelAfter { token: token.clone(), bars: 3 }),
+            Box::new(FixedSize { quantity: dec!(1), leverage: 1 }),
+            Box::new(config.simulated_executor(ws_tx)),
+            config,
+        )
+        .with_cancellation(token);
+        let klines: Vec<Kline> = (0..10).map(|i| kline(i, dec!(100), dec!(100), dec!(100), dec!(100))).collect();
+
+        let (report, _, equity_curve) = backtester.run(klines, None).await.unwrap();
+
+        // The third bar finishes; the loop stops before the fourth of nine.
+        assert!(report.cancelled);
+        assert!(!report.aborted);
+        assert_eq!(equity_curve.len(), 3);
+        assert_eq!(equity_curve.last().unwrap().timestamp, Utc.timestamp_millis_opt(3 * 60_000).unwrap());
+    }
+}
@@ -31,8 +31,8 @@ impl TradeLogger {
 
     /// Records a completed trade by combining the entry position and the closing execution.
     pub fn record_trade(&mut self, open_pos: &Position, close_exec: &Execution, exit_time: DateTime<Utc>) {
-        // Calculate total fees (assuming entry fee was already deducted from cash)
-        let fees = (open_pos.quantity * open_pos.entry_price * dec!(0.0004)) + close_exec.fee; // Placeholder taker fee
+        // Total fees are the fees actually paid on entry and on exit
+        let fees = open_pos.entry_fee + close_exec.fee;
 
         // Calculate PnL
         let pnl = (close_exec.price - open_pos.entry_price)
@@ -41,7 +41,7 @@ impl TradeLogger {
 
         // Extract confidence from the originating signal
         let confidence = match close_exec.source_request.originating_signal {
-            Signal::GoLong { confidence } | Signal::GoShort { confidence } => confidence,
+            Signal::GoLong { confidence, .. } | Signal::GoShort { confidence, .. } => confidence,
             _ => 0.0, // Default for system-generated closes (e.g., SL)
         };
 
@@ -0,0 +1,296 @@
+// In crates/backtester/src/multi.rs
+
+use std::collections::{BTreeSet, HashMap};
+
+use analytics::engine::AnalyticsEngine;
+use analytics::types::{EquityPoint, PerformanceReport, Trade};
+use chrono::{TimeZone, Utc};
+use core_types::{Kline, Symbol};
+use execution::{Executor, Portfolio};
+use rust_decimal::prelude::*;
+use risk::RiskManager;
+use strategies::Strategy;
+
+use crate::types::BacktestConfig;
+use crate::{print_report, process_bar, BacktestLogger};
+
+/// One `(symbol, interval, strategy)` series of a portfolio backtest.
+pub struct BacktestLeg {
+    pub symbol: Symbol,
+    pub interval: String,
+    pub strategy: Box<dyn Strategy + Send>,
+    /// The leg's klines, sorted by `open_time`.
+    pub klines: Vec<Kline>,
+}
+
+impl BacktestLeg {
+    /// The number of klines handed to the leg's strategy on each bar: `history_size` if
+    /// given, the strategy's `required_history` otherwise.
+    fn history_size(&self, history_size: Option<usize>) -> usize {
+        history_size.unwrap_or_else(|| self.strategy.required_history()).max(1)
+    }
+}
+
+/// The outcome of a portfolio backtest.
+#[derive(Debug, Clone)]
+pub struct PortfolioBacktestResult {
+    /// The report over all legs combined.
+    pub report: PerformanceReport,
+    pub trades: Vec<Trade>,
+    pub equity_curve: Vec<EquityPoint>,
+    /// A report per symbol, computed from that symbol's trades only.
+    pub per_symbol: HashMap<String, PerformanceReport>,
+}
+
+/// Runs several legs against one shared `Portfolio`.
+///
+/// Klines of all legs are merged by `open_time`, so every entry is sized against the cash
+/// that is actually left after the positions opened by the other legs.
+pub struct PortfolioBacktester {
+    pub legs: Vec<BacktestLeg>,
+    /// The risk manager instance, shared by all legs.
+    pub risk_manager: Box<dyn RiskManager + Send + Sync>,
+    /// The execution simulator, shared by all legs.
+    pub executor: Box<dyn Executor>,
+    logger: BacktestLogger,
+    portfolio: Portfolio,
+    config: BacktestConfig,
+}
+
+impl PortfolioBacktester {
+    /// Creates a portfolio backtester with the tunables in `config`, which apply to every
+    /// leg: `history_size` overrides each strategy's own.
+    ///
+    /// As with `Backtester::new`, `executor` is taken as-is; `config.simulated_executor`
+    /// builds the usual one.
+    pub fn new(
+        legs: Vec<BacktestLeg>,
+        risk_manager: Box<dyn RiskManager + Send + Sync>,
+        executor: Box<dyn Executor>,
+        config: BacktestConfig,
+    ) -> Self {
+        Self {
+            legs,
+            risk_manager,
+            executor,
+            logger: BacktestLogger::new(config.initial_capital),
+            portfolio: Portfolio::new(config.initial_capital),
+            config,
+        }
+    }
+
+    /// Runs the backtest over the merged klines of all legs.
+    ///
+    /// With `BacktestConfig::max_drawdown_abort_percent` set, the run stops early once the
+    /// mark-to-market equity falls below the threshold, and the combined report has
+    /// `aborted` set.
+    pub async fn run(&mut self) -> anyhow::Result<PortfolioBacktestResult> {
+        let history_size = self.config.history_size;
+        for leg in &mut self.legs {
+            let leg_history = leg.history_size(history_size);
+            if leg.klines.len() > leg_history {
+                leg.strategy.warm_up(&leg.klines[..leg_history - 1]);
+            }
+        }
+
+        let abort_equity = self.config.max_drawdown_abort_percent.map(|percent| {
+            self.portfolio.initial_capital
+                * (Decimal::ONE - Decimal::from_f64(percent / 100.0).unwrap_or(Decimal::ZERO))
+        });
+        let mut aborted = false;
+
+        // The merged timeline: every distinct open time across all legs, in order.
+        let timeline: BTreeSet<i64> = self
+            .legs
+            .iter()
+            .flat_map(|leg| leg.klines.iter().map(|k| k.open_time))
+            .collect();
+        let mut cursors = vec![0usize; self.legs.len()];
+
+        for open_time in timeline {
+            for (leg, cursor) in self.legs.iter_mut().zip(cursors.iter_mut()) {
+                let i = *cursor;
+                if leg.klines.get(i).map(|k| k.open_time) != Some(open_time) {
+                    continue;
+                }
+                *cursor += 1;
+                let history_size = leg.history_size(history_size);
+                if i >= history_size {
+                    process_bar(
+                        &leg.symbol,
+                        leg.strategy.as_mut(),
+                        self.risk_manager.as_ref(),
+                        self.executor.as_mut(),
+                        &mut self.portfolio,
+                        &mut self.logger,
+                        &leg.klines[(i - history_size)..i],
+                        &leg.klines[i],
+                    ).await;
+                }
+                // Mark the leg to the bar's close only once the bar has been traded.
+                self.portfolio.update_mark_price(leg.symbol.clone(), leg.klines[i].close);
+            }
+
+            // Both the equity curve and the drawdown check include unrealized P&L.
+            let equity = self.portfolio.total_value();
+            self.logger.record_equity(Utc.timestamp_millis_opt(open_time).unwrap(), equity);
+
+            if let Some(abort_equity) = abort_equity
+                && equity < abort_equity
+            {
+                tracing::warn!(
+                    time = %Utc.timestamp_millis_opt(open_time).unwrap(),
+                    equity = equity.to_f64().unwrap_or(0.0),
+                    "Equity fell below the drawdown abort threshold. Aborting portfolio backtest."
+                );
+                aborted = true;
+                break;
+            }
+        }
+        tracing::info!(portfolio = ?self.portfolio, "Portfolio backtest finished. Final portfolio state:");
+
+        // --- Analytics Calculation & Reporting ---
+        let initial_capital = self.portfolio.initial_capital;
+        let analytics_engine = AnalyticsEngine::with_config(self.config.analytics);
+        let mut report = analytics_engine.calculate(
+            initial_capital,
+            &self.logger.trades,
+            &self.logger.equity_points,
+        );
+        report.aborted = aborted;
+        print_report(&report);
+
+        let mut per_symbol = HashMap::new();
+        for leg in &self.legs {
+            let trades: Vec<Trade> = self
+                .logger
+                .trades
+                .iter()
+                .filter(|t| t.symbol == leg.symbol)
+                .cloned()
+                .collect();
+            let equity_curve = symbol_equity_curve(initial_capital, &trades);
+            per_symbol.insert(
+                leg.symbol.0.clone(),
+                analytics_engine.calculate(initial_capital, &trades, &equity_curve),
+            );
+        }
+
+        Ok(PortfolioBacktestResult {
+            report,
+            trades: self.logger.trades.clone(),
+            equity_curve: self.logger.equity_points.clone(),
+            per_symbol,
+        })
+    }
+}
+
+/// Builds the equity curve of a single symbol: the initial capital plus the cumulative P&L
+/// of its trades, stepped at each exit time.
+fn symbol_equity_curve(initial_capital: Decimal, trades: &[Trade]) -> Vec<EquityPoint> {
+    let mut equity = initial_capital;
+    let mut curve = Vec::with_capacity(trades.len() + 1);
+    if let Some(first) = trades.first() {
+        curve.push(EquityPoint { timestamp: first.entry_time, value: equity });
+    }
+    for trade in trades {
+        equity += trade.pnl;
+        curve.push(EquityPoint { timestamp: trade.exit_time, value: equity });
+    }
+    curve
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{go_long, kline, FixedSize, Scripted};
+    use rust_decimal_macros::dec;
+    use tokio::sync::broadcast;
+
+    /// A leg that goes long on its first assessed bar, at a flat price of `price`.
+    fn leg(symbol: &str, price: Decimal) -> BacktestLeg {
+        BacktestLeg {
+            symbol: Symbol::new(symbol).unwrap(),
+            interval: "1m".to_string(),
+            strategy: Box::new(Scripted(vec![go_long()].into())),
+            klines: (0..3).map(|i| kline(i, price, price, price, price)).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn second_entry_is_limited_by_the_margin_spent_on_the_first() {
+        // Each entry of 60 units at 100 without leverage locks 6,000 of the 10,000 capital,
+        // so only the first of the two legs can open its position.
+        let config = BacktestConfig { history_size: Some(1), ..BacktestConfig::default() };
+        let (ws_tx, _) = broadcast::channel(16);
+        let mut backtester = PortfolioBacktester::new(
+            vec![leg("BTCUSDT", dec!(100)), leg("ETHUSDT", dec!(100))],
+            Box::new(FixedSize { quantity: dec!(60), leverage: 1 }),
+            Box::new(config.simulated_executor(ws_tx)),
+            config,
+        );
+
+        backtester.run().await.unwrap();
+
+        let open: Vec<_> = backtester.portfolio.open_positions.keys().map(|s| s.0.as_str()).collect();
+        assert_eq!(open, vec!["BTCUSDT"]);
+        assert_eq!(backtester.portfolio.available_margin(), dec!(4_000));
+    }
+
+    #[tokio::test]
+    async fn initial_capital_comes_from_the_config() {
+        // With 20,000 of capital both entries fit.
+        let config = BacktestConfig {
+            initial_capital: dec!(20_000),
+            history_size: Some(1),
+            ..BacktestConfig::default()
+        };
+        let (ws_tx, _) = broadcast::channel(16);
+        let mut backtester = PortfolioBacktester::new(
+            vec![leg("BTCUSDT", dec!(100)), leg("ETHUSDT", dec!(100))],
+            Box::new(FixedSize { quantity: dec!(60), leverage: 1 }),
+            Box::new(config.simulated_executor(ws_tx)),
+            config,
+        );
+
+        let result = backtester.run().await.unwrap();
+
+        assert_eq!(backtester.portfolio.open_positions.len(), 2);
+        assert_eq!(result.equity_curve.first().unwrap().value, dec!(20_000));
+    }
+
+    #[tokio::test]
+    async fn unrealized_losses_across_legs_abort_the_run() {
+        // Two legs of 40 units bought at 100. A drop to 90 costs 400 each, 8% of the
+        // capital together, while the cash stays at 10,000.
+        let config = BacktestConfig {
+            history_size: Some(1),
+            max_drawdown_abort_percent: Some(5.0),
+            ..BacktestConfig::default()
+        };
+        let falling_leg = |symbol: &str| BacktestLeg {
+            klines: [100, 100, 90, 130]
+                .iter()
+                .enumerate()
+                .map(|(i, &close)| {
+                    let close = Decimal::from(close);
+                    kline(i as i64, close, close, close, close)
+                })
+                .collect(),
+            ..leg(symbol, dec!(100))
+        };
+        let (ws_tx, _) = broadcast::channel(16);
+        let mut backtester = PortfolioBacktester::new(
+            vec![falling_leg("BTCUSDT"), falling_leg("ETHUSDT")],
+            Box::new(FixedSize { quantity: dec!(40), leverage: 1 }),
+            Box::new(config.simulated_executor(ws_tx)),
+            config,
+        );
+
+        let result = backtester.run().await.unwrap();
+
+        assert!(result.report.aborted);
+        let equity: Vec<Decimal> = result.equity_curve.iter().map(|point| point.value).collect();
+        assert_eq!(equity, vec![dec!(10_000), dec!(10_000), dec!(9_200)]);
+    }
+}
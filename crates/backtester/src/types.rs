@@ -0,0 +1,73 @@
+// In crates/backtester/src/types.rs
+
+use analytics::types::AnalyticsConfig;
+use core_types::SimulationSettings;
+use events::WsMessage;
+use execution::simulated::SimulatedExecutor;
+use risk::types::SimpleRiskSettings;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// The tunables of a single backtest run.
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    /// The starting cash of the portfolio.
+    pub initial_capital: Decimal,
+    /// The number of klines handed to the strategy on each bar (at least 1). Defaults to the
+    /// strategy's `required_history`. The first `history_size` klines of a run only warm the
+    /// strategy up and are never traded.
+    pub history_size: Option<usize>,
+    /// Fee, slippage and fill settings for the simulated executor.
+    pub fees: SimulationSettings,
+    /// Seed for the simulated executor. Overrides `fees.seed` when set.
+    pub seed: Option<u64>,
+    /// Stops the run once the mark-to-market equity has fallen this many percent below
+    /// `initial_capital`. The report then covers the bars processed so far and has
+    /// `aborted` set.
+    pub max_drawdown_abort_percent: Option<f64>,
+    /// Settings for the metrics of the final performance report.
+    pub analytics: AnalyticsConfig,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            initial_capital: dec!(10_000),
+            history_size: None,
+            fees: SimulationSettings::default(),
+            seed: None,
+            max_drawdown_abort_percent: None,
+            analytics: AnalyticsConfig::default(),
+        }
+    }
+}
+
+impl BacktestConfig {
+    /// Builds the simulated executor described by `fees` and `seed`.
+    pub fn simulated_executor(&self, ws_tx: broadcast::Sender<WsMessage>) -> SimulatedExecutor {
+        let mut settings = self.fees.clone();
+        if self.seed.is_some() {
+            settings.seed = self.seed;
+        }
+        SimulatedExecutor::new(settings, ws_tx)
+    }
+}
+
+/// The settings a stored run was produced with, saved alongside it so that it can be re-run
+/// later under the same assumptions, whatever the config says by then.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunConfigSnapshot {
+    pub fees: SimulationSettings,
+    pub risk: SimpleRiskSettings,
+    /// The seed the simulated executor ran with.
+    pub seed: Option<u64>,
+}
+
+impl RunConfigSnapshot {
+    /// Captures the fees and effective seed of `config` together with the risk settings.
+    pub fn new(config: &BacktestConfig, risk: SimpleRiskSettings) -> Self {
+        Self { fees: config.fees.clone(), risk, seed: config.seed.or(config.fees.seed) }
+    }
+}
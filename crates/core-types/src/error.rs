@@ -6,5 +6,11 @@ use thiserror::Error;
 pub enum Error {
     #[error("A placeholder error for the config module")]
     Placeholder,
+
+    #[error("Invalid symbol: {0}")]
+    InvalidSymbol(String),
+
+    #[error("Invalid interval: {0}")]
+    InvalidInterval(String),
 }
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
@@ -0,0 +1,72 @@
+// In crates/core-types/src/heikin_ashi.rs
+
+use crate::types::Kline;
+use rust_decimal::Decimal;
+
+/// Transforms a series of regular klines into Heikin-Ashi candles.
+///
+/// Each HA candle depends on the previous one, so the transform is computed iteratively.
+/// The first candle is seeded with `HA-open = (open + close) / 2`. Timestamps and volume
+/// are carried over unchanged.
+pub fn to_heikin_ashi(klines: &[Kline]) -> Vec<Kline> {
+    let two = Decimal::from(2);
+    let four = Decimal::from(4);
+
+    let mut candles: Vec<Kline> = Vec::with_capacity(klines.len());
+    for kline in klines {
+        let ha_close = (kline.open + kline.high + kline.low + kline.close) / four;
+        let ha_open = match candles.last() {
+            Some(prev) => (prev.open + prev.close) / two,
+            None => (kline.open + kline.close) / two,
+        };
+
+        candles.push(Kline {
+            open_time: kline.open_time,
+            open: ha_open,
+            high: kline.high.max(ha_open).max(ha_close),
+            low: kline.low.min(ha_open).min(ha_close),
+            close: ha_close,
+            volume: kline.volume,
+            close_time: kline.close_time,
+        });
+    }
+
+    candles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn kline(open_time: i64, open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> Kline {
+        Kline { open_time, open, high, low, close, volume: dec!(5), close_time: open_time + 59_999 }
+    }
+
+    #[test]
+    fn matches_a_hand_computed_reference_sequence() {
+        let klines = [
+            kline(0, dec!(10), dec!(12), dec!(9), dec!(11)),
+            kline(60_000, dec!(11), dec!(13), dec!(10), dec!(12)),
+            kline(120_000, dec!(12), dec!(12.5), dec!(8), dec!(9)),
+            kline(180_000, dec!(9), dec!(9.5), dec!(7), dec!(8)),
+        ];
+
+        let candles = to_heikin_ashi(&klines);
+
+        let expected = [
+            // The first candle is seeded with HA-open = (open + close) / 2.
+            kline(0, dec!(10.5), dec!(12), dec!(9), dec!(10.5)),
+            kline(60_000, dec!(10.5), dec!(13), dec!(10), dec!(11.5)),
+            kline(120_000, dec!(11), dec!(12.5), dec!(8), dec!(10.375)),
+            // The HA-open is above the raw high, so it becomes the candle's high.
+            kline(180_000, dec!(10.6875), dec!(10.6875), dec!(7), dec!(8.375)),
+        ];
+        assert_eq!(candles, expected);
+    }
+
+    #[test]
+    fn empty_input_gives_no_candles() {
+        assert!(to_heikin_ashi(&[]).is_empty());
+    }
+}
@@ -0,0 +1,27 @@
+// In crates/core-types/src/interval.rs
+
+/// Converts a Binance kline interval string (e.g., "1m", "4h", "1d") into its
+/// duration in milliseconds.
+///
+/// Returns `None` for unknown or malformed intervals. The monthly interval ("1M")
+/// is intentionally unsupported because it does not have a fixed length.
+pub fn interval_to_millis(interval: &str) -> Option<i64> {
+    if interval.len() < 2 {
+        return None;
+    }
+    let (amount, unit) = interval.split_at(interval.len() - 1);
+    let amount: i64 = amount.parse().ok()?;
+    if amount <= 0 {
+        return None;
+    }
+
+    let unit_millis = match unit {
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 604_800_000,
+        _ => return None,
+    };
+
+    Some(amount * unit_millis)
+}
@@ -1,10 +1,17 @@
 // In crates/core-types/src/lib.rs (REPLACE ENTIRE FILE)
 
 pub mod error;
+pub mod heikin_ashi;
+pub mod interval;
+pub mod resample;
 pub mod types;
 
 // Re-export the most important types for easy access from other crates.
 pub use error::{Error, Result};
+pub use heikin_ashi::to_heikin_ashi;
+pub use interval::interval_to_millis;
+pub use resample::resample;
 pub use types::{
-    Execution, Kline, OrderRequest, Position, Side, Signal, Symbol,
+    Execution, FundingRate, Kline, OrderRequest, OrderType, Position, Side, Signal,
+    SimulationSettings, Symbol,
 };
\ No newline at end of file
@@ -0,0 +1,121 @@
+// In crates/core-types/src/resample.rs
+
+use crate::error::{Error, Result};
+use crate::interval::interval_to_millis;
+use crate::types::Kline;
+
+/// Aggregates klines of interval `from` into candles of the higher interval `to`.
+///
+/// Klines are grouped into buckets aligned to multiples of `to` since the Unix epoch. Each
+/// resulting candle takes the first open, the highest high, the lowest low, the last close
+/// and the summed volume of its bucket. Buckets at the edges of `klines` that are only
+/// partially covered are still emitted.
+///
+/// `klines` must be in ascending time order. Fails if either interval is unknown or if
+/// `to` is not a whole multiple of `from`.
+pub fn resample(klines: &[Kline], from: &str, to: &str) -> Result<Vec<Kline>> {
+    let from_millis = interval_to_millis(from)
+        .ok_or_else(|| Error::InvalidInterval(format!("unknown interval '{}'", from)))?;
+    let to_millis = interval_to_millis(to)
+        .ok_or_else(|| Error::InvalidInterval(format!("unknown interval '{}'", to)))?;
+    if to_millis < from_millis || to_millis % from_millis != 0 {
+        return Err(Error::InvalidInterval(format!(
+            "'{}' is not a whole multiple of '{}'",
+            to, from
+        )));
+    }
+
+    let mut candles: Vec<Kline> = Vec::with_capacity(klines.len() / (to_millis / from_millis) as usize + 1);
+    for kline in klines {
+        let bucket_open = kline.open_time - kline.open_time.rem_euclid(to_millis);
+        match candles.last_mut() {
+            Some(candle) if candle.open_time == bucket_open => {
+                candle.high = candle.high.max(kline.high);
+                candle.low = candle.low.min(kline.low);
+                candle.close = kline.close;
+                candle.volume += kline.volume;
+            }
+            _ => candles.push(Kline {
+                open_time: bucket_open,
+                open: kline.open,
+                high: kline.high,
+                low: kline.low,
+                close: kline.close,
+                volume: kline.volume,
+                close_time: bucket_open + to_millis - 1,
+            }),
+        }
+    }
+
+    Ok(candles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    const FIVE_MINUTES: i64 = 5 * 60_000;
+
+    /// Five-minute klines starting at `start`, with the n-th kline's prices around `100 + n`
+    /// and a volume of 1.
+    fn five_minute_klines(start: i64, count: usize) -> Vec<Kline> {
+        (0..count)
+            .map(|i| {
+                let base = dec!(100) + Decimal::from(i);
+                Kline {
+                    open_time: start + i as i64 * FIVE_MINUTES,
+                    open: base,
+                    high: base + dec!(2),
+                    low: base - dec!(1),
+                    close: base + dec!(1),
+                    volume: dec!(1),
+                    close_time: start + (i as i64 + 1) * FIVE_MINUTES - 1,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn twelve_five_minute_klines_make_one_hourly_candle() {
+        let klines = five_minute_klines(0, 12);
+
+        let candles = resample(&klines, "5m", "1h").unwrap();
+
+        assert_eq!(
+            candles,
+            vec![Kline {
+                open_time: 0,
+                open: dec!(100),
+                high: dec!(113),
+                low: dec!(99),
+                close: dec!(112),
+                volume: dec!(12),
+                close_time: 3_600_000 - 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn klines_are_bucketed_on_interval_boundaries() {
+        // Starting at 00:30, the first six klines fall in the 00:00 hour and the rest in 01:00.
+        let klines = five_minute_klines(6 * FIVE_MINUTES, 12);
+
+        let candles = resample(&klines, "5m", "1h").unwrap();
+
+        assert_eq!(candles.len(), 2);
+        assert_eq!((candles[0].open_time, candles[0].volume), (0, dec!(6)));
+        assert_eq!((candles[1].open_time, candles[1].volume), (3_600_000, dec!(6)));
+        assert_eq!(candles[1].open, dec!(106));
+    }
+
+    #[test]
+    fn intervals_that_are_not_whole_multiples_are_rejected() {
+        let klines = five_minute_klines(0, 12);
+
+        assert!(matches!(resample(&klines, "5m", "3m"), Err(Error::InvalidInterval(_))));
+        assert!(matches!(resample(&klines, "1h", "5m"), Err(Error::InvalidInterval(_))));
+        assert!(matches!(resample(&klines, "5m", "7x"), Err(Error::InvalidInterval(_))));
+    }
+}
@@ -1,11 +1,13 @@
 // In crates/core-types/src/types.rs
 
+use crate::error::{Error, Result};
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::collections::HashSet;
 use serde::Serialize;
 
 /// Represents a single candlestick (Kline).
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct Kline {
     /// The start time of this kline, as a Unix timestamp in milliseconds.
     pub open_time: i64,
@@ -23,10 +25,95 @@ pub struct Kline {
     pub close_time: i64,
 }
 
+/// Represents a single funding-rate settlement of a perpetual futures contract.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FundingRate {
+    /// The time of the funding settlement, as a Unix timestamp in milliseconds.
+    pub funding_time: i64,
+    /// The funding rate applied at that time (e.g., 0.0001 for 0.01%).
+    pub funding_rate: Decimal,
+}
+
+/// Fee and slippage settings for the simulated executor, loaded from the `[simulation]`
+/// config table.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SimulationSettings {
+    /// The maker fee for the exchange (e.g., 0.0002 for 0.02%).
+    pub maker_fee: f64,
+
+    /// The taker fee for the exchange (e.g., 0.0004 for 0.04%).
+    pub taker_fee: f64,
+
+    /// The simulated slippage percentage for market orders (e.g., 0.0005 for 0.05%).
+    pub slippage_percent: f64,
+
+    /// The seed for the executor's random number generator. Runs with the same seed and
+    /// inputs are reproducible; `None` falls back to a fixed default seed.
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    /// Whether an entry order in the direction of an existing position adds to it. When
+    /// disabled (the default), such orders are rejected and the position is left unchanged.
+    #[serde(default)]
+    pub allow_pyramiding: bool,
+}
+
 /// Represents a trading pair, like "BTCUSDT".
+///
+/// Prefer [`Symbol::new`], which normalizes and validates the input. Building the
+/// tuple struct directly (`Symbol(s)`) skips validation, so it should only be used for
+/// symbols that come back from the exchange itself.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Symbol(pub String);
 
+impl Symbol {
+    /// The shortest symbol accepted by [`Symbol::new`].
+    pub const MIN_LEN: usize = 5;
+    /// The longest symbol accepted by [`Symbol::new`].
+    pub const MAX_LEN: usize = 20;
+
+    /// Creates a validated symbol, normalizing it to uppercase.
+    ///
+    /// The symbol must be ASCII alphanumeric and between `MIN_LEN` and `MAX_LEN`
+    /// characters long. Leading and trailing whitespace is ignored.
+    pub fn new(s: &str) -> Result<Self> {
+        let normalized = s.trim().to_ascii_uppercase();
+        if normalized.len() < Self::MIN_LEN || normalized.len() > Self::MAX_LEN {
+            return Err(Error::InvalidSymbol(format!(
+                "'{}' must be between {} and {} characters long",
+                s,
+                Self::MIN_LEN,
+                Self::MAX_LEN
+            )));
+        }
+        if !normalized.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::InvalidSymbol(format!(
+                "'{}' must only contain letters and digits",
+                s
+            )));
+        }
+        Ok(Symbol(normalized))
+    }
+
+    /// Creates a validated symbol and additionally checks that it is listed on the
+    /// exchange, e.g. against the symbol set from a cached exchange-info response.
+    pub fn new_listed(s: &str, listed: &HashSet<String>) -> Result<Self> {
+        let symbol = Self::new(s)?;
+        if !listed.contains(&symbol.0) {
+            return Err(Error::InvalidSymbol(format!(
+                "'{}' is not listed on the exchange",
+                symbol.0
+            )));
+        }
+        Ok(symbol)
+    }
+
+    /// Returns the symbol as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Represents the side of a trade or position.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Side {
@@ -36,20 +123,34 @@ pub enum Side {
 
 /// Represents a trading signal generated by a strategy.
 /// This is the primary output of the strategy engine.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Signal {
     /// A signal to enter a long position.
-    GoLong { confidence: f64 },
+    ///
+    /// Strategies may suggest their own stop-loss and take-profit levels, which the
+    /// risk manager prefers over its generic percentage-based levels.
+    GoLong {
+        confidence: f64,
+        suggested_sl: Option<Decimal>,
+        suggested_tp: Option<Decimal>,
+    },
     /// A signal to enter a short position.
-    GoShort { confidence: f64 },
+    GoShort {
+        confidence: f64,
+        suggested_sl: Option<Decimal>,
+        suggested_tp: Option<Decimal>,
+    },
     /// A signal to hold the current position or stay out of the market.
     Hold,
     /// A signal to close any currently open position for the given symbol.
     Close,
+    /// A signal to close a fraction (in `(0, 1]`) of the currently open position,
+    /// leaving the remainder open.
+    ClosePartial { fraction: f64 },
 }
 
 /// Represents an active position in the market.
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     /// The symbol for the position (e.g., "BTCUSDT").
     pub symbol: Symbol,
@@ -63,12 +164,48 @@ pub struct Position {
     pub leverage: u8,
     /// The calculated stop-loss price for this position.
     pub sl_price: Decimal,
+    /// The optional take-profit price for this position.
+    pub tp_price: Option<Decimal>,
     pub entry_time: i64,
+    /// The fee actually paid when this position was opened.
+    pub entry_fee: Decimal,
+}
+
+impl Position {
+    /// The margin locked by this position (`notional / leverage`), valued at the entry price.
+    pub fn initial_margin(&self) -> Decimal {
+        self.quantity * self.entry_price / Decimal::from(self.leverage.max(1))
+    }
+
+    /// Approximates the isolated-margin liquidation price of this position.
+    ///
+    /// The position is liquidated once its loss has consumed the initial margin down to the
+    /// maintenance margin, i.e. when price moves `1 / leverage - maintenance_margin_rate`
+    /// against the entry.
+    pub fn liquidation_price(&self, maintenance_margin_rate: Decimal) -> Decimal {
+        let margin_fraction = Decimal::ONE / Decimal::from(self.leverage.max(1));
+        match self.side {
+            Side::Long => self.entry_price * (Decimal::ONE - margin_fraction + maintenance_margin_rate),
+            Side::Short => self.entry_price * (Decimal::ONE + margin_fraction - maintenance_margin_rate),
+        }
+    }
+}
+
+/// How an order is to be filled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum OrderType {
+    /// Fill immediately at the best available price.
+    #[default]
+    Market,
+    /// Fill only at `price` or better.
+    Limit { price: Decimal },
+    /// Become a market order once the price reaches `stop_price`.
+    StopMarket { stop_price: Decimal },
 }
 
 /// Represents a fully-formed request to place an order on the exchange.
 /// This is the primary output of the Risk Management engine.
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct OrderRequest {
     /// The symbol for the order (e.g., "BTCUSDT").
     pub symbol: Symbol,
@@ -76,12 +213,18 @@ pub struct OrderRequest {
     pub side: Side,
     /// The quantity of the asset to trade.
     pub quantity: Decimal,
+    /// How the order is to be filled. Defaults to `Market` for older serialized requests.
+    #[serde(default)]
+    pub order_type: OrderType,
     /// The leverage to use.
     pub leverage: u8,
     
     /// The calculated stop-loss price for this order.
     pub sl_price: Decimal,
 
+    /// The optional take-profit price for this order.
+    pub tp_price: Option<Decimal>,
+
     /// The signal that triggered this order, including its confidence.
     /// We carry this forward for logging and analysis.
     pub originating_signal: Signal,
@@ -89,7 +232,7 @@ pub struct OrderRequest {
 
 /// Represents a completed trade execution.
 /// This is the primary output of the Execution engine.
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Execution {
     /// The symbol that was traded.
     pub symbol: Symbol,
@@ -101,7 +244,77 @@ pub struct Execution {
     pub quantity: Decimal,
     /// The total fee paid for this execution.
     pub fee: Decimal,
+    /// The exchange's ID for the order, if it was placed on an exchange.
+    #[serde(default)]
+    pub order_id: Option<i64>,
     /// The order request that led to this execution.
     /// Storing this provides a full audit trail for the trade.
     pub source_request: OrderRequest,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn order_requests_without_an_order_type_default_to_market() {
+        // An order request as stored before `order_type` existed.
+        let json = r#"{
+            "symbol": "BTCUSDT",
+            "side": "Long",
+            "quantity": "0.5",
+            "leverage": 10,
+            "sl_price": "58000",
+            "tp_price": null,
+            "originating_signal": "Close"
+        }"#;
+
+        let order: OrderRequest = serde_json::from_str(json).unwrap();
+
+        assert_eq!(order.order_type, OrderType::Market);
+        assert_eq!(order.quantity, dec!(0.5));
+    }
+
+    #[test]
+    fn order_types_round_trip_through_json() {
+        for order_type in [
+            OrderType::Market,
+            OrderType::Limit { price: dec!(60_000.5) },
+            OrderType::StopMarket { stop_price: dec!(59_000) },
+        ] {
+            let json = serde_json::to_string(&order_type).unwrap();
+            assert_eq!(serde_json::from_str::<OrderType>(&json).unwrap(), order_type);
+        }
+    }
+
+    #[test]
+    fn symbols_are_trimmed_and_uppercased() {
+        assert_eq!(Symbol::new("BTCUSDT").unwrap().as_str(), "BTCUSDT");
+        assert_eq!(Symbol::new("  ethusdt ").unwrap().as_str(), "ETHUSDT");
+        assert_eq!(Symbol::new("1000pepeUSDT").unwrap().as_str(), "1000PEPEUSDT");
+    }
+
+    #[test]
+    fn symbols_outside_the_length_bounds_are_rejected() {
+        assert!(matches!(Symbol::new("BTC"), Err(Error::InvalidSymbol(_))));
+        assert!(matches!(Symbol::new("   "), Err(Error::InvalidSymbol(_))));
+        assert!(matches!(Symbol::new(&"A".repeat(Symbol::MAX_LEN + 1)), Err(Error::InvalidSymbol(_))));
+        assert!(Symbol::new(&"A".repeat(Symbol::MAX_LEN)).is_ok());
+    }
+
+    #[test]
+    fn symbols_with_separators_are_rejected() {
+        assert!(matches!(Symbol::new("BTC-USDT"), Err(Error::InvalidSymbol(_))));
+        assert!(matches!(Symbol::new("BTC/USDT"), Err(Error::InvalidSymbol(_))));
+        assert!(matches!(Symbol::new("BTC USDT"), Err(Error::InvalidSymbol(_))));
+    }
+
+    #[test]
+    fn listed_symbols_must_be_in_the_exchange_set() {
+        let listed: HashSet<String> = ["BTCUSDT".to_string()].into_iter().collect();
+
+        assert_eq!(Symbol::new_listed("btcusdt", &listed).unwrap().as_str(), "BTCUSDT");
+        assert!(matches!(Symbol::new_listed("ETHUSDT", &listed), Err(Error::InvalidSymbol(_))));
+        assert!(matches!(Symbol::new_listed("BTC", &listed), Err(Error::InvalidSymbol(_))));
+    }
+}
@@ -11,6 +11,12 @@ pub enum Error {
     MigrateError(#[from] sqlx::migrate::MigrateError),
     #[error("Database operation failed")]
     OperationFailed(sqlx::Error),
+    #[error("Invalid kline interval: {0}")]
+    InvalidInterval(String),
+    #[error("Failed to convert numeric value: {0}")]
+    ConversionError(String),
+    #[error("Unsupported backtest export: {0}")]
+    UnsupportedExport(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
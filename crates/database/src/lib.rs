@@ -1,20 +1,24 @@
 // In crates/database/src/lib.rs (REPLACE ENTIRE FILE)
 
 use app_config::types::DatabaseSettings;
-use sqlx::{postgres::PgPoolOptions, PgPool};
-use core_types::{Kline, Symbol};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool};
+use core_types::{Execution, FundingRate, Kline, Side, Symbol};
 use bigdecimal::BigDecimal;
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::time::Duration;
 use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, StreamExt};
 use serde_json::Value as JsonValue; 
 use analytics::types::PerformanceReport;
 use analytics::types::Trade; // Add this
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use analytics::types::EquityPoint;
 use rust_decimal::Decimal;
+use utoipa::ToSchema;
 
 // API DTO for trades - avoids circular dependency with web-server
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct ApiTrade {
     pub symbol: String,
     pub side: String,
@@ -27,6 +31,20 @@ pub struct ApiTrade {
     pub fees: Decimal,
     pub signal_confidence: f64,
     pub leverage: i32,
+    /// Defaults to zero so exports written before excursions were tracked still import.
+    #[serde(default)]
+    pub max_adverse_excursion: Decimal,
+    #[serde(default)]
+    pub max_favorable_excursion: Decimal,
+    /// Defaults to "unknown" so exports written before reasons were tracked still import.
+    #[serde(default = "default_trade_reason")]
+    pub entry_reason: String,
+    #[serde(default = "default_trade_reason")]
+    pub exit_reason: String,
+}
+
+fn default_trade_reason() -> String {
+    "unknown".to_string()
 }
 
 /// A struct to fetch the report along with its parameters
@@ -37,6 +55,41 @@ pub struct FullReport {
     pub report: PerformanceReport,
 }
 
+impl AsRef<PerformanceReport> for FullReport {
+    fn as_ref(&self) -> &PerformanceReport {
+        &self.report
+    }
+}
+
+/// The current version of the `FullBacktestExport` format. Bump it whenever the format
+/// changes in a way older readers cannot handle.
+pub const BACKTEST_EXPORT_VERSION: u32 = 1;
+
+/// A self-contained archive of a backtest run, for sharing runs outside the database.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FullBacktestExport {
+    /// The format version this document was written with.
+    pub version: u32,
+    pub run: ExportedRunMeta,
+    pub report: PerformanceReport,
+    pub trades: Vec<ApiTrade>,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+/// The descriptive metadata of an exported backtest run.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedRunMeta {
+    pub strategy_name: String,
+    pub symbol: String,
+    pub interval: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub parameters: JsonValue,
+    /// The settings the run was produced with. Absent from exports of runs saved without one.
+    #[serde(default)]
+    pub config: Option<JsonValue>,
+}
+
 pub mod error;
 pub mod types;
 
@@ -50,7 +103,32 @@ pub use error::{Error, Result};
 #[derive(Debug, Clone)]
 pub struct Db(PgPool);
 
-/// Establishes a connection pool to the PostgreSQL database and runs migrations.
+/// Converts a `rust_decimal::Decimal` into the `BigDecimal` type used by `sqlx` for NUMERIC columns.
+pub fn decimal_to_bigdecimal(value: &Decimal) -> Result<BigDecimal> {
+    BigDecimal::from_str(&value.to_string())
+        .map_err(|e| Error::ConversionError(format!("'{}' is not a valid BigDecimal: {}", value, e)))
+}
+
+/// Converts a `BigDecimal` read from a NUMERIC column back into a `rust_decimal::Decimal`.
+///
+/// Fails instead of panicking when the stored value is out of `Decimal`'s range or precision.
+pub fn bigdecimal_to_decimal(value: &BigDecimal) -> Result<Decimal> {
+    let as_string = value.to_string();
+    Decimal::from_str(&as_string)
+        .or_else(|_| Decimal::from_scientific(&as_string))
+        .map_err(|e| Error::ConversionError(format!("'{}' is not a valid Decimal: {}", as_string, e)))
+}
+
+/// The pool size used when `DatabaseSettings::max_connections` is not set.
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+/// The acquire timeout used when `DatabaseSettings::acquire_timeout_secs` is not set.
+pub const DEFAULT_ACQUIRE_TIMEOUT_SECS: u64 = 30;
+/// The idle timeout used when `DatabaseSettings::idle_timeout_secs` is not set.
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
+
+/// Establishes a connection pool to the PostgreSQL database.
+///
+/// Migrations are not run here; see `Db::migrate`.
 ///
 /// # Arguments
 ///
@@ -61,21 +139,51 @@ pub struct Db(PgPool);
 /// A `Result` containing the `Db` wrapper on success, or an `Error` on failure.
 pub async fn connect(settings: &DatabaseSettings) -> Result<Db> {
     // Create a connection pool.
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
+    let pool = pool_options(settings)
         // The `?` operator uses the `#[from]` attribute in our error enum
         // to automatically convert the `sqlx::Error` into a `database::Error`.
         .connect(&settings.url)
         .await?;
 
-    // Run database migrations. This ensures the database schema is up-to-date.
-    sqlx::migrate!("../../migrations").run(&pool).await.map_err(Error::from)?;
-
     Ok(Db(pool))
 }
 
+/// Creates a connection pool that only connects once a query needs a connection.
+///
+/// Unlike `connect`, an unreachable database is not reported here but by the first query.
+pub fn connect_lazy(settings: &DatabaseSettings) -> Result<Db> {
+    Ok(Db(pool_options(settings).connect_lazy(&settings.url)?))
+}
+
+/// The pool options described by `settings`.
+fn pool_options(settings: &DatabaseSettings) -> PgPoolOptions {
+    PgPoolOptions::new()
+        .max_connections(settings.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS))
+        .acquire_timeout(Duration::from_secs(
+            settings.acquire_timeout_secs.unwrap_or(DEFAULT_ACQUIRE_TIMEOUT_SECS),
+        ))
+        .idle_timeout(Duration::from_secs(
+            settings.idle_timeout_secs.unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS),
+        ))
+}
+
+/// Wraps an existing pool, such as the per-test database pool of `sqlx::test`.
+impl From<PgPool> for Db {
+    fn from(pool: PgPool) -> Self {
+        Db(pool)
+    }
+}
+
 // Add the impl block for our Db wrapper struct
 impl Db {
+    /// Runs the database migrations, bringing the schema up to date.
+    ///
+    /// `connect` does not migrate on its own; call this once per process before first use.
+    pub async fn migrate(&self) -> Result<()> {
+        sqlx::migrate!("../../migrations").run(&self.0).await?;
+        Ok(())
+    }
+
     /// Inserts a slice of `Kline` data for a specific interval into the database.
     pub async fn insert_klines(
         &self,
@@ -96,11 +204,11 @@ impl Db {
                 symbol.0,
                 interval, // <-- NEW: Bind the interval variable
                 kline.open_time,
-                BigDecimal::from_str(&kline.open.to_string()).unwrap(),
-                BigDecimal::from_str(&kline.high.to_string()).unwrap(),
-                BigDecimal::from_str(&kline.low.to_string()).unwrap(),
-                BigDecimal::from_str(&kline.close.to_string()).unwrap(),
-                BigDecimal::from_str(&kline.volume.to_string()).unwrap(),
+                decimal_to_bigdecimal(&kline.open)?,
+                decimal_to_bigdecimal(&kline.high)?,
+                decimal_to_bigdecimal(&kline.low)?,
+                decimal_to_bigdecimal(&kline.close)?,
+                decimal_to_bigdecimal(&kline.volume)?,
                 kline.close_time
             )
             .execute(&mut *tx)
@@ -113,6 +221,63 @@ impl Db {
         Ok(())
     }
 
+    /// Inserts funding rates for a symbol, overwriting the rate of any settlement already stored.
+    pub async fn insert_funding_rates(&self, symbol: &Symbol, rates: &[FundingRate]) -> Result<()> {
+        let mut tx = self.0.begin().await.map_err(Error::OperationFailed)?;
+
+        for rate in rates {
+            sqlx::query!(
+                r#"
+                INSERT INTO funding_rates (symbol, funding_time, funding_rate)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (symbol, funding_time) DO UPDATE SET funding_rate = EXCLUDED.funding_rate
+                "#,
+                symbol.0,
+                rate.funding_time,
+                decimal_to_bigdecimal(&rate.funding_rate)?
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::OperationFailed)?;
+        }
+
+        tx.commit().await.map_err(Error::OperationFailed)?;
+
+        Ok(())
+    }
+
+    /// Fetches the funding rates of a symbol settled within a date range (inclusive), in time order.
+    pub async fn get_funding_rates_by_date_range(
+        &self,
+        symbol: &Symbol,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<FundingRate>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT funding_time, funding_rate
+            FROM funding_rates
+            WHERE symbol = $1 AND funding_time >= $2 AND funding_time <= $3
+            ORDER BY funding_time ASC
+            "#,
+            symbol.0,
+            start_time.timestamp_millis(),
+            end_time.timestamp_millis()
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(FundingRate {
+                    funding_time: row.funding_time,
+                    funding_rate: bigdecimal_to_decimal(&row.funding_rate)?,
+                })
+            })
+            .collect()
+    }
+
     /// Fetches klines for a given symbol, interval, and date range from the database.
     ///
     /// # Arguments
@@ -154,119 +319,213 @@ impl Db {
 
         let klines = rows
             .into_iter()
-            .map(|row| Kline {
-                open_time: row.open_time,
-                open: row.open.to_string().parse().unwrap(),
-                high: row.high.to_string().parse().unwrap(),
-                low: row.low.to_string().parse().unwrap(),
-                close: row.close.to_string().parse().unwrap(),
-                volume: row.volume.to_string().parse().unwrap(),
-                close_time: row.close_time,
+            .map(|row| {
+                Ok(Kline {
+                    open_time: row.open_time,
+                    open: bigdecimal_to_decimal(&row.open)?,
+                    high: bigdecimal_to_decimal(&row.high)?,
+                    low: bigdecimal_to_decimal(&row.low)?,
+                    close: bigdecimal_to_decimal(&row.close)?,
+                    volume: bigdecimal_to_decimal(&row.volume)?,
+                    close_time: row.close_time,
+                })
             })
-            .collect();
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(klines)
     }
 
-    /// Saves a backtest run and its corresponding performance report to the database.
+    /// Like [`Db::get_klines_by_date_range`], but also checks the range for missing klines
+    /// and returns a summary of the gaps found, so callers can warn about unreliable data.
+    pub async fn get_klines_checked(
+        &self,
+        symbol: &Symbol,
+        interval: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<(Vec<Kline>, KlineGapSummary)> {
+        let klines = self.get_klines_by_date_range(symbol, interval, start_time, end_time).await?;
+        let gaps = self.find_kline_gaps(symbol, interval, start_time, end_time).await?;
+        let interval_ms = core_types::interval_to_millis(interval)
+            .ok_or_else(|| Error::InvalidInterval(interval.to_string()))?;
+        let summary = KlineGapSummary::from_gaps(&gaps, interval_ms, klines.len());
+        Ok((klines, summary))
+    }
+
+    /// Fetches the most recent `limit` klines for a symbol and interval, in ascending time order.
     ///
-    /// # Arguments
+    /// This is used to warm up strategies before they start consuming a live stream.
+    pub async fn get_latest_klines(
+        &self,
+        symbol: &Symbol,
+        interval: &str,
+        limit: i64,
+    ) -> Result<Vec<Kline>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT open_time, open, high, low, close, volume, close_time
+            FROM klines
+            WHERE symbol = $1 AND interval = $2
+            ORDER BY open_time DESC
+            LIMIT $3
+            "#,
+            symbol.0,
+            interval,
+            limit
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        let mut klines = rows
+            .into_iter()
+            .map(|row| {
+                Ok(Kline {
+                    open_time: row.open_time,
+                    open: bigdecimal_to_decimal(&row.open)?,
+                    high: bigdecimal_to_decimal(&row.high)?,
+                    low: bigdecimal_to_decimal(&row.low)?,
+                    close: bigdecimal_to_decimal(&row.close)?,
+                    volume: bigdecimal_to_decimal(&row.volume)?,
+                    close_time: row.close_time,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        klines.reverse();
+
+        Ok(klines)
+    }
+
+    /// Detects missing klines for a given symbol and interval within a date range.
     ///
-    /// * `strategy_name`: The name of the strategy that was tested.
-    /// * `symbol`: The symbol the test was run on.
-    /// * `interval`: The interval used for the test.
-    /// * `start_date`: The start date of the test period.
-    /// * `end_date`: The end date of the test period.
-    /// * `parameters`: The strategy parameters, to be serialized to JSON.
-    /// * `report`: The calculated `PerformanceReport`.
+    /// The expected `open_time` of every kline is derived from the interval and compared
+    /// against what is stored, so holes left by interrupted backfills or exchange outages
+    /// are reported, including missing data at the very start or end of the range.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the ID of the new backtest run on success.
-    pub async fn save_backtest_report<T: serde::Serialize>(
+    /// A `Result` containing the list of missing `open_time` ranges, in ascending order.
+    pub async fn find_kline_gaps(
         &self,
-        job_id: Option<i64>, // <-- Add this parameter
-        strategy_name: &str,
         symbol: &Symbol,
         interval: &str,
-        start_date: DateTime<Utc>,
-        end_date: DateTime<Utc>,
-        parameters: &T,
-        report: &PerformanceReport,
-    ) -> Result<i64> {
-        // --- 1. Start a Transaction ---
-        let mut tx = self.0.begin().await.map_err(Error::OperationFailed)?;
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<KlineGap>> {
+        let interval_ms = core_types::interval_to_millis(interval)
+            .ok_or_else(|| Error::InvalidInterval(interval.to_string()))?;
 
-        // --- 2. Serialize Parameters to JSON ---
-        let params_json: JsonValue = serde_json::to_value(parameters)
-            .map_err(|e| Error::OperationFailed(sqlx::Error::Decode(e.into())))?;
+        // Align the range to the interval grid so partial bars at the edges are ignored.
+        let start_ts = start_time.timestamp_millis();
+        let first_expected = start_ts + (interval_ms - start_ts.rem_euclid(interval_ms)) % interval_ms;
+        let end_ts = end_time.timestamp_millis();
+        let last_expected = end_ts - end_ts.rem_euclid(interval_ms);
+
+        if first_expected > last_expected {
+            return Ok(Vec::new());
+        }
 
-        // --- 3. Insert into `backtest_runs` and get the new ID ---
-        let run_id: i64 = sqlx::query!(
+        let open_times: Vec<i64> = sqlx::query_scalar!(
             r#"
-            INSERT INTO backtest_runs (job_id, strategy_name, symbol, interval, start_date, end_date, parameters)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id
+            SELECT open_time
+            FROM klines
+            WHERE symbol = $1 AND interval = $2 AND open_time >= $3 AND open_time <= $4
+            ORDER BY open_time ASC
             "#,
-            job_id, // <-- Bind the new parameter
-            strategy_name,
             symbol.0,
             interval,
-            start_date,
-            end_date,
-            params_json
+            first_expected,
+            last_expected
         )
-        .fetch_one(&mut *tx)
+        .fetch_all(&self.0)
         .await
-        .map_err(Error::OperationFailed)?
-        .id;
+        .map_err(Error::OperationFailed)?;
 
-        // --- 4. Serialize the Confidence Performance to JSON ---
-        let confidence_json: JsonValue = serde_json::to_value(&report.confidence_performance)
-             .map_err(|e| Error::OperationFailed(sqlx::Error::Decode(e.into())))?;
+        let mut gaps = Vec::new();
+        let mut next_expected = first_expected;
+        for open_time in open_times {
+            if open_time > next_expected {
+                gaps.push(KlineGap {
+                    start: next_expected,
+                    end: open_time - interval_ms,
+                });
+            }
+            next_expected = next_expected.max(open_time + interval_ms);
+        }
+        if next_expected <= last_expected {
+            gaps.push(KlineGap {
+                start: next_expected,
+                end: last_expected,
+            });
+        }
 
-        // --- Convert Decimal fields to BigDecimal for sqlx ---
-        let net_pnl_absolute_bd = BigDecimal::from_str(&report.net_pnl_absolute.to_string()).unwrap();
-        let max_drawdown_absolute_bd = BigDecimal::from_str(&report.max_drawdown_absolute.to_string()).unwrap();
-        let expectancy_bd = BigDecimal::from_str(&report.expectancy.to_string()).unwrap();
-        let funding_pnl_bd = BigDecimal::from_str(&report.funding_pnl.to_string()).unwrap();
+        Ok(gaps)
+    }
 
-        // --- 5. Insert into `performance_reports` ---
-        sqlx::query!(
+    /// Deletes all klines for a symbol and interval whose `open_time` is before the cutoff.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows deleted.
+    pub async fn prune_klines(
+        &self,
+        symbol: &Symbol,
+        interval: &str,
+        older_than: DateTime<Utc>,
+    ) -> Result<u64> {
+        let result = sqlx::query!(
+            "DELETE FROM klines WHERE symbol = $1 AND interval = $2 AND open_time < $3",
+            symbol.0,
+            interval,
+            older_than.timestamp_millis()
+        )
+        .execute(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Lists every symbol/interval combination that has kline data, with its coverage.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing one `KlineSeries` per distinct `(symbol, interval)` pair.
+    pub async fn get_available_series(&self) -> Result<Vec<KlineSeries>> {
+        let series = sqlx::query_as!(
+            KlineSeries,
             r#"
-            INSERT INTO performance_reports (
-                run_id, net_pnl_absolute, net_pnl_percentage, max_drawdown_absolute,
-                max_drawdown_percentage, sharpe_ratio, win_rate, profit_factor, total_trades,
-                sortino_ratio, calmar_ratio, avg_trade_duration_secs, expectancy,
-                confidence_performance, larom, funding_pnl, drawdown_duration_secs
-            )
-            VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17
-            )
-            "#,
-            run_id,
-            net_pnl_absolute_bd,
-            report.net_pnl_percentage,
-            max_drawdown_absolute_bd,
-            report.max_drawdown_percentage,
-            report.sharpe_ratio,
-            report.win_rate,
-            report.profit_factor,
-            report.total_trades as i32, // cast u32 to i32 for postgres
-            report.sortino_ratio,
-            report.calmar_ratio,
-            report.avg_trade_duration_secs as i64, // cast f64 to i64
-            expectancy_bd,
-            confidence_json,
-            report.larom,
-            funding_pnl_bd,
-            report.drawdown_duration_secs
+            SELECT
+                symbol,
+                interval,
+                MIN(open_time) as "min_open_time!",
+                MAX(open_time) as "max_open_time!",
+                COUNT(*) as "count!"
+            FROM klines
+            GROUP BY symbol, interval
+            ORDER BY symbol ASC, interval ASC
+            "#
         )
-        .execute(&mut *tx)
+        .fetch_all(&self.0)
         .await
         .map_err(Error::OperationFailed)?;
 
-        // --- 6. Commit the Transaction ---
+        Ok(series)
+    }
+
+    /// Saves a backtest run and its corresponding performance report to the database.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the ID of the new backtest run on success.
+    pub async fn save_backtest_report<T: serde::Serialize>(
+        &self,
+        job_id: Option<i64>,
+        meta: &BacktestRunMeta<'_, T>,
+        report: &PerformanceReport,
+    ) -> Result<i64> {
+        let mut tx = self.0.begin().await.map_err(Error::OperationFailed)?;
+        let run_id = insert_backtest_report(&mut tx, job_id, meta, report).await?;
         tx.commit().await.map_err(Error::OperationFailed)?;
 
         Ok(run_id)
@@ -278,35 +537,129 @@ impl Db {
             return Ok(());
         }
         let mut tx = self.0.begin().await.map_err(Error::OperationFailed)?;
-        for trade in trades {
-            sqlx::query!(
-                r#"
-                INSERT INTO trades (
-                    run_id, symbol, side, entry_time, exit_time, entry_price,
-                    exit_price, quantity, pnl, fees, signal_confidence, leverage
-                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-                "#,
-                run_id,
-                trade.symbol.0,
-                format!("{:?}", trade.side), // "Long" or "Short"
-                trade.entry_time,
-                trade.exit_time,
-                BigDecimal::from_str(&trade.entry_price.to_string()).unwrap(),
-                BigDecimal::from_str(&trade.exit_price.to_string()).unwrap(),
-                BigDecimal::from_str(&trade.quantity.to_string()).unwrap(),
-                BigDecimal::from_str(&trade.pnl.to_string()).unwrap(),
-                BigDecimal::from_str(&trade.fees.to_string()).unwrap(),
-                trade.signal_confidence,
-                trade.leverage as i32
-            )
-            .execute(&mut *tx)
-            .await
-            .map_err(Error::OperationFailed)?;
-        }
+        insert_trades(&mut tx, run_id, trades).await?;
         tx.commit().await.map_err(Error::OperationFailed)?;
         Ok(())
     }
 
+    /// Saves a complete backtest (run metadata, report, trades, and equity curve) atomically.
+    ///
+    /// All inserts happen inside a single transaction, so a failure at any stage rolls
+    /// everything back and never leaves a run without its trades or equity curve.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the ID of the new backtest run on success.
+    pub async fn save_full_backtest<T: serde::Serialize>(
+        &self,
+        job_id: Option<i64>,
+        meta: &BacktestRunMeta<'_, T>,
+        report: &PerformanceReport,
+        trades: &[Trade],
+        equity_curve: &[EquityPoint],
+    ) -> Result<i64> {
+        let mut tx = self.0.begin().await.map_err(Error::OperationFailed)?;
+
+        let run_id = insert_backtest_report(&mut tx, job_id, meta, report).await?;
+        insert_trades(&mut tx, run_id, trades).await?;
+        insert_equity_curve(&mut tx, run_id, equity_curve).await?;
+
+        tx.commit().await.map_err(Error::OperationFailed)?;
+
+        Ok(run_id)
+    }
+
+    /// Bundles a backtest run's metadata, report, trades and equity curve into a single
+    /// `FullBacktestExport`. Returns `None` if the run does not exist.
+    pub async fn export_backtest(&self, run_id: i64) -> Result<Option<FullBacktestExport>> {
+        let Some(run) = sqlx::query!(
+            "SELECT strategy_name, symbol, interval, start_date, end_date, parameters, config FROM backtest_runs WHERE id = $1",
+            run_id
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?
+        else {
+            return Ok(None);
+        };
+        let Some(report) = self.get_performance_report(run_id).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(FullBacktestExport {
+            version: BACKTEST_EXPORT_VERSION,
+            run: ExportedRunMeta {
+                strategy_name: run.strategy_name,
+                symbol: run.symbol,
+                interval: run.interval,
+                start_date: run.start_date,
+                end_date: run.end_date,
+                parameters: run.parameters,
+                config: run.config,
+            },
+            report,
+            trades: self.get_all_trades_for_run(run_id).await?,
+            equity_curve: self.get_equity_curve_for_run(run_id).await?,
+        }))
+    }
+
+    /// Inserts a `FullBacktestExport` as a new standalone run, atomically.
+    ///
+    /// Exports written by a newer format version than `BACKTEST_EXPORT_VERSION` are rejected.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the ID of the new backtest run on success.
+    pub async fn import_backtest(&self, export: &FullBacktestExport) -> Result<i64> {
+        if export.version == 0 || export.version > BACKTEST_EXPORT_VERSION {
+            return Err(Error::UnsupportedExport(format!(
+                "format version {} is not supported (expected 1 to {})",
+                export.version, BACKTEST_EXPORT_VERSION
+            )));
+        }
+
+        let symbol = Symbol::new(&export.run.symbol).map_err(|e| Error::UnsupportedExport(e.to_string()))?;
+        let trades = export
+            .trades
+            .iter()
+            .map(|t| {
+                Ok(Trade {
+                    symbol: Symbol::new(&t.symbol).map_err(|e| Error::UnsupportedExport(e.to_string()))?,
+                    side: match t.side.as_str() {
+                        "Long" => Side::Long,
+                        "Short" => Side::Short,
+                        other => return Err(Error::UnsupportedExport(format!("unknown trade side '{}'", other))),
+                    },
+                    entry_time: t.entry_time,
+                    exit_time: t.exit_time,
+                    entry_price: t.entry_price,
+                    exit_price: t.exit_price,
+                    quantity: t.quantity,
+                    pnl: t.pnl,
+                    fees: t.fees,
+                    signal_confidence: t.signal_confidence,
+                    leverage: u8::try_from(t.leverage)
+                        .map_err(|_| Error::ConversionError(format!("leverage {} out of range", t.leverage)))?,
+                    max_adverse_excursion: t.max_adverse_excursion,
+                    max_favorable_excursion: t.max_favorable_excursion,
+                    entry_reason: t.entry_reason.parse().map_err(Error::UnsupportedExport)?,
+                    exit_reason: t.exit_reason.parse().map_err(Error::UnsupportedExport)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let meta = BacktestRunMeta {
+            strategy_name: &export.run.strategy_name,
+            symbol: &symbol,
+            interval: &export.run.interval,
+            start_date: export.run.start_date,
+            end_date: export.run.end_date,
+            parameters: &export.run.parameters,
+            config: export.run.config.as_ref(),
+        };
+
+        self.save_full_backtest(None, &meta, &export.report, &trades, &export.equity_curve).await
+    }
+
     /// Creates a new optimization job entry and returns its ID.
     pub async fn create_optimization_job(&self, name: &str) -> Result<i64> {
         let record = sqlx::query!(
@@ -320,6 +673,58 @@ impl Db {
         Ok(record.id)
     }
 
+    /// Flags an optimization job for cancellation. Returns `false` if the job does not exist.
+    pub async fn request_optimization_cancel(&self, job_id: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE optimization_jobs SET cancel_requested = true WHERE id = $1",
+            job_id
+        )
+        .execute(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Checks whether cancellation of an optimization job has been requested.
+    pub async fn is_optimization_cancel_requested(&self, job_id: i64) -> Result<bool> {
+        let record = sqlx::query!(
+            "SELECT cancel_requested FROM optimization_jobs WHERE id = $1",
+            job_id
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        Ok(record.is_some_and(|r| r.cancel_requested))
+    }
+
+    /// Records how an optimization job ended.
+    pub async fn finish_optimization_job(&self, job_id: i64, status: OptimizationJobStatus) -> Result<()> {
+        sqlx::query!(
+            "UPDATE optimization_jobs SET status = $1 WHERE id = $2",
+            status.as_str(),
+            job_id
+        )
+        .execute(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        Ok(())
+    }
+
+    /// Fetches a single optimization job by its ID.
+    pub async fn get_optimization_job(&self, job_id: i64) -> Result<Option<OptimizationJob>> {
+        sqlx::query_as!(
+            OptimizationJob,
+            "SELECT id, name, status, created_at FROM optimization_jobs WHERE id = $1",
+            job_id
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::OperationFailed)
+    }
+
     /// Fetches all performance reports associated with a given optimization job ID.
     pub async fn get_reports_for_job(&self, job_id: i64) -> Result<Vec<FullReport>> {
         let records = sqlx::query!(
@@ -338,9 +743,9 @@ impl Db {
         let full_reports = records.into_iter().map(|r| {
             let report = PerformanceReport {
                 run_id: r.run_id,
-                net_pnl_absolute: r.net_pnl_absolute.to_string().parse().unwrap_or_default(),
+                net_pnl_absolute: bigdecimal_to_decimal(&r.net_pnl_absolute)?,
                 net_pnl_percentage: r.net_pnl_percentage,
-                max_drawdown_absolute: r.max_drawdown_absolute.to_string().parse().unwrap_or_default(),
+                max_drawdown_absolute: bigdecimal_to_decimal(&r.max_drawdown_absolute)?,
                 max_drawdown_percentage: r.max_drawdown_percentage,
                 sharpe_ratio: r.sharpe_ratio,
                 win_rate: r.win_rate,
@@ -348,19 +753,26 @@ impl Db {
                 total_trades: r.total_trades as u32,
                 sortino_ratio: r.sortino_ratio,
                 calmar_ratio: r.calmar_ratio,
+                omega_ratio: r.omega_ratio,
+                tail_ratio: r.tail_ratio,
+                value_at_risk_95: r.value_at_risk_95,
+                conditional_var_95: r.conditional_var_95,
                 avg_trade_duration_secs: r.avg_trade_duration_secs as f64,
-                expectancy: r.expectancy.to_string().parse().unwrap_or_default(),
+                expectancy: bigdecimal_to_decimal(&r.expectancy)?,
                 confidence_performance: serde_json::from_value(r.confidence_performance.unwrap_or_default()).unwrap_or_default(),
+                by_exit_reason: serde_json::from_value(r.by_exit_reason.unwrap_or_default()).unwrap_or_default(),
                 larom: r.larom,
-                funding_pnl: r.funding_pnl.to_string().parse().unwrap_or_default(),
+                funding_pnl: bigdecimal_to_decimal(&r.funding_pnl)?,
                 drawdown_duration_secs: r.drawdown_duration_secs,
+                aborted: r.aborted,
+                cancelled: r.cancelled,
             };
-            FullReport { 
+            Ok(FullReport { 
                 run_id: r.run_id, 
                 parameters: r.parameters, 
                 report 
-            }
-        }).collect();
+            })
+        }).collect::<Result<Vec<_>>>()?;
 
         Ok(full_reports)
     }
@@ -383,7 +795,7 @@ impl Db {
 
         let jobs = sqlx::query_as!(
             OptimizationJob,
-            "SELECT id, name, created_at FROM optimization_jobs ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+            "SELECT id, name, status, created_at FROM optimization_jobs ORDER BY created_at DESC LIMIT $1 OFFSET $2",
             page_size as i64,
             offset as i64
         )
@@ -401,18 +813,131 @@ impl Db {
         Ok((jobs, total_count))
     }
 
-    /// Fetches the detailed summary for a single optimization job.
-    pub async fn get_optimization_summary(&self, job_id: i64) -> Result<Option<JsonValue>> {
-        let record = sqlx::query!(
-            "SELECT top_n_results FROM optimization_summaries WHERE job_id = $1",
-            job_id
-        )
-        .fetch_optional(&self.0)
-        .await
-        .map_err(Error::OperationFailed)?;
-
-        // `fetch_optional` returns an Option, which is perfect.
-        // If the record exists, we return Some(json), otherwise None.
+    /// Records an execution made by the live engine, along with the portfolio state after it.
+    pub async fn save_live_trade<T: Serialize>(
+        &self,
+        execution: &Execution,
+        portfolio_snapshot: &T,
+    ) -> Result<i64> {
+        let snapshot_json: JsonValue = serde_json::to_value(portfolio_snapshot)
+            .map_err(|e| Error::OperationFailed(sqlx::Error::Decode(e.into())))?;
+        let signal_json: JsonValue = serde_json::to_value(execution.source_request.originating_signal)
+            .map_err(|e| Error::OperationFailed(sqlx::Error::Decode(e.into())))?;
+
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO live_trades (symbol, side, price, quantity, fee, leverage, order_id, originating_signal, portfolio_snapshot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id
+            "#,
+            execution.symbol.0,
+            format!("{:?}", execution.side), // "Long" or "Short"
+            decimal_to_bigdecimal(&execution.price)?,
+            decimal_to_bigdecimal(&execution.quantity)?,
+            decimal_to_bigdecimal(&execution.fee)?,
+            execution.source_request.leverage as i32,
+            execution.order_id,
+            signal_json,
+            snapshot_json
+        )
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?
+        .id;
+
+        Ok(id)
+    }
+
+    /// Records a snapshot of the live portfolio's equity.
+    pub async fn save_live_equity_point(&self, point: &EquityPoint) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO live_equity_curve (timestamp, equity) VALUES ($1, $2)",
+            point.timestamp,
+            decimal_to_bigdecimal(&point.value)?
+        )
+        .execute(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+        Ok(())
+    }
+
+    /// Fetches the live equity curve, oldest first.
+    pub async fn get_live_equity_curve(&self) -> Result<Vec<EquityPoint>> {
+        let rows = sqlx::query!(
+            "SELECT timestamp, equity FROM live_equity_curve ORDER BY timestamp ASC, id ASC"
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(EquityPoint {
+                    timestamp: row.timestamp,
+                    value: bigdecimal_to_decimal(&row.equity)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Fetches a paginated list of live trades, newest first.
+    pub async fn get_live_trades_paginated(
+        &self,
+        page: u32,
+        page_size: u32,
+    ) -> Result<(Vec<LiveTrade>, i64)> {
+        let offset = (page - 1) * page_size;
+
+        let rows = sqlx::query!(
+            r#"SELECT id, symbol, side, price, quantity, fee, leverage, order_id, originating_signal, portfolio_snapshot, executed_at FROM live_trades ORDER BY executed_at DESC, id DESC LIMIT $1 OFFSET $2"#,
+            page_size as i64,
+            offset as i64
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        let trades = rows
+            .into_iter()
+            .map(|row| {
+                Ok(LiveTrade {
+                    id: row.id,
+                    symbol: row.symbol,
+                    side: row.side,
+                    price: bigdecimal_to_decimal(&row.price)?,
+                    quantity: bigdecimal_to_decimal(&row.quantity)?,
+                    fee: bigdecimal_to_decimal(&row.fee)?,
+                    leverage: row.leverage,
+                    order_id: row.order_id,
+                    originating_signal: row.originating_signal,
+                    portfolio_snapshot: row.portfolio_snapshot,
+                    executed_at: row.executed_at,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let total_count = sqlx::query!("SELECT COUNT(*) as count FROM live_trades")
+            .fetch_one(&self.0)
+            .await
+            .map_err(Error::OperationFailed)?
+            .count
+            .unwrap_or(0);
+
+        Ok((trades, total_count))
+    }
+
+    /// Fetches the detailed summary for a single optimization job.
+    pub async fn get_optimization_summary(&self, job_id: i64) -> Result<Option<JsonValue>> {
+        let record = sqlx::query!(
+            "SELECT top_n_results FROM optimization_summaries WHERE job_id = $1",
+            job_id
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        // `fetch_optional` returns an Option, which is perfect.
+        // If the record exists, we return Some(json), otherwise None.
         Ok(record.map(|r| r.top_n_results))
     }
 
@@ -427,11 +952,11 @@ impl Db {
         .map_err(Error::OperationFailed)?;
         
         // Manual mapping from the flat DB record to our PerformanceReport struct
-        Ok(record.map(|r| PerformanceReport {
+        record.map(|r| Ok(PerformanceReport {
             run_id: r.run_id,
-            net_pnl_absolute: r.net_pnl_absolute.to_string().parse().unwrap_or_default(),
+            net_pnl_absolute: bigdecimal_to_decimal(&r.net_pnl_absolute)?,
             net_pnl_percentage: r.net_pnl_percentage,
-            max_drawdown_absolute: r.max_drawdown_absolute.to_string().parse().unwrap_or_default(),
+            max_drawdown_absolute: bigdecimal_to_decimal(&r.max_drawdown_absolute)?,
             max_drawdown_percentage: r.max_drawdown_percentage,
             sharpe_ratio: r.sharpe_ratio,
             win_rate: r.win_rate,
@@ -439,13 +964,21 @@ impl Db {
             total_trades: r.total_trades as u32,
             sortino_ratio: r.sortino_ratio,
             calmar_ratio: r.calmar_ratio,
+            omega_ratio: r.omega_ratio,
+            tail_ratio: r.tail_ratio,
+            value_at_risk_95: r.value_at_risk_95,
+            conditional_var_95: r.conditional_var_95,
             avg_trade_duration_secs: r.avg_trade_duration_secs as f64,
-            expectancy: r.expectancy.to_string().parse().unwrap_or_default(),
+            expectancy: bigdecimal_to_decimal(&r.expectancy)?,
             confidence_performance: serde_json::from_value(r.confidence_performance.unwrap_or_default()).unwrap_or_default(),
+            by_exit_reason: serde_json::from_value(r.by_exit_reason.unwrap_or_default()).unwrap_or_default(),
             larom: r.larom,
-            funding_pnl: r.funding_pnl.to_string().parse().unwrap_or_default(),
+            funding_pnl: bigdecimal_to_decimal(&r.funding_pnl)?,
             drawdown_duration_secs: r.drawdown_duration_secs,
+            aborted: r.aborted,
+            cancelled: r.cancelled,
         }))
+        .transpose()
     }
 
     /// Fetches the full equity curve for a single backtest run ID.
@@ -460,58 +993,105 @@ impl Db {
         
         let points = rows
             .into_iter()
-            .map(|row| EquityPoint {
-                timestamp: row.timestamp,
-                value: row.equity.to_string().parse().unwrap_or_default(),
+            .map(|row| {
+                Ok(EquityPoint {
+                    timestamp: row.timestamp,
+                    value: bigdecimal_to_decimal(&row.equity)?,
+                })
             })
-            .collect();
+            .collect::<Result<Vec<_>>>()?;
         
         Ok(points)
     }
 
+    /// Fetches the P&L of every trade of a backtest run, in entry order.
+    pub async fn get_trade_pnls_for_run(&self, run_id: i64) -> Result<Vec<Decimal>> {
+        let rows = sqlx::query!(
+            "SELECT pnl FROM trades WHERE run_id = $1 ORDER BY entry_time ASC",
+            run_id
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        rows.iter().map(|row| bigdecimal_to_decimal(&row.pnl)).collect()
+    }
+
+    /// Fetches every trade of a single backtest run ID, in entry order.
+    pub async fn get_all_trades_for_run(&self, run_id: i64) -> Result<Vec<ApiTrade>> {
+        let rows = sqlx::query!(
+            r#"SELECT symbol, side, entry_time, exit_time, entry_price, exit_price, quantity, pnl, fees, signal_confidence, leverage, max_adverse_excursion, max_favorable_excursion, entry_reason, exit_reason FROM trades WHERE run_id = $1 ORDER BY entry_time ASC"#,
+            run_id
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(ApiTrade {
+                    symbol: row.symbol,
+                    side: row.side,
+                    entry_time: row.entry_time,
+                    exit_time: row.exit_time,
+                    entry_price: bigdecimal_to_decimal(&row.entry_price)?,
+                    exit_price: bigdecimal_to_decimal(&row.exit_price)?,
+                    quantity: bigdecimal_to_decimal(&row.quantity)?,
+                    pnl: bigdecimal_to_decimal(&row.pnl)?,
+                    fees: bigdecimal_to_decimal(&row.fees)?,
+                    signal_confidence: row.signal_confidence,
+                    leverage: row.leverage,
+                    max_adverse_excursion: bigdecimal_to_decimal(&row.max_adverse_excursion)?,
+                    max_favorable_excursion: bigdecimal_to_decimal(&row.max_favorable_excursion)?,
+                    entry_reason: row.entry_reason,
+                    exit_reason: row.exit_reason,
+                })
+            })
+            .collect()
+    }
+
+    /// Streams every trade of a single backtest run ID, in entry order, from a database
+    /// cursor, so large runs are never loaded into memory at once.
+    pub fn stream_trades_for_run(&self, run_id: i64) -> BoxStream<'_, Result<ApiTrade>> {
+        sqlx::query(
+            "SELECT symbol, side, entry_time, exit_time, entry_price, exit_price, quantity, pnl, fees, signal_confidence, leverage, max_adverse_excursion, max_favorable_excursion, entry_reason, exit_reason FROM trades WHERE run_id = $1 ORDER BY entry_time ASC, id ASC"
+        )
+        .bind(run_id)
+        .fetch(&self.0)
+        .map(|row| row.map_err(Error::OperationFailed).and_then(|row| api_trade_from_row(&row)))
+        .boxed()
+    }
+
     /// Fetches a paginated list of trades for a single backtest run ID.
     pub async fn get_trades_for_run_paginated(
         &self,
         run_id: i64,
         page: u32,
         page_size: u32,
+        filter: &TradeFilter,
     ) -> Result<(Vec<ApiTrade>, i64)> {
         let offset = (page - 1) * page_size;
 
-        let rows = sqlx::query!(
-            r#"SELECT symbol, side, entry_time, exit_time, entry_price, exit_price, quantity, pnl, fees, signal_confidence, leverage FROM trades WHERE run_id = $1 ORDER BY entry_time ASC LIMIT $2 OFFSET $3"#,
-            run_id,
-            page_size as i64,
-            offset as i64
-        )
-        .fetch_all(&self.0)
-        .await
-        .map_err(Error::OperationFailed)?;
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "SELECT symbol, side, entry_time, exit_time, entry_price, exit_price, quantity, pnl, fees, signal_confidence, leverage, max_adverse_excursion, max_favorable_excursion, entry_reason, exit_reason FROM trades WHERE run_id = "
+        );
+        query_builder.push_bind(run_id);
+        let mut count_builder = sqlx::QueryBuilder::new("SELECT COUNT(*) as count FROM trades WHERE run_id = ");
+        count_builder.push_bind(run_id);
 
-        let trades = rows
-            .into_iter()
-            .map(|row| ApiTrade {
-                symbol: row.symbol,
-                side: row.side,
-                entry_time: row.entry_time,
-                exit_time: row.exit_time,
-                entry_price: row.entry_price.to_string().parse().unwrap_or_default(),
-                exit_price: row.exit_price.to_string().parse().unwrap_or_default(),
-                quantity: row.quantity.to_string().parse().unwrap_or_default(),
-                pnl: row.pnl.to_string().parse().unwrap_or_default(),
-                fees: row.fees.to_string().parse().unwrap_or_default(),
-                signal_confidence: row.signal_confidence,
-                leverage: row.leverage,
-            })
-            .collect();
+        // The count uses the same filter, so pagination stays consistent with the results.
+        push_trade_filter(&mut query_builder, filter);
+        push_trade_filter(&mut count_builder, filter);
+
+        query_builder.push(" ORDER BY entry_time ASC LIMIT ");
+        query_builder.push_bind(page_size as i64);
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset as i64);
+
+        let rows = query_builder.build().fetch_all(&self.0).await.map_err(Error::OperationFailed)?;
+        let trades = rows.iter().map(api_trade_from_row).collect::<Result<Vec<_>>>()?;
+        let total_count = count_builder.build_query_scalar::<i64>().fetch_one(&self.0).await.map_err(Error::OperationFailed)?;
 
-        let total_count = sqlx::query!("SELECT COUNT(*) as count FROM trades WHERE run_id = $1", run_id)
-            .fetch_one(&self.0)
-            .await
-            .map_err(Error::OperationFailed)?
-            .count
-            .unwrap_or(0);
-        
         Ok((trades, total_count))
     }
 
@@ -540,21 +1120,54 @@ impl Db {
             return Ok(());
         }
         let mut tx = self.0.begin().await.map_err(Error::OperationFailed)?;
-        for point in equity_curve {
-            sqlx::query!(
-                "INSERT INTO equity_curves (run_id, timestamp, equity) VALUES ($1, $2, $3)",
-                run_id,
-                point.timestamp,
-                BigDecimal::from_str(&point.value.to_string()).unwrap()
-            )
-            .execute(&mut *tx)
-            .await
-            .map_err(Error::OperationFailed)?;
-        }
+        insert_equity_curve(&mut tx, run_id, equity_curve).await?;
         tx.commit().await.map_err(Error::OperationFailed)?;
         Ok(())
     }
 
+    /// Computes aggregate statistics across all backtest runs.
+    pub async fn get_global_stats(&self) -> Result<GlobalStats> {
+        // One round trip: every aggregate is a scalar subquery over the same CTE, with the
+        // best/worst runs and the strategy distribution returned as JSON.
+        let row = sqlx::query!(
+            r#"
+            WITH run_stats AS (
+                SELECT r.id, r.strategy_name, r.symbol, p.net_pnl_percentage, p.sharpe_ratio, p.total_trades
+                FROM backtest_runs r
+                LEFT JOIN performance_reports p ON p.run_id = r.id
+            )
+            SELECT
+                (SELECT COUNT(*) FROM run_stats) AS "total_runs!",
+                (SELECT COALESCE(SUM(total_trades), 0) FROM run_stats) AS "total_trades!: i64",
+                (SELECT AVG(sharpe_ratio) FROM run_stats
+                    WHERE sharpe_ratio NOT IN ('NaN', 'Infinity', '-Infinity')) AS average_sharpe_ratio,
+                (SELECT to_jsonb(b) FROM (
+                    SELECT id AS run_id, strategy_name, symbol, net_pnl_percentage FROM run_stats
+                    WHERE net_pnl_percentage IS NOT NULL AND net_pnl_percentage <> 'NaN'
+                    ORDER BY net_pnl_percentage DESC LIMIT 1) b) AS best_run,
+                (SELECT to_jsonb(w) FROM (
+                    SELECT id AS run_id, strategy_name, symbol, net_pnl_percentage FROM run_stats
+                    WHERE net_pnl_percentage IS NOT NULL AND net_pnl_percentage <> 'NaN'
+                    ORDER BY net_pnl_percentage ASC LIMIT 1) w) AS worst_run,
+                (SELECT COALESCE(jsonb_object_agg(strategy_name, runs), '{}'::jsonb) FROM (
+                    SELECT strategy_name, COUNT(*) AS runs FROM run_stats GROUP BY strategy_name) s) AS "runs_per_strategy!"
+            "#
+        )
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::OperationFailed)?;
+
+        let decode = |e: serde_json::Error| Error::OperationFailed(sqlx::Error::Decode(e.into()));
+        Ok(GlobalStats {
+            total_runs: row.total_runs,
+            total_trades: row.total_trades,
+            average_sharpe_ratio: row.average_sharpe_ratio,
+            best_run: row.best_run.map(serde_json::from_value).transpose().map_err(decode)?,
+            worst_run: row.worst_run.map(serde_json::from_value).transpose().map_err(decode)?,
+            runs_per_strategy: serde_json::from_value(row.runs_per_strategy).map_err(decode)?,
+        })
+    }
+
     /// Fetches a paginated list of backtest runs from the database.
     /// Also returns the total count of all runs for pagination controls.
     pub async fn get_backtest_runs_paginated(
@@ -592,8 +1205,207 @@ impl Db {
     }
 }
 
+/// Appends the `AND ...` conditions for `filter` to a query over the `trades` table.
+fn push_trade_filter(builder: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>, filter: &TradeFilter) {
+    if let Some(side) = filter.side {
+        builder.push(" AND side = ").push_bind(format!("{:?}", side)); // "Long" or "Short"
+    }
+    if filter.only_winners {
+        builder.push(" AND pnl > 0");
+    }
+    if filter.only_losers {
+        builder.push(" AND pnl < 0");
+    }
+}
+
+/// Maps a row selecting every `ApiTrade` column of the `trades` table.
+fn api_trade_from_row(row: &sqlx::postgres::PgRow) -> Result<ApiTrade> {
+    use sqlx::Row;
+    let get_decimal = |column: &str| -> Result<Decimal> {
+        bigdecimal_to_decimal(&row.try_get::<BigDecimal, _>(column).map_err(Error::OperationFailed)?)
+    };
+    Ok(ApiTrade {
+        symbol: row.try_get("symbol").map_err(Error::OperationFailed)?,
+        side: row.try_get("side").map_err(Error::OperationFailed)?,
+        entry_time: row.try_get("entry_time").map_err(Error::OperationFailed)?,
+        exit_time: row.try_get("exit_time").map_err(Error::OperationFailed)?,
+        entry_price: get_decimal("entry_price")?,
+        exit_price: get_decimal("exit_price")?,
+        quantity: get_decimal("quantity")?,
+        pnl: get_decimal("pnl")?,
+        fees: get_decimal("fees")?,
+        signal_confidence: row.try_get("signal_confidence").map_err(Error::OperationFailed)?,
+        leverage: row.try_get("leverage").map_err(Error::OperationFailed)?,
+        max_adverse_excursion: get_decimal("max_adverse_excursion")?,
+        max_favorable_excursion: get_decimal("max_favorable_excursion")?,
+        entry_reason: row.try_get("entry_reason").map_err(Error::OperationFailed)?,
+        exit_reason: row.try_get("exit_reason").map_err(Error::OperationFailed)?,
+    })
+}
+
+// --- Transactional Insert Helpers ---
+// These run against an open connection (usually a transaction) so the public
+// `save_*` methods can be composed into a single atomic unit.
+
+/// Inserts a `backtest_runs` row and its `performance_reports` row, returning the new run ID.
+async fn insert_backtest_report<T: serde::Serialize>(
+    conn: &mut PgConnection,
+    job_id: Option<i64>,
+    meta: &BacktestRunMeta<'_, T>,
+    report: &PerformanceReport,
+) -> Result<i64> {
+    // --- 1. Serialize Parameters to JSON ---
+    let params_json: JsonValue = serde_json::to_value(meta.parameters)
+        .map_err(|e| Error::OperationFailed(sqlx::Error::Decode(e.into())))?;
+
+    // --- 2. Insert into `backtest_runs` and get the new ID ---
+    let run_id: i64 = sqlx::query!(
+        r#"
+        INSERT INTO backtest_runs (job_id, strategy_name, symbol, interval, start_date, end_date, parameters, config)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#,
+        job_id,
+        meta.strategy_name,
+        meta.symbol.0,
+        meta.interval,
+        meta.start_date,
+        meta.end_date,
+        params_json,
+        meta.config
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(Error::OperationFailed)?
+    .id;
+
+    // --- 3. Serialize the Confidence and Exit-Reason Performance to JSON ---
+    let confidence_json: JsonValue = serde_json::to_value(&report.confidence_performance)
+         .map_err(|e| Error::OperationFailed(sqlx::Error::Decode(e.into())))?;
+    let exit_reason_json: JsonValue = serde_json::to_value(&report.by_exit_reason)
+         .map_err(|e| Error::OperationFailed(sqlx::Error::Decode(e.into())))?;
+
+    // --- Convert Decimal fields to BigDecimal for sqlx ---
+    let net_pnl_absolute_bd = decimal_to_bigdecimal(&report.net_pnl_absolute)?;
+    let max_drawdown_absolute_bd = decimal_to_bigdecimal(&report.max_drawdown_absolute)?;
+    let expectancy_bd = decimal_to_bigdecimal(&report.expectancy)?;
+    let funding_pnl_bd = decimal_to_bigdecimal(&report.funding_pnl)?;
+
+    // --- 4. Insert into `performance_reports` ---
+    sqlx::query!(
+        r#"
+        INSERT INTO performance_reports (
+            run_id, net_pnl_absolute, net_pnl_percentage, max_drawdown_absolute,
+            max_drawdown_percentage, sharpe_ratio, win_rate, profit_factor, total_trades,
+            sortino_ratio, calmar_ratio, avg_trade_duration_secs, expectancy,
+            confidence_performance, larom, funding_pnl, drawdown_duration_secs,
+            omega_ratio, tail_ratio, aborted, value_at_risk_95, conditional_var_95, by_exit_reason,
+            cancelled
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20,
+            $21, $22, $23, $24
+        )
+        "#,
+        run_id,
+        net_pnl_absolute_bd,
+        report.net_pnl_percentage,
+        max_drawdown_absolute_bd,
+        report.max_drawdown_percentage,
+        report.sharpe_ratio,
+        report.win_rate,
+        report.profit_factor,
+        report.total_trades as i32, // cast u32 to i32 for postgres
+        report.sortino_ratio,
+        report.calmar_ratio,
+        report.avg_trade_duration_secs as i64, // cast f64 to i64
+        expectancy_bd,
+        confidence_json,
+        report.larom,
+        funding_pnl_bd,
+        report.drawdown_duration_secs,
+        report.omega_ratio,
+        report.tail_ratio,
+        report.aborted,
+        report.value_at_risk_95,
+        report.conditional_var_95,
+        exit_reason_json,
+        report.cancelled
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(Error::OperationFailed)?;
+
+    Ok(run_id)
+}
+
+/// Inserts every trade of a run.
+async fn insert_trades(conn: &mut PgConnection, run_id: i64, trades: &[Trade]) -> Result<()> {
+    for trade in trades {
+        sqlx::query!(
+            r#"
+            INSERT INTO trades (
+                run_id, symbol, side, entry_time, exit_time, entry_price,
+                exit_price, quantity, pnl, fees, signal_confidence, leverage,
+                max_adverse_excursion, max_favorable_excursion, entry_reason, exit_reason
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            "#,
+            run_id,
+            trade.symbol.0,
+            format!("{:?}", trade.side), // "Long" or "Short"
+            trade.entry_time,
+            trade.exit_time,
+            decimal_to_bigdecimal(&trade.entry_price)?,
+            decimal_to_bigdecimal(&trade.exit_price)?,
+            decimal_to_bigdecimal(&trade.quantity)?,
+            decimal_to_bigdecimal(&trade.pnl)?,
+            decimal_to_bigdecimal(&trade.fees)?,
+            trade.signal_confidence,
+            trade.leverage as i32,
+            decimal_to_bigdecimal(&trade.max_adverse_excursion)?,
+            decimal_to_bigdecimal(&trade.max_favorable_excursion)?,
+            trade.entry_reason.as_str(),
+            trade.exit_reason.as_str()
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(Error::OperationFailed)?;
+    }
+    Ok(())
+}
+
+/// Inserts every point of a run's equity curve.
+async fn insert_equity_curve(conn: &mut PgConnection, run_id: i64, equity_curve: &[EquityPoint]) -> Result<()> {
+    for point in equity_curve {
+        sqlx::query!(
+            "INSERT INTO equity_curves (run_id, timestamp, equity) VALUES ($1, $2, $3)",
+            run_id,
+            point.timestamp,
+            decimal_to_bigdecimal(&point.value)?
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(Error::OperationFailed)?;
+    }
+    Ok(())
+}
+
+/// The descriptive metadata of a backtest run, saved alongside its report.
+#[derive(Debug)]
+pub struct BacktestRunMeta<'a, T> {
+    pub strategy_name: &'a str,
+    pub symbol: &'a Symbol,
+    pub interval: &'a str,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// The strategy parameters, serialized to JSON on insert.
+    pub parameters: &'a T,
+    /// The fees, risk settings and seed the run was produced with, if known.
+    pub config: Option<&'a JsonValue>,
+}
+
 // This struct will now hold a mix of metadata and key performance metrics.
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct BacktestRun {
     pub id: i64,
     pub strategy_name: String,
@@ -609,9 +1421,714 @@ pub struct BacktestRun {
     pub max_drawdown_percentage: Option<f64>,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+/// Optional filters for listing a run's trades. Setting both `only_winners` and
+/// `only_losers` matches nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TradeFilter {
+    pub side: Option<Side>,
+    /// Only trades with a positive P&L.
+    pub only_winners: bool,
+    /// Only trades with a negative P&L.
+    pub only_losers: bool,
+}
+
+/// Aggregate statistics across all backtest runs, for the dashboard overview.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GlobalStats {
+    pub total_runs: i64,
+    pub total_trades: i64,
+    /// The mean Sharpe ratio of all runs with a finite one.
+    pub average_sharpe_ratio: Option<f64>,
+    /// The run with the highest net P&L percentage.
+    pub best_run: Option<RunPnlSummary>,
+    /// The run with the lowest net P&L percentage.
+    pub worst_run: Option<RunPnlSummary>,
+    /// The number of runs per strategy name.
+    pub runs_per_strategy: BTreeMap<String, i64>,
+}
+
+/// Identifies a run and its net P&L, as referenced by `GlobalStats`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RunPnlSummary {
+    pub run_id: i64,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub net_pnl_percentage: f64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct OptimizationJob {
     pub id: i64,
     pub name: String,
+    /// One of the `OptimizationJobStatus` names.
+    pub status: String,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+/// The lifecycle of an optimization job, as stored in `optimization_jobs.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizationJobStatus {
+    /// Created and not yet finished.
+    Running,
+    /// Every parameter set was run.
+    Completed,
+    /// Stopped on a cancellation request, with the remaining parameter sets skipped.
+    Cancelled,
+}
+
+impl OptimizationJobStatus {
+    /// The name the status is stored under.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OptimizationJobStatus::Running => "running",
+            OptimizationJobStatus::Completed => "completed",
+            OptimizationJobStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// An execution made by the live engine, as stored in `live_trades`.
+#[derive(Debug, Serialize)]
+pub struct LiveTrade {
+    pub id: i64,
+    pub symbol: String,
+    pub side: String,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub fee: Decimal,
+    pub leverage: i32,
+    pub order_id: Option<i64>,
+    pub originating_signal: JsonValue,
+    pub portfolio_snapshot: JsonValue,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Summarizes the stored kline data for a single symbol and interval.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct KlineSeries {
+    pub symbol: String,
+    pub interval: String,
+    /// The `open_time` (Unix ms) of the oldest stored kline.
+    pub min_open_time: i64,
+    /// The `open_time` (Unix ms) of the newest stored kline.
+    pub max_open_time: i64,
+    /// The number of stored klines.
+    pub count: i64,
+}
+
+/// A contiguous range of missing klines, expressed as the `open_time` (Unix ms)
+/// of the first and last missing bar. Both bounds are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct KlineGap {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl KlineGap {
+    /// The number of klines missing in this gap for the given interval length.
+    pub fn missing_bars(&self, interval_ms: i64) -> i64 {
+        (self.end - self.start) / interval_ms + 1
+    }
+}
+
+/// The gaps found in a range of klines, summarized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct KlineGapSummary {
+    /// The number of separate gaps.
+    pub gap_count: usize,
+    /// The total number of missing klines.
+    pub missing_bars: i64,
+    /// The number of klines missing in the largest gap.
+    pub largest_gap_bars: i64,
+    /// The number of klines the range should contain (loaded plus missing).
+    pub expected_bars: i64,
+}
+
+impl KlineGapSummary {
+    /// Summarizes `gaps` in a range for which `loaded` klines were found.
+    pub fn from_gaps(gaps: &[KlineGap], interval_ms: i64, loaded: usize) -> Self {
+        let missing: Vec<i64> = gaps.iter().map(|gap| gap.missing_bars(interval_ms)).collect();
+        let missing_bars = missing.iter().sum::<i64>();
+        Self {
+            gap_count: gaps.len(),
+            missing_bars,
+            largest_gap_bars: missing.iter().copied().max().unwrap_or(0),
+            expected_bars: loaded as i64 + missing_bars,
+        }
+    }
+
+    /// The fraction of the expected klines that are missing, in `[0, 1]`.
+    pub fn missing_fraction(&self) -> f64 {
+        if self.expected_bars == 0 {
+            0.0
+        } else {
+            self.missing_bars as f64 / self.expected_bars as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analytics::types::{EntryReason, ExitReason};
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    const MINUTE_MS: i64 = 60_000;
+
+    /// A flat one-minute kline opening at `open_time`.
+    fn kline(open_time: i64) -> Kline {
+        Kline {
+            open_time,
+            open: dec!(100),
+            high: dec!(101),
+            low: dec!(99),
+            close: dec!(100.5),
+            volume: dec!(10),
+            close_time: open_time + MINUTE_MS - 1,
+        }
+    }
+
+    fn at_ms(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(ms).unwrap()
+    }
+
+    /// A closed long trade with the given P&L.
+    fn trade(pnl: Decimal) -> Trade {
+        Trade {
+            symbol: Symbol::new("BTCUSDT").unwrap(),
+            side: Side::Long,
+            entry_time: at_ms(0),
+            exit_time: at_ms(MINUTE_MS),
+            entry_price: dec!(100),
+            exit_price: dec!(100) + pnl,
+            quantity: dec!(1),
+            pnl,
+            fees: dec!(0.1),
+            signal_confidence: 0.8,
+            leverage: 10,
+            max_adverse_excursion: dec!(1),
+            max_favorable_excursion: dec!(2),
+            entry_reason: EntryReason::LongSignal,
+            exit_reason: ExitReason::Signal,
+        }
+    }
+
+    fn equity_point(ms: i64, value: Decimal) -> EquityPoint {
+        EquityPoint { timestamp: at_ms(ms), value }
+    }
+
+    /// Saves a run of `trades` and `equity_curve` through `save_full_backtest`.
+    async fn save_run(db: &Db, trades: &[Trade], equity_curve: &[EquityPoint]) -> Result<i64> {
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let parameters = serde_json::json!({ "fast_period": 10 });
+        let meta = BacktestRunMeta {
+            strategy_name: "MACrossover",
+            symbol: &symbol,
+            interval: "1m",
+            start_date: at_ms(0),
+            end_date: at_ms(10 * MINUTE_MS),
+            parameters: &parameters,
+            config: None,
+        };
+        db.save_full_backtest(None, &meta, &PerformanceReport::new(), trades, equity_curve)
+            .await
+    }
+
+    async fn count_rows(db: &Db, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(&db.0)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn bigdecimal_to_decimal_rejects_out_of_range_values() {
+        // Far beyond `Decimal::MAX` (about 7.9e28).
+        let huge = BigDecimal::from_str("1e40").unwrap();
+        assert!(matches!(bigdecimal_to_decimal(&huge), Err(Error::ConversionError(_))));
+    }
+
+    #[test]
+    fn decimal_conversions_round_trip() {
+        let value = dec!(-1234.56789);
+        let converted = decimal_to_bigdecimal(&value).unwrap();
+        assert_eq!(bigdecimal_to_decimal(&converted).unwrap(), value);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn find_kline_gaps_reports_a_deliberate_hole(pool: PgPool) {
+        let db = Db(pool);
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let start = 1_700_000_040_000; // aligned to the minute
+        // Ten one-minute bars with bars 4, 5 and 6 missing.
+        let klines: Vec<Kline> = (0..10)
+            .filter(|i| !(4..=6).contains(i))
+            .map(|i| kline(start + i * MINUTE_MS))
+            .collect();
+        db.insert_klines(&symbol, "1m", &klines).await.unwrap();
+
+        let gaps = db
+            .find_kline_gaps(&symbol, "1m", at_ms(start), at_ms(start + 9 * MINUTE_MS))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            gaps,
+            vec![KlineGap { start: start + 4 * MINUTE_MS, end: start + 6 * MINUTE_MS }]
+        );
+        assert_eq!(gaps[0].missing_bars(MINUTE_MS), 3);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn find_kline_gaps_reports_missing_edges(pool: PgPool) {
+        let db = Db(pool);
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let start = 1_700_000_040_000;
+        let klines: Vec<Kline> = (2..5).map(|i| kline(start + i * MINUTE_MS)).collect();
+        db.insert_klines(&symbol, "1m", &klines).await.unwrap();
+
+        let gaps = db
+            .find_kline_gaps(&symbol, "1m", at_ms(start), at_ms(start + 6 * MINUTE_MS))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            gaps,
+            vec![
+                KlineGap { start, end: start + MINUTE_MS },
+                KlineGap { start: start + 5 * MINUTE_MS, end: start + 6 * MINUTE_MS },
+            ]
+        );
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn get_klines_checked_summarizes_the_gaps(pool: PgPool) {
+        let db = Db(pool);
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let start = 1_700_000_040_000;
+        // Twenty one-minute bars with bar 3 and bars 10 to 14 missing.
+        let klines: Vec<Kline> = (0..20)
+            .filter(|i| *i != 3 && !(10..=14).contains(i))
+            .map(|i| kline(start + i * MINUTE_MS))
+            .collect();
+        db.insert_klines(&symbol, "1m", &klines).await.unwrap();
+
+        let (loaded, summary) = db
+            .get_klines_checked(&symbol, "1m", at_ms(start), at_ms(start + 19 * MINUTE_MS))
+            .await
+            .unwrap();
+
+        assert_eq!(loaded.len(), 14);
+        assert_eq!(
+            summary,
+            KlineGapSummary { gap_count: 2, missing_bars: 6, largest_gap_bars: 5, expected_bars: 20 }
+        );
+        assert_eq!(summary.missing_fraction(), 0.3);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn global_stats_aggregate_every_run(pool: PgPool) {
+        let db = Db(pool);
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let parameters = serde_json::json!({});
+        let mut run_ids = Vec::new();
+        for (strategy_name, net_pnl_percentage, sharpe_ratio, total_trades) in [
+            ("MACrossover", 12.5, 1.5, 10),
+            ("MACrossover", -4.0, -0.5, 6),
+            ("SuperTrend", 3.0, 0.5, 4),
+        ] {
+            let meta = BacktestRunMeta {
+                strategy_name,
+                symbol: &symbol,
+                interval: "1m",
+                start_date: at_ms(0),
+                end_date: at_ms(10 * MINUTE_MS),
+                parameters: &parameters,
+                config: None,
+            };
+            let report = PerformanceReport { net_pnl_percentage, sharpe_ratio, total_trades, ..PerformanceReport::new() };
+            run_ids.push(db.save_full_backtest(None, &meta, &report, &[], &[]).await.unwrap());
+        }
+
+        let stats = db.get_global_stats().await.unwrap();
+
+        assert_eq!(stats.total_runs, 3);
+        assert_eq!(stats.total_trades, 20);
+        assert!((stats.average_sharpe_ratio.unwrap() - 0.5).abs() < 1e-9);
+        let best = stats.best_run.unwrap();
+        assert_eq!((best.run_id, best.net_pnl_percentage), (run_ids[0], 12.5));
+        let worst = stats.worst_run.unwrap();
+        assert_eq!((worst.run_id, worst.strategy_name.as_str()), (run_ids[1], "MACrossover"));
+        assert_eq!(stats.runs_per_strategy, BTreeMap::from([("MACrossover".to_string(), 2), ("SuperTrend".to_string(), 1)]));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn trades_are_filtered_by_side_and_outcome(pool: PgPool) {
+        let db = Db(pool);
+        let trades: Vec<Trade> = [(Side::Long, dec!(5)), (Side::Short, dec!(-3)), (Side::Long, dec!(-2)), (Side::Short, dec!(1))]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (side, pnl))| Trade {
+                side,
+                entry_time: at_ms(i as i64 * MINUTE_MS),
+                exit_time: at_ms((i as i64 + 1) * MINUTE_MS),
+                ..trade(pnl)
+            })
+            .collect();
+        let run_id = save_run(&db, &trades, &[]).await.unwrap();
+        let pnls = |trades: &[ApiTrade]| trades.iter().map(|trade| trade.pnl).collect::<Vec<_>>();
+
+        let shorts = TradeFilter { side: Some(Side::Short), ..TradeFilter::default() };
+        let (page, total) = db.get_trades_for_run_paginated(run_id, 1, 50, &shorts).await.unwrap();
+        assert_eq!(pnls(&page), [dec!(-3), dec!(1)]);
+        assert!(page.iter().all(|trade| trade.side == "Short"));
+        assert_eq!(total, 2);
+
+        let losers = TradeFilter { only_losers: true, ..TradeFilter::default() };
+        let (page, total) = db.get_trades_for_run_paginated(run_id, 1, 50, &losers).await.unwrap();
+        assert_eq!(pnls(&page), [dec!(-3), dec!(-2)]);
+        assert_eq!(total, 2);
+
+        // The count follows the filter, not the page.
+        let (page, total) = db.get_trades_for_run_paginated(run_id, 2, 1, &losers).await.unwrap();
+        assert_eq!(pnls(&page), [dec!(-2)]);
+        assert_eq!(total, 2);
+
+        let long_winners = TradeFilter { side: Some(Side::Long), only_winners: true, ..TradeFilter::default() };
+        let (page, total) = db.get_trades_for_run_paginated(run_id, 1, 50, &long_winners).await.unwrap();
+        assert_eq!(pnls(&page), [dec!(5)]);
+        assert_eq!(total, 1);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn save_full_backtest_saves_every_part(pool: PgPool) {
+        let db = Db(pool);
+        let trades = [trade(dec!(5)), trade(dec!(-2))];
+        let equity_curve = [equity_point(0, dec!(10_005)), equity_point(MINUTE_MS, dec!(10_003))];
+
+        let run_id = save_run(&db, &trades, &equity_curve).await.unwrap();
+
+        assert_eq!(db.get_all_trades_for_run(run_id).await.unwrap().len(), 2);
+        assert_eq!(db.get_equity_curve_for_run(run_id).await.unwrap().len(), 2);
+        assert!(db.get_performance_report(run_id).await.unwrap().is_some());
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn save_full_backtest_rolls_back_when_the_equity_insert_fails(pool: PgPool) {
+        let db = Db(pool);
+        let trades = [trade(dec!(5))];
+        // Equity is stored as NUMERIC(30, 15), so this value overflows the column and the
+        // equity insert fails after the report and trades were already written.
+        let equity_curve = [equity_point(0, dec!(10_005)), equity_point(MINUTE_MS, dec!(1e20))];
+
+        let result = save_run(&db, &trades, &equity_curve).await;
+
+        assert!(result.is_err());
+        assert_eq!(count_rows(&db, "backtest_runs").await, 0);
+        assert_eq!(count_rows(&db, "performance_reports").await, 0);
+        assert_eq!(count_rows(&db, "trades").await, 0);
+        assert_eq!(count_rows(&db, "equity_curves").await, 0);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn get_available_series_reports_each_series_with_its_coverage(pool: PgPool) {
+        let db = Db(pool);
+        let btc = Symbol::new("BTCUSDT").unwrap();
+        let eth = Symbol::new("ETHUSDT").unwrap();
+        let btc_klines: Vec<Kline> = (0..5).map(|i| kline(1_000 * MINUTE_MS + i * MINUTE_MS)).collect();
+        let eth_klines: Vec<Kline> = (0..3).map(|i| kline(2_000 * MINUTE_MS + i * 5 * MINUTE_MS)).collect();
+        db.insert_klines(&btc, "1m", &btc_klines).await.unwrap();
+        db.insert_klines(&eth, "5m", &eth_klines).await.unwrap();
+
+        let series = db.get_available_series().await.unwrap();
+
+        assert_eq!(series.len(), 2);
+        let btc_series = &series[0];
+        assert_eq!((btc_series.symbol.as_str(), btc_series.interval.as_str()), ("BTCUSDT", "1m"));
+        assert_eq!(btc_series.min_open_time, 1_000 * MINUTE_MS);
+        assert_eq!(btc_series.max_open_time, 1_004 * MINUTE_MS);
+        assert_eq!(btc_series.count, 5);
+        let eth_series = &series[1];
+        assert_eq!((eth_series.symbol.as_str(), eth_series.interval.as_str()), ("ETHUSDT", "5m"));
+        assert_eq!(eth_series.min_open_time, 2_000 * MINUTE_MS);
+        assert_eq!(eth_series.max_open_time, 2_010 * MINUTE_MS);
+        assert_eq!(eth_series.count, 3);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn prune_klines_removes_only_klines_before_the_cutoff(pool: PgPool) {
+        let db = Db(pool);
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let klines: Vec<Kline> = (0..10).map(|i| kline(i * MINUTE_MS)).collect();
+        db.insert_klines(&symbol, "1m", &klines).await.unwrap();
+        // Another interval of the same symbol must be left alone.
+        db.insert_klines(&symbol, "5m", &[kline(0)]).await.unwrap();
+
+        let deleted = db.prune_klines(&symbol, "1m", at_ms(4 * MINUTE_MS)).await.unwrap();
+
+        assert_eq!(deleted, 4);
+        let remaining = db
+            .get_klines_by_date_range(&symbol, "1m", at_ms(0), at_ms(10 * MINUTE_MS))
+            .await
+            .unwrap();
+        let open_times: Vec<i64> = remaining.iter().map(|k| k.open_time).collect();
+        assert_eq!(open_times, (4..10).map(|i| i * MINUTE_MS).collect::<Vec<_>>());
+        assert_eq!(count_rows(&db, "klines").await, 7);
+    }
+
+    fn database_settings(
+        max_connections: Option<u32>,
+        acquire_timeout_secs: Option<u64>,
+        idle_timeout_secs: Option<u64>,
+    ) -> DatabaseSettings {
+        DatabaseSettings {
+            url: std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for database tests"),
+            max_connections,
+            acquire_timeout_secs,
+            idle_timeout_secs,
+        }
+    }
+
+    #[sqlx::test]
+    async fn connect_applies_the_configured_pool_settings() {
+        let db = connect(&database_settings(Some(3), Some(7), Some(90))).await.unwrap();
+
+        let options = db.0.options();
+        assert_eq!(options.get_max_connections(), 3);
+        assert_eq!(options.get_acquire_timeout(), Duration::from_secs(7));
+        assert_eq!(options.get_idle_timeout(), Some(Duration::from_secs(90)));
+        // The pool is usable.
+        let one: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&db.0).await.unwrap();
+        assert_eq!(one, 1);
+    }
+
+    #[sqlx::test]
+    async fn unset_pool_settings_use_the_defaults() {
+        let db = connect_lazy(&database_settings(None, None, None)).unwrap();
+
+        let options = db.0.options();
+        assert_eq!(options.get_max_connections(), DEFAULT_MAX_CONNECTIONS);
+        assert_eq!(options.get_acquire_timeout(), Duration::from_secs(DEFAULT_ACQUIRE_TIMEOUT_SECS));
+        assert_eq!(options.get_idle_timeout(), Some(Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS)));
+    }
+
+    /// Settings pointing at the database behind `pool`, which `sqlx::test` creates empty.
+    async fn settings_for(pool: &PgPool, max_connections: u32) -> DatabaseSettings {
+        let name: String = sqlx::query_scalar("SELECT current_database()").fetch_one(pool).await.unwrap();
+        let mut settings = database_settings(Some(max_connections), None, None);
+        let server = settings.url.rsplit_once('/').unwrap().0.to_string();
+        settings.url = format!("{}/{}", server, name);
+        settings
+    }
+
+    async fn has_klines_table(db: &Db) -> bool {
+        sqlx::query_scalar::<_, bool>("SELECT to_regclass('klines') IS NOT NULL")
+            .fetch_one(&db.0)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn connect_does_not_migrate_until_asked(pool: PgPool) {
+        let db = connect(&settings_for(&pool, 2).await).await.unwrap();
+        assert!(!has_klines_table(&db).await);
+
+        db.migrate().await.unwrap();
+        assert!(has_klines_table(&db).await);
+        // Migrating an up-to-date schema is a no-op.
+        db.migrate().await.unwrap();
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn clones_share_one_pool(pool: PgPool) {
+        let db = connect(&settings_for(&pool, 1).await).await.unwrap();
+        let clones: Vec<Db> = (0..8).map(|_| db.clone()).collect();
+
+        for clone in &clones {
+            let one: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&clone.0).await.unwrap();
+            assert_eq!(one, 1);
+        }
+
+        // Every clone went through the single connection of the original pool.
+        assert_eq!(db.0.size(), 1);
+        assert!(clones.iter().all(|clone| clone.0.size() == 1));
+    }
+
+    fn funding(hour: i64, rate: Decimal) -> FundingRate {
+        FundingRate { funding_time: hour * 3_600_000, funding_rate: rate }
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn funding_rates_round_trip_over_a_date_range(pool: PgPool) {
+        let db = Db(pool);
+        let btc = Symbol::new("BTCUSDT").unwrap();
+        let eth = Symbol::new("ETHUSDT").unwrap();
+        let rates: Vec<_> = [0, 8, 16, 24, 32].iter().map(|&h| funding(h, dec!(0.0001) * Decimal::from(h + 1))).collect();
+        db.insert_funding_rates(&btc, &rates).await.unwrap();
+        db.insert_funding_rates(&eth, &[funding(8, dec!(-0.0003))]).await.unwrap();
+
+        // Both ends of the range are inclusive, and other symbols are not returned.
+        let fetched = db
+            .get_funding_rates_by_date_range(&btc, at_ms(8 * 3_600_000), at_ms(24 * 3_600_000))
+            .await
+            .unwrap();
+
+        let times: Vec<_> = fetched.iter().map(|r| r.funding_time / 3_600_000).collect();
+        assert_eq!(times, vec![8, 16, 24]);
+        assert_eq!(fetched[0].funding_rate, dec!(0.0009));
+        assert_eq!(fetched[2].funding_rate, dec!(0.0025));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn reinserted_funding_rates_are_not_duplicated(pool: PgPool) {
+        let db = Db(pool);
+        let btc = Symbol::new("BTCUSDT").unwrap();
+        db.insert_funding_rates(&btc, &[funding(0, dec!(0.0001)), funding(8, dec!(0.0002))]).await.unwrap();
+
+        // A backfill overlapping the stored settlements keeps one row per settlement,
+        // with the latest rate.
+        db.insert_funding_rates(&btc, &[funding(8, dec!(0.00025)), funding(16, dec!(0.0003))]).await.unwrap();
+
+        assert_eq!(count_rows(&db, "funding_rates").await, 3);
+        let fetched = db.get_funding_rates_by_date_range(&btc, at_ms(0), at_ms(16 * 3_600_000)).await.unwrap();
+        let rates: Vec<_> = fetched.iter().map(|r| r.funding_rate).collect();
+        assert_eq!(rates, vec![dec!(0.0001), dec!(0.00025), dec!(0.0003)]);
+    }
+
+    fn execution(index: i64) -> Execution {
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let side = if index % 2 == 0 { Side::Long } else { Side::Short };
+        Execution {
+            symbol: symbol.clone(),
+            side,
+            price: dec!(60_000) + Decimal::from(index),
+            quantity: dec!(0.01),
+            fee: dec!(0.24),
+            order_id: Some(1_000 + index),
+            source_request: core_types::OrderRequest {
+                symbol,
+                side,
+                quantity: dec!(0.01),
+                order_type: core_types::OrderType::Market,
+                leverage: 5,
+                sl_price: dec!(59_000),
+                tp_price: None,
+                originating_signal: core_types::Signal::Close,
+            },
+        }
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn live_trades_are_saved_and_paginated_newest_first(pool: PgPool) {
+        let db = Db(pool);
+        let mut ids = Vec::new();
+        for index in 0..5 {
+            let snapshot = serde_json::json!({ "cash": 10_000 - index });
+            ids.push(db.save_live_trade(&execution(index), &snapshot).await.unwrap());
+        }
+
+        let (first_page, total) = db.get_live_trades_paginated(1, 2).await.unwrap();
+        let (last_page, _) = db.get_live_trades_paginated(3, 2).await.unwrap();
+
+        assert_eq!(total, 5);
+        assert_eq!(first_page.iter().map(|t| t.id).collect::<Vec<_>>(), vec![ids[4], ids[3]]);
+        assert_eq!(last_page.iter().map(|t| t.id).collect::<Vec<_>>(), vec![ids[0]]);
+        let newest = &first_page[0];
+        assert_eq!(newest.symbol, "BTCUSDT");
+        assert_eq!(newest.side, "Long");
+        assert_eq!(newest.price, dec!(60_004));
+        assert_eq!(newest.order_id, Some(1_004));
+        assert_eq!(newest.leverage, 5);
+        assert_eq!(newest.originating_signal, serde_json::json!("Close"));
+        assert_eq!(newest.portfolio_snapshot["cash"], 9_996);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn exported_runs_import_with_the_same_metrics(pool: PgPool) {
+        let db = Db(pool);
+        let report = PerformanceReport {
+            net_pnl_absolute: dec!(12.5),
+            net_pnl_percentage: 0.125,
+            sharpe_ratio: 1.2,
+            win_rate: 0.5,
+            total_trades: 2,
+            ..PerformanceReport::new()
+        };
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let parameters = serde_json::json!({ "fast_period": 10 });
+        let config = serde_json::json!({ "seed": 7 });
+        let meta = BacktestRunMeta {
+            strategy_name: "MACrossover",
+            symbol: &symbol,
+            interval: "1m",
+            start_date: at_ms(0),
+            end_date: at_ms(10 * MINUTE_MS),
+            parameters: &parameters,
+            config: Some(&config),
+        };
+        let trades = [trade(dec!(15)), trade(dec!(-2.5))];
+        let equity_curve = [equity_point(0, dec!(100)), equity_point(MINUTE_MS, dec!(112.5))];
+        let run_id = db.save_full_backtest(None, &meta, &report, &trades, &equity_curve).await.unwrap();
+
+        let export = db.export_backtest(run_id).await.unwrap().unwrap();
+        let json = serde_json::to_string(&export).unwrap();
+        let imported_id = db.import_backtest(&serde_json::from_str(&json).unwrap()).await.unwrap();
+        let mut reexported = db.export_backtest(imported_id).await.unwrap().unwrap();
+
+        assert_ne!(imported_id, run_id);
+        assert_eq!(export.version, BACKTEST_EXPORT_VERSION);
+        assert_eq!(reexported.report.run_id, imported_id);
+        assert_eq!(reexported.report.net_pnl_absolute, dec!(12.5));
+        assert_eq!(reexported.report.sharpe_ratio, 1.2);
+        assert_eq!(reexported.trades.len(), 2);
+        assert_eq!(reexported.equity_curve.len(), 2);
+        assert_eq!(reexported.run.config, Some(config));
+        // Apart from the run ID, the imported run is identical to the original.
+        reexported.report.run_id = export.report.run_id;
+        assert_eq!(serde_json::to_value(&reexported).unwrap(), serde_json::to_value(&export).unwrap());
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn exports_from_a_newer_format_are_rejected(pool: PgPool) {
+        let db = Db(pool);
+        let run_id = save_run(&db, &[trade(dec!(1))], &[]).await.unwrap();
+        let mut export = db.export_backtest(run_id).await.unwrap().unwrap();
+        export.version = BACKTEST_EXPORT_VERSION + 1;
+
+        let result = db.import_backtest(&export).await;
+
+        assert!(matches!(result, Err(Error::UnsupportedExport(_))), "{:?}", result);
+        assert_eq!(count_rows(&db, "backtest_runs").await, 1);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn a_cancelled_job_keeps_its_status_and_its_partial_runs(pool: PgPool) {
+        let db = Db(pool);
+        let job_id = db.create_optimization_job("cancel me").await.unwrap();
+        assert_eq!(db.get_optimization_job(job_id).await.unwrap().unwrap().status, "running");
+
+        assert!(db.request_optimization_cancel(job_id).await.unwrap());
+        assert!(db.is_optimization_cancel_requested(job_id).await.unwrap());
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let parameters = serde_json::json!({ "fast_period": 10 });
+        let meta = BacktestRunMeta {
+            strategy_name: "MACrossover",
+            symbol: &symbol,
+            interval: "1m",
+            start_date: at_ms(0),
+            end_date: at_ms(10 * MINUTE_MS),
+            parameters: &parameters,
+            config: None,
+        };
+        let report = PerformanceReport { cancelled: true, ..PerformanceReport::new() };
+        db.save_full_backtest(Some(job_id), &meta, &report, &[], &[]).await.unwrap();
+        db.finish_optimization_job(job_id, OptimizationJobStatus::Cancelled).await.unwrap();
+
+        assert_eq!(db.get_optimization_job(job_id).await.unwrap().unwrap().status, "cancelled");
+        let reports = db.get_reports_for_job(job_id).await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].report.cancelled);
+        assert!(db.get_optimization_job(job_id + 1).await.unwrap().is_none());
+    }
+}
@@ -1,16 +1,19 @@
 // In crates/engine/src/bot.rs
 
-use core_types::{Kline, Symbol, Signal, Side, OrderRequest};
+use core_types::{Kline, Symbol, Signal, Side, OrderRequest, OrderType};
 use strategies::Strategy;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use risk::RiskManager;
 use execution::Executor;
 use execution::types::Portfolio;
+use events::WsMessage;
+use tokio::sync::broadcast;
 use rust_decimal_macros::dec;
-
-const KLINE_HISTORY_SIZE: usize = 2; // The number of klines to maintain for the strategy.
+use core_types::Execution;
+use database::Db;
+use crate::equity_snapshotter::equity_point;
 
 /// Represents a single, independent trading instance for a specific asset and strategy.
 pub struct Bot<'a> {
@@ -24,6 +27,11 @@ pub struct Bot<'a> {
     
     /// The in-memory "hot" cache of recent klines for this bot's specific symbol and interval.
     klines: VecDeque<Kline>,
+    /// The number of klines to maintain for the strategy, from its `required_history`.
+    history_size: usize,
+
+    /// Separate caches of the same symbol's klines on each context interval.
+    context_klines: HashMap<String, VecDeque<Kline>>,
 }
 
 impl<'a> Bot<'a> {
@@ -36,31 +44,100 @@ impl<'a> Bot<'a> {
         let id = format!("{}_{}_{}", symbol.0, interval, strategy.name());
         tracing::info!(id = %id, "Creating new bot instance.");
         
+        let history_size = strategy.required_history().max(1);
         Self {
             id,
             symbol,
             interval,
             strategy,
-            klines: VecDeque::with_capacity(KLINE_HISTORY_SIZE + 1),
+            klines: VecDeque::with_capacity(history_size + 1),
+            history_size,
+            context_klines: HashMap::new(),
+        }
+    }
+
+    /// Also buffers the bot's symbol on each of `intervals`, for multi-timeframe strategies.
+    pub fn with_context_intervals(mut self, intervals: &[String]) -> Self {
+        for interval in intervals.iter().filter(|interval| **interval != self.interval) {
+            self.context_klines.insert(interval.clone(), VecDeque::with_capacity(self.history_size + 1));
         }
+        self
+    }
+
+    /// The intervals buffered in addition to the bot's own `interval`.
+    pub fn context_intervals(&self) -> impl Iterator<Item = &String> {
+        self.context_klines.keys()
+    }
+
+    /// The buffered klines of a context interval, oldest first.
+    pub fn context_klines(&self, interval: &str) -> Option<&VecDeque<Kline>> {
+        self.context_klines.get(interval)
+    }
+
+    /// The number of klines the bot keeps for its strategy.
+    pub fn history_size(&self) -> usize {
+        self.history_size
     }
     
+    /// Preloads the bot's kline cache and its strategy with historical data.
+    ///
+    /// `klines` must be in ascending time order and end just before the first live kline.
+    /// The history size is recomputed here, as the strategy may have been swapped since.
+    pub fn warm_up(&mut self, klines: &[Kline]) {
+        self.strategy.warm_up(klines);
+
+        self.history_size = self.strategy.required_history().max(1);
+        let keep_from = klines.len().saturating_sub(self.history_size);
+        self.klines.clear();
+        self.klines.extend(klines[keep_from..].iter().cloned());
+        tracing::info!(id = %self.id, warm_up_klines = klines.len(), "Bot warmed up.");
+    }
+
+    /// Preloads the cache of a context interval and hands it to the strategy.
+    pub fn warm_up_context(&mut self, interval: &str, klines: &[Kline]) {
+        let keep_from = klines.len().saturating_sub(self.history_size);
+        let Some(buffer) = self.context_klines.get_mut(interval) else {
+            return;
+        };
+        buffer.clear();
+        buffer.extend(klines[keep_from..].iter().cloned());
+        let history: Vec<_> = buffer.iter().cloned().collect();
+        self.strategy.update_context(interval, &history);
+    }
+
+    /// Buffers a closed kline of a context interval and hands the updated cache to the
+    /// strategy. Context klines never trigger an assessment on their own.
+    pub fn on_context_kline(&mut self, interval: &str, kline: Kline) {
+        let Some(buffer) = self.context_klines.get_mut(interval) else {
+            tracing::warn!(id = %self.id, interval, "Received a kline for an interval the bot does not buffer.");
+            return;
+        };
+        buffer.push_back(kline);
+        while buffer.len() > self.history_size {
+            buffer.pop_front();
+        }
+        let history: Vec<_> = buffer.iter().cloned().collect();
+        self.strategy.update_context(interval, &history);
+    }
+
     /// This is the primary logic loop for a single bot instance.
     /// It is called by the main Engine when a new kline for this bot's symbol is received.
     pub async fn on_kline(
         &mut self,
         kline: Kline,
-        risk_manager: &Box<dyn RiskManager + Send + Sync + 'a>,
+        risk_manager: &(dyn RiskManager + Send + Sync + 'a),
         executor: &mut Box<dyn Executor + Send + Sync + 'a>,
         portfolio: &Arc<Mutex<Portfolio>>,
+        ws_tx: &broadcast::Sender<WsMessage>,
+        db: &Db,
     ) -> Result<(), anyhow::Error> {
         // Add new kline to our local cache and maintain history size
         self.klines.push_back(kline.clone());
-        if self.klines.len() > KLINE_HISTORY_SIZE {
+        if self.klines.len() > self.history_size {
             self.klines.pop_front();
         }
 
-        if self.klines.len() < KLINE_HISTORY_SIZE {
+        if self.klines.len() < self.history_size {
             return Ok(()); // Wait until we have a full history before trading
         }
 
@@ -84,15 +161,22 @@ impl<'a> Bot<'a> {
                 Side::Long => current_price <= open_position.sl_price,
                 Side::Short => current_price >= open_position.sl_price,
             };
+            let should_trigger_tp = match (open_position.side, open_position.tp_price) {
+                (Side::Long, Some(tp)) => current_price >= tp,
+                (Side::Short, Some(tp)) => current_price <= tp,
+                (_, None) => false,
+            };
             
-            if should_trigger_sl {
+            if should_trigger_sl || should_trigger_tp {
                 tracing::info!(
                     bot_id = %self.id,
                     symbol = %open_position.symbol.0,
                     side = ?open_position.side,
                     current_price = %current_price,
                     sl_price = %open_position.sl_price,
-                    "Stop-loss triggered! Closing position."
+                    tp_price = ?open_position.tp_price,
+                    "{} triggered! Closing position.",
+                    if should_trigger_sl { "Stop-loss" } else { "Take-profit" }
                 );
                 
                 let close_order = OrderRequest {
@@ -102,19 +186,26 @@ impl<'a> Bot<'a> {
                         Side::Short => Side::Long,  // Close short with long
                     },
                     quantity: open_position.quantity,
+                    order_type: OrderType::Market,
                     leverage: open_position.leverage,
                     sl_price: dec!(0), // No stop-loss for closing orders
+                    tp_price: None,
                     originating_signal: Signal::Close,
                 };
                 
                 let mut portfolio_guard = portfolio.lock().await;
-                let _ = executor.execute(
+                if let Ok(Some((execution, closed_pos))) = executor.execute(
                     &close_order,
                     current_price,
                     current_kline.open_time,
-                    &mut *portfolio_guard,
-                ).await;
-                return Ok(()); // Skip strategy evaluation after stop-loss
+                    &mut portfolio_guard,
+                ).await {
+                    if let Some(closed_pos) = closed_pos {
+                        risk_manager.on_position_closed(&closed_pos, &execution, current_kline.open_time);
+                    }
+                    record_live_trade(&self.id, db, &execution, &portfolio_guard).await;
+                }
+                return Ok(()); // Skip strategy evaluation after a protective exit
             }
         }
 
@@ -126,37 +217,294 @@ impl<'a> Bot<'a> {
         tracing::info!(bot_id = %self.id, ?signal, "Strategy generated a signal.");
 
         // 3. Evaluate Signal with Risk Manager
-        let (portfolio_value, open_position) = {
+        let (portfolio_value, open_position, open_positions) = {
             let portfolio_guard = portfolio.lock().await;
             (
-                portfolio_guard.cash,
-                portfolio_guard.open_positions.get(&self.symbol).cloned()
+                portfolio_guard.total_value(), // Marked to market when mark prices are available
+                portfolio_guard.open_positions.get(&self.symbol).cloned(),
+                portfolio_guard.open_positions.clone(),
             )
         };
         
-        let calculation_kline = &history_slice[history_slice.len() - 2];
+        // The newest kline is still forming, so size against the history up to the one before it.
+        let calculation_klines = &history_slice[..history_slice.len() - 1];
         let order_request_result = risk_manager.evaluate(
             &signal,
             &self.symbol,
             portfolio_value,
-            calculation_kline,
+            calculation_klines,
             open_position.as_ref(),
+            &open_positions,
         );
 
+        // Publish the strategy -> risk decision so vetoed signals are visible on the dashboard.
+        let _ = ws_tx.send(WsMessage::SignalGenerated {
+            symbol: self.symbol.0.clone(),
+            signal,
+            accepted: matches!(order_request_result, Ok(Some(_))),
+            veto_reason: order_request_result.as_ref().err().map(|e| e.to_string()),
+        });
+
         // 4. Execute Approved Order
         if let Ok(Some(order_request)) = order_request_result {
             tracing::info!(bot_id = %self.id, ?order_request, "Signal approved by risk manager.");
             let mut portfolio_guard = portfolio.lock().await;
-            let _ = executor.execute(
+            let fill = match executor.execute(
                 &order_request,
                 current_kline.open,
                 current_kline.open_time,
-                &mut *portfolio_guard,
-            ).await;
+                &mut portfolio_guard,
+            ).await {
+                Ok(fill) => fill,
+                Err(execution::Error::ReversalFailed { closed, reason }) => {
+                    tracing::warn!(bot_id = %self.id, %reason, "Position closed but the reversing entry failed.");
+                    Some(*closed)
+                }
+                Err(e) => {
+                    tracing::error!(bot_id = %self.id, error = %e, "Order execution failed.");
+                    None
+                }
+            };
+            if let Some((execution, closed_pos)) = fill {
+                if let Some(closed_pos) = closed_pos {
+                    risk_manager.on_position_closed(&closed_pos, &execution, current_kline.open_time);
+                }
+                record_live_trade(&self.id, db, &execution, &portfolio_guard).await;
+            }
         } else if let Err(e) = order_request_result {
             tracing::warn!(bot_id = %self.id, error = %e, "Risk manager vetoed the signal.");
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Persists an execution to `live_trades`, along with an equity snapshot. Failures are
+/// logged but never abort trading.
+async fn record_live_trade(bot_id: &str, db: &Db, execution: &Execution, portfolio: &Portfolio) {
+    if let Err(e) = db.save_live_trade(execution, &portfolio.snapshot()).await {
+        tracing::error!(bot_id = %bot_id, error = %e, "Failed to persist live trade.");
+    }
+    if let Err(e) = db.save_live_equity_point(&equity_point(portfolio)).await {
+        tracing::error!(bot_id = %bot_id, error = %e, "Failed to persist live equity snapshot.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use app_config::types::DatabaseSettings;
+    use execution::simulated::SimulatedExecutor;
+    use execution::types::SimulationSettings;
+    use rust_decimal::Decimal;
+    use strategies::ensemble::EnsembleStrategy;
+    use strategies::types::EnsembleRule;
+
+    /// Goes long on every bar once it has two klines.
+    struct AlwaysLong;
+
+    impl Strategy for AlwaysLong {
+        fn name(&self) -> &'static str {
+            "AlwaysLong"
+        }
+
+        fn required_history(&self) -> usize {
+            2
+        }
+
+        fn assess(&mut self, _klines: &[Kline]) -> Signal {
+            Signal::GoLong { confidence: 1.0, suggested_sl: None, suggested_tp: None }
+        }
+    }
+
+    /// Vetoes every signal.
+    struct VetoAll;
+
+    impl RiskManager for VetoAll {
+        fn name(&self) -> &'static str {
+            "VetoAll"
+        }
+
+        fn evaluate(
+            &self,
+            _signal: &Signal,
+            _symbol: &Symbol,
+            _portfolio_value: Decimal,
+            _klines: &[Kline],
+            _open_position: Option<&core_types::Position>,
+            _open_positions: &HashMap<Symbol, core_types::Position>,
+        ) -> risk::Result<Option<OrderRequest>> {
+            Err(risk::Error::Vetoed { reason: "Daily loss limit reached".to_string() })
+        }
+    }
+
+    fn kline(index: i64) -> Kline {
+        Kline {
+            open_time: index * 60_000,
+            open: dec!(100),
+            high: dec!(101),
+            low: dec!(99),
+            close: dec!(100),
+            volume: dec!(10),
+            close_time: index * 60_000 + 59_999,
+        }
+    }
+
+    #[tokio::test]
+    async fn vetoed_signal_is_published_with_its_reason() {
+        // A vetoed signal never reaches the database, so the pool never has to connect.
+        let db = database::connect_lazy(&DatabaseSettings {
+            url: "postgres://localhost/unused".to_string(),
+            max_connections: None,
+            acquire_timeout_secs: None,
+            idle_timeout_secs: None,
+        })
+        .unwrap();
+        let (ws_tx, mut ws_rx) = broadcast::channel(16);
+        let risk_manager: Box<dyn RiskManager + Send + Sync> = Box::new(VetoAll);
+        let mut executor: Box<dyn Executor + Send + Sync> =
+            Box::new(SimulatedExecutor::new(SimulationSettings::default(), ws_tx.clone()));
+        let portfolio = Arc::new(Mutex::new(Portfolio::new(dec!(10_000))));
+        let mut bot = Bot::new(Symbol::new("BTCUSDT").unwrap(), "1m".to_string(), Box::new(AlwaysLong));
+
+        for index in 0..2 {
+            bot.on_kline(kline(index), risk_manager.as_ref(), &mut executor, &portfolio, &ws_tx, &db)
+                .await
+                .unwrap();
+        }
+
+        let message = ws_rx.try_recv().unwrap();
+        let WsMessage::SignalGenerated { symbol, signal, accepted, veto_reason } = message else {
+            panic!("expected a SignalGenerated event, got {:?}", message);
+        };
+        assert_eq!(symbol, "BTCUSDT");
+        assert!(matches!(signal, Signal::GoLong { .. }));
+        assert!(!accepted);
+        assert!(veto_reason.unwrap().contains("Daily loss limit reached"));
+        assert!(ws_rx.try_recv().is_err(), "a vetoed signal must not trade");
+        assert!(portfolio.lock().await.open_positions.is_empty());
+    }
+
+    /// Never trades.
+    struct AlwaysHold;
+
+    impl Strategy for AlwaysHold {
+        fn name(&self) -> &'static str {
+            "AlwaysHold"
+        }
+
+        fn required_history(&self) -> usize {
+            2
+        }
+
+        fn assess(&mut self, _klines: &[Kline]) -> Signal {
+            Signal::Hold
+        }
+    }
+
+    /// Approves every entry for one unit with a distant stop-loss.
+    struct ApproveAll;
+
+    impl RiskManager for ApproveAll {
+        fn name(&self) -> &'static str {
+            "ApproveAll"
+        }
+
+        fn evaluate(
+            &self,
+            signal: &Signal,
+            symbol: &Symbol,
+            _portfolio_value: Decimal,
+            _klines: &[Kline],
+            _open_position: Option<&core_types::Position>,
+            _open_positions: &HashMap<Symbol, core_types::Position>,
+        ) -> risk::Result<Option<OrderRequest>> {
+            Ok(Some(OrderRequest {
+                symbol: symbol.clone(),
+                side: Side::Long,
+                quantity: Decimal::ONE,
+                order_type: OrderType::Market,
+                leverage: 1,
+                sl_price: dec!(50),
+                tp_price: None,
+                originating_signal: *signal,
+            }))
+        }
+    }
+
+    /// Runs a bot trading an ensemble of `AlwaysLong` and `AlwaysHold` under `rule` for two
+    /// klines, and returns the signal it published, if any, and whether a long was opened.
+    async fn run_ensemble(db: &Db, rule: EnsembleRule) -> (Option<Signal>, bool) {
+        let (ws_tx, mut ws_rx) = broadcast::channel(16);
+        let risk_manager: Box<dyn RiskManager + Send + Sync> = Box::new(ApproveAll);
+        let mut executor: Box<dyn Executor + Send + Sync> =
+            Box::new(SimulatedExecutor::new(SimulationSettings::default(), ws_tx.clone()));
+        let portfolio = Arc::new(Mutex::new(Portfolio::new(dec!(10_000))));
+        let ensemble = EnsembleStrategy::new(vec![Box::new(AlwaysLong), Box::new(AlwaysHold)], rule);
+        let mut bot = Bot::new(Symbol::new("BTCUSDT").unwrap(), "1m".to_string(), Box::new(ensemble));
+
+        for index in 0..2 {
+            bot.on_kline(kline(index), risk_manager.as_ref(), &mut executor, &portfolio, &ws_tx, db)
+                .await
+                .unwrap();
+        }
+
+        let mut published = None;
+        while let Ok(message) = ws_rx.try_recv() {
+            if let WsMessage::SignalGenerated { signal, .. } = message {
+                published = Some(signal);
+            }
+        }
+        let opened_long = portfolio
+            .lock()
+            .await
+            .open_positions
+            .values()
+            .any(|position| position.side == Side::Long);
+        (published, opened_long)
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn the_ensemble_rule_decides_whether_the_bot_trades(pool: sqlx::PgPool) {
+        let db = Db::from(pool);
+
+        // One of two members going long is not unanimous, so nothing is traded...
+        let (signal, opened_long) = run_ensemble(&db, EnsembleRule::Unanimous).await;
+        assert!(signal.is_none());
+        assert!(!opened_long);
+
+        // ...while confidence weighting enters at half the member's confidence.
+        let (signal, opened_long) = run_ensemble(&db, EnsembleRule::ConfidenceWeighted).await;
+        assert!(matches!(signal, Some(Signal::GoLong { confidence, .. }) if confidence == 0.5));
+        assert!(opened_long);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn a_kline_stream_enters_and_stops_out_one_trade(pool: sqlx::PgPool) {
+        let db = Db::from(pool);
+        let (ws_tx, _ws_rx) = broadcast::channel(16);
+        let risk_manager: Box<dyn RiskManager + Send + Sync> = Box::new(ApproveAll);
+        let mut executor: Box<dyn Executor + Send + Sync> =
+            Box::new(SimulatedExecutor::new(SimulationSettings::default(), ws_tx.clone()));
+        let portfolio = Arc::new(Mutex::new(Portfolio::new(dec!(10_000))));
+        let mut bot = Bot::new(Symbol::new("BTCUSDT").unwrap(), "1m".to_string(), Box::new(AlwaysLong));
+        // The long enters at the open of the second kline and the third closes below its
+        // stop-loss at 50.
+        let crash = Kline { low: dec!(35), close: dec!(40), ..kline(2) };
+
+        for kline in [kline(0), kline(1), crash] {
+            bot.on_kline(kline, risk_manager.as_ref(), &mut executor, &portfolio, &ws_tx, &db)
+                .await
+                .unwrap();
+        }
+
+        let portfolio = portfolio.lock().await;
+        assert!(portfolio.open_positions.is_empty());
+        assert_eq!(portfolio.cash, dec!(10_000) - dec!(60));
+        // Both legs of the trade are persisted, newest first.
+        let (trades, total) = db.get_live_trades_paginated(1, 10).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!((trades[0].price, trades[1].price), (dec!(40), dec!(100)));
+        assert_ne!(trades[0].side, trades[1].side);
+    }
+}
@@ -0,0 +1,46 @@
+// In crates/engine/src/equity_snapshotter.rs
+
+use analytics::types::EquityPoint;
+use chrono::Utc;
+use database::Db;
+use execution::types::Portfolio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::interval;
+
+/// A background task that periodically persists the live portfolio's equity, so the
+/// equity history survives restarts.
+pub struct EquitySnapshotter {
+    db: Db,
+    /// A shared, thread-safe reference to the engine's portfolio.
+    portfolio: Arc<Mutex<Portfolio>>,
+    /// The time between snapshots.
+    period: Duration,
+}
+
+impl EquitySnapshotter {
+    pub fn new(db: Db, portfolio: Arc<Mutex<Portfolio>>, period: Duration) -> Self {
+        Self { db, portfolio, period }
+    }
+
+    /// The main snapshot loop.
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let mut interval = interval(self.period);
+        loop {
+            interval.tick().await;
+            let point = equity_point(&*self.portfolio.lock().await);
+            if let Err(e) = self.db.save_live_equity_point(&point).await {
+                tracing::error!(error = %e, "Failed to persist live equity snapshot.");
+            }
+        }
+    }
+}
+
+/// The portfolio's current equity (cash plus unrealized P&L at mark prices), timestamped now.
+pub fn equity_point(portfolio: &Portfolio) -> EquityPoint {
+    EquityPoint {
+        timestamp: Utc::now(),
+        value: portfolio.total_value(),
+    }
+}
@@ -0,0 +1,328 @@
+// In crates/engine/src/hot_reload.rs
+
+use app_config::types::{LiveConfig, StrategySettings};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// The settings shared between the `ConfigWatcher` and the running `Engine`.
+///
+/// The watcher swaps new values in place and bumps the generation; the engine compares the
+/// generation against the last one it applied to know when to rebuild its bots' strategies.
+#[derive(Clone)]
+pub struct HotReloadHandle {
+    /// The current strategy parameters.
+    pub strategy_settings: Arc<RwLock<StrategySettings>>,
+    /// The current bot configuration. Only hot-swappable fields are ever updated.
+    pub live_config: Arc<RwLock<LiveConfig>>,
+    generation: Arc<AtomicU64>,
+}
+
+impl HotReloadHandle {
+    pub fn new(strategy_settings: StrategySettings, live_config: LiveConfig) -> Self {
+        Self {
+            strategy_settings: Arc::new(RwLock::new(strategy_settings)),
+            live_config: Arc::new(RwLock::new(live_config)),
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The number of reloads applied so far.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+}
+
+/// Loads the strategy settings and bot configuration a reload swaps in.
+pub type ConfigLoader = Box<dyn Fn() -> anyhow::Result<(StrategySettings, LiveConfig)> + Send + Sync>;
+
+/// How long to wait after a file event for the rest of the burst an editor save produces.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// A background task that watches the config files and hot-reloads strategy parameters and
+/// bot `enabled` flags into a `HotReloadHandle`.
+///
+/// The files' directories are watched with `notify`, so saves that replace a file (as many
+/// editors do) are picked up too. Changes to a bot's symbol or interval, and added or removed
+/// bots, need new stream subscriptions, so they are logged and ignored until the next restart.
+pub struct ConfigWatcher {
+    handle: HotReloadHandle,
+    /// The files whose modification triggers a reload.
+    paths: Vec<PathBuf>,
+    loader: ConfigLoader,
+}
+
+impl ConfigWatcher {
+    /// Creates a watcher that reloads through `app_config::load_settings` and
+    /// `app_config::load_live_config`.
+    pub fn new(handle: HotReloadHandle, paths: Vec<PathBuf>) -> Self {
+        Self {
+            handle,
+            paths,
+            loader: Box::new(|| Ok((app_config::load_settings()?.strategies, app_config::load_live_config()?))),
+        }
+    }
+
+    /// Replaces how the configuration is loaded on reload.
+    pub fn with_loader(
+        mut self,
+        loader: impl Fn() -> anyhow::Result<(StrategySettings, LiveConfig)> + Send + Sync + 'static,
+    ) -> Self {
+        self.loader = Box::new(loader);
+        self
+    }
+
+    /// The main watch loop.
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let _ = event_tx.send(event);
+        })?;
+        for directory in self.watched_directories() {
+            watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+        }
+
+        while let Some(event) = event_rx.recv().await {
+            if !self.is_relevant(event) {
+                continue;
+            }
+            // Coalesce the burst of events a single save produces into one reload.
+            tokio::time::sleep(DEBOUNCE).await;
+            while event_rx.try_recv().is_ok() {}
+
+            match self.reload() {
+                Ok(()) => tracing::info!(generation = self.handle.generation(), "Config reloaded."),
+                Err(e) => tracing::error!(error = %e, "Failed to reload config; keeping the previous settings."),
+            }
+        }
+        anyhow::bail!("Config watcher stopped unexpectedly")
+    }
+
+    /// The distinct directories containing the watched files.
+    fn watched_directories(&self) -> HashSet<PathBuf> {
+        self.paths
+            .iter()
+            .map(|path| match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => PathBuf::from("."),
+            })
+            .collect()
+    }
+
+    /// Whether `event` modified one of the watched files.
+    fn is_relevant(&self, event: notify::Result<Event>) -> bool {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!(error = %e, "Config watcher error.");
+                return false;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return false;
+        }
+        event.paths.iter().any(|changed| {
+            self.paths
+                .iter()
+                .any(|path| path.file_name().is_some() && changed.file_name() == path.file_name())
+        })
+    }
+
+    /// Re-parses the config files and swaps the hot-swappable parts into the shared settings.
+    ///
+    /// A lock poisoned by a panicking reader is recovered: the strategy settings are replaced
+    /// wholesale and the bot configuration is only ever updated field by field, so neither
+    /// can be left half-written.
+    fn reload(&self) -> anyhow::Result<()> {
+        let (strategies, new_live_config) = (self.loader)()?;
+
+        *write_recovering(&self.handle.strategy_settings) = strategies;
+        merge_live_config(&mut write_recovering(&self.handle.live_config), &new_live_config);
+
+        self.handle.generation.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+}
+
+/// Write-locks `lock`, clearing the poison left by a thread that panicked while holding it.
+fn write_recovering<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|poisoned| {
+        tracing::warn!("Recovering a poisoned hot-reload lock.");
+        lock.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// Read-locks `lock`, clearing the poison left by a thread that panicked while holding it.
+pub(crate) fn read_recovering<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|poisoned| {
+        tracing::warn!("Recovering a poisoned hot-reload lock.");
+        lock.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// Copies the hot-swappable fields (`enabled`, `strategy_params`) of `new` into the bots of
+/// `current` with the same symbol and interval, and logs every other change as ignored.
+fn merge_live_config(current: &mut LiveConfig, new: &LiveConfig) {
+    for new_bot in &new.bot {
+        let existing = current
+            .bot
+            .iter_mut()
+            .find(|bot| bot.symbol == new_bot.symbol && bot.interval == new_bot.interval);
+        match existing {
+            Some(bot) => {
+                bot.enabled = new_bot.enabled;
+                bot.strategy_params = new_bot.strategy_params.clone();
+                if bot.context_intervals != new_bot.context_intervals {
+                    tracing::warn!(
+                        symbol = %new_bot.symbol,
+                        interval = %new_bot.interval,
+                        "Context interval change in live.toml ignored; restart to apply."
+                    );
+                }
+            }
+            None => tracing::warn!(
+                symbol = %new_bot.symbol,
+                interval = %new_bot.interval,
+                "New bot or symbol/interval change in live.toml ignored; restart to apply."
+            ),
+        }
+    }
+    for bot in &current.bot {
+        let removed = !new.bot.iter().any(|b| b.symbol == bot.symbol && b.interval == bot.interval);
+        if removed {
+            tracing::warn!(
+                symbol = %bot.symbol,
+                interval = %bot.interval,
+                "Removed bot in live.toml ignored; disable it instead or restart to apply."
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keltner_toml(multiplier: f64) -> String {
+        format!(
+            "[keltner]\nema_period = 20\natr_period = 10\nmultiplier = {:.1}\nconfidence = 1.0\n",
+            multiplier
+        )
+    }
+
+    fn live_config(bots: &str) -> LiveConfig {
+        toml::from_str(bots).unwrap()
+    }
+
+    /// A fresh, empty directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("{}-{}-{}", name, std::process::id(), nanos));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn editing_the_watched_file_updates_the_shared_strategy_settings() {
+        let dir = temp_dir("hot-reload");
+        let path = dir.join("strategies.toml");
+        std::fs::write(&path, keltner_toml(2.0)).unwrap();
+        let handle = HotReloadHandle::new(toml::from_str(&keltner_toml(2.0)).unwrap(), live_config(""));
+        let watched = path.clone();
+        let watcher = ConfigWatcher::new(handle.clone(), vec![path.clone()]).with_loader(move || {
+            let strategies = toml::from_str(&std::fs::read_to_string(&watched)?)?;
+            Ok((strategies, live_config("")))
+        });
+        let task = tokio::spawn(async move { watcher.run().await });
+
+        // The watch is registered asynchronously, so keep saving until the reload lands.
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while handle.generation() == 0 {
+            assert!(tokio::time::Instant::now() < deadline, "the edit was never picked up");
+            std::fs::write(&path, keltner_toml(3.0)).unwrap();
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        task.abort();
+
+        let multiplier = handle.strategy_settings.read().unwrap().keltner.as_ref().unwrap().multiplier;
+        assert_eq!(multiplier, 3.0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reload_recovers_a_poisoned_lock() {
+        let handle = HotReloadHandle::new(toml::from_str(&keltner_toml(2.0)).unwrap(), live_config(""));
+        let poisoner = handle.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.strategy_settings.write().unwrap();
+            panic!("poison the strategy settings");
+        })
+        .join();
+        assert!(handle.strategy_settings.is_poisoned());
+        let watcher = ConfigWatcher::new(handle.clone(), Vec::new())
+            .with_loader(|| Ok((toml::from_str(&keltner_toml(3.0))?, live_config(""))));
+
+        watcher.reload().unwrap();
+
+        assert!(!handle.strategy_settings.is_poisoned());
+        assert_eq!(handle.strategy_settings.read().unwrap().keltner.as_ref().unwrap().multiplier, 3.0);
+        assert_eq!(handle.generation(), 1);
+    }
+
+    #[test]
+    fn a_poisoned_lock_can_still_be_read() {
+        let handle = HotReloadHandle::new(toml::from_str(&keltner_toml(2.0)).unwrap(), live_config(""));
+        let poisoner = handle.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.strategy_settings.write().unwrap();
+            panic!("poison the strategy settings");
+        })
+        .join();
+
+        let multiplier = read_recovering(&handle.strategy_settings).keltner.as_ref().unwrap().multiplier;
+
+        assert_eq!(multiplier, 2.0);
+        assert!(!handle.strategy_settings.is_poisoned());
+    }
+
+    #[test]
+    fn only_hot_swappable_bot_fields_are_merged() {
+        let mut current = live_config(
+            r#"
+            [[bot]]
+            symbol = "BTCUSDT"
+            interval = "5m"
+            strategy_name = "keltner"
+            strategy_params = "keltner"
+            "#,
+        );
+        let new = live_config(
+            r#"
+            [[bot]]
+            enabled = false
+            symbol = "BTCUSDT"
+            interval = "5m"
+            strategy_name = "keltner"
+            strategy_params = "supertrend"
+
+            [[bot]]
+            symbol = "ETHUSDT"
+            interval = "5m"
+            strategy_name = "keltner"
+            strategy_params = "keltner"
+            "#,
+        );
+
+        merge_live_config(&mut current, &new);
+
+        assert_eq!(current.bot.len(), 1);
+        assert!(!current.bot[0].enabled);
+        assert_eq!(current.bot[0].strategy_params, "supertrend");
+    }
+}
@@ -1,26 +1,28 @@
 // In crates/engine/src/lib.rs
 
 use api_client::live_connector::LiveConnector;
+use api_client::types::WsKline;
 use core_types::{Symbol, Kline};
 use database::Db;
 use execution::Executor;
+use execution::types::Portfolio;
 use futures::StreamExt;
 use risk::RiskManager;
 use strategies::Strategy;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tokio::sync::Mutex;
 use events::WsMessage;
 use crate::bot::Bot;
-use app_config::types::{BinanceSettings, LiveConfig};
-use strategies::ma_crossover::MACrossover;
-use strategies::prob_reversion::ProbReversion;
-use strategies::supertrend::SuperTrend;
+use crate::hot_reload::{read_recovering, HotReloadHandle};
+use app_config::types::{BinanceSettings, LiveConfig, StrategySettings};
+use strategies::registry::StrategyKind;
 pub mod bot;
-const KLINE_HISTORY_SIZE: usize = 2; // Same as in backtester
-use anyhow;
-use toml;
+pub mod equity_snapshotter;
+pub mod hot_reload;
+pub mod reconciler;
+const WARM_UP_KLINE_COUNT: i64 = 500; // The number of stored klines used to warm up each bot's strategy.
 /// The core trading engine that orchestrates live data and decision making for a portfolio of bots.
 pub struct Engine<'a> {
     /// A map of all active bot instances, keyed by their unique stream name (e.g., "btcusdt@kline_1m").
@@ -36,10 +38,22 @@ pub struct Engine<'a> {
     
     /// The shared portfolio state, wrapped in Arc<Mutex<>> for thread-safe access
     portfolio: Arc<Mutex<Portfolio>>,
+
+    /// The `strategy_params` key of each bot, keyed by stream name.
+    bot_params: HashMap<String, String>,
+    /// The bots (keyed by their own stream names) that each context-interval stream feeds.
+    context_routes: HashMap<String, Vec<String>>,
+    /// The stream names of bots disabled by a config reload.
+    disabled_bots: HashSet<String>,
+    /// The shared settings to hot-reload from, if enabled.
+    hot_reload: Option<HotReloadHandle>,
+    /// The `HotReloadHandle` generation last applied to the bots.
+    applied_generation: u64,
 }
 
 impl<'a> Engine<'a> {
     /// Creates a new Engine and instantiates all bots based on the provided configuration.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         live_config: &LiveConfig,
         strategy_settings: &StrategySettings,
@@ -48,9 +62,10 @@ impl<'a> Engine<'a> {
         risk_manager: Box<dyn RiskManager + Send + Sync + 'a>,
         executor: Box<dyn Executor + Send + Sync + 'a>,
         ws_tx: broadcast::Sender<WsMessage>,
-        binance_settings: BinanceSettings, // Pass this through
+        portfolio: Arc<Mutex<Portfolio>>, // Shared with the reconciler and web server
     ) -> Self {
         let mut bots = HashMap::new();
+        let mut bot_params = HashMap::new();
 
         // Iterate through the bot configurations from live.toml
         for bot_config in &live_config.bot {
@@ -58,43 +73,35 @@ impl<'a> Engine<'a> {
                 continue; // Skip disabled bots
             }
 
+            let symbol = match Symbol::new(&bot_config.symbol) {
+                Ok(symbol) => symbol,
+                Err(e) => {
+                    tracing::warn!(symbol = %bot_config.symbol, error = %e, "Invalid symbol in live.toml, skipping bot.");
+                    continue;
+                }
+            };
+
             // --- Strategy Factory Logic ---
             // Find the correct strategy parameters from the main config
             // and instantiate the strategy trait object.
-            let strategy: Box<dyn Strategy + Send + 'a> = 
-                match bot_config.strategy_params.as_str() {
-                    "ma_crossover" => {
-                        let params = strategy_settings.ma_crossover.clone()
-                            .expect("Missing ma_crossover params in main config");
-                        Box::new(MACrossover::new(params))
-                    },
-                    "supertrend" => {
-                        let params = strategy_settings.supertrend.clone()
-                            .expect("Missing supertrend params in main config");
-                        Box::new(SuperTrend::new(params))
-                    },
-                    "prob_reversion" => {
-                        let params = strategy_settings.prob_reversion.clone()
-                            .expect("Missing prob_reversion params in main config");
-                        Box::new(ProbReversion::new(params))
-                    },
-                    _ => {
-                        tracing::warn!(name = %bot_config.strategy_params, "Unknown strategy params key in live.toml, skipping bot.");
-                        continue;
-                    }
-                };
+            let Some(strategy) = build_strategy(&bot_config.strategy_params, strategy_settings) else {
+                continue;
+            };
             
             // Create the new bot instance
             let bot = Bot::new(
-                Symbol(bot_config.symbol.clone()),
+                symbol,
                 bot_config.interval.clone(),
                 strategy,
-            );
+            )
+            .with_context_intervals(&bot_config.context_intervals);
             
             // Use the WebSocket stream name as the unique key
-            let stream_name = format!("{}@kline_{}", bot_config.symbol.to_lowercase(), bot_config.interval);
+            let stream_name = kline_stream_name(&bot_config.symbol, &bot_config.interval);
+            bot_params.insert(stream_name.clone(), bot_config.strategy_params.clone());
             bots.insert(stream_name, bot);
         }
+        let context_routes = context_routes(&bots);
 
         Self {
             bots,
@@ -105,22 +112,91 @@ impl<'a> Engine<'a> {
             binance_settings,
             ws_tx,
             portfolio,
+            bot_params,
+            context_routes,
+            disabled_bots: HashSet::new(),
+            hot_reload: None,
+            applied_generation: 0,
         }
     }
 
+    /// Enables hot-reloading of strategy parameters and bot `enabled` flags from `handle`,
+    /// which is kept up to date by a `ConfigWatcher`.
+    pub fn with_hot_reload(mut self, handle: HotReloadHandle) -> Self {
+        self.applied_generation = handle.generation();
+        self.hot_reload = Some(handle);
+        self
+    }
+
+    /// Rebuilds and re-warms every bot's strategy if the watched config changed since the
+    /// last call, and refreshes which bots are disabled.
+    async fn apply_hot_reload(&mut self) -> anyhow::Result<()> {
+        let Some(handle) = &self.hot_reload else {
+            return Ok(());
+        };
+        let generation = handle.generation();
+        if generation == self.applied_generation {
+            return Ok(());
+        }
+        self.applied_generation = generation;
+
+        let live_config = read_recovering(&handle.live_config).clone();
+        self.disabled_bots = live_config
+            .bot
+            .iter()
+            .filter(|bot_config| !bot_config.enabled)
+            .map(|bot_config| kline_stream_name(&bot_config.symbol, &bot_config.interval))
+            .collect();
+        for bot_config in &live_config.bot {
+            let stream_name = kline_stream_name(&bot_config.symbol, &bot_config.interval);
+            if bot_config.enabled && !self.bots.contains_key(&stream_name) {
+                tracing::warn!(stream = %stream_name, "Bot enabled in live.toml was not started; restart to apply.");
+            }
+            self.bot_params.insert(stream_name, bot_config.strategy_params.clone());
+        }
+
+        for (stream_name, bot) in self.bots.iter_mut() {
+            let strategy = {
+                let strategy_settings = read_recovering(&handle.strategy_settings);
+                self.bot_params
+                    .get(stream_name)
+                    .and_then(|params_key| build_strategy(params_key, &strategy_settings))
+            };
+            let Some(strategy) = strategy else {
+                tracing::warn!(bot_id = %bot.id, "Could not rebuild strategy from reloaded config; keeping the current one.");
+                continue;
+            };
+            bot.strategy = strategy;
+            let count = WARM_UP_KLINE_COUNT.max(bot.strategy.required_history() as i64);
+            let klines = self.db.get_latest_klines(&bot.symbol, &bot.interval, count).await?;
+            bot.warm_up(&klines);
+            warm_up_context(&self.db, bot).await?;
+        }
+        tracing::info!(generation, "Applied reloaded strategy settings to all bots.");
+        Ok(())
+    }
+
     /// The main, long-running loop of the trading engine.
     pub async fn run(&mut self) -> anyhow::Result<()> {
         // --- 1. Warm-up Phase (for all bots) ---
         tracing::info!("Warming up all bot instances...");
-        // TODO: Implement a `get_latest_klines` DB method and warm up each bot.
-        // for bot in self.bots.values_mut() {
-        //     let klines = self.db.get_latest_klines(&bot.symbol, &bot.interval, KLINE_HISTORY_SIZE).await?;
-        //     bot.warm_up(klines);
-        // }
+        for bot in self.bots.values_mut() {
+            let count = WARM_UP_KLINE_COUNT.max(bot.history_size() as i64);
+            let klines = self.db.get_latest_klines(&bot.symbol, &bot.interval, count).await?;
+            bot.warm_up(&klines);
+            warm_up_context(&self.db, bot).await?;
+        }
         tracing::info!("Engine warmup complete.");
 
         // --- 2. Subscribe to all streams ---
-        let stream_names: Vec<String> = self.bots.keys().cloned().collect();
+        let stream_names: Vec<String> = self
+            .bots
+            .keys()
+            .chain(self.context_routes.keys())
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
         if stream_names.is_empty() {
             tracing::warn!("No bots configured to run. Engine will idle.");
             // Prevent the engine from exiting
@@ -131,43 +207,224 @@ impl<'a> Engine<'a> {
             stream_names,
             &self.binance_settings.ws_base_url,
         ));
+
+        // Mark prices keep the shared portfolio's valuation current between kline closes.
+        let mut symbols: Vec<Symbol> = self.bots.values().map(|bot| bot.symbol.clone()).collect();
+        symbols.sort_by(|a, b| a.0.cmp(&b.0));
+        symbols.dedup();
+        let mut mark_price_stream = Box::pin(self.live_connector.subscribe_to_mark_price(
+            &symbols,
+            &self.binance_settings.ws_base_url,
+        ));
         tracing::info!("Engine subscribed to all streams and is now live.");
 
         // --- 3. The Main Data Router Loop ---
-        while let Some(Ok(event)) = combined_stream.next().await {
+        loop {
+            let event = tokio::select! {
+                kline_event = combined_stream.next() => match kline_event {
+                    Some(Ok(event)) => event,
+                    _ => break,
+                },
+                Some(mark_price_event) = mark_price_stream.next() => {
+                    if let Ok(update) = mark_price_event {
+                        self.portfolio.lock().await.update_mark_price(update.symbol, update.mark_price);
+                    }
+                    continue;
+                }
+            };
+
             // Only process closed klines
             if !event.kline.is_closed {
                 continue;
             }
 
-            let stream_key = format!("{}@kline_{}", event.kline.symbol.to_lowercase(), event.kline.interval);
+            if let Err(e) = self.apply_hot_reload().await {
+                tracing::error!(error = %e, "Failed to apply reloaded config.");
+            }
+
+            let stream_key = kline_stream_name(&event.kline.symbol, &event.kline.interval);
+            // Context klines are only buffered; each bot's own stream drives its decisions.
+            route_context_kline(
+                &mut self.bots,
+                &self.context_routes,
+                &self.disabled_bots,
+                &stream_key,
+                &event.kline.interval,
+                &to_kline(&event.kline),
+            );
+            if self.disabled_bots.contains(&stream_key) {
+                continue;
+            }
 
             if let Some(bot) = self.bots.get_mut(&stream_key) {
-                // Convert the WsKline into our core Kline type
-                let kline = Kline {
-                    open_time: event.kline.open_time,
-                    open: event.kline.open,
-                    high: event.kline.high,
-                    low: event.kline.low,
-                    close: event.kline.close,
-                    volume: event.kline.volume,
-                    close_time: event.kline.close_time,
-                };
+                let kline = to_kline(&event.kline);
                 
                 // Delegate all decision-making logic to the bot instance.
                 if let Err(e) = bot.on_kline(
                     kline,
-                    &self.risk_manager,
+                    self.risk_manager.as_ref(),
                     &mut self.executor,
                     &self.portfolio,
+                    &self.ws_tx,
+                    &self.db,
                 ).await {
                     tracing::error!(bot_id = %bot.id, error = %e, "An error occurred in a bot's on_kline handler.");
                 }
-            } else {
+            } else if !self.context_routes.contains_key(&stream_key) {
                 tracing::warn!(stream = %stream_key, "Received data for a stream with no configured bot.");
             }
         }
         
         anyhow::bail!("Combined kline stream unexpectedly ended.")
     }
-}
\ No newline at end of file
+}
+
+/// Converts a streamed kline into our core `Kline` type.
+fn to_kline(kline: &WsKline) -> Kline {
+    Kline {
+        open_time: kline.open_time,
+        open: kline.open,
+        high: kline.high,
+        low: kline.low,
+        close: kline.close,
+        volume: kline.volume,
+        close_time: kline.close_time,
+    }
+}
+
+/// The name of a symbol's kline stream on one interval, which is also the key of the bot
+/// trading it (e.g. "btcusdt@kline_1m").
+fn kline_stream_name(symbol: &str, interval: &str) -> String {
+    format!("{}@kline_{}", symbol.to_lowercase(), interval)
+}
+
+/// Maps each context-interval stream to the bots (keyed by their own stream names) that
+/// buffer it. A stream may feed several bots, and may also be another bot's own stream.
+fn context_routes(bots: &HashMap<String, Bot<'_>>) -> HashMap<String, Vec<String>> {
+    let mut routes: HashMap<String, Vec<String>> = HashMap::new();
+    for (bot_key, bot) in bots {
+        for interval in bot.context_intervals() {
+            routes.entry(kline_stream_name(&bot.symbol.0, interval)).or_default().push(bot_key.clone());
+        }
+    }
+    routes
+}
+
+/// Buffers a closed kline of `stream_key` in every enabled bot it is a context stream of.
+fn route_context_kline(
+    bots: &mut HashMap<String, Bot<'_>>,
+    routes: &HashMap<String, Vec<String>>,
+    disabled_bots: &HashSet<String>,
+    stream_key: &str,
+    interval: &str,
+    kline: &Kline,
+) {
+    for bot_key in routes.get(stream_key).into_iter().flatten() {
+        if disabled_bots.contains(bot_key) {
+            continue;
+        }
+        if let Some(bot) = bots.get_mut(bot_key) {
+            bot.on_context_kline(interval, kline.clone());
+        }
+    }
+}
+
+/// Preloads each of the bot's context-interval caches with the latest stored klines.
+async fn warm_up_context(db: &Db, bot: &mut Bot<'_>) -> anyhow::Result<()> {
+    let intervals: Vec<String> = bot.context_intervals().cloned().collect();
+    for interval in intervals {
+        let klines = db.get_latest_klines(&bot.symbol, &interval, bot.history_size() as i64).await?;
+        bot.warm_up_context(&interval, &klines);
+    }
+    Ok(())
+}
+
+/// Instantiates the strategy whose parameters are stored under `params_key` in
+/// `strategy_settings`, or returns `None` (with a warning) if there are none.
+fn build_strategy<'a>(params_key: &str, strategy_settings: &StrategySettings) -> Option<Box<dyn Strategy + Send + 'a>> {
+    let Ok(kind) = params_key.parse::<StrategyKind>() else {
+        tracing::warn!(name = %params_key, "Unknown strategy params key in live.toml, skipping bot.");
+        return None;
+    };
+    Some(strategy_settings.params(kind)?.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use core_types::Signal;
+
+    /// Never trades; only its history size matters here.
+    struct Idle;
+
+    impl Strategy for Idle {
+        fn name(&self) -> &'static str {
+            "Idle"
+        }
+
+        fn required_history(&self) -> usize {
+            3
+        }
+
+        fn assess(&mut self, _klines: &[Kline]) -> Signal {
+            Signal::Hold
+        }
+    }
+
+    /// A BTCUSDT bot on `interval` that also buffers `context_intervals`, keyed by its stream.
+    fn bot(interval: &str, context_intervals: &[&str]) -> (String, Bot<'static>) {
+        let context_intervals: Vec<String> = context_intervals.iter().map(|i| i.to_string()).collect();
+        let bot = Bot::new(Symbol::new("BTCUSDT").unwrap(), interval.to_string(), Box::new(Idle))
+            .with_context_intervals(&context_intervals);
+        (kline_stream_name("BTCUSDT", interval), bot)
+    }
+
+    fn kline(open_time: i64, close: Decimal) -> Kline {
+        Kline {
+            open_time,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: dec!(1),
+            close_time: open_time + 1,
+        }
+    }
+
+    fn closes(bot: &Bot<'_>, interval: &str) -> Vec<Decimal> {
+        bot.context_klines(interval).unwrap().iter().map(|k| k.close).collect()
+    }
+
+    #[test]
+    fn a_bot_with_two_context_intervals_buffers_each_independently() {
+        let mut bots: HashMap<String, Bot<'static>> =
+            [bot("1m", &["1h", "4h"]), bot("15m", &["1h"])].into_iter().collect();
+        let routes = context_routes(&bots);
+
+        let mut hourly = routes["btcusdt@kline_1h"].clone();
+        hourly.sort();
+        assert_eq!(hourly, ["btcusdt@kline_15m", "btcusdt@kline_1m"]);
+        assert_eq!(routes["btcusdt@kline_4h"], ["btcusdt@kline_1m"]);
+
+        let none = HashSet::new();
+        route_context_kline(&mut bots, &routes, &none, "btcusdt@kline_1h", "1h", &kline(0, dec!(100)));
+        route_context_kline(&mut bots, &routes, &none, "btcusdt@kline_4h", "4h", &kline(0, dec!(200)));
+        route_context_kline(&mut bots, &routes, &none, "btcusdt@kline_1h", "1h", &kline(1, dec!(101)));
+
+        let minute = &bots["btcusdt@kline_1m"];
+        assert_eq!(closes(minute, "1h"), [dec!(100), dec!(101)]);
+        assert_eq!(closes(minute, "4h"), [dec!(200)]);
+        // The hourly stream also reached the other bot sharing it, which has no 4h buffer.
+        let quarter = &bots["btcusdt@kline_15m"];
+        assert_eq!(closes(quarter, "1h"), [dec!(100), dec!(101)]);
+        assert!(quarter.context_klines("4h").is_none());
+
+        // A disabled bot's buffers are left alone.
+        let disabled = HashSet::from(["btcusdt@kline_15m".to_string()]);
+        route_context_kline(&mut bots, &routes, &disabled, "btcusdt@kline_1h", "1h", &kline(2, dec!(102)));
+        assert_eq!(closes(&bots["btcusdt@kline_1m"], "1h"), [dec!(100), dec!(101), dec!(102)]);
+        assert_eq!(closes(&bots["btcusdt@kline_15m"], "1h"), [dec!(100), dec!(101)]);
+    }
+}
@@ -2,11 +2,12 @@
 
 use api_client::ApiClient;
 use core_types::{Position, Side, Symbol};
+use events::WsMessage;
 use execution::types::Portfolio;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use std::time::Duration;
 use tokio::time::interval;
 
@@ -18,33 +19,58 @@ pub struct StateReconciler {
     
     /// A shared, thread-safe reference to the executor's portfolio.
     portfolio: Arc<Mutex<Portfolio>>,
+
+    /// The channel the corrected portfolio is broadcast on when positions drifted.
+    ws_tx: broadcast::Sender<WsMessage>,
+
+    /// The time between two reconciliations.
+    period: Duration,
 }
 
 impl StateReconciler {
-    pub fn new(api_client: ApiClient, portfolio: Arc<Mutex<Portfolio>>) -> Self {
-        Self { api_client, portfolio }
+    pub fn new(
+        api_client: ApiClient,
+        portfolio: Arc<Mutex<Portfolio>>,
+        ws_tx: broadcast::Sender<WsMessage>,
+        period: Duration,
+    ) -> Self {
+        Self { api_client, portfolio, ws_tx, period }
     }
 
     /// The main reconciliation loop.
     pub async fn run(&self) -> anyhow::Result<()> {
-        let mut interval = interval(Duration::from_secs(60)); // Reconcile every 60 seconds
+        let mut interval = interval(self.period);
+        let mut seeded = false;
         loop {
             interval.tick().await;
-            if let Err(e) = self.reconcile().await {
-                tracing::error!("Failed to reconcile state: {}", e);
+            match self.reconcile(!seeded).await {
+                Ok(()) => seeded = true,
+                Err(e) => tracing::error!("Failed to reconcile state: {}", e),
             }
         }
     }
 
-    async fn reconcile(&self) -> anyhow::Result<()> {
+    /// Overwrites the portfolio with the exchange's account state.
+    ///
+    /// If the positions differ from the internal ones (e.g. after a manual trade or a missed
+    /// fill), each drift is logged as a warning, which also reaches the UI as a `Log` event,
+    /// and the corrected portfolio is broadcast as a `PortfolioUpdate`.
+    ///
+    /// On the first successful reconciliation (`seed`), the wallet balance also becomes the
+    /// portfolio's initial capital, so equity percentages refer to the real account.
+    async fn reconcile(&self, seed: bool) -> anyhow::Result<()> {
         // Fetch the real account state from the exchange
         let account_state = self.api_client.get_account_balance().await?;
 
         // Lock the portfolio to update it
-        let mut portfolio = self.portfolio.lock().unwrap();
+        let mut portfolio = self.portfolio.lock().await;
 
         // Update cash balance
         portfolio.cash = account_state.total_wallet_balance;
+        if seed {
+            tracing::info!(initial_capital = %account_state.total_wallet_balance, "Seeded initial capital from the exchange balance.");
+            portfolio.initial_capital = account_state.total_wallet_balance;
+        }
 
         // Update positions
         let mut open_positions = HashMap::new();
@@ -59,13 +85,185 @@ impl StateReconciler {
                         entry_price: position.entry_price,
                         leverage: position.leverage.parse().unwrap_or(1),
                         sl_price: Default::default(), // SL price is not available from this API endpoint
+                        tp_price: None,
                         entry_time: 0,
+                        entry_fee: Decimal::ZERO, // Fees paid are not available from this API endpoint
                     },
                 );
             }
         }
+        let drifts = position_drifts(&portfolio.open_positions, &open_positions);
         portfolio.open_positions = open_positions;
 
+        if !drifts.is_empty() {
+            for drift in &drifts {
+                tracing::warn!(drift = %drift, "Reconciler corrected a position drift.");
+            }
+            let _ = self.ws_tx.send(WsMessage::PortfolioUpdate(portfolio.snapshot()));
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Describes every difference between the `internal` positions and the `exchange` ones:
+/// positions opened or closed outside the bot, and positions whose side, quantity or entry
+/// price changed.
+fn position_drifts(
+    internal: &HashMap<Symbol, Position>,
+    exchange: &HashMap<Symbol, Position>,
+) -> Vec<String> {
+    let mut drifts = Vec::new();
+    for (symbol, actual) in exchange {
+        match internal.get(symbol) {
+            None => drifts.push(format!(
+                "{} {:?} position of {} found on the exchange",
+                symbol.0, actual.side, actual.quantity
+            )),
+            Some(known)
+                if known.side != actual.side
+                    || known.quantity != actual.quantity
+                    || known.entry_price != actual.entry_price =>
+            {
+                drifts.push(format!(
+                    "{} position was {:?} {} @ {}, exchange has {:?} {} @ {}",
+                    symbol.0,
+                    known.side,
+                    known.quantity,
+                    known.entry_price,
+                    actual.side,
+                    actual.quantity,
+                    actual.entry_price
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    for (symbol, known) in internal {
+        if !exchange.contains_key(symbol) {
+            drifts.push(format!(
+                "{} {:?} position of {} is no longer open on the exchange",
+                symbol.0, known.side, known.quantity
+            ));
+        }
+    }
+    drifts
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use api_client::test_support;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    /// An `ApiClient` whose `/fapi/v2/account` reports 10,500 of balance and a 0.5 BTC
+    /// short at 40,000.
+    async fn mock_exchange() -> ApiClient {
+        mock_exchange_counting(Arc::default()).await
+    }
+
+    /// Like `mock_exchange`, counting the account requests in `requests`.
+    async fn mock_exchange_counting(requests: Arc<std::sync::atomic::AtomicUsize>) -> ApiClient {
+        let account = json!({
+            "assets": [],
+            "positions": [
+                {
+                    "symbol": "BTCUSDT",
+                    "positionAmt": "-0.5",
+                    "entryPrice": "40000",
+                    "markPrice": "39800",
+                    "unrealizedProfit": "100",
+                    "leverage": "5",
+                    "positionSide": "BOTH"
+                },
+                {
+                    "symbol": "ETHUSDT",
+                    "positionAmt": "0",
+                    "entryPrice": "0",
+                    "markPrice": "2000",
+                    "unrealizedProfit": "0",
+                    "leverage": "10",
+                    "positionSide": "BOTH"
+                }
+            ],
+            "totalWalletBalance": "10500",
+            "totalUnrealizedProfit": "100",
+            "totalMarginBalance": "10600",
+            "totalAvailableBalance": "6600"
+        });
+        let app = Router::new().route(
+            "/fapi/v2/account",
+            get(move || async move {
+                requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Json(account)
+            }),
+        );
+        test_support::mock_exchange(app).await
+    }
+
+    #[tokio::test]
+    async fn a_position_opened_outside_the_bot_is_broadcast() {
+        let portfolio = Arc::new(Mutex::new(Portfolio::new(dec!(10_000))));
+        let (ws_tx, mut ws_rx) = broadcast::channel(16);
+        let reconciler = StateReconciler::new(mock_exchange().await, portfolio.clone(), ws_tx, Duration::from_secs(60));
+
+        reconciler.reconcile(false).await.unwrap();
+
+        let update = loop {
+            match ws_rx.try_recv().expect("expected a portfolio update") {
+                WsMessage::PortfolioUpdate(update) => break update,
+                _ => continue, // e.g. log events
+            }
+        };
+        assert_eq!(update.cash, dec!(10_500));
+        let position = &update.open_positions["BTCUSDT"].position;
+        assert_eq!((position.side, position.quantity, position.entry_price), (Side::Short, dec!(0.5), dec!(40_000)));
+        assert!(!update.open_positions.contains_key("ETHUSDT"));
+        assert_eq!(portfolio.lock().await.open_positions.len(), 1);
+
+        // Nothing drifted the second time, so nothing is broadcast.
+        reconciler.reconcile(false).await.unwrap();
+        assert!(ws_rx.try_recv().is_err());
+    }
+
+    /// Runs a reconciler polling every `period` for `window` and returns how often it polled.
+    async fn polls_within(period: Duration, window: Duration) -> usize {
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let api_client = mock_exchange_counting(requests.clone()).await;
+        let portfolio = Arc::new(Mutex::new(Portfolio::new(dec!(10_000))));
+        let reconciler = StateReconciler::new(api_client, portfolio, broadcast::channel(16).0, period);
+
+        let _ = tokio::time::timeout(window, reconciler.run()).await;
+        requests.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn the_configured_interval_paces_the_polling() {
+        // Polls at once and then every 200 ms: at 0, 200, 400 and 600 ms within 700 ms.
+        let fast = polls_within(Duration::from_millis(200), Duration::from_millis(700)).await;
+        // A one-minute interval only polls once within the same window.
+        let slow = polls_within(Duration::from_secs(60), Duration::from_millis(700)).await;
+
+        assert!((3..=5).contains(&fast), "polled {} times", fast);
+        assert_eq!(slow, 1);
+    }
+
+    #[tokio::test]
+    async fn the_first_reconciliation_seeds_the_initial_capital_from_the_exchange() {
+        // Configured with 2,500, while the account holds 10,500.
+        let portfolio = Arc::new(Mutex::new(Portfolio::new(dec!(2_500))));
+        let reconciler = StateReconciler::new(mock_exchange().await, portfolio.clone(), broadcast::channel(16).0, Duration::from_secs(60));
+
+        reconciler.reconcile(true).await.unwrap();
+        assert_eq!(portfolio.lock().await.initial_capital, dec!(10_500));
+
+        // Later reconciliations only follow the balance.
+        portfolio.lock().await.initial_capital = dec!(9_000);
+        reconciler.reconcile(false).await.unwrap();
+        let portfolio = portfolio.lock().await;
+        assert_eq!(portfolio.initial_capital, dec!(9_000));
+        assert_eq!(portfolio.cash, dec!(10_500));
+    }
+}
@@ -1,33 +1,231 @@
 // --- WebSocket Message Structures (moved from web-server) ---
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use core_types::{Execution, Position};
+use core_types::{Execution, Position, Signal};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 
 /// Represents a log message event to be sent to the UI.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WsLogMessage {
     pub timestamp: DateTime<Utc>,
     pub level: String,
     pub message: String,
 }
 
+/// An open position together with its performance at the latest known mark price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionView {
+    #[serde(flatten)]
+    pub position: Position,
+    /// The latest known mark price, or the entry price if none has been seen yet.
+    pub mark_price: Decimal,
+    pub unrealized_pnl: Decimal,
+    /// `unrealized_pnl` as a percentage of the position's initial margin.
+    pub unrealized_pnl_percent: f64,
+}
+
 /// Represents the full, updated state of the portfolio.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WsPortfolioUpdate {
     pub cash: Decimal,
     pub total_value: Decimal, // cash + value of open positions
-    pub open_positions: HashMap<String, Position>, // Keyed by symbol string for easy JS access
+    pub open_positions: HashMap<String, PositionView>, // Keyed by symbol string for easy JS access
 }
 
 /// The top-level WebSocket message enum.
 /// `tag` and `content` are used by serde for clean JSON representation.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum WsMessage {
     Log(WsLogMessage),
     PortfolioUpdate(WsPortfolioUpdate),
     TradeExecuted(Execution), // We can reuse our core `Execution` type
+    /// A strategy signal and the risk manager's decision on it.
+    SignalGenerated {
+        symbol: String,
+        signal: Signal,
+        accepted: bool,
+        veto_reason: Option<String>,
+    },
+    /// Periodic progress of a running backtest.
+    BacktestProgress {
+        processed_bars: usize,
+        total_bars: usize,
+        current_time: DateTime<Utc>,
+    },
+}
+
+/// A bounded, shared buffer of the most recent WebSocket messages, replayed to clients
+/// when they connect. Pushing past the capacity evicts the oldest message.
+#[derive(Debug, Clone)]
+pub struct ReplayCache {
+    messages: Arc<Mutex<VecDeque<WsMessage>>>,
+    capacity: usize,
+}
+
+impl ReplayCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            messages: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Appends `msg`, evicting the oldest messages to stay within the capacity.
+    pub fn push(&self, msg: WsMessage) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut messages = self.messages.lock().unwrap();
+        while messages.len() >= self.capacity {
+            messages.pop_front();
+        }
+        messages.push_back(msg);
+    }
+
+    /// Returns a copy of the cached messages, oldest first.
+    pub fn snapshot(&self) -> Vec<WsMessage> {
+        self.messages.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use core_types::{OrderRequest, OrderType, Side, Symbol};
+
+    fn position() -> Position {
+        Position {
+            symbol: Symbol::new("BTCUSDT").unwrap(),
+            side: Side::Long,
+            quantity: Decimal::new(15, 1),
+            entry_price: Decimal::new(60_000, 0),
+            leverage: 5,
+            sl_price: Decimal::new(58_500, 0),
+            tp_price: Some(Decimal::new(63_000, 0)),
+            entry_time: 1_700_000_000_000,
+            entry_fee: Decimal::new(36, 0),
+        }
+    }
+
+    fn execution() -> Execution {
+        let position = position();
+        Execution {
+            symbol: position.symbol.clone(),
+            side: position.side,
+            price: position.entry_price,
+            quantity: position.quantity,
+            fee: position.entry_fee,
+            order_id: Some(42),
+            source_request: OrderRequest {
+                symbol: position.symbol,
+                side: position.side,
+                quantity: position.quantity,
+                order_type: OrderType::Limit { price: position.entry_price },
+                leverage: position.leverage,
+                sl_price: position.sl_price,
+                tp_price: position.tp_price,
+                originating_signal: Signal::GoLong { confidence: 0.8, suggested_sl: None, suggested_tp: None },
+            },
+        }
+    }
+
+    fn every_variant() -> Vec<WsMessage> {
+        let timestamp = Utc.timestamp_millis_opt(1_700_000_000_000).unwrap();
+        vec![
+            WsMessage::Log(WsLogMessage { timestamp, level: "INFO".to_string(), message: "Engine started".to_string() }),
+            WsMessage::PortfolioUpdate(WsPortfolioUpdate {
+                cash: Decimal::new(9_964, 0),
+                total_value: Decimal::new(10_114, 0),
+                open_positions: HashMap::from([(
+                    "BTCUSDT".to_string(),
+                    PositionView {
+                        position: position(),
+                        mark_price: Decimal::new(60_100, 0),
+                        unrealized_pnl: Decimal::new(150, 0),
+                        unrealized_pnl_percent: 0.83,
+                    },
+                )]),
+            }),
+            WsMessage::TradeExecuted(execution()),
+            WsMessage::SignalGenerated {
+                symbol: "BTCUSDT".to_string(),
+                signal: Signal::ClosePartial { fraction: 0.5 },
+                accepted: false,
+                veto_reason: Some("Trade signal was vetoed by risk manager: cooldown".to_string()),
+            },
+            WsMessage::BacktestProgress { processed_bars: 10_000, total_bars: 25_000, current_time: timestamp },
+        ]
+    }
+
+    #[test]
+    fn every_variant_round_trips_through_json() {
+        for message in every_variant() {
+            let json = serde_json::to_value(&message).unwrap();
+            let parsed: WsMessage = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn messages_are_tagged_with_type_and_payload() {
+        let json = serde_json::to_value(WsMessage::BacktestProgress {
+            processed_bars: 1,
+            total_bars: 2,
+            current_time: Utc.timestamp_millis_opt(0).unwrap(),
+        })
+        .unwrap();
+        assert_eq!(json["type"], "BacktestProgress");
+        assert_eq!(json["payload"]["processed_bars"], 1);
+    }
+
+    fn log(message: &str) -> WsMessage {
+        WsMessage::Log(WsLogMessage {
+            timestamp: Utc.timestamp_millis_opt(0).unwrap(),
+            level: "INFO".to_string(),
+            message: message.to_string(),
+        })
+    }
+
+    fn cached_messages(cache: &ReplayCache) -> Vec<String> {
+        cache
+            .snapshot()
+            .into_iter()
+            .map(|message| match message {
+                WsMessage::Log(log) => log.message,
+                other => panic!("unexpected message {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pushing_past_the_capacity_evicts_the_oldest_message() {
+        let cache = ReplayCache::new(3);
+        // Clones share one buffer, as the log layer's and the server's copies do.
+        let layer_cache = cache.clone();
+
+        for message in ["one", "two", "three", "four"] {
+            layer_cache.push(log(message));
+        }
+        cache.push(log("five"));
+
+        assert_eq!(cached_messages(&cache), vec!["three", "four", "five"]);
+        assert_eq!(cached_messages(&layer_cache), cached_messages(&cache));
+    }
+
+    #[test]
+    fn a_zero_capacity_cache_stays_empty() {
+        let cache = ReplayCache::new(0);
+        cache.push(log("dropped"));
+
+        assert!(cache.snapshot().is_empty());
+    }
 }
@@ -1,11 +1,17 @@
 // In crates/execution/src/error.rs
 
+use core_types::{Execution, Position};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Execution failed: {reason}")]
     ExecutionFailed { reason: String },
+
+    /// The open position was closed, but the entry reversing it failed. The closing fill is
+    /// carried along so that callers can still record the closed trade.
+    #[error("Position closed but the reversing entry failed: {reason}")]
+    ReversalFailed { closed: Box<(Execution, Option<Position>)>, reason: String },
     
     // We can add more specific variants later, e.g., for different exchange rejection reasons.
 }
@@ -34,9 +34,11 @@ pub trait Executor: Sync {
     ///
     /// # Returns
     ///
-    /// A `Result` containing a tuple of:
-    /// - The `Execution` details on success
-    /// - An optional `Position` if a position was closed
+    /// A `Result` containing:
+    /// - `Some` tuple of the `Execution` details and, if a position was closed, that
+    ///   `Position`, when the order was filled
+    /// - `None` if the order was deliberately not filled (e.g., an entry in the direction of
+    ///   an open position while pyramiding is disabled)
     /// - Or an `Error` if the order could not be successfully executed.
     async fn execute(
         &mut self,
@@ -44,5 +46,5 @@ pub trait Executor: Sync {
         current_price: rust_decimal::Decimal,
         current_time: i64,
         portfolio: &mut Portfolio,
-    ) -> Result<(Execution, Option<Position>)>;
+    ) -> Result<Option<(Execution, Option<Position>)>>;
 }
\ No newline at end of file
@@ -1,8 +1,9 @@
 // In crates/execution/src/live.rs
 use crate::{Error, Executor, Result}; 
 use api_client::ApiClient;
+use api_client::types::{NewOrderResponse, PositionMode};
 use async_trait::async_trait;
-use core_types::{Execution, OrderRequest, Position};
+use core_types::{Execution, OrderRequest, OrderType, Position, Signal};
 use events::WsMessage;
 use num_traits::FromPrimitive;
 use tokio::sync::broadcast;
@@ -19,6 +20,12 @@ pub struct LiveExecutor {
     /// The sender for broadcasting events to the UI.
     ws_tx: broadcast::Sender<WsMessage>,
 
+    /// The account's position mode, queried from the exchange on the first order.
+    position_mode: Option<PositionMode>,
+
+    /// The taker fee rate charged on the notional of every (market) order.
+    taker_fee: rust_decimal::Decimal,
+
     // Portfolio is now passed in via the execute method
     // and managed by the Engine
 }
@@ -30,15 +37,36 @@ impl LiveExecutor {
     ///
     /// * `api_client`: The Binance API client
     /// * `ws_tx`: The broadcast channel for WebSocket messages
-    /// * `initial_capital`: The starting cash balance for the portfolio (for trait compatibility)
+    /// * `taker_fee`: The configured taker fee rate (e.g. 0.0004 for 0.04%)
     pub fn new(
         api_client: ApiClient,
         ws_tx: broadcast::Sender<WsMessage>,
+        taker_fee: f64,
     ) -> Self {
         Self {
             api_client,
             ws_tx,
+            position_mode: None,
+            taker_fee: rust_decimal::Decimal::from_f64(taker_fee).unwrap_or_default(),
+        }
+    }
+
+    /// Estimates the commission of a market fill, as the order response does not report it.
+    /// Market orders always take liquidity, so the taker rate applies to the filled notional.
+    fn fill_fee(&self, order_response: &NewOrderResponse) -> rust_decimal::Decimal {
+        order_response.cum_quote * self.taker_fee
+    }
+
+    /// Returns the account's position mode, querying the exchange only once.
+    async fn position_mode(&mut self) -> Result<PositionMode> {
+        if let Some(mode) = self.position_mode {
+            return Ok(mode);
         }
+        let mode = self.api_client.get_position_mode().await
+            .map_err(|e| Error::ExecutionFailed { reason: format!("Failed to query position mode: {}", e) })?;
+        tracing::info!(?mode, "Detected account position mode.");
+        self.position_mode = Some(mode);
+        Ok(mode)
     }
 }
 
@@ -54,9 +82,16 @@ impl Executor for LiveExecutor {
         _current_price: rust_decimal::Decimal, // Ignored, as we get the real fill price
         _current_time: i64, // Ignored, as the exchange provides timestamps
         _portfolio: &mut crate::types::Portfolio, // Portfolio is now passed in
-    ) -> Result<(Execution, Option<Position>)> {
+    ) -> Result<Option<(Execution, Option<Position>)>> {
         tracing::info!(?order_request, "Executing live order request...");
 
+        // Only market orders are placed live for now; reject the rest before touching the account.
+        if order_request.order_type != OrderType::Market {
+            return Err(Error::ExecutionFailed {
+                reason: format!("Order type {:?} is not supported by the live executor", order_request.order_type),
+            });
+        }
+
         // --- Step 1: Set Leverage ---
         // We set leverage before every trade to ensure it's correct.
         if let Err(e) = self.api_client.set_leverage(&order_request.symbol, order_request.leverage).await {
@@ -67,10 +102,14 @@ impl Executor for LiveExecutor {
         tracing::info!(leverage = order_request.leverage, "Leverage set successfully.");
 
         // --- Step 2: Place the Market Order ---
+        let position_mode = self.position_mode().await?;
+        let reduce_only = matches!(order_request.originating_signal, Signal::Close | Signal::ClosePartial { .. });
         let order_response = match self.api_client.place_market_order(
             &order_request.symbol,
             &order_request.side,
             order_request.quantity,
+            position_mode,
+            reduce_only,
         ).await {
             Ok(resp) => resp,
             Err(e) => {
@@ -82,13 +121,13 @@ impl Executor for LiveExecutor {
 
         // --- Step 3: Create the Execution Record from the REAL Fill Data ---
         // We use the `avgPrice` and `executedQty` from the exchange response, which is the source of truth.
-        let execution_fee = (order_response.cum_quote / rust_decimal::Decimal::from(order_request.leverage)) * rust_decimal::Decimal::from_f64(0.0004).unwrap();
         let execution = Execution {
             symbol: order_request.symbol.clone(),
             side: order_request.side,
             price: order_response.avg_price,
             quantity: order_response.executed_qty,
-            fee: execution_fee, // Approximate fee calculation for now
+            fee: self.fill_fee(&order_response),
+            order_id: order_response.order_id,
             source_request: order_request.clone(),
         };
 
@@ -101,6 +140,107 @@ impl Executor for LiveExecutor {
         // For a live executor, we don't manage the closing of positions internally.
         // The exchange handles this. So we return `None` for the closed position.
         // The State Reconciler will be the one to confirm the position is gone.
-        Ok((execution, None))
+        Ok(Some((execution, None)))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Portfolio;
+    use core_types::{Side, Symbol};
+    use rust_decimal::Decimal;
+
+    /// A client pointed at a closed port, so any request it makes fails.
+    fn unreachable_client() -> ApiClient {
+        ApiClient::with_base_url("http://127.0.0.1:9")
+    }
+
+    #[tokio::test]
+    async fn non_market_orders_are_rejected_before_reaching_the_exchange() {
+        let mut executor = LiveExecutor::new(unreachable_client(), broadcast::channel(16).0, 0.0004);
+        let mut portfolio = Portfolio::new(Decimal::from(10_000));
+
+        for order_type in [
+            OrderType::Limit { price: Decimal::from(100) },
+            OrderType::StopMarket { stop_price: Decimal::from(95) },
+        ] {
+            let order = OrderRequest {
+                symbol: Symbol::new("BTCUSDT").unwrap(),
+                side: Side::Long,
+                quantity: Decimal::ONE,
+                order_type,
+                leverage: 5,
+                sl_price: Decimal::from(90),
+                tp_price: None,
+                originating_signal: Signal::GoLong { confidence: 1.0, suggested_sl: None, suggested_tp: None },
+            };
+
+            let Err(Error::ExecutionFailed { reason }) = executor.execute(&order, Decimal::from(100), 0, &mut portfolio).await else {
+                panic!("{:?} should be rejected", order_type);
+            };
+            assert!(reason.contains("not supported"), "{}", reason);
+        }
+        // The position mode is only queried once an order is actually placed.
+        assert!(executor.position_mode.is_none());
+    }
+
+    #[tokio::test]
+    async fn market_orders_are_sent_to_the_exchange() {
+        let mut executor = LiveExecutor::new(unreachable_client(), broadcast::channel(16).0, 0.0004);
+        let order = OrderRequest {
+            symbol: Symbol::new("BTCUSDT").unwrap(),
+            side: Side::Long,
+            quantity: Decimal::ONE,
+            order_type: OrderType::Market,
+            leverage: 5,
+            sl_price: Decimal::from(90),
+            tp_price: None,
+            originating_signal: Signal::Close,
+        };
+
+        // The order gets past the type check and fails only when the exchange is contacted.
+        let Err(Error::ExecutionFailed { reason }) =
+            executor.execute(&order, Decimal::from(100), 0, &mut Portfolio::new(Decimal::from(10_000))).await
+        else {
+            panic!("the unreachable exchange should fail the order");
+        };
+        assert!(reason.starts_with("Failed to set leverage"), "{}", reason);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn fill_fee_is_the_taker_fee_on_the_notional_as_in_simulation() {
+        let live = LiveExecutor::new(unreachable_client(), broadcast::channel(16).0, 0.0005);
+        // 0.5 BTC filled at 40,000 with 10x leverage: the fee is charged on the 20,000 of
+        // notional, not on the 2,000 of margin.
+        let fill = NewOrderResponse {
+            order_id: Some(1),
+            symbol: "BTCUSDT".to_string(),
+            side: "BUY".to_string(),
+            r#type: "MARKET".to_string(),
+            avg_price: Decimal::from(40_000),
+            executed_qty: Decimal::new(5, 1),
+            cum_quote: Decimal::from(20_000),
+        };
+        assert_eq!(live.fill_fee(&fill), Decimal::from(10));
+
+        // The simulated executor charges the same for the same fill.
+        let settings = crate::types::SimulationSettings { taker_fee: 0.0005, ..Default::default() };
+        let mut simulated = crate::simulated::SimulatedExecutor::new(settings, broadcast::channel(16).0);
+        let order = OrderRequest {
+            symbol: Symbol::new("BTCUSDT").unwrap(),
+            side: Side::Long,
+            quantity: Decimal::new(5, 1),
+            order_type: OrderType::Market,
+            leverage: 10,
+            sl_price: Decimal::from(36_000),
+            tp_price: None,
+            originating_signal: Signal::GoLong { confidence: 1.0, suggested_sl: None, suggested_tp: None },
+        };
+        let (execution, _) = simulated
+            .execute(&order, Decimal::from(40_000), 0, &mut Portfolio::new(Decimal::from(10_000)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(execution.fee, live.fill_fee(&fill));
+    }
+}
@@ -5,16 +5,21 @@ use rust_decimal::Decimal;
 use crate::{Error, Executor, Result};
 use async_trait::async_trait;
 use rust_decimal_macros::dec;
-use core_types::{OrderRequest, Execution, Side, Position};
+use core_types::{OrderRequest, OrderType, Execution, Side, Position, Signal};
 use num_traits::FromPrimitive;
 use tokio::sync::broadcast;
 use events::WsMessage;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 // use std::sync::{Arc, Mutex};
 
+/// The seed used when `SimulationSettings::seed` is not set.
+pub const DEFAULT_SEED: u64 = 42;
 
 pub struct SimulatedExecutor {
     settings: SimulationSettings,
     ws_tx: broadcast::Sender<WsMessage>,
+    rng: StdRng,
 }
 
 impl SimulatedExecutor {
@@ -22,40 +27,68 @@ impl SimulatedExecutor {
         settings: SimulationSettings,
         ws_tx: broadcast::Sender<WsMessage>,
     ) -> Self {
-        Self { settings, ws_tx }
+        let rng = StdRng::seed_from_u64(settings.seed.unwrap_or(DEFAULT_SEED));
+        Self { settings, ws_tx, rng }
+    }
+
+    /// The seeded random number generator. Every randomized simulation decision must draw
+    /// from it so that backtests stay reproducible.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
     }
 
     fn create_portfolio_update(portfolio: &Portfolio) -> events::WsPortfolioUpdate {
-        let open_positions_str_keys = portfolio.open_positions
-            .iter()
-            .map(|(k, v)| (k.0.clone(), v.clone()))
-            .collect();
-        // TODO: Calculate total value (cash + position values)
-        let total_value = portfolio.cash;
-        events::WsPortfolioUpdate {
-            cash: portfolio.cash,
-            total_value,
-            open_positions: open_positions_str_keys,
+        portfolio.snapshot()
+    }
+
+    /// Calculates the fill price of an order at `current_price`, including slippage.
+    ///
+    /// Slippage always works against the order: buys fill higher, sells lower. A limit order
+    /// never fills past its limit price. Orders that could not fill at `current_price` (a limit
+    /// that is not marketable, a stop that has not triggered) are rejected, since resting
+    /// orders are not simulated.
+    fn fill_price(&self, order: &OrderRequest, current_price: Decimal) -> Result<Decimal> {
+        let slippage_factor = Decimal::from_f64(self.settings.slippage_percent).unwrap();
+        let is_buy = order.side == Side::Long;
+        let slipped_price = if is_buy {
+            current_price * (dec!(1) + slippage_factor)
+        } else {
+            current_price * (dec!(1) - slippage_factor)
+        };
+
+        match order.order_type {
+            OrderType::Market => Ok(slipped_price),
+            OrderType::Limit { price } => {
+                let marketable = if is_buy { current_price <= price } else { current_price >= price };
+                if !marketable {
+                    return Err(Error::ExecutionFailed {
+                        reason: format!("Limit order at {} is not marketable at {}", price, current_price),
+                    });
+                }
+                Ok(if is_buy { slipped_price.min(price) } else { slipped_price.max(price) })
+            }
+            OrderType::StopMarket { stop_price } => {
+                let triggered = if is_buy { current_price >= stop_price } else { current_price <= stop_price };
+                if !triggered {
+                    return Err(Error::ExecutionFailed {
+                        reason: format!("Stop order at {} has not triggered at {}", stop_price, current_price),
+                    });
+                }
+                Ok(slipped_price)
+            }
         }
     }
 
-    /// Processes an entry order (opening a new long or short position).
-    fn process_entry(
+    /// Fills the opening side of `order`: calculates the execution price and fee, checks the
+    /// margin, and charges the fee. Returns `(execution_price, fee)`.
+    fn fill_opening(
         &self,
         order: &OrderRequest,
         current_price: Decimal,
-        current_time: i64,
         portfolio: &mut Portfolio,
-    ) -> Result<(Execution, Option<Position>)> {
+    ) -> Result<(Decimal, Decimal)> {
         // --- 1. Calculate Execution Price with Slippage ---
-        let slippage_factor = Decimal::from_f64(self.settings.slippage_percent).unwrap();
-        let execution_price = if order.side == Side::Long {
-            // For a long entry, slippage makes the price worse (higher).
-            current_price * (dec!(1) + slippage_factor)
-        } else {
-            // For a short entry, slippage also makes the price worse (lower).
-            current_price * (dec!(1) - slippage_factor)
-        };
+        let execution_price = self.fill_price(order, current_price)?;
 
         // --- 2. Calculate Costs ---
         let position_value = order.quantity * execution_price;
@@ -63,13 +96,31 @@ impl SimulatedExecutor {
         let fee = position_value * fee_rate;
 
         // --- 3. Update Portfolio State ---
-        // Veto if not enough cash to cover the fee. A real exchange would check margin.
-        if portfolio.cash < fee {
+        // Veto if the margin for this position plus the fee exceeds the available margin.
+        let required_margin = position_value / Decimal::from(order.leverage.max(1));
+        let available_margin = portfolio.available_margin();
+        if available_margin < required_margin + fee {
             return Err(Error::ExecutionFailed {
-                reason: "Insufficient cash for fees".to_string(),
+                reason: format!(
+                    "Insufficient margin: required {} (incl. fee), available {}",
+                    required_margin + fee,
+                    available_margin
+                ),
             });
         }
         portfolio.cash -= fee;
+        Ok((execution_price, fee))
+    }
+
+    /// Processes an entry order (opening a new long or short position).
+    fn process_entry(
+        &self,
+        order: &OrderRequest,
+        current_price: Decimal,
+        current_time: i64,
+        portfolio: &mut Portfolio,
+    ) -> Result<(Execution, Option<Position>)> {
+        let (execution_price, fee) = self.fill_opening(order, current_price, portfolio)?;
 
         let new_position = Position {
             symbol: order.symbol.clone(),
@@ -78,7 +129,9 @@ impl SimulatedExecutor {
             entry_price: execution_price,
             leverage: order.leverage,
             sl_price: order.sl_price,
+            tp_price: order.tp_price,
             entry_time: current_time, // <-- Use the passed-in time
+            entry_fee: fee,
         };
 
         // Add the new position to our portfolio's open positions.
@@ -91,6 +144,7 @@ impl SimulatedExecutor {
             price: execution_price,
             quantity: order.quantity,
             fee,
+            order_id: None,
             source_request: order.clone(),
         };
         let _ = self.ws_tx.send(events::WsMessage::TradeExecuted(execution.clone()));
@@ -100,28 +154,69 @@ impl SimulatedExecutor {
         Ok((execution, None))
     }
 
+    /// Processes an entry order in the direction of an open position by adding to it.
+    ///
+    /// The position's entry price becomes the quantity-weighted average of the old and new
+    /// fills, and the fees are accumulated. Its stop-loss and take-profit are kept.
+    fn process_add(
+        &self,
+        order: &OrderRequest,
+        current_price: Decimal,
+        portfolio: &mut Portfolio,
+    ) -> Result<(Execution, Option<Position>)> {
+        let (execution_price, fee) = self.fill_opening(order, current_price, portfolio)?;
+
+        let position = portfolio.open_positions.get_mut(&order.symbol).unwrap();
+        let total_quantity = position.quantity + order.quantity;
+        position.entry_price =
+            (position.entry_price * position.quantity + execution_price * order.quantity) / total_quantity;
+        position.quantity = total_quantity;
+        position.entry_fee += fee;
+
+        let execution = Execution {
+            symbol: order.symbol.clone(),
+            side: order.side,
+            price: execution_price,
+            quantity: order.quantity,
+            fee,
+            order_id: None,
+            source_request: order.clone(),
+        };
+        let _ = self.ws_tx.send(WsMessage::TradeExecuted(execution.clone()));
+        let _ = self.ws_tx.send(WsMessage::PortfolioUpdate(Self::create_portfolio_update(portfolio)));
+        Ok((execution, None))
+    }
+
     /// Processes a closing order.
+    ///
+    /// If the order quantity is smaller than the position, only that part is closed: the
+    /// position's quantity is reduced and the P&L of the closed part is realized. The
+    /// returned `Position` describes the part that was closed.
     fn process_close(
         &self,
         order: &OrderRequest,
         current_price: Decimal,
         portfolio: &mut Portfolio,
     ) -> Result<(Execution, Option<Position>)> {
-        // --- 1. Find the Position to Close ---
-        let open_position = portfolio.open_positions.remove(&order.symbol).ok_or_else(
+        // --- 1. Calculate Execution Price with Slippage ---
+        // Done before touching the portfolio, so a rejected order leaves it unchanged.
+        let execution_price = self.fill_price(order, current_price)?;
+
+        // --- 2. Find the Position (or Part of It) to Close ---
+        let position = portfolio.open_positions.get_mut(&order.symbol).ok_or_else(
             || Error::ExecutionFailed {
                 reason: format!("No open position found for symbol {}", order.symbol.0),
             },
         )?;
 
-        // --- 2. Calculate Execution Price with Slippage ---
-        let slippage_factor = Decimal::from_f64(self.settings.slippage_percent).unwrap();
-        let execution_price = if open_position.side == Side::Long {
-            // To close a long, we sell. Slippage makes the price worse (lower).
-            current_price * (dec!(1) - slippage_factor)
+        let open_position = if order.quantity > Decimal::ZERO && order.quantity < position.quantity {
+            // The entry fee is split pro rata between the closed part and the remainder.
+            let closed_entry_fee = position.entry_fee * order.quantity / position.quantity;
+            position.quantity -= order.quantity;
+            position.entry_fee -= closed_entry_fee;
+            Position { quantity: order.quantity, entry_fee: closed_entry_fee, ..position.clone() }
         } else {
-            // To close a short, we buy. Slippage makes the price worse (higher).
-            current_price * (dec!(1) + slippage_factor)
+            portfolio.open_positions.remove(&order.symbol).unwrap()
         };
 
         // --- 3. Calculate P&L and Costs ---
@@ -144,6 +239,7 @@ impl SimulatedExecutor {
             price: execution_price,
             quantity: open_position.quantity,
             fee,
+            order_id: None,
             source_request: order.clone(),
         };
         let _ = self.ws_tx.send(WsMessage::TradeExecuted(execution.clone()));
@@ -159,20 +255,288 @@ impl Executor for SimulatedExecutor {
     }
 
     /// The public method that fulfills the `Executor` trait contract.
-    /// It acts as a router to the appropriate internal simulation logic.
+    /// It acts as a router to the appropriate internal simulation logic:
+    ///
+    /// - `Close`/`ClosePartial` orders reduce the open position.
+    /// - Other orders with no open position open one.
+    /// - Orders in the direction of the open position add to it if
+    ///   `SimulationSettings::allow_pyramiding` is set, and are not filled otherwise.
+    /// - Orders against the open position close it and open a new one in the order's
+    ///   direction. The closing execution and position are returned; the reversing entry is
+    ///   broadcast as its own `TradeExecuted` message. If the reversing entry fails, the
+    ///   position stays closed and `Error::ReversalFailed` carries the closing fill.
     async fn execute(
         &mut self,
         order_request: &OrderRequest,
         current_price: rust_decimal::Decimal,
         current_time: i64,
         portfolio: &mut Portfolio,
-    ) -> Result<(Execution, Option<Position>)> {
-        let is_entry = !portfolio.open_positions.contains_key(&order_request.symbol);
+    ) -> Result<Option<(Execution, Option<Position>)>> {
+        let reduce_only = matches!(order_request.originating_signal, Signal::Close | Signal::ClosePartial { .. });
+        let open_side = portfolio.open_positions.get(&order_request.symbol).map(|pos| pos.side);
 
-        if is_entry {
-            self.process_entry(order_request, current_price, current_time, portfolio)
-        } else {
-            self.process_close(order_request, current_price, portfolio)
+        match open_side {
+            _ if reduce_only => self.process_close(order_request, current_price, portfolio).map(Some),
+            None => self.process_entry(order_request, current_price, current_time, portfolio).map(Some),
+            Some(side) if side == order_request.side => {
+                if self.settings.allow_pyramiding {
+                    self.process_add(order_request, current_price, portfolio).map(Some)
+                } else {
+                    tracing::debug!(
+                        symbol = %order_request.symbol.0,
+                        ?side,
+                        "Position already open and pyramiding is disabled; order not filled."
+                    );
+                    Ok(None)
+                }
+            }
+            Some(_) => {
+                // Close the whole position first, then open the reversed one.
+                let open_position = &portfolio.open_positions[&order_request.symbol];
+                let close_order = OrderRequest {
+                    quantity: open_position.quantity,
+                    order_type: OrderType::Market,
+                    originating_signal: Signal::Close,
+                    ..order_request.clone()
+                };
+                let closed = self.process_close(&close_order, current_price, portfolio)?;
+                match self.process_entry(order_request, current_price, current_time, portfolio) {
+                    Ok(_) => Ok(Some(closed)),
+                    Err(e) => Err(Error::ReversalFailed { closed: Box::new(closed), reason: e.to_string() }),
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_types::Symbol;
+
+    /// An executor with a 0.1% taker fee and no slippage.
+    fn executor(allow_pyramiding: bool) -> SimulatedExecutor {
+        let settings = SimulationSettings {
+            maker_fee: 0.0,
+            taker_fee: 0.001,
+            slippage_percent: 0.0,
+            seed: None,
+            allow_pyramiding,
+        };
+        SimulatedExecutor::new(settings, broadcast::channel(16).0)
+    }
+
+    fn order(side: Side, quantity: Decimal, signal: Signal) -> OrderRequest {
+        OrderRequest {
+            symbol: Symbol::new("BTCUSDT").unwrap(),
+            side,
+            quantity,
+            order_type: OrderType::Market,
+            leverage: 1,
+            sl_price: Decimal::ZERO,
+            tp_price: None,
+            originating_signal: signal,
+        }
+    }
+
+    fn go_long() -> Signal {
+        Signal::GoLong { confidence: 1.0, suggested_sl: None, suggested_tp: None }
+    }
+
+    #[tokio::test]
+    async fn closing_half_a_long_realizes_half_the_pnl() {
+        let mut executor = executor(false);
+        let mut portfolio = Portfolio::new(dec!(10_000));
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        executor.execute(&order(Side::Long, dec!(2), go_long()), dec!(100), 0, &mut portfolio).await.unwrap();
+        assert_eq!(portfolio.cash, dec!(10_000) - dec!(0.2));
+
+        let partial_close = order(Side::Short, dec!(1), Signal::ClosePartial { fraction: 0.5 });
+        let (execution, closed) = executor.execute(&partial_close, dec!(110), 1, &mut portfolio).await.unwrap().unwrap();
+
+        // The closed half carries half the entry fee; the remainder keeps the other half.
+        let closed = closed.unwrap();
+        assert_eq!(closed.quantity, dec!(1));
+        assert_eq!(closed.entry_fee, dec!(0.1));
+        assert_eq!(execution.quantity, dec!(1));
+        assert_eq!(execution.fee, dec!(0.11));
+        let remaining = &portfolio.open_positions[&symbol];
+        assert_eq!(remaining.quantity, dec!(1));
+        assert_eq!(remaining.entry_fee, dec!(0.1));
+        assert_eq!(remaining.entry_price, dec!(100));
+        // Half of the 20 of P&L on the whole position is realized, minus the closing fee.
+        assert_eq!(portfolio.cash, dec!(10_000) - dec!(0.2) + dec!(10) - dec!(0.11));
+    }
+
+    #[test]
+    fn same_seed_draws_the_same_numbers() {
+        use rand::Rng;
+        let seeded = |seed| {
+            let settings = SimulationSettings { seed: Some(seed), ..SimulationSettings::default() };
+            SimulatedExecutor::new(settings, broadcast::channel(1).0)
+        };
+        let draws = |executor: &mut SimulatedExecutor| (0..8).map(|_| executor.rng().r#gen::<u64>()).collect::<Vec<_>>();
+
+        assert_eq!(draws(&mut seeded(7)), draws(&mut seeded(7)));
+        assert_ne!(draws(&mut seeded(7)), draws(&mut seeded(8)));
+        // Without a seed the executor falls back to `DEFAULT_SEED`.
+        let mut unseeded = SimulatedExecutor::new(SimulationSettings::default(), broadcast::channel(1).0);
+        assert_eq!(draws(&mut unseeded), draws(&mut seeded(DEFAULT_SEED)));
+    }
+
+    /// An executor with 0.1% slippage and no fees.
+    fn slipping_executor() -> SimulatedExecutor {
+        let settings = SimulationSettings { slippage_percent: 0.001, ..SimulationSettings::default() };
+        SimulatedExecutor::new(settings, broadcast::channel(16).0)
+    }
+
+    fn typed_order(side: Side, order_type: OrderType) -> OrderRequest {
+        OrderRequest { order_type, ..order(side, dec!(1), go_long()) }
+    }
+
+    #[tokio::test]
+    async fn market_orders_fill_with_slippage_against_the_order() {
+        let mut executor = slipping_executor();
+        let mut portfolio = Portfolio::new(dec!(10_000));
+
+        let (buy, _) = executor.execute(&typed_order(Side::Long, OrderType::Market), dec!(100), 0, &mut portfolio).await.unwrap().unwrap();
+        portfolio.open_positions.clear();
+        let (sell, _) = executor.execute(&typed_order(Side::Short, OrderType::Market), dec!(100), 0, &mut portfolio).await.unwrap().unwrap();
+
+        assert_eq!(buy.price, dec!(100.1));
+        assert_eq!(sell.price, dec!(99.9));
+    }
+
+    #[tokio::test]
+    async fn marketable_limit_orders_never_fill_past_the_limit() {
+        let mut executor = slipping_executor();
+        let mut portfolio = Portfolio::new(dec!(10_000));
+
+        // The slipped price 100.1 is above the 100.05 limit, so the limit caps the fill.
+        let limit = typed_order(Side::Long, OrderType::Limit { price: dec!(100.05) });
+        let (execution, _) = executor.execute(&limit, dec!(100), 0, &mut portfolio).await.unwrap().unwrap();
+        assert_eq!(execution.price, dec!(100.05));
+
+        // A generous limit fills at the slipped price.
+        portfolio.open_positions.clear();
+        let limit = typed_order(Side::Long, OrderType::Limit { price: dec!(105) });
+        let (execution, _) = executor.execute(&limit, dec!(100), 0, &mut portfolio).await.unwrap().unwrap();
+        assert_eq!(execution.price, dec!(100.1));
+    }
+
+    #[tokio::test]
+    async fn unfillable_limit_and_stop_orders_are_rejected_without_side_effects() {
+        let mut executor = slipping_executor();
+        let mut portfolio = Portfolio::new(dec!(10_000));
+
+        let resting_limit = typed_order(Side::Long, OrderType::Limit { price: dec!(99) });
+        let untriggered_stop = typed_order(Side::Long, OrderType::StopMarket { stop_price: dec!(101) });
+        for order in [resting_limit, untriggered_stop] {
+            let result = executor.execute(&order, dec!(100), 0, &mut portfolio).await;
+            assert!(matches!(result, Err(Error::ExecutionFailed { .. })), "{:?} should be rejected", order.order_type);
+        }
+        assert!(portfolio.open_positions.is_empty());
+        assert_eq!(portfolio.cash, dec!(10_000));
+    }
+
+    #[tokio::test]
+    async fn triggered_stop_orders_fill_like_market_orders() {
+        let mut executor = slipping_executor();
+        let mut portfolio = Portfolio::new(dec!(10_000));
+
+        let stop = typed_order(Side::Short, OrderType::StopMarket { stop_price: dec!(100.5) });
+        let (execution, _) = executor.execute(&stop, dec!(100), 0, &mut portfolio).await.unwrap().unwrap();
+
+        assert_eq!(execution.price, dec!(99.9));
+        assert_eq!(portfolio.open_positions.len(), 1);
+    }
+
+    fn go_short() -> Signal {
+        Signal::GoShort { confidence: 1.0, suggested_sl: None, suggested_tp: None }
+    }
+
+    /// A portfolio holding a 2 BTC long entered at 100 through `executor`.
+    async fn holding_a_long(executor: &mut SimulatedExecutor) -> Portfolio {
+        let mut portfolio = Portfolio::new(dec!(10_000));
+        executor.execute(&order(Side::Long, dec!(2), go_long()), dec!(100), 0, &mut portfolio).await.unwrap();
+        portfolio
+    }
+
+    #[tokio::test]
+    async fn entry_without_a_position_opens_one() {
+        let mut executor = executor(false);
+        let mut portfolio = Portfolio::new(dec!(10_000));
+
+        let (execution, closed) =
+            executor.execute(&order(Side::Long, dec!(2), go_long()), dec!(100), 0, &mut portfolio).await.unwrap().unwrap();
+
+        assert!(closed.is_none());
+        assert_eq!(execution.fee, dec!(0.2));
+        let position = &portfolio.open_positions[&Symbol::new("BTCUSDT").unwrap()];
+        assert_eq!((position.side, position.quantity, position.entry_price), (Side::Long, dec!(2), dec!(100)));
+    }
+
+    #[tokio::test]
+    async fn same_direction_entry_without_pyramiding_is_not_filled() {
+        let mut executor = executor(false);
+        let mut portfolio = holding_a_long(&mut executor).await;
+
+        let result = executor.execute(&order(Side::Long, dec!(2), go_long()), dec!(110), 1, &mut portfolio).await;
+
+        assert!(matches!(result, Ok(None)), "{:?}", result);
+        let position = &portfolio.open_positions[&Symbol::new("BTCUSDT").unwrap()];
+        assert_eq!((position.quantity, position.entry_price), (dec!(2), dec!(100)));
+        assert_eq!(portfolio.cash, dec!(10_000) - dec!(0.2));
+    }
+
+    #[tokio::test]
+    async fn same_direction_entry_with_pyramiding_adds_at_the_average_price() {
+        let mut executor = executor(true);
+        let mut portfolio = holding_a_long(&mut executor).await;
+
+        let (execution, closed) =
+            executor.execute(&order(Side::Long, dec!(2), go_long()), dec!(110), 1, &mut portfolio).await.unwrap().unwrap();
+
+        assert!(closed.is_none());
+        assert_eq!(execution.fee, dec!(0.22));
+        let position = &portfolio.open_positions[&Symbol::new("BTCUSDT").unwrap()];
+        assert_eq!((position.quantity, position.entry_price), (dec!(4), dec!(105)));
+        assert_eq!(position.entry_fee, dec!(0.42));
+        assert_eq!(portfolio.cash, dec!(10_000) - dec!(0.42));
+    }
+
+    #[tokio::test]
+    async fn opposite_direction_entry_closes_and_reverses() {
+        let mut executor = executor(false);
+        let mut portfolio = holding_a_long(&mut executor).await;
+
+        let (execution, closed) =
+            executor.execute(&order(Side::Short, dec!(1), go_short()), dec!(110), 1, &mut portfolio).await.unwrap().unwrap();
+
+        // The returned fill closes the whole long; the short is opened on top of it.
+        let closed = closed.unwrap();
+        assert_eq!((closed.side, closed.quantity), (Side::Long, dec!(2)));
+        assert_eq!((execution.side, execution.quantity, execution.fee), (Side::Short, dec!(2), dec!(0.22)));
+        let position = &portfolio.open_positions[&Symbol::new("BTCUSDT").unwrap()];
+        assert_eq!((position.side, position.quantity, position.entry_price), (Side::Short, dec!(1), dec!(110)));
+        assert_eq!(portfolio.cash, dec!(10_000) - dec!(0.2) + dec!(20) - dec!(0.22) - dec!(0.11));
+    }
+
+    #[tokio::test]
+    async fn failed_reversing_entry_reports_the_closed_position() {
+        let mut executor = executor(false);
+        let mut portfolio = holding_a_long(&mut executor).await;
+
+        // 1,000 BTC at 110 with 1x leverage is far beyond the available margin.
+        let result = executor.execute(&order(Side::Short, dec!(1_000), go_short()), dec!(110), 1, &mut portfolio).await;
+
+        let Err(Error::ReversalFailed { closed, reason }) = result else {
+            panic!("expected the reversal to fail, got {:?}", result);
+        };
+        let (execution, closed_position) = *closed;
+        assert_eq!(execution.quantity, dec!(2));
+        assert_eq!(closed_position.unwrap().side, Side::Long);
+        assert!(reason.contains("Insufficient margin"), "{}", reason);
+        assert!(portfolio.open_positions.is_empty());
+        assert_eq!(portfolio.cash, dec!(10_000) - dec!(0.2) + dec!(20) - dec!(0.22));
+    }
+}
@@ -1,22 +1,11 @@
 // In crates/execution/src/types.rs
 
-use serde::Deserialize;
+// `SimulationSettings` lives in `core-types` so that `app-config` can load it without
+// depending on this crate.
+pub use core_types::SimulationSettings;
 
-#[derive(Clone)]
-#[derive(Debug, Deserialize)]
-pub struct SimulationSettings {
-    /// The maker fee for the exchange (e.g., 0.0002 for 0.02%).
-    pub maker_fee: f64,
-    
-    /// The taker fee for the exchange (e.g., 0.0004 for 0.04%).
-    pub taker_fee: f64,
-    
-    /// The simulated slippage percentage for market orders (e.g., 0.0005 for 0.05%).
-    pub slippage_percent: f64,
-}
-
-use core_types::{Position, Symbol};
-use rust_decimal::Decimal;
+use core_types::{Position, Side, Symbol};
+use rust_decimal::prelude::*;
 use std::collections::HashMap;
 
 /// Represents the state of the simulated trading portfolio.
@@ -29,6 +18,9 @@ pub struct Portfolio {
     
     /// A map holding the currently open positions, keyed by symbol.
     pub open_positions: HashMap<Symbol, Position>,
+
+    /// The latest known mark price for each symbol, used for mark-to-market valuation.
+    pub mark_prices: HashMap<Symbol, Decimal>,
 }
 
 impl Portfolio {
@@ -38,6 +30,120 @@ impl Portfolio {
             initial_capital,
             cash: initial_capital,
             open_positions: HashMap::new(),
+            mark_prices: HashMap::new(),
         }
     }
-}
\ No newline at end of file
+
+    /// Drops all positions and puts the cash back to `initial_capital`, as if freshly created.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.initial_capital);
+    }
+
+    /// Records the latest mark price for a symbol.
+    pub fn update_mark_price(&mut self, symbol: Symbol, mark_price: Decimal) {
+        self.mark_prices.insert(symbol, mark_price);
+    }
+
+    /// Calculates the unrealized P&L of all open positions at their latest mark prices.
+    ///
+    /// Positions without a known mark price are valued at their entry price.
+    pub fn unrealized_pnl(&self) -> Decimal {
+        self.open_positions.values().map(|pos| self.position_pnl(pos).1).sum()
+    }
+
+    /// Returns the mark price and unrealized P&L of a single position, valued at its entry
+    /// price if no mark price is known.
+    fn position_pnl(&self, pos: &Position) -> (Decimal, Decimal) {
+        let mark_price = self.mark_prices.get(&pos.symbol).copied().unwrap_or(pos.entry_price);
+        let direction = if pos.side == Side::Long { Decimal::ONE } else { -Decimal::ONE };
+        (mark_price, (mark_price - pos.entry_price) * pos.quantity * direction)
+    }
+
+    /// Calculates the mark-to-market value of the portfolio (cash + unrealized P&L).
+    pub fn total_value(&self) -> Decimal {
+        self.cash + self.unrealized_pnl()
+    }
+
+    /// Builds a snapshot of the portfolio for broadcasting to the UI, valued at mark prices.
+    pub fn snapshot(&self) -> events::WsPortfolioUpdate {
+        events::WsPortfolioUpdate {
+            cash: self.cash,
+            total_value: self.total_value(),
+            open_positions: self.open_positions
+                .iter()
+                .map(|(k, v)| (k.0.clone(), self.position_view(v)))
+                .collect(),
+        }
+    }
+
+    /// Values a position at its latest mark price for the UI.
+    fn position_view(&self, pos: &Position) -> events::PositionView {
+        let (mark_price, unrealized_pnl) = self.position_pnl(pos);
+        let margin = pos.initial_margin();
+        let unrealized_pnl_percent = if margin.is_zero() {
+            0.0
+        } else {
+            (unrealized_pnl / margin * Decimal::from(100)).to_f64().unwrap_or(0.0)
+        };
+        events::PositionView {
+            position: pos.clone(),
+            mark_price,
+            unrealized_pnl,
+            unrealized_pnl_percent,
+        }
+    }
+
+    /// Calculates the margin locked by all open positions.
+    pub fn used_margin(&self) -> Decimal {
+        self.open_positions.values().map(|pos| pos.initial_margin()).sum()
+    }
+
+    /// Calculates the margin still available for new positions.
+    pub fn available_margin(&self) -> Decimal {
+        self.total_value() - self.used_margin()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn position(symbol: &Symbol, side: Side, quantity: Decimal, entry_price: Decimal, leverage: u8) -> Position {
+        Position {
+            symbol: symbol.clone(),
+            side,
+            quantity,
+            entry_price,
+            leverage,
+            sl_price: Decimal::ZERO,
+            tp_price: None,
+            entry_time: 0,
+            entry_fee: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn snapshot_values_each_position_at_its_mark_price() {
+        let (btc, eth, sol) = (Symbol::new("BTCUSDT").unwrap(), Symbol::new("ETHUSDT").unwrap(), Symbol::new("SOLUSDT").unwrap());
+        let mut portfolio = Portfolio::new(dec!(10_000));
+        // 2 at 100 with 4x leverage lock 50 of margin; 1 short at 2,000 with 2x locks 1,000.
+        portfolio.open_positions.insert(btc.clone(), position(&btc, Side::Long, dec!(2), dec!(100), 4));
+        portfolio.open_positions.insert(eth.clone(), position(&eth, Side::Short, dec!(1), dec!(2_000), 2));
+        portfolio.open_positions.insert(sol.clone(), position(&sol, Side::Long, dec!(10), dec!(150), 1));
+        portfolio.update_mark_price(btc, dec!(110));
+        portfolio.update_mark_price(eth, dec!(2_100));
+
+        let snapshot = portfolio.snapshot();
+
+        let btc = &snapshot.open_positions["BTCUSDT"];
+        assert_eq!((btc.mark_price, btc.unrealized_pnl), (dec!(110), dec!(20)));
+        assert_eq!(btc.unrealized_pnl_percent, 40.0);
+        let eth = &snapshot.open_positions["ETHUSDT"];
+        assert_eq!((eth.mark_price, eth.unrealized_pnl), (dec!(2_100), dec!(-100)));
+        assert_eq!(eth.unrealized_pnl_percent, -10.0);
+        // Without a mark price, a position is valued at its entry.
+        let sol = &snapshot.open_positions["SOLUSDT"];
+        assert_eq!((sol.mark_price, sol.unrealized_pnl, sol.unrealized_pnl_percent), (dec!(150), dec!(0), 0.0));
+        assert_eq!(snapshot.total_value, dec!(10_000) + dec!(20) - dec!(100));
+    }
+}
@@ -1,6 +1,7 @@
 // In crates/risk/src/lib.rs (REPLACE ENTIRE FILE)
 
-use core_types::{OrderRequest, Position, Signal, Kline};
+use core_types::{Execution, OrderRequest, Position, Signal, Kline, Symbol};
+use std::collections::HashMap;
 pub mod simple_manager;
 
 pub mod error;
@@ -25,9 +26,13 @@ pub trait RiskManager: Sync {
     /// * `signal`: The trading `Signal` produced by a strategy.
     /// * `symbol`: The symbol for which the signal was generated.
     /// * `portfolio_value`: The total value of the account.
-    /// * `current_kline`: The current kline data for price information.
+    /// * `klines`: The recent kline history, in ascending time order. The last kline is the
+    ///   current one and provides price information; earlier ones are used for
+    ///   volatility estimates.
     /// * `open_position`: An `Option` containing the currently open position for the
     ///   signal's symbol, if one exists.
+    /// * `open_positions`: All currently open positions, keyed by symbol, for rules that
+    ///   look at exposure across symbols.
     ///
     /// # Returns
     ///
@@ -39,7 +44,20 @@ pub trait RiskManager: Sync {
         signal: &Signal,
         symbol: &core_types::Symbol,
         portfolio_value: rust_decimal::Decimal,
-        current_kline: &Kline,
+        klines: &[Kline],
         open_position: Option<&Position>,
+        open_positions: &HashMap<Symbol, Position>,
     ) -> Result<Option<OrderRequest>>;
+
+    /// Notifies the risk manager that a position was closed.
+    ///
+    /// Risk managers that depend on recent trade outcomes (e.g., a cooldown after a loss)
+    /// record them here. The default implementation ignores the notification.
+    ///
+    /// # Arguments
+    ///
+    /// * `closed_position`: The position as it was before being closed.
+    /// * `execution`: The execution that closed the position.
+    /// * `close_time`: The time of the close, as a Unix timestamp in milliseconds.
+    fn on_position_closed(&self, _closed_position: &Position, _execution: &Execution, _close_time: i64) {}
 }
\ No newline at end of file
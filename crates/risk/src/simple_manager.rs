@@ -1,98 +1,204 @@
 // In crates/risk/src/simple_manager.rs
 
-use crate::types::SimpleRiskSettings;
+use crate::types::{RiskDecision, SimpleRiskSettings};
 use crate::{Error, Result, RiskManager}; // Import our own trait and errors
-use core_types::{OrderRequest, Position, Side, Signal, Kline};
+use core_types::{Execution, OrderRequest, OrderType, Position, Side, Signal, Kline, Symbol};
 use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use rust_decimal_macros::dec; // For creating decimals from literals
-use num_traits::{FromPrimitive };
+use num_traits::{FromPrimitive, ToPrimitive};
 
 /// A simple risk manager that uses a fixed fractional position sizing model.
 ///
-/// This manager implements two basic rules:
+/// This manager implements three basic rules:
 /// 1. Vetoes trades if signal confidence is below a configured threshold.
-/// 2. Calculates position size based on a fixed percentage of portfolio value
-///    and a pre-defined stop-loss distance.
+/// 2. Vetoes entries during the configured cooldown after a losing trade.
+/// 3. Calculates position size based on a fixed percentage of portfolio value
+///    and a pre-defined stop-loss distance, capped at a maximum notional and vetoed
+///    below a minimum one.
 #[derive(Debug)]
 pub struct SimpleRiskManager {
     /// The configuration for this risk manager instance.
     settings: SimpleRiskSettings,
+    /// The close time (Unix ms) of the most recent losing trade, per symbol.
+    last_loss_times: Mutex<HashMap<Symbol, i64>>,
+    /// The exchange's minimum order notional, per symbol, when known.
+    exchange_min_notionals: HashMap<Symbol, Decimal>,
+    /// Whether entries in the direction of an open position are approved, to be added to it.
+    allow_pyramiding: bool,
+    /// Whether entries against an open position are approved, to be closed and reversed.
+    allow_reversals: bool,
 }
 
 impl SimpleRiskManager {
     /// Creates a new `SimpleRiskManager` instance from its settings.
     pub fn new(settings: SimpleRiskSettings) -> Self {
-        Self { settings }
+        Self {
+            settings,
+            last_loss_times: Mutex::new(HashMap::new()),
+            exchange_min_notionals: HashMap::new(),
+            allow_pyramiding: false,
+            allow_reversals: false,
+        }
     }
-}
 
-impl RiskManager for SimpleRiskManager {
-    fn name(&self) -> &'static str {
-        "SimpleRiskManager"
+    /// Approves entries in the direction of an open position, for executors that add them
+    /// to it (see `SimulationSettings::allow_pyramiding`). Disabled by default.
+    pub fn with_pyramiding(mut self, allow_pyramiding: bool) -> Self {
+        self.allow_pyramiding = allow_pyramiding;
+        self
     }
 
-    fn evaluate(
+    /// Approves entries against an open position, for executors that close it and open the
+    /// reversed one (such as the simulated executor). Disabled by default.
+    pub fn with_reversals(mut self, allow_reversals: bool) -> Self {
+        self.allow_reversals = allow_reversals;
+        self
+    }
+
+    /// Sets the exchange's minimum order notional per symbol. Entries below the larger of
+    /// this and `min_position_notional` are vetoed.
+    pub fn with_exchange_min_notionals(mut self, min_notionals: HashMap<Symbol, Decimal>) -> Self {
+        self.exchange_min_notionals = min_notionals;
+        self
+    }
+
+    /// Applies the optional volatility scaling to `base_leverage`.
+    ///
+    /// Falls back to `base_leverage` when scaling is disabled or `klines` is too short to
+    /// measure volatility.
+    fn scaled_leverage(&self, base_leverage: u8, klines: &[Kline]) -> u8 {
+        let Some(vol_settings) = &self.settings.volatility_leverage else {
+            return base_leverage;
+        };
+        let Some(volatility) = average_true_range_percent(klines, vol_settings.lookback) else {
+            return base_leverage;
+        };
+        if volatility <= 0.0 {
+            return vol_settings.max_leverage;
+        }
+        let scaled = f64::from(base_leverage) * vol_settings.target_volatility / volatility;
+        scaled
+            .floor()
+            .max(f64::from(vol_settings.min_leverage))
+            .min(f64::from(vol_settings.max_leverage)) as u8
+    }
+
+    /// Evaluates a signal like [`RiskManager::evaluate`], but returns a structured
+    /// explanation of the decision, including the intermediate sizing values.
+    ///
+    /// Sizing fields are `None` when the decision was made before they were computed
+    /// (e.g., for `Close` signals or early vetoes).
+    pub fn explain(
         &self,
         signal: &Signal,
         symbol: &core_types::Symbol,
         portfolio_value: Decimal,
-        current_kline: &Kline,
+        klines: &[Kline],
         open_position: Option<&Position>,
-    ) -> Result<Option<OrderRequest>> {
+        open_positions: &HashMap<Symbol, Position>,
+    ) -> RiskDecision {
+        let mut decision = RiskDecision::default();
+        let Some(current_kline) = klines.last() else {
+            return decision.vetoed("No kline data to evaluate the signal against.".to_string());
+        };
+
         // --- Veto & Early Exit Logic ---
 
         // Rule: If signal is Hold, do nothing.
         if matches!(signal, Signal::Hold) {
-            return Ok(None);
+            return decision;
         }
 
         // Rule: If signal is Close, construct a closing order if a position exists.
         if let Signal::Close = signal {
-            return match open_position {
-                Some(pos) => {
-                    // Create a simple market order to close the position.
-                    // The quantity will be the full size of the open position.
-                    // The SL price is irrelevant for a closing order.
-                    Ok(Some(OrderRequest {
-                        symbol: pos.symbol.clone(),
-                        side: if pos.side == Side::Long { Side::Short } else { Side::Long },
-                        quantity: pos.quantity,
-                        
-                        // USE the position's leverage
-                        leverage: pos.leverage,
-
-                        sl_price: dec!(0), // Placeholder
-                        originating_signal: *signal,
-                    }))
+            decision.order = open_position.map(|pos| {
+                // Create a simple market order to close the position.
+                // The quantity will be the full size of the open position.
+                // The SL price is irrelevant for a closing order.
+                OrderRequest {
+                    symbol: pos.symbol.clone(),
+                    side: if pos.side == Side::Long { Side::Short } else { Side::Long },
+                    quantity: pos.quantity,
+                    order_type: OrderType::Market,
+
+                    // USE the position's leverage
+                    leverage: pos.leverage,
+
+                    sl_price: dec!(0), // Placeholder
+                    tp_price: None,
+                    originating_signal: *signal,
                 }
-                None => Ok(None), // No position to close.
-            };
+            }); // `None` if there is no position to close.
+            return decision;
+        }
+
+        // Rule: If signal is ClosePartial, construct an order for that fraction of the position.
+        if let Signal::ClosePartial { fraction } = signal {
+            if !(*fraction > 0.0 && *fraction <= 1.0) {
+                return decision.vetoed(format!("Partial close fraction ({}) must be in (0, 1]", fraction));
+            }
+            decision.order = open_position.map(|pos| OrderRequest {
+                symbol: pos.symbol.clone(),
+                side: if pos.side == Side::Long { Side::Short } else { Side::Long },
+                quantity: pos.quantity * Decimal::from_f64(*fraction).unwrap(),
+                order_type: OrderType::Market,
+                leverage: pos.leverage,
+                sl_price: dec!(0), // Placeholder
+                tp_price: None,
+                originating_signal: *signal,
+            }); // `None` if there is no position to reduce.
+            return decision;
         }
 
         // --- Entry Signal Logic ---
 
         // We are now dealing with a GoLong or GoShort signal.
-        let (signal_side, confidence) = match signal {
-            Signal::GoLong { confidence } => (Side::Long, *confidence),
-            Signal::GoShort { confidence } => (Side::Short, *confidence),
-            _ => unreachable!(), // We already handled Hold and Close.
+        let (signal_side, confidence, suggested_sl, suggested_tp) = match signal {
+            Signal::GoLong { confidence, suggested_sl, suggested_tp } => {
+                (Side::Long, *confidence, *suggested_sl, *suggested_tp)
+            }
+            Signal::GoShort { confidence, suggested_sl, suggested_tp } => {
+                (Side::Short, *confidence, *suggested_sl, *suggested_tp)
+            }
+            _ => unreachable!(), // We already handled Hold, Close and ClosePartial.
         };
 
-        // Rule: Veto if a position is already open. (No pyramiding in V1).
-        if open_position.is_some() {
-            return Err(Error::Vetoed {
-                reason: "A position is already open for this symbol.".to_string(),
-            });
+        // Rule: Veto entries on an open position unless the executor is expected to add to
+        // it (same direction) or reverse it (opposite direction).
+        let reversing = open_position.is_some_and(|pos| pos.side != signal_side);
+        match open_position {
+            Some(_) if reversing && !self.allow_reversals => {
+                return decision.vetoed("A position is already open for this symbol and reversals are disabled.".to_string());
+            }
+            Some(_) if !reversing && !self.allow_pyramiding => {
+                return decision.vetoed("A position is already open for this symbol and pyramiding is disabled.".to_string());
+            }
+            _ => {}
+        }
+
+        // Rule: Veto entries while the cooldown after a losing trade is active.
+        if self.settings.cooldown_bars_after_loss > 0 {
+            let last_loss_time = self.last_loss_times.lock().unwrap().get(symbol).copied();
+            if let Some(last_loss_time) = last_loss_time {
+                let bar_millis = current_kline.close_time - current_kline.open_time + 1;
+                let cooldown_end = last_loss_time + bar_millis * i64::from(self.settings.cooldown_bars_after_loss);
+                if current_kline.open_time < cooldown_end {
+                    return decision.vetoed(format!(
+                        "Cooldown of {} bars after a losing trade is still active",
+                        self.settings.cooldown_bars_after_loss
+                    ));
+                }
+            }
         }
 
         // Rule: Veto if confidence is below the configured threshold.
         if confidence < self.settings.minimum_confidence_threshold {
-            return Err(Error::Vetoed {
-                reason: format!(
-                    "Signal confidence ({:.2}) is below threshold ({:.2})",
-                    confidence, self.settings.minimum_confidence_threshold
-                ),
-            });
+            return decision.vetoed(format!(
+                "Signal confidence ({:.2}) is below threshold ({:.2})",
+                confidence, self.settings.minimum_confidence_threshold
+            ));
         }
 
         // --- Position Sizing Logic ---
@@ -105,25 +211,86 @@ impl RiskManager for SimpleRiskManager {
         // Convert portfolio_value to Decimal
         // let portfolio_value = Decimal::from_f64(portfolio_value).unwrap(); // This line is removed as portfolio_value is now Decimal
 
-        // Calculate stop-loss price
-        let sl_price = if signal_side == Side::Long {
-            entry_price * (dec!(1) - Decimal::from_f64(self.settings.stop_loss_percent).unwrap())
-        } else {
-            entry_price * (dec!(1) + Decimal::from_f64(self.settings.stop_loss_percent).unwrap())
+        // Calculate stop-loss price. A strategy-suggested level is preferred, but only
+        // if it sits on the losing side of the entry; otherwise fall back to the
+        // configured percentage.
+        let sl_price = match suggested_sl {
+            Some(sl) if signal_side == Side::Long && sl > dec!(0) && sl < entry_price => sl,
+            Some(sl) if signal_side == Side::Short && sl > entry_price => sl,
+            _ if signal_side == Side::Long => {
+                entry_price * (dec!(1) - Decimal::from_f64(self.settings.stop_loss_percent_for(&symbol.0)).unwrap())
+            }
+            _ => entry_price * (dec!(1) + Decimal::from_f64(self.settings.stop_loss_percent_for(&symbol.0)).unwrap()),
         };
+        decision.sl_price = Some(sl_price);
+
+        // A suggested take-profit is only kept if it sits on the winning side of the entry.
+        let tp_price = suggested_tp.filter(|tp| match signal_side {
+            Side::Long => *tp > entry_price,
+            Side::Short => *tp > dec!(0) && *tp < entry_price,
+        });
+
+        // The actual stop distance as a fraction of the entry price.
+        let stop_distance = (entry_price - sl_price).abs() / entry_price;
 
         // Calculate position size
-        let risk_per_trade = Decimal::from_f64(self.settings.risk_per_trade_percent).unwrap();
+        let risk_per_trade = Decimal::from_f64(self.settings.risk_per_trade_percent_for(&symbol.0)).unwrap();
         let amount_to_risk = portfolio_value * risk_per_trade;
+        decision.amount_to_risk = Some(amount_to_risk);
 
         // Scale risk by confidence
         let scaled_amount_to_risk = amount_to_risk * Decimal::from_f64(confidence).unwrap();
+        decision.scaled_amount_to_risk = Some(scaled_amount_to_risk);
 
         // Position size in quote asset (e.g., USDT)
-        let position_size_quote = scaled_amount_to_risk / Decimal::from_f64(self.settings.stop_loss_percent).unwrap();
-        
+        let mut position_size_quote = scaled_amount_to_risk / stop_distance;
+
+        // Rule: Cap the notional at the configured maximum.
+        if let Some(max_notional) = self.settings.max_position_notional.and_then(Decimal::from_f64) {
+            position_size_quote = position_size_quote.min(max_notional);
+        }
+        decision.notional = Some(position_size_quote);
+
+        // Rule: Veto if the notional is too small to be worthwhile or to be accepted by the exchange.
+        let configured_min = self.settings.min_position_notional.and_then(Decimal::from_f64);
+        let exchange_min = self.exchange_min_notionals.get(symbol).copied();
+        if let Some(min_notional) = configured_min.max(exchange_min)
+            && position_size_quote < min_notional
+        {
+            return decision.vetoed(format!(
+                "Position notional ({:.2}) is below the minimum ({:.2})",
+                position_size_quote, min_notional
+            ));
+        }
+
+        // Rule: Veto if the entry would push its correlation group's net exposure over the limit.
+        if let Some(max_exposure) = self.settings.max_group_exposure.and_then(Decimal::from_f64)
+            && let Some(group) = self.settings.correlation_groups.iter().find(|g| g.contains(&symbol.0))
+        {
+            // A reversed position is closed before the new one opens, so it no longer counts.
+            let group_exposure: Decimal = open_positions
+                .values()
+                .filter(|pos| group.contains(&pos.symbol.0))
+                .filter(|pos| !(reversing && pos.symbol == *symbol))
+                .map(|pos| signed_notional(pos.side, pos.quantity * pos.entry_price))
+                .sum();
+            let new_exposure = group_exposure + signed_notional(signal_side, position_size_quote);
+            let exposure_limit = portfolio_value * max_exposure;
+            if new_exposure.abs() > exposure_limit {
+                return decision.vetoed(format!(
+                    "Correlation group net exposure ({:.2}) would exceed the limit ({:.2})",
+                    new_exposure, exposure_limit
+                ));
+            }
+        }
+
         // Convert to base asset quantity
         let quantity_base = position_size_quote / entry_price;
+        decision.quantity = Some(quantity_base);
+
+        // Scale the leverage down in volatile conditions, if configured.
+        let leverage = self.scaled_leverage(self.settings.leverage_for(&symbol.0), klines);
+        decision.leverage = Some(leverage);
 
         // --- Construct the Order Request ---
         
@@ -131,14 +298,439 @@ impl RiskManager for SimpleRiskManager {
             symbol: symbol.clone(), // Use the actual symbol passed to the method
             side: signal_side,
             quantity: quantity_base,
+            order_type: OrderType::Market,
             
-            // USE the configured value
-            leverage: self.settings.leverage,
+            // USE the configured value, honouring any per-symbol override and volatility scaling
+            leverage,
 
             sl_price,
+            tp_price,
             originating_signal: *signal,
         };
 
-        Ok(Some(order_request))
+        decision.order = Some(order_request);
+        decision
+    }
+}
+
+impl RiskManager for SimpleRiskManager {
+    fn name(&self) -> &'static str {
+        "SimpleRiskManager"
+    }
+
+    fn evaluate(
+        &self,
+        signal: &Signal,
+        symbol: &core_types::Symbol,
+        portfolio_value: Decimal,
+        klines: &[Kline],
+        open_position: Option<&Position>,
+        open_positions: &HashMap<Symbol, Position>,
+    ) -> Result<Option<OrderRequest>> {
+        let decision = self.explain(signal, symbol, portfolio_value, klines, open_position, open_positions);
+        match decision.veto_reason {
+            Some(reason) => Err(Error::Vetoed { reason }),
+            None => Ok(decision.order),
+        }
+    }
+
+    fn on_position_closed(&self, closed_position: &Position, execution: &Execution, close_time: i64) {
+        let price_change = match closed_position.side {
+            Side::Long => execution.price - closed_position.entry_price,
+            Side::Short => closed_position.entry_price - execution.price,
+        };
+        let net_pnl = price_change * execution.quantity - closed_position.entry_fee - execution.fee;
+        if net_pnl < Decimal::ZERO {
+            self.last_loss_times
+                .lock()
+                .unwrap()
+                .insert(closed_position.symbol.clone(), close_time);
+        }
+    }
+}
+
+/// Returns `notional` signed by direction: positive for longs, negative for shorts.
+fn signed_notional(side: Side, notional: Decimal) -> Decimal {
+    match side {
+        Side::Long => notional,
+        Side::Short => -notional,
+    }
+}
+
+/// Computes the average true range over the last `lookback` bars of `klines`, as a fraction
+/// of the last close. Returns `None` if there are fewer than two klines.
+fn average_true_range_percent(klines: &[Kline], lookback: usize) -> Option<f64> {
+    if klines.len() < 2 || lookback == 0 {
+        return None;
+    }
+    let window = &klines[klines.len().saturating_sub(lookback + 1)..];
+    let true_ranges: Vec<Decimal> = window
+        .windows(2)
+        .map(|pair| {
+            let (prev, curr) = (&pair[0], &pair[1]);
+            (curr.high - curr.low)
+                .max((curr.high - prev.close).abs())
+                .max((curr.low - prev.close).abs())
+        })
+        .collect();
+    let last_close = window.last()?.close;
+    if last_close <= Decimal::ZERO {
+        return None;
+    }
+    let atr = true_ranges.iter().sum::<Decimal>() / Decimal::from(true_ranges.len());
+    (atr / last_close).to_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SymbolRiskOverride, VolatilityLeverageSettings};
+
+    fn settings() -> SimpleRiskSettings {
+        SimpleRiskSettings {
+            risk_per_trade_percent: 0.01,
+            stop_loss_percent: 0.02,
+            minimum_confidence_threshold: 0.5,
+            leverage: 10,
+            cooldown_bars_after_loss: 0,
+            min_position_notional: None,
+            max_position_notional: None,
+            correlation_groups: Vec::new(),
+            max_group_exposure: None,
+            volatility_leverage: None,
+            symbol_overrides: HashMap::new(),
+        }
+    }
+
+    fn symbol() -> Symbol {
+        Symbol::new("BTCUSDT").unwrap()
+    }
+
+    /// One-minute klines following `closes`, each opening at the previous close with a 1%
+    /// range beyond the body.
+    fn klines_from_closes(closes: &[f64]) -> Vec<Kline> {
+        let mut previous_close = closes[0];
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                let open = previous_close;
+                previous_close = close;
+                let price = |value: f64| Decimal::from_f64(value).unwrap();
+                Kline {
+                    open_time: i as i64 * 60_000,
+                    open: price(open),
+                    high: price(open.max(close) * 1.01),
+                    low: price(open.min(close) * 0.99),
+                    close: price(close),
+                    volume: dec!(100),
+                    close_time: i as i64 * 60_000 + 59_999,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn supertrend_suggested_stop_flows_into_the_order() {
+        use strategies::supertrend::SuperTrend;
+        use strategies::types::SuperTrendSettings;
+        use strategies::Strategy;
+
+        let mut strategy = SuperTrend::new(SuperTrendSettings {
+            period: 5,
+            multiplier: 1.0,
+            exit_multiplier: 0.5,
+            volume_threshold: 0.0,
+            confirmation_bars: 1,
+            ema_confirmation_period: 5,
+            confidence: 0.8,
+            use_heikin_ashi: false,
+        });
+        // A steady decline followed by a strong rally flips SuperTrend to an uptrend.
+        let mut closes: Vec<f64> = (0..30).map(|i| 200.0 - 2.0 * i as f64).collect();
+        closes.extend((1..=15).map(|i| 142.0 + 4.0 * i as f64));
+        let klines = klines_from_closes(&closes);
+
+        let (end, signal) = (1..=klines.len())
+            .map(|end| (end, strategy.assess(&klines[..end])))
+            .find(|(_, signal)| matches!(signal, Signal::GoLong { .. }))
+            .expect("the rally should produce a long entry");
+        let Signal::GoLong { suggested_sl: Some(suggested_sl), .. } = signal else {
+            panic!("SuperTrend should suggest a stop-loss: {:?}", signal);
+        };
+
+        let manager = SimpleRiskManager::new(settings());
+        let order = manager
+            .evaluate(&signal, &symbol(), dec!(10_000), &klines[..end], None, &HashMap::new())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(order.sl_price, suggested_sl);
+        assert!(order.sl_price < klines[end - 1].close);
     }
-}
\ No newline at end of file
+
+    fn go_long() -> Signal {
+        Signal::GoLong { confidence: 1.0, suggested_sl: None, suggested_tp: None }
+    }
+
+    /// A one-unit long entered at 100.
+    fn long_position() -> Position {
+        Position {
+            symbol: symbol(),
+            side: Side::Long,
+            quantity: dec!(1),
+            entry_price: dec!(100),
+            leverage: 10,
+            sl_price: dec!(98),
+            tp_price: None,
+            entry_time: 0,
+            entry_fee: dec!(0),
+        }
+    }
+
+    /// Closes [`long_position`] at `exit_price`, notifying `manager` at `close_time`.
+    fn close_long(manager: &SimpleRiskManager, exit_price: Decimal, close_time: i64) {
+        let execution = Execution {
+            symbol: symbol(),
+            side: Side::Short,
+            price: exit_price,
+            quantity: dec!(1),
+            fee: dec!(0),
+            order_id: None,
+            source_request: OrderRequest {
+                symbol: symbol(),
+                side: Side::Short,
+                quantity: dec!(1),
+                order_type: OrderType::Market,
+                leverage: 10,
+                sl_price: dec!(0),
+                tp_price: None,
+                originating_signal: Signal::Close,
+            },
+        };
+        manager.on_position_closed(&long_position(), &execution, close_time);
+    }
+
+    #[test]
+    fn entries_are_vetoed_until_the_cooldown_after_a_loss_elapses() {
+        let manager = SimpleRiskManager::new(SimpleRiskSettings { cooldown_bars_after_loss: 3, ..settings() });
+        let klines = klines_from_closes(&[100.0; 10]);
+        // The loss is realized at the close of bar 2, so bars 3 to 5 are in the cooldown.
+        close_long(&manager, dec!(95), klines[2].close_time);
+        let evaluate_at = |bar: usize| {
+            manager.evaluate(&go_long(), &symbol(), dec!(10_000), &klines[..=bar], None, &HashMap::new())
+        };
+
+        assert!(matches!(evaluate_at(3), Err(Error::Vetoed { .. })));
+        assert!(matches!(evaluate_at(5), Err(Error::Vetoed { .. })));
+        assert!(evaluate_at(6).unwrap().is_some());
+    }
+
+    #[test]
+    fn winning_trades_and_closes_are_not_subject_to_the_cooldown() {
+        let manager = SimpleRiskManager::new(SimpleRiskSettings { cooldown_bars_after_loss: 3, ..settings() });
+        let klines = klines_from_closes(&[100.0; 10]);
+        close_long(&manager, dec!(105), klines[2].close_time);
+
+        assert!(
+            manager
+                .evaluate(&go_long(), &symbol(), dec!(10_000), &klines[..=3], None, &HashMap::new())
+                .unwrap()
+                .is_some()
+        );
+
+        close_long(&manager, dec!(95), klines[3].close_time);
+        let close = manager
+            .evaluate(&Signal::Close, &symbol(), dec!(10_000), &klines[..=4], Some(&long_position()), &HashMap::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(close.side, Side::Short);
+    }
+
+    #[test]
+    fn symbol_overrides_change_the_sizing_of_that_symbol_only() {
+        let btc_override = SymbolRiskOverride {
+            risk_per_trade_percent: Some(0.02),
+            leverage: Some(5),
+            ..Default::default()
+        };
+        let manager = SimpleRiskManager::new(SimpleRiskSettings {
+            symbol_overrides: HashMap::from([("BTCUSDT".to_string(), btc_override)]),
+            ..settings()
+        });
+        let klines = klines_from_closes(&[100.0; 3]);
+        let evaluate = |symbol: &str| {
+            manager
+                .evaluate(&go_long(), &Symbol::new(symbol).unwrap(), dec!(10_000), &klines, None, &HashMap::new())
+                .unwrap()
+                .unwrap()
+        };
+
+        // 2% of 10,000 at a 2% stop is a 10,000 notional; 1% is 5,000. At a price of 100
+        // that is 100 and 50 units.
+        let btc = evaluate("BTCUSDT");
+        let eth = evaluate("ETHUSDT");
+        assert_eq!(btc.quantity, dec!(100));
+        assert_eq!(btc.leverage, 5);
+        assert_eq!(eth.quantity, dec!(50));
+        assert_eq!(eth.leverage, 10);
+    }
+
+    #[test]
+    fn sizings_below_the_minimum_notional_are_vetoed() {
+        // The default sizing is a 5,000 notional (1% of 10,000 at a 2% stop).
+        let klines = klines_from_closes(&[100.0; 3]);
+        let configured = SimpleRiskManager::new(SimpleRiskSettings { min_position_notional: Some(6_000.0), ..settings() });
+        let exchange = SimpleRiskManager::new(SimpleRiskSettings { min_position_notional: Some(1_000.0), ..settings() })
+            .with_exchange_min_notionals(HashMap::from([(symbol(), dec!(6_000))]));
+
+        for manager in [configured, exchange] {
+            let result = manager.evaluate(&go_long(), &symbol(), dec!(10_000), &klines, None, &HashMap::new());
+            assert!(matches!(result, Err(Error::Vetoed { .. })), "{:?}", result);
+        }
+    }
+
+    #[test]
+    fn sizings_above_the_maximum_notional_are_capped() {
+        let manager = SimpleRiskManager::new(SimpleRiskSettings { max_position_notional: Some(2_000.0), ..settings() });
+        let klines = klines_from_closes(&[100.0; 3]);
+
+        let order = manager
+            .evaluate(&go_long(), &symbol(), dec!(10_000), &klines, None, &HashMap::new())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(order.quantity, dec!(20));
+    }
+
+    #[test]
+    fn explain_reports_the_intermediate_sizing_values() {
+        let manager = SimpleRiskManager::new(settings());
+        let klines = klines_from_closes(&[100.0; 3]);
+        let signal = Signal::GoLong { confidence: 0.8, suggested_sl: None, suggested_tp: None };
+
+        let decision = manager.explain(&signal, &symbol(), dec!(10_000), &klines, None, &HashMap::new());
+
+        // 1% of 10,000 is 100 at risk, 80 after confidence scaling; at a 2% stop
+        // (98) that is a 4,000 notional, or 40 units at a price of 100.
+        assert_eq!(decision.sl_price, Some(dec!(98)));
+        assert_eq!(decision.amount_to_risk, Some(dec!(100)));
+        assert_eq!(decision.scaled_amount_to_risk, Some(dec!(80)));
+        assert_eq!(decision.notional, Some(dec!(4_000)));
+        assert_eq!(decision.quantity, Some(dec!(40)));
+        assert_eq!(decision.leverage, Some(10));
+        assert_eq!(decision.veto_reason, None);
+        assert_eq!(decision.order.unwrap().quantity, dec!(40));
+    }
+
+    #[test]
+    fn explain_reports_the_veto_reason() {
+        let manager = SimpleRiskManager::new(settings());
+        let klines = klines_from_closes(&[100.0; 3]);
+        let signal = Signal::GoLong { confidence: 0.3, suggested_sl: None, suggested_tp: None };
+
+        let decision = manager.explain(&signal, &symbol(), dec!(10_000), &klines, None, &HashMap::new());
+
+        assert!(decision.veto_reason.unwrap().contains("below threshold"));
+        assert_eq!(decision.order, None);
+        assert_eq!(decision.notional, None);
+    }
+
+    #[test]
+    fn entries_that_breach_the_correlation_group_limit_are_vetoed() {
+        let manager = SimpleRiskManager::new(SimpleRiskSettings {
+            correlation_groups: vec![vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()]],
+            max_group_exposure: Some(0.6),
+            ..settings()
+        });
+        let klines = klines_from_closes(&[100.0; 3]);
+        // A 2,000 BTC long is open; each new entry is sized at a 5,000 notional against a
+        // 6,000 group limit.
+        let open_positions = HashMap::from([(symbol(), Position { quantity: dec!(20), ..long_position() })]);
+        let evaluate = |signal: Signal, symbol: &str| {
+            manager.evaluate(&signal, &Symbol::new(symbol).unwrap(), dec!(10_000), &klines, None, &open_positions)
+        };
+        let go_short = Signal::GoShort { confidence: 1.0, suggested_sl: None, suggested_tp: None };
+
+        assert!(matches!(evaluate(go_long(), "ETHUSDT"), Err(Error::Vetoed { .. })));
+        assert!(evaluate(go_short, "ETHUSDT").unwrap().is_some());
+        assert!(evaluate(go_long(), "SOLUSDT").unwrap().is_some());
+    }
+
+    #[test]
+    fn volatile_windows_get_lower_leverage_than_calm_ones() {
+        let manager = SimpleRiskManager::new(SimpleRiskSettings {
+            volatility_leverage: Some(VolatilityLeverageSettings {
+                lookback: 5,
+                target_volatility: 0.03,
+                min_leverage: 2,
+                max_leverage: 20,
+            }),
+            ..settings()
+        });
+        let leverage_for = |closes: &[f64]| {
+            let klines = klines_from_closes(closes);
+            manager
+                .evaluate(&go_long(), &symbol(), dec!(10_000), &klines, None, &HashMap::new())
+                .unwrap()
+                .unwrap()
+                .leverage
+        };
+
+        // Flat bars have a 2% true range; swinging between 100 and 110 pushes it past 10%.
+        let calm = leverage_for(&[100.0; 8]);
+        let volatile = leverage_for(&[100.0, 110.0, 100.0, 110.0, 100.0, 110.0, 100.0, 110.0]);
+
+        assert!(volatile < calm, "volatile {} should be below calm {}", volatile, calm);
+        assert!(calm > 10 && calm <= 20);
+        assert!(volatile >= 2);
+    }
+
+    #[test]
+    fn entries_on_an_open_position_follow_the_pyramiding_and_reversal_settings() {
+        let klines = klines_from_closes(&[100.0; 3]);
+        let go_short = Signal::GoShort { confidence: 1.0, suggested_sl: None, suggested_tp: None };
+        let evaluate = |manager: &SimpleRiskManager, signal: &Signal| {
+            manager.evaluate(signal, &symbol(), dec!(10_000), &klines, Some(&long_position()), &HashMap::new())
+        };
+
+        let strict = SimpleRiskManager::new(settings());
+        assert!(matches!(evaluate(&strict, &go_long()), Err(Error::Vetoed { .. })));
+        assert!(matches!(evaluate(&strict, &go_short), Err(Error::Vetoed { .. })));
+
+        let reversing = SimpleRiskManager::new(settings()).with_reversals(true);
+        assert!(matches!(evaluate(&reversing, &go_long()), Err(Error::Vetoed { .. })));
+        assert_eq!(evaluate(&reversing, &go_short).unwrap().unwrap().side, Side::Short);
+
+        let pyramiding = SimpleRiskManager::new(settings()).with_pyramiding(true);
+        assert_eq!(evaluate(&pyramiding, &go_long()).unwrap().unwrap().side, Side::Long);
+        assert!(matches!(evaluate(&pyramiding, &go_short), Err(Error::Vetoed { .. })));
+    }
+
+    #[test]
+    fn reversals_are_measured_against_the_group_exposure_after_the_close() {
+        let manager = SimpleRiskManager::new(SimpleRiskSettings {
+            correlation_groups: vec![vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()]],
+            max_group_exposure: Some(0.85),
+            ..settings()
+        })
+        .with_reversals(true);
+        let klines = klines_from_closes(&[100.0; 3]);
+        // A 4,000 ETH short and a 1,000 BTC long are open. Reversing the BTC long into a
+        // 5,000 short leaves -9,000 of exposure, over the 8,500 limit, even though counting
+        // the long about to be closed would suggest only -8,000.
+        let eth_short = Position {
+            symbol: Symbol::new("ETHUSDT").unwrap(),
+            side: Side::Short,
+            quantity: dec!(40),
+            ..long_position()
+        };
+        let btc_long = Position { quantity: dec!(10), ..long_position() };
+        let open_positions = HashMap::from([(eth_short.symbol.clone(), eth_short), (symbol(), btc_long.clone())]);
+        let go_short = Signal::GoShort { confidence: 1.0, suggested_sl: None, suggested_tp: None };
+
+        let result = manager.evaluate(&go_short, &symbol(), dec!(10_000), &klines, Some(&btc_long), &open_positions);
+
+        assert!(matches!(result, Err(Error::Vetoed { .. })), "{:?}", result);
+    }
+}
@@ -1,8 +1,11 @@
 // In crates/risk/src/types.rs
 
-use serde::Deserialize;
+use core_types::OrderRequest;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SimpleRiskSettings {
     /// The percentage of the total portfolio to risk on a single trade (e.g., 0.01 for 1%).
     pub risk_per_trade_percent: f64,
@@ -15,4 +18,131 @@ pub struct SimpleRiskSettings {
     
     /// The leverage to be used for all trades generated by this manager.
     pub leverage: u8,
+
+    /// The number of bars after a losing trade closes during which new entries on the
+    /// same symbol are vetoed. `0` disables the cooldown.
+    #[serde(default)]
+    pub cooldown_bars_after_loss: u32,
+
+    /// The smallest position notional (in quote currency) worth trading. Smaller sizings
+    /// are vetoed.
+    #[serde(default)]
+    pub min_position_notional: Option<f64>,
+
+    /// The largest allowed position notional (in quote currency). Larger sizings are capped.
+    #[serde(default)]
+    pub max_position_notional: Option<f64>,
+
+    /// Groups of correlated symbols (e.g., `[["BTCUSDT", "ETHUSDT"]]`) whose net directional
+    /// exposure is limited together by `max_group_exposure`.
+    #[serde(default)]
+    pub correlation_groups: Vec<Vec<String>>,
+
+    /// The maximum absolute net notional of a correlation group, as a fraction of the
+    /// portfolio value (e.g., 1.0 for 100%). Entries that would exceed it are vetoed.
+    #[serde(default)]
+    pub max_group_exposure: Option<f64>,
+
+    /// Optional volatility-based scaling of the leverage.
+    #[serde(default)]
+    pub volatility_leverage: Option<VolatilityLeverageSettings>,
+
+    /// Per-symbol overrides of the global settings, keyed by symbol (e.g., "BTCUSDT").
+    #[serde(default)]
+    pub symbol_overrides: HashMap<String, SymbolRiskOverride>,
+}
+
+impl SimpleRiskSettings {
+    /// The risk per trade for `symbol`, falling back to the global setting.
+    pub fn risk_per_trade_percent_for(&self, symbol: &str) -> f64 {
+        self.symbol_overrides
+            .get(symbol)
+            .and_then(|o| o.risk_per_trade_percent)
+            .unwrap_or(self.risk_per_trade_percent)
+    }
+
+    /// The stop-loss distance for `symbol`, falling back to the global setting.
+    pub fn stop_loss_percent_for(&self, symbol: &str) -> f64 {
+        self.symbol_overrides
+            .get(symbol)
+            .and_then(|o| o.stop_loss_percent)
+            .unwrap_or(self.stop_loss_percent)
+    }
+
+    /// The leverage for `symbol`, falling back to the global setting.
+    pub fn leverage_for(&self, symbol: &str) -> u8 {
+        self.symbol_overrides
+            .get(symbol)
+            .and_then(|o| o.leverage)
+            .unwrap_or(self.leverage)
+    }
+}
+
+/// Scales the leverage inversely to recent volatility, measured as the average true range
+/// relative to the close price.
+///
+/// At `target_volatility` the configured leverage is used unchanged; at twice that volatility
+/// it is halved, and so on. The result is clamped to `[min_leverage, max_leverage]`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VolatilityLeverageSettings {
+    /// The number of recent bars used to compute the average true range.
+    pub lookback: usize,
+
+    /// The ATR-to-price ratio at which the configured leverage applies (e.g., 0.01 for 1%).
+    pub target_volatility: f64,
+
+    /// The lowest leverage the scaling may produce.
+    pub min_leverage: u8,
+
+    /// The highest leverage the scaling may produce.
+    pub max_leverage: u8,
+}
+
+/// Overrides of `SimpleRiskSettings` for a single symbol. Unset fields use the global value.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SymbolRiskOverride {
+    /// Overrides `risk_per_trade_percent`.
+    pub risk_per_trade_percent: Option<f64>,
+
+    /// Overrides `stop_loss_percent`.
+    pub stop_loss_percent: Option<f64>,
+
+    /// Overrides `leverage`.
+    pub leverage: Option<u8>,
+}
+
+/// A structured explanation of a risk manager's decision on a single signal.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct RiskDecision {
+    /// The stop-loss price chosen for an entry.
+    pub sl_price: Option<Decimal>,
+
+    /// The portfolio amount put at risk before confidence scaling.
+    pub amount_to_risk: Option<Decimal>,
+
+    /// The amount at risk after scaling by signal confidence.
+    pub scaled_amount_to_risk: Option<Decimal>,
+
+    /// The position notional in quote currency, after any maximum-notional cap.
+    pub notional: Option<Decimal>,
+
+    /// The position quantity in the base asset.
+    pub quantity: Option<Decimal>,
+
+    /// The leverage chosen for an entry, after any volatility scaling.
+    pub leverage: Option<u8>,
+
+    /// The resulting order, if the signal was approved and requires action.
+    pub order: Option<OrderRequest>,
+
+    /// Why the signal was vetoed, if it was.
+    pub veto_reason: Option<String>,
+}
+
+impl RiskDecision {
+    /// Marks this decision as vetoed for `reason`.
+    pub fn vetoed(mut self, reason: String) -> Self {
+        self.veto_reason = Some(reason);
+        self
+    }
 }
\ No newline at end of file
@@ -0,0 +1,271 @@
+// In crates/strategies/src/ensemble.rs
+
+use crate::types::EnsembleRule;
+use crate::{Signal, Strategy};
+use core_types::{Kline, Side};
+use rust_decimal::Decimal;
+
+/// A strategy that combines the signals of several sub-strategies into one.
+///
+/// Every sub-strategy is assessed on every bar (so stateful strategies stay in sync), and
+/// their signals are merged according to the configured `EnsembleRule`. Opposing entry
+/// signals (one long, one short) always result in `Hold`.
+pub struct EnsembleStrategy {
+    strategies: Vec<Box<dyn Strategy + Send>>,
+    rule: EnsembleRule,
+}
+
+impl EnsembleStrategy {
+    /// Creates a new `EnsembleStrategy` from its sub-strategies and combination rule.
+    pub fn new(strategies: Vec<Box<dyn Strategy + Send>>, rule: EnsembleRule) -> Self {
+        Self { strategies, rule }
+    }
+
+    /// Merges the entry signals of the agreeing sub-strategies into a single signal.
+    ///
+    /// The stop-loss is the tightest one suggested, and the take-profit is the first one
+    /// suggested.
+    fn merge_entries(side: Side, entries: &[Signal], confidence: f64) -> Signal {
+        let mut suggested_sl: Option<Decimal> = None;
+        let mut suggested_tp: Option<Decimal> = None;
+
+        for signal in entries {
+            if let Signal::GoLong { suggested_sl: sl, suggested_tp: tp, .. }
+            | Signal::GoShort { suggested_sl: sl, suggested_tp: tp, .. } = signal
+            {
+                suggested_sl = match (suggested_sl, *sl) {
+                    (Some(current), Some(new)) if side == Side::Long => Some(current.max(new)),
+                    (Some(current), Some(new)) => Some(current.min(new)),
+                    (current, new) => current.or(new),
+                };
+                suggested_tp = suggested_tp.or(*tp);
+            }
+        }
+
+        match side {
+            Side::Long => Signal::GoLong { confidence, suggested_sl, suggested_tp },
+            Side::Short => Signal::GoShort { confidence, suggested_sl, suggested_tp },
+        }
+    }
+
+    /// Combines a set of sub-strategy signals according to `self.rule`.
+    fn combine(&self, signals: &[Signal]) -> Signal {
+        let total = signals.len();
+        if total == 0 {
+            return Signal::Hold;
+        }
+
+        let longs: Vec<Signal> = signals.iter().copied().filter(|s| matches!(s, Signal::GoLong { .. })).collect();
+        let shorts: Vec<Signal> = signals.iter().copied().filter(|s| matches!(s, Signal::GoShort { .. })).collect();
+        let closes = signals.iter().filter(|s| matches!(s, Signal::Close)).count();
+
+        // Rule: Conflicting entries are never resolved; stay out of the market.
+        if !longs.is_empty() && !shorts.is_empty() {
+            return Signal::Hold;
+        }
+
+        let (side, entries) = match (longs.is_empty(), shorts.is_empty()) {
+            (false, _) => (Some(Side::Long), longs),
+            (_, false) => (Some(Side::Short), shorts),
+            _ => (None, Vec::new()),
+        };
+        let confidence_sum: f64 = entries
+            .iter()
+            .map(|s| match s {
+                Signal::GoLong { confidence, .. } | Signal::GoShort { confidence, .. } => *confidence,
+                _ => 0.0,
+            })
+            .sum();
+
+        match self.rule {
+            EnsembleRule::Unanimous => match side {
+                Some(side) if entries.len() == total => {
+                    Self::merge_entries(side, &entries, confidence_sum / total as f64)
+                }
+                None if closes == total => Signal::Close,
+                _ => Signal::Hold,
+            },
+            EnsembleRule::MajorityVote => match side {
+                Some(side) if entries.len() * 2 > total => {
+                    Self::merge_entries(side, &entries, confidence_sum / entries.len() as f64)
+                }
+                _ if closes * 2 > total => Signal::Close,
+                _ => Signal::Hold,
+            },
+            EnsembleRule::ConfidenceWeighted => match side {
+                // Abstaining strategies count as zero confidence, diluting the average.
+                Some(side) => Self::merge_entries(side, &entries, confidence_sum / total as f64),
+                None if closes * 2 > total => Signal::Close,
+                None => Signal::Hold,
+            },
+        }
+    }
+}
+
+impl Strategy for EnsembleStrategy {
+    fn name(&self) -> &'static str {
+        "Ensemble"
+    }
+
+    fn warm_up(&mut self, klines: &[Kline]) {
+        for strategy in self.strategies.iter_mut() {
+            strategy.warm_up(klines);
+        }
+    }
+
+    fn update_context(&mut self, interval: &str, klines: &[Kline]) {
+        for strategy in self.strategies.iter_mut() {
+            strategy.update_context(interval, klines);
+        }
+    }
+
+    fn reset(&mut self) {
+        for strategy in self.strategies.iter_mut() {
+            strategy.reset();
+        }
+    }
+
+    /// The longest history any sub-strategy needs.
+    fn required_history(&self) -> usize {
+        self.strategies.iter().map(|s| s.required_history()).max().unwrap_or(0)
+    }
+
+    fn assess(&mut self, klines: &[Kline]) -> Signal {
+        let signals: Vec<Signal> = self.strategies.iter_mut().map(|s| s.assess(klines)).collect();
+        self.combine(&signals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::klines_from_closes;
+    use std::sync::{Arc, Mutex};
+
+    /// A strategy that always returns the same signal.
+    struct Fixed(Signal);
+
+    impl Strategy for Fixed {
+        fn name(&self) -> &'static str {
+            "Fixed"
+        }
+
+        fn required_history(&self) -> usize {
+            1
+        }
+
+        fn assess(&mut self, _klines: &[Kline]) -> Signal {
+            self.0
+        }
+    }
+
+    fn long(confidence: f64) -> Signal {
+        Signal::GoLong { confidence, suggested_sl: None, suggested_tp: None }
+    }
+
+    fn short(confidence: f64) -> Signal {
+        Signal::GoShort { confidence, suggested_sl: None, suggested_tp: None }
+    }
+
+    fn assess(rule: EnsembleRule, signals: &[Signal]) -> Signal {
+        let members = signals
+            .iter()
+            .map(|signal| Box::new(Fixed(*signal)) as Box<dyn Strategy + Send>)
+            .collect();
+        EnsembleStrategy::new(members, rule).assess(&klines_from_closes(&[100.0]))
+    }
+
+    #[test]
+    fn unanimous_long_goes_long_with_the_average_confidence() {
+        assert_eq!(assess(EnsembleRule::Unanimous, &[long(0.6), long(0.8)]), long(0.7));
+    }
+
+    #[test]
+    fn unanimous_holds_unless_every_member_agrees() {
+        assert_eq!(assess(EnsembleRule::Unanimous, &[long(0.6), Signal::Hold]), Signal::Hold);
+    }
+
+    #[test]
+    fn conflicting_entries_hold_under_every_rule() {
+        for rule in [EnsembleRule::Unanimous, EnsembleRule::MajorityVote, EnsembleRule::ConfidenceWeighted] {
+            assert_eq!(assess(rule, &[long(0.9), long(0.9), short(0.5)]), Signal::Hold, "{:?}", rule);
+        }
+    }
+
+    #[test]
+    fn majority_vote_needs_more_than_half_of_the_members() {
+        assert_eq!(
+            assess(EnsembleRule::MajorityVote, &[short(0.5), short(0.7), Signal::Hold]),
+            short(0.6)
+        );
+        assert_eq!(assess(EnsembleRule::MajorityVote, &[short(0.5), Signal::Hold]), Signal::Hold);
+    }
+
+    #[test]
+    fn majority_vote_closes_when_most_members_close() {
+        assert_eq!(
+            assess(EnsembleRule::MajorityVote, &[Signal::Close, Signal::Close, Signal::Hold]),
+            Signal::Close
+        );
+    }
+
+    #[test]
+    fn confidence_weighted_counts_abstaining_members_as_zero() {
+        assert_eq!(
+            assess(EnsembleRule::ConfidenceWeighted, &[long(0.9), Signal::Hold, Signal::Hold]),
+            long(0.3)
+        );
+    }
+
+    #[test]
+    fn merged_entry_keeps_the_tightest_stop() {
+        let wide = Signal::GoLong { confidence: 0.5, suggested_sl: Some(Decimal::from(90)), suggested_tp: None };
+        let tight = Signal::GoLong { confidence: 0.5, suggested_sl: Some(Decimal::from(95)), suggested_tp: Some(Decimal::from(120)) };
+
+        assert_eq!(
+            assess(EnsembleRule::Unanimous, &[wide, tight]),
+            Signal::GoLong { confidence: 0.5, suggested_sl: Some(Decimal::from(95)), suggested_tp: Some(Decimal::from(120)) }
+        );
+    }
+
+    /// The interval and length of every context update a `ContextRecorder` received.
+    type ContextLog = Arc<Mutex<Vec<(String, usize)>>>;
+
+    /// Holds, recording every context update it receives.
+    struct ContextRecorder(ContextLog);
+
+    impl Strategy for ContextRecorder {
+        fn name(&self) -> &'static str {
+            "ContextRecorder"
+        }
+
+        fn update_context(&mut self, interval: &str, klines: &[Kline]) {
+            self.0.lock().unwrap().push((interval.to_string(), klines.len()));
+        }
+
+        fn required_history(&self) -> usize {
+            1
+        }
+
+        fn assess(&mut self, _klines: &[Kline]) -> Signal {
+            Signal::Hold
+        }
+    }
+
+    #[test]
+    fn context_updates_reach_every_member() {
+        let seen: Vec<ContextLog> = (0..2).map(|_| ContextLog::default()).collect();
+        let members = seen
+            .iter()
+            .map(|log| Box::new(ContextRecorder(log.clone())) as Box<dyn Strategy + Send>)
+            .collect();
+        let mut ensemble = EnsembleStrategy::new(members, EnsembleRule::Unanimous);
+
+        ensemble.update_context("1h", &klines_from_closes(&[100.0, 101.0]));
+        ensemble.update_context("4h", &klines_from_closes(&[100.0]));
+
+        for log in &seen {
+            assert_eq!(*log.lock().unwrap(), [("1h".to_string(), 2), ("4h".to_string(), 1)]);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+// In crates/strategies/src/error.rs
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unknown strategy '{0}'")]
+    UnknownStrategy(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,171 @@
+// In crates/strategies/src/keltner.rs
+
+use crate::types::KeltnerSettings;
+use crate::{prepare_klines, Signal, Strategy};
+use core_types::{Kline, Side};
+use rust_decimal::prelude::*;
+use ta::indicators::{AverageTrueRange, ExponentialMovingAverage as Ema};
+use ta::{DataItem, Next};
+
+/// The Keltner channel of a single bar.
+#[derive(Debug, Clone, Copy)]
+struct Channel {
+    middle: f64,
+    upper: f64,
+    lower: f64,
+}
+
+/// The struct for the Keltner-channel breakout strategy.
+///
+/// The channel is an EMA of the close, widened by `multiplier` ATRs on each side. A close
+/// breaking out above the upper band enters long and a close breaking out below the lower
+/// band enters short. The position is closed once price reverts back to the midline.
+#[derive(Debug)]
+pub struct Keltner {
+    settings: KeltnerSettings,
+    ema: Ema,
+    atr: AverageTrueRange,
+    // Tracks the current position side to generate correct exit signals.
+    last_signal_side: Option<Side>,
+}
+
+impl Keltner {
+    /// Creates a new `Keltner` strategy instance from its settings.
+    pub fn new(settings: KeltnerSettings) -> Self {
+        Self {
+            ema: Ema::new(settings.ema_period as usize).unwrap(),
+            atr: AverageTrueRange::new(settings.atr_period as usize).unwrap(),
+            settings,
+            last_signal_side: None,
+        }
+    }
+
+    /// Returns the channel for every kline.
+    fn channels(&self, klines: &[Kline]) -> Vec<Channel> {
+        let mut ema = self.ema.clone();
+        let mut atr = self.atr.clone();
+
+        klines
+            .iter()
+            .map(|kline| {
+                let close = kline.close.to_f64().unwrap_or(0.0);
+                let data_item = DataItem::builder()
+                    .high(kline.high.to_f64().unwrap_or(0.0))
+                    .low(kline.low.to_f64().unwrap_or(0.0))
+                    .close(close)
+                    .open(close)
+                    .volume(0.0)
+                    .build()
+                    .unwrap();
+                let middle = ema.next(close);
+                let width = self.settings.multiplier * atr.next(&data_item);
+                Channel { middle, upper: middle + width, lower: middle - width }
+            })
+            .collect()
+    }
+}
+
+impl Strategy for Keltner {
+    fn name(&self) -> &'static str {
+        "KeltnerBreakout"
+    }
+
+    fn reset(&mut self) {
+        self.last_signal_side = None;
+    }
+
+    /// One extra bar is needed to tell a fresh breakout from an ongoing one.
+    fn required_history(&self) -> usize {
+        self.settings.ema_period.max(self.settings.atr_period) as usize + 1
+    }
+
+    fn assess(&mut self, klines: &[Kline]) -> Signal {
+        if klines.len() < self.required_history() {
+            return Signal::Hold;
+        }
+
+        // We recalculate the channels from the provided klines on every call, so the
+        // only state kept between calls is the side of the last entry.
+        let klines = prepare_klines(klines, self.settings.use_heikin_ashi);
+        let klines = klines.as_ref();
+        let channels = self.channels(klines);
+
+        let channel = channels[channels.len() - 1];
+        let prev_channel = channels[channels.len() - 2];
+        let close = klines[klines.len() - 1].close.to_f64().unwrap_or(0.0);
+        let prev_close = klines[klines.len() - 2].close.to_f64().unwrap_or(0.0);
+
+        // --- Mean-Reversion Exits ---
+        let reverted = match self.last_signal_side {
+            Some(Side::Long) => close <= channel.middle,
+            Some(Side::Short) => close >= channel.middle,
+            None => false,
+        };
+        if reverted {
+            self.last_signal_side = None;
+            return Signal::Close;
+        }
+
+        // --- Breakout Entries ---
+        if self.last_signal_side != Some(Side::Long) && prev_close <= prev_channel.upper && close > channel.upper {
+            self.last_signal_side = Some(Side::Long);
+            return Signal::GoLong {
+                confidence: self.settings.confidence,
+                suggested_sl: None,
+                suggested_tp: None,
+            };
+        }
+
+        if self.last_signal_side != Some(Side::Short) && prev_close >= prev_channel.lower && close < channel.lower {
+            self.last_signal_side = Some(Side::Short);
+            return Signal::GoShort {
+                confidence: self.settings.confidence,
+                suggested_sl: None,
+                suggested_tp: None,
+            };
+        }
+
+        Signal::Hold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::klines_from_closes;
+
+    fn settings() -> KeltnerSettings {
+        KeltnerSettings { ema_period: 5, atr_period: 5, multiplier: 1.0, confidence: 0.6, use_heikin_ashi: false }
+    }
+
+    /// A flat market around 100 followed by a jump to 110.
+    fn breakout_closes() -> Vec<f64> {
+        let mut closes: Vec<f64> = (0..10).map(|i| if i % 2 == 0 { 100.0 } else { 100.5 }).collect();
+        closes.push(110.0);
+        closes
+    }
+
+    #[test]
+    fn close_above_the_upper_channel_goes_long() {
+        let klines = klines_from_closes(&breakout_closes());
+
+        let signal = Keltner::new(settings()).assess(&klines);
+
+        assert_eq!(signal, Signal::GoLong { confidence: 0.6, suggested_sl: None, suggested_tp: None });
+    }
+
+    #[test]
+    fn return_to_the_midline_closes_the_breakout() {
+        let mut strategy = Keltner::new(settings());
+        let mut closes = breakout_closes();
+        assert!(matches!(strategy.assess(&klines_from_closes(&closes)), Signal::GoLong { .. }));
+
+        // Holding above the midline keeps the position open...
+        closes.push(109.0);
+        assert_eq!(strategy.assess(&klines_from_closes(&closes)), Signal::Hold);
+
+        // ...and falling back to the pre-breakout level closes it.
+        closes.push(100.0);
+        assert_eq!(strategy.assess(&klines_from_closes(&closes)), Signal::Close);
+    }
+}
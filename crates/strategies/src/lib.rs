@@ -1,11 +1,19 @@
 // In crates/strategies/src/lib.rs (REPLACE ENTIRE FILE)
 
 use core_types::{Kline, Signal};
+use std::borrow::Cow;
 pub mod ma_crossover;
 pub mod error;
 pub mod types;
 pub mod supertrend;
 pub mod prob_reversion;
+pub mod rsi_divergence;
+pub mod stochastic;
+pub mod keltner;
+pub mod ensemble;
+pub mod registry;
+#[cfg(test)]
+pub(crate) mod test_support;
 /// The universal interface for a trading strategy.
 ///
 /// A strategy is responsible for analyzing market data and producing a trading `Signal`.
@@ -15,5 +23,37 @@ pub trait Strategy {
     /// The name of the strategy.
     fn name(&self) -> &'static str;
 
+    /// Preloads the strategy's indicators with historical data before live assessment begins.
+    ///
+    /// Callers should pass the klines that precede the first bar given to `assess`, so the
+    /// first assessment can already produce a signal. The default implementation does
+    /// nothing, which suits strategies that recompute their state from each slice.
+    fn warm_up(&mut self, _klines: &[Kline]) {}
+
+    /// Receives the latest klines of one of the bot's context intervals (see
+    /// `BotConfig::context_intervals`) whenever a kline on that interval closes. The default
+    /// implementation ignores them, which suits single-timeframe strategies.
+    fn update_context(&mut self, _interval: &str, _klines: &[Kline]) {}
+
+    /// Clears all internal state, returning the strategy to its freshly-constructed condition.
+    ///
+    /// This allows a single instance to be reused across independent backtests without
+    /// state from one run leaking into the next. The default implementation does nothing.
+    fn reset(&mut self) {}
+
+    /// The number of klines `assess` needs to see before it can produce a signal, derived
+    /// from the strategy's settings. Callers size their kline history to at least this.
+    fn required_history(&self) -> usize;
+
     fn assess(&mut self, klines: &[Kline]) -> Signal;
+}
+
+/// Returns the klines a strategy should compute its indicators on, applying the
+/// Heikin-Ashi transform when the strategy's settings ask for it.
+pub(crate) fn prepare_klines(klines: &[Kline], use_heikin_ashi: bool) -> Cow<'_, [Kline]> {
+    if use_heikin_ashi {
+        Cow::Owned(core_types::to_heikin_ashi(klines))
+    } else {
+        Cow::Borrowed(klines)
+    }
 }
\ No newline at end of file
@@ -1,12 +1,18 @@
 use crate::types::MACrossoverSettings;
-use crate::{Signal, Strategy};
+use crate::{prepare_klines, Signal, Strategy};
 use core_types::Kline;
 use ta::indicators::ExponentialMovingAverage as Ema;
 use ta::Next; // Import the `Next` trait to use the `.next()` method on indicators.
 use num_traits::ToPrimitive; // <-- Add this import for to_f64
 
+/// The number of base-interval klines aggregated into one "H1" candle for the regime filter.
+///
+/// The strategy only receives a single kline slice, so the H1 view is approximated by
+/// resampling that slice. This assumes the strategy runs on M5 data (12 x 5m = 1h); on
+/// other intervals the "H1" regime is really a 12-bar higher timeframe.
+const H1_RESAMPLE_FACTOR: usize = 12;
+
 // Enum to represent the H1 market regime.
-// While not used in the simplified `assess` method yet, it's part of the complete struct.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum MarketRegime {
     #[default]
@@ -26,7 +32,7 @@ struct TimeframeIndicators {
 }
 
 /// The stateful struct for our Multi-Timeframe MA Crossover strategy.
-/// This initial implementation focuses on a single timeframe (M5) for signal generation.
+/// The M5 crossover generates signals, and the H1 EMA relationship acts as a regime filter.
 #[derive(Debug)]
 pub struct MACrossover {
     /// The configuration for this strategy instance.
@@ -56,6 +62,44 @@ impl MACrossover {
             regime: MarketRegime::default(),
         }
     }
+
+    /// Recomputes the H1 market regime from the given M5 kline slice.
+    ///
+    /// The slice is resampled into `H1_RESAMPLE_FACTOR`-bar candles, aligned to the most
+    /// recent kline (the oldest partial bucket is dropped). If there are not enough
+    /// resampled candles for the slow H1 EMA, the regime is `Sideways`.
+    fn update_regime(&mut self, klines: &[Kline]) {
+        let h1_closes = resample_closes(klines, H1_RESAMPLE_FACTOR);
+        if h1_closes.len() < self.settings.h1_slow_period as usize {
+            self.regime = MarketRegime::Sideways;
+            return;
+        }
+
+        let mut fast_ema = Ema::new(self.settings.h1_fast_period as usize).unwrap();
+        let mut slow_ema = Ema::new(self.settings.h1_slow_period as usize).unwrap();
+        for close in h1_closes {
+            self.h1_indicators.last_fast_ema_val = fast_ema.next(close);
+            self.h1_indicators.last_slow_ema_val = slow_ema.next(close);
+        }
+
+        self.regime = if self.h1_indicators.last_fast_ema_val > self.h1_indicators.last_slow_ema_val {
+            MarketRegime::Bullish
+        } else if self.h1_indicators.last_fast_ema_val < self.h1_indicators.last_slow_ema_val {
+            MarketRegime::Bearish
+        } else {
+            MarketRegime::Sideways
+        };
+    }
+}
+
+/// Aggregates klines into higher-timeframe buckets of `factor` bars and returns each bucket's close.
+/// Buckets are aligned to the end of the slice so the latest bucket always ends on the latest kline.
+fn resample_closes(klines: &[Kline], factor: usize) -> Vec<f64> {
+    let skip = klines.len() % factor;
+    klines[skip..]
+        .chunks(factor)
+        .map(|chunk| chunk.last().unwrap().close.to_f64().unwrap_or(0.0))
+        .collect()
 }
 
 impl Strategy for MACrossover {
@@ -63,32 +107,53 @@ impl Strategy for MACrossover {
         "MultiTimeframeMACrossover"
     }
 
-    /// This simplified `assess` method implements the M5 crossover logic.
-    /// It does not yet incorporate the H1 market regime filter.
+    fn reset(&mut self) {
+        self.h1_indicators = TimeframeIndicators::default();
+        self.m5_indicators = TimeframeIndicators::default();
+        self.regime = MarketRegime::default();
+    }
+
+    /// Seeds the M5 EMAs with the historical closes so the next `assess` can detect a crossover.
+    fn warm_up(&mut self, klines: &[Kline]) {
+        let mut fast_ema = Ema::new(self.settings.m5_fast_period as usize).unwrap();
+        let mut slow_ema = Ema::new(self.settings.m5_slow_period as usize).unwrap();
+
+        let klines = prepare_klines(klines, self.settings.use_heikin_ashi);
+        for kline in klines.iter() {
+            let close_f64 = kline.close.to_f64().unwrap_or(0.0);
+            self.m5_indicators.last_fast_ema_val = fast_ema.next(close_f64);
+            self.m5_indicators.last_slow_ema_val = slow_ema.next(close_f64);
+        }
+
+        self.m5_indicators.fast_ema = Some(fast_ema);
+        self.m5_indicators.slow_ema = Some(slow_ema);
+    }
+
+    /// The H1 regime filter needs `h1_slow_period` resampled candles before it allows a trade.
+    fn required_history(&self) -> usize {
+        (self.settings.m5_slow_period as usize).max(self.settings.h1_slow_period as usize * H1_RESAMPLE_FACTOR)
+    }
+
+    /// Generates M5 crossover signals, filtered by the H1 market regime.
+    ///
+    /// Longs are only emitted in a `Bullish` regime and shorts only in a `Bearish` one.
+    /// The caller must provide at least `required_history` klines for the filter to ever
+    /// allow a trade.
     fn assess(&mut self, klines: &[Kline]) -> Signal {
         // 1. Ensure we have enough data to calculate the slowest indicator.
         if klines.len() < self.settings.m5_slow_period as usize {
             return Signal::Hold; // Not enough data to warm up indicators.
         }
 
-        // 2. Lazily initialize indicators on the first valid run.
+        // 2. Lazily initialize indicators if `warm_up` was never called.
         if self.m5_indicators.fast_ema.is_none() {
-            // Warm up the indicators by feeding them the historical data slice.
-            let mut fast_ema = Ema::new(self.settings.m5_fast_period as usize).unwrap();
-            let mut slow_ema = Ema::new(self.settings.m5_slow_period as usize).unwrap();
-
-            for kline in klines {
-                let close_f64 = kline.close.to_f64().unwrap_or(0.0);
-                self.m5_indicators.last_fast_ema_val = fast_ema.next(close_f64);
-                self.m5_indicators.last_slow_ema_val = slow_ema.next(close_f64);
-            }
-            
-            self.m5_indicators.fast_ema = Some(fast_ema);
-            self.m5_indicators.slow_ema = Some(slow_ema);
+            self.warm_up(klines);
 
             // Cannot generate a signal on the warm-up bar.
             return Signal::Hold;
         }
+        let klines = prepare_klines(klines, self.settings.use_heikin_ashi);
+        let klines = klines.as_ref();
 
         // 3. Update indicators with the latest kline data point.
         let fast_ema = self.m5_indicators.fast_ema.as_mut().unwrap();
@@ -105,6 +170,8 @@ impl Strategy for MACrossover {
             // Bullish Crossover: Fast EMA just crossed ABOVE the Slow EMA.
             Signal::GoLong {
                 confidence: self.settings.confidence,
+                suggested_sl: None,
+                suggested_tp: None,
             }
         } else if current_fast_ema < current_slow_ema
             && self.m5_indicators.last_fast_ema_val >= self.m5_indicators.last_slow_ema_val
@@ -112,6 +179,8 @@ impl Strategy for MACrossover {
             // Bearish Crossover: Fast EMA just crossed BELOW the Slow EMA.
             Signal::GoShort {
                 confidence: self.settings.confidence,
+                suggested_sl: None,
+                suggested_tp: None,
             }
         } else {
             // No crossover event occurred on this kline.
@@ -122,13 +191,98 @@ impl Strategy for MACrossover {
         self.m5_indicators.last_fast_ema_val = current_fast_ema;
         self.m5_indicators.last_slow_ema_val = current_slow_ema;
 
-        // TODO: The H1 regime filter will be applied here in a future phase.
-        // For example:
-        // if (matches!(signal, Signal::GoLong) && self.regime != MarketRegime::Bullish) ||
-        //    (matches!(signal, Signal::GoShort) && self.regime != MarketRegime::Bearish) {
-        //     return Signal::Hold;
-        // }
+        // 6. Apply the H1 regime filter ("The General").
+        self.update_regime(klines);
+        if (matches!(signal, Signal::GoLong { .. }) && self.regime != MarketRegime::Bullish)
+            || (matches!(signal, Signal::GoShort { .. }) && self.regime != MarketRegime::Bearish)
+        {
+            return Signal::Hold;
+        }
 
         signal
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::klines_from_closes;
+
+    fn settings() -> MACrossoverSettings {
+        MACrossoverSettings {
+            h1_fast_period: 2,
+            h1_slow_period: 4,
+            m5_fast_period: 3,
+            m5_slow_period: 6,
+            confidence: 0.7,
+            use_heikin_ashi: false,
+        }
+    }
+
+    /// Warms the strategy up on the first `warm_up_len` klines, then assesses every later
+    /// bar and returns each signal with the regime at that bar and whether the M5 fast EMA
+    /// crossed above the slow one on it.
+    fn run(closes: &[f64], warm_up_len: usize) -> Vec<(Signal, MarketRegime, bool)> {
+        let klines = klines_from_closes(closes);
+        let mut strategy = MACrossover::new(settings());
+        strategy.warm_up(&klines[..warm_up_len]);
+        (warm_up_len..klines.len())
+            .map(|end| {
+                let was_below = strategy.m5_indicators.last_fast_ema_val <= strategy.m5_indicators.last_slow_ema_val;
+                let signal = strategy.assess(&klines[..=end]);
+                let is_above = strategy.m5_indicators.last_fast_ema_val > strategy.m5_indicators.last_slow_ema_val;
+                (signal, strategy.regime, was_below && is_above)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bullish_m5_cross_is_vetoed_in_a_bearish_h1_regime() {
+        // A long decline, then a short bounce that turns the M5 EMAs but not the H1 ones.
+        let mut closes: Vec<f64> = (0..120).map(|i| 300.0 - i as f64).collect();
+        closes.extend((1..=4).map(|i| 181.0 + 2.0 * i as f64));
+
+        let results = run(&closes, 110);
+
+        let crosses: Vec<_> = results.iter().filter(|(_, _, crossed_up)| *crossed_up).collect();
+        assert!(!crosses.is_empty(), "the bounce should cross the M5 EMAs");
+        for (signal, regime, _) in crosses {
+            assert_eq!(*regime, MarketRegime::Bearish);
+            assert!(matches!(signal, Signal::Hold));
+        }
+    }
+
+    #[test]
+    fn bullish_m5_cross_is_emitted_in_a_bullish_h1_regime() {
+        // A long rally with a short pullback, then a resumption that re-crosses the M5 EMAs.
+        let mut closes: Vec<f64> = (0..120).map(|i| 100.0 + i as f64).collect();
+        closes.extend((1..=4).map(|i| 219.0 - 2.0 * i as f64));
+        closes.extend((1..=4).map(|i| 211.0 + 3.0 * i as f64));
+
+        let results = run(&closes, 110);
+
+        let crosses: Vec<_> = results.iter().filter(|(_, _, crossed_up)| *crossed_up).collect();
+        assert!(!crosses.is_empty(), "the resumption should cross the M5 EMAs");
+        for (signal, regime, _) in crosses {
+            assert_eq!(*regime, MarketRegime::Bullish);
+            assert!(matches!(signal, Signal::GoLong { .. }));
+        }
+    }
+
+    #[test]
+    fn warmed_up_strategy_signals_on_the_first_assess() {
+        // A rally, a pullback that turns the M5 EMAs down, then one bar that jumps back above.
+        let mut closes: Vec<f64> = (0..120).map(|i| 100.0 + i as f64).collect();
+        closes.extend((1..=5).map(|i| 219.0 - 2.0 * i as f64));
+        closes.push(230.0);
+        let klines = klines_from_closes(&closes);
+        let history = &klines[..klines.len() - 1];
+
+        let mut warmed = MACrossover::new(settings());
+        warmed.warm_up(history);
+        assert!(matches!(warmed.assess(&klines), Signal::GoLong { .. }));
+
+        // Without a warm-up the same bar only initializes the indicators.
+        let mut cold = MACrossover::new(settings());
+        assert!(matches!(cold.assess(&klines), Signal::Hold));
+    }
+}
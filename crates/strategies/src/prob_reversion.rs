@@ -1,7 +1,7 @@
 // In crates/strategies/src/prob_reversion.rs
 
 use crate::types::ProbReversionSettings;
-use crate::{Signal, Strategy};
+use crate::{prepare_klines, Signal, Strategy};
 use core_types::Kline;
 use rust_decimal::prelude::*;
 use ta::indicators::{BollingerBands, RelativeStrengthIndex as Rsi, SimpleMovingAverage as Sma};
@@ -100,17 +100,39 @@ impl Strategy for ProbReversion {
         "ProbabilisticReversion"
     }
 
-    fn assess(&mut self, klines: &[Kline]) -> Signal {
-        // Determine the longest lookback period required by any indicator
-        let longest_lookback = (self.settings.adx_period * 2)
+    fn reset(&mut self) {
+        self.prev_rsi_sma = 0.0;
+        self.pending_buy_signal_close = None;
+        self.in_position = false;
+    }
+
+    /// Seeds the previous smoothed RSI so the momentum filter is valid on the first assessment.
+    fn warm_up(&mut self, klines: &[Kline]) {
+        let mut rsi = self.rsi.clone();
+        let mut rsi_sma = self.rsi_sma.clone();
+        for kline in prepare_klines(klines, self.settings.use_heikin_ashi).iter() {
+            let rsi_value = rsi.next(kline.close.to_f64().unwrap_or(0.0));
+            self.prev_rsi_sma = rsi_sma.next(rsi_value);
+        }
+    }
+
+    /// The longest lookback period required by any indicator. ADX needs one bar more than
+    /// two periods: one to seed the true range and two periods of smoothing.
+    fn required_history(&self) -> usize {
+        (self.settings.adx_period * 2 + 1)
             .max(self.settings.bband_period)
-            .max(self.settings.rsi_period + self.settings.rsi_smoothing);
+            .max(self.settings.rsi_period + self.settings.rsi_smoothing) as usize
+    }
 
-        if klines.len() < longest_lookback as usize {
+    fn assess(&mut self, klines: &[Kline]) -> Signal {
+        if klines.len() < self.required_history() {
             return Signal::Hold;
         }
 
         // --- Data Preparation & Indicator Calculation ---
+        let klines = prepare_klines(klines, self.settings.use_heikin_ashi);
+        let klines = klines.as_ref();
+
         let closes: Vec<f64> = klines.iter().map(|k| k.close.to_f64().unwrap_or(0.0)).collect();
         let highs: Vec<f64> = klines.iter().map(|k| k.high.to_f64().unwrap_or(0.0)).collect();
         let lows: Vec<f64> = klines.iter().map(|k| k.low.to_f64().unwrap_or(0.0)).collect();
@@ -140,7 +162,6 @@ impl Strategy for ProbReversion {
         }
         let current_rsi_sma = last_rsi_sma;
         
-        let current_kline = klines.last().unwrap();
         let current_close = closes.last().unwrap();
         let current_low = lows.last().unwrap();
         let current_rsi = *rsi_values.last().unwrap();
@@ -159,7 +180,11 @@ impl Strategy for ProbReversion {
             self.pending_buy_signal_close = None; // Consume the pending signal
             if *current_close > setup_close {
                 self.in_position = true; // Mark that we've entered a position
-                return Signal::GoLong { confidence: self.settings.confidence };
+                return Signal::GoLong {
+                    confidence: self.settings.confidence,
+                    suggested_sl: None,
+                    suggested_tp: None,
+                };
             }
         }
 
@@ -189,4 +214,53 @@ impl Strategy for ProbReversion {
 
         Signal::Hold
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::klines_from_closes;
+
+    fn settings() -> ProbReversionSettings {
+        ProbReversionSettings {
+            bband_period: 10,
+            bband_stddev: 1.0,
+            adx_period: 5,
+            // Effectively disables the regime filter so the test only depends on price.
+            adx_range_threshold: 100.0,
+            rsi_period: 5,
+            rsi_oversold: 45.0,
+            rsi_smoothing: 3,
+            confidence: 0.6,
+            use_heikin_ashi: false,
+        }
+    }
+
+    /// A flat market, a sharp sell-off that bottoms out, and a recovery back to the mean.
+    fn dip_and_recovery() -> Vec<Kline> {
+        let mut closes = vec![100.0; 20];
+        closes.extend([98.0, 95.0, 91.0, 88.0, 87.5, 88.5, 90.0, 93.0, 97.0, 100.0, 101.0, 101.5]);
+        klines_from_closes(&closes)
+    }
+
+    fn assess_each_bar(strategy: &mut ProbReversion, klines: &[Kline]) -> Vec<Signal> {
+        (1..=klines.len()).map(|end| strategy.assess(&klines[..end])).collect()
+    }
+
+    #[test]
+    fn reset_reproduces_the_signals_of_a_fresh_instance() {
+        let klines = dip_and_recovery();
+        let mut strategy = ProbReversion::new(settings());
+
+        let first_run = assess_each_bar(&mut strategy, &klines);
+        assert!(
+            first_run.iter().any(|signal| matches!(signal, Signal::GoLong { .. })),
+            "the dip should trigger an entry: {:?}",
+            first_run
+        );
+
+        strategy.reset();
+        let second_run = assess_each_bar(&mut strategy, &klines);
+
+        assert_eq!(first_run, second_run);
+    }
+}
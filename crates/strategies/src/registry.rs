@@ -0,0 +1,314 @@
+// In crates/strategies/src/registry.rs
+
+use crate::ensemble::EnsembleStrategy;
+use crate::error::{Error, Result};
+use crate::keltner::Keltner;
+use crate::ma_crossover::MACrossover;
+use crate::prob_reversion::ProbReversion;
+use crate::rsi_divergence::RsiDivergence;
+use crate::stochastic::Stochastic;
+use crate::supertrend::SuperTrend;
+use crate::types::{
+    EnsembleSettings, KeltnerSettings, MACrossoverSettings, ProbReversionSettings, RsiDivergenceSettings,
+    StochasticSettings, SuperTrendSettings,
+};
+use crate::Strategy;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Every strategy that can be built by name.
+///
+/// This is the single place a new strategy has to be registered; the config loaders, the
+/// engine and the optimizer all go through `StrategyParams`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StrategyKind {
+    MaCrossover,
+    SuperTrend,
+    ProbReversion,
+    RsiDivergence,
+    Stochastic,
+    Keltner,
+    Ensemble,
+}
+
+impl StrategyKind {
+    /// All registered strategies, in the order they are picked when several are configured.
+    pub const ALL: [StrategyKind; 7] = [
+        StrategyKind::MaCrossover,
+        StrategyKind::SuperTrend,
+        StrategyKind::ProbReversion,
+        StrategyKind::RsiDivergence,
+        StrategyKind::Stochastic,
+        StrategyKind::Keltner,
+        StrategyKind::Ensemble,
+    ];
+
+    /// The key the strategy goes by in the config files and the database.
+    pub fn key(self) -> &'static str {
+        match self {
+            StrategyKind::MaCrossover => "ma_crossover",
+            StrategyKind::SuperTrend => "supertrend",
+            StrategyKind::ProbReversion => "prob_reversion",
+            StrategyKind::RsiDivergence => "rsi_divergence",
+            StrategyKind::Stochastic => "stochastic",
+            StrategyKind::Keltner => "keltner",
+            StrategyKind::Ensemble => "ensemble",
+        }
+    }
+}
+
+impl FromStr for StrategyKind {
+    type Err = Error;
+
+    fn from_str(key: &str) -> Result<Self> {
+        StrategyKind::ALL
+            .into_iter()
+            .find(|kind| kind.key() == key)
+            .ok_or_else(|| Error::UnknownStrategy(key.to_string()))
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+/// The settings of one strategy, tagged with the strategy they belong to.
+///
+/// Serializes as the bare settings struct, which is how parameters are stored.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum StrategyParams {
+    MaCrossover(MACrossoverSettings),
+    SuperTrend(SuperTrendSettings),
+    ProbReversion(ProbReversionSettings),
+    RsiDivergence(RsiDivergenceSettings),
+    Stochastic(StochasticSettings),
+    Keltner(KeltnerSettings),
+    Ensemble(EnsembleSettings),
+}
+
+impl StrategyParams {
+    /// Deserializes `params` (e.g. a TOML table or a JSON object) as the settings of `kind`.
+    pub fn from_params<'de, D: Deserializer<'de>>(kind: StrategyKind, params: D) -> std::result::Result<Self, D::Error> {
+        Ok(match kind {
+            StrategyKind::MaCrossover => StrategyParams::MaCrossover(Deserialize::deserialize(params)?),
+            StrategyKind::SuperTrend => StrategyParams::SuperTrend(Deserialize::deserialize(params)?),
+            StrategyKind::ProbReversion => StrategyParams::ProbReversion(Deserialize::deserialize(params)?),
+            StrategyKind::RsiDivergence => StrategyParams::RsiDivergence(Deserialize::deserialize(params)?),
+            StrategyKind::Stochastic => StrategyParams::Stochastic(Deserialize::deserialize(params)?),
+            StrategyKind::Keltner => StrategyParams::Keltner(Deserialize::deserialize(params)?),
+            StrategyKind::Ensemble => StrategyParams::Ensemble(Deserialize::deserialize(params)?),
+        })
+    }
+
+    /// The strategy these settings belong to.
+    pub fn kind(&self) -> StrategyKind {
+        match self {
+            StrategyParams::MaCrossover(_) => StrategyKind::MaCrossover,
+            StrategyParams::SuperTrend(_) => StrategyKind::SuperTrend,
+            StrategyParams::ProbReversion(_) => StrategyKind::ProbReversion,
+            StrategyParams::RsiDivergence(_) => StrategyKind::RsiDivergence,
+            StrategyParams::Stochastic(_) => StrategyKind::Stochastic,
+            StrategyParams::Keltner(_) => StrategyKind::Keltner,
+            StrategyParams::Ensemble(_) => StrategyKind::Ensemble,
+        }
+    }
+
+    /// Creates a fresh strategy instance from these settings.
+    pub fn build(&self) -> Box<dyn Strategy + Send> {
+        match self {
+            StrategyParams::MaCrossover(s) => Box::new(MACrossover::new(s.clone())),
+            StrategyParams::SuperTrend(s) => Box::new(SuperTrend::new(s.clone())),
+            StrategyParams::ProbReversion(s) => Box::new(ProbReversion::new(s.clone())),
+            StrategyParams::RsiDivergence(s) => Box::new(RsiDivergence::new(s.clone())),
+            StrategyParams::Stochastic(s) => Box::new(Stochastic::new(s.clone())),
+            StrategyParams::Keltner(s) => Box::new(Keltner::new(s.clone())),
+            StrategyParams::Ensemble(s) => Box::new(EnsembleStrategy::new(
+                s.members.iter().map(|member| member.0.build()).collect(),
+                s.rule,
+            )),
+        }
+    }
+}
+
+/// One sub-strategy of an ensemble, written as `{ strategy = "<key>", params = { ... } }`.
+///
+/// Unlike a bare `StrategyParams`, a member carries its strategy's key, so it can be
+/// deserialized without knowing the kind up front.
+#[derive(Debug, Clone)]
+pub struct EnsembleMember(pub StrategyParams);
+
+impl Serialize for EnsembleMember {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut member = serializer.serialize_struct("EnsembleMember", 2)?;
+        member.serialize_field("strategy", self.0.kind().key())?;
+        member.serialize_field("params", &self.0)?;
+        member.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for EnsembleMember {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct RawMember {
+            strategy: String,
+            params: serde_json::Value,
+        }
+
+        let raw = RawMember::deserialize(deserializer)?;
+        let kind: StrategyKind = raw.strategy.parse().map_err(serde::de::Error::custom)?;
+        StrategyParams::from_params(kind, raw.params)
+            .map(EnsembleMember)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Builds the strategy of `kind` from its raw, not yet deserialized settings.
+pub fn build<'de, D: Deserializer<'de>>(kind: StrategyKind, params: D) -> std::result::Result<Box<dyn Strategy + Send>, D::Error> {
+    Ok(StrategyParams::from_params(kind, params)?.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_kind_round_trips_through_its_key() {
+        for kind in StrategyKind::ALL {
+            assert_eq!(kind.key().parse::<StrategyKind>().unwrap(), kind);
+        }
+        assert!("unknown".parse::<StrategyKind>().is_err());
+    }
+
+    /// Sample stored settings for every registered strategy.
+    fn sample_params(kind: StrategyKind) -> serde_json::Value {
+        match kind {
+            StrategyKind::MaCrossover => serde_json::json!({
+                "h1_fast_period": 10, "h1_slow_period": 30, "m5_fast_period": 5, "m5_slow_period": 20,
+                "confidence": 0.8, "use_heikin_ashi": false,
+            }),
+            StrategyKind::SuperTrend => serde_json::json!({
+                "period": 10, "multiplier": 3.0, "exit_multiplier": 1.5, "volume_threshold": 1.2,
+                "confirmation_bars": 2, "ema_confirmation_period": 50, "confidence": 0.7, "use_heikin_ashi": false,
+            }),
+            StrategyKind::ProbReversion => serde_json::json!({
+                "bband_period": 20, "bband_stddev": 2.0, "adx_period": 14, "adx_range_threshold": 25.0,
+                "rsi_period": 14, "rsi_oversold": 30.0, "rsi_smoothing": 3, "confidence": 0.6, "use_heikin_ashi": false,
+            }),
+            StrategyKind::RsiDivergence => serde_json::json!({
+                "rsi_period": 14, "lookback_swings": 3, "confidence": 0.65, "use_heikin_ashi": false,
+            }),
+            StrategyKind::Stochastic => serde_json::json!({
+                "k_period": 14, "d_period": 3, "smoothing": 3, "oversold": 20.0, "overbought": 80.0,
+                "confidence": 0.7, "exit_at_midline": true, "use_heikin_ashi": false,
+            }),
+            StrategyKind::Keltner => serde_json::json!({
+                "ema_period": 20, "atr_period": 10, "multiplier": 2.0, "confidence": 0.6, "use_heikin_ashi": false,
+            }),
+            StrategyKind::Ensemble => serde_json::json!({
+                "rule": "majority_vote",
+                "members": [{ "strategy": "keltner", "params": sample_params(StrategyKind::Keltner) }],
+            }),
+        }
+    }
+
+    #[test]
+    fn every_registered_strategy_is_built_from_its_settings() {
+        let names: Vec<&str> = StrategyKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let params = sample_params(kind);
+                let parsed = StrategyParams::from_params(kind.key().parse().unwrap(), params.clone()).unwrap();
+
+                assert_eq!(parsed.kind(), kind);
+                assert_eq!(serde_json::to_value(&parsed).unwrap(), params, "{kind} settings changed on the way");
+                let strategy = build(kind, params).unwrap();
+                assert!(strategy.required_history() > 0, "{kind} needs no history");
+                strategy.name()
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            [
+                "MultiTimeframeMACrossover",
+                "EnhancedSuperTrend",
+                "ProbabilisticReversion",
+                "RsiDivergence",
+                "Stochastic",
+                "KeltnerBreakout",
+                "Ensemble",
+            ]
+        );
+    }
+
+    #[test]
+    fn ensemble_is_built_from_its_members() {
+        let params = serde_json::json!({
+            "rule": "majority_vote",
+            "members": [
+                { "strategy": "rsi_divergence", "params": { "rsi_period": 14, "lookback_swings": 3, "confidence": 1.0 } },
+                { "strategy": "keltner", "params": { "ema_period": 20, "atr_period": 10, "multiplier": 2.0, "confidence": 1.0 } },
+            ],
+        });
+
+        let params = StrategyParams::from_params(StrategyKind::Ensemble, params).unwrap();
+        let strategy = params.build();
+
+        assert_eq!(params.kind(), StrategyKind::Ensemble);
+        assert_eq!(strategy.name(), "Ensemble");
+        // The RSI divergence member needs the longest history: 14 + (3 * 2 + 1) * 2.
+        assert_eq!(strategy.required_history(), 28);
+    }
+
+    #[test]
+    fn ensemble_params_serialize_back_to_their_stored_form() {
+        let params = serde_json::json!({
+            "rule": "unanimous",
+            "members": [
+                { "strategy": "keltner", "params": { "ema_period": 20, "atr_period": 10, "multiplier": 2.0, "confidence": 1.0, "use_heikin_ashi": false } },
+            ],
+        });
+
+        let parsed = StrategyParams::from_params(StrategyKind::Ensemble, params.clone()).unwrap();
+
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), params);
+    }
+
+    #[test]
+    fn ensemble_member_with_an_unknown_strategy_is_rejected() {
+        let params = serde_json::json!({
+            "rule": "unanimous",
+            "members": [{ "strategy": "nope", "params": {} }],
+        });
+
+        assert!(StrategyParams::from_params(StrategyKind::Ensemble, params).is_err());
+    }
+
+    #[test]
+    fn every_strategy_needs_at_least_its_longest_indicator_period() {
+        // The longest lookback of each sample, in base klines. The H1 filter of the MA
+        // crossover runs on 12-kline candles.
+        let longest_period = |kind| match kind {
+            StrategyKind::MaCrossover => 30 * 12,
+            StrategyKind::SuperTrend => 50,
+            StrategyKind::ProbReversion => 20,
+            StrategyKind::RsiDivergence => 14,
+            StrategyKind::Stochastic => 14,
+            StrategyKind::Keltner | StrategyKind::Ensemble => 20,
+        };
+
+        for kind in StrategyKind::ALL {
+            let strategy = build(kind, sample_params(kind)).unwrap();
+            assert!(
+                strategy.required_history() >= longest_period(kind),
+                "{kind} asks for {} klines",
+                strategy.required_history()
+            );
+        }
+    }
+}
@@ -0,0 +1,203 @@
+// In crates/strategies/src/rsi_divergence.rs
+
+use crate::types::RsiDivergenceSettings;
+use crate::{prepare_klines, Signal, Strategy};
+use core_types::Kline;
+use rust_decimal::prelude::*;
+use ta::indicators::RelativeStrengthIndex as Rsi;
+use ta::Next;
+
+/// The kind of swing point to look for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SwingKind {
+    Low,
+    High,
+}
+
+/// Returns the indices of all confirmed swing points in `values`.
+///
+/// A swing low (high) is a bar whose value is strictly lower (higher) than the `width`
+/// bars on either side of it, so the last `width` bars can never be confirmed swings.
+fn find_swings(values: &[f64], width: usize, kind: SwingKind) -> Vec<usize> {
+    if width == 0 || values.len() < width * 2 + 1 {
+        return Vec::new();
+    }
+
+    (width..values.len() - width)
+        .filter(|&i| {
+            let mut neighbours = values[i - width..i].iter().chain(&values[i + 1..=i + width]);
+            match kind {
+                SwingKind::Low => neighbours.all(|v| values[i] < *v),
+                SwingKind::High => neighbours.all(|v| values[i] > *v),
+            }
+        })
+        .collect()
+}
+
+/// The stateless struct for the RSI Divergence strategy.
+///
+/// The strategy looks for regular divergence between price and RSI at the last two swing
+/// points: a lower price low with a higher RSI low is bullish, and a higher price high
+/// with a lower RSI high is bearish. A signal is only emitted on the bar that confirms the
+/// newest swing, so each divergence is traded once.
+#[derive(Debug)]
+pub struct RsiDivergence {
+    settings: RsiDivergenceSettings,
+    rsi: Rsi,
+}
+
+impl RsiDivergence {
+    /// Creates a new `RsiDivergence` strategy instance.
+    pub fn new(settings: RsiDivergenceSettings) -> Self {
+        Self {
+            rsi: Rsi::new(settings.rsi_period as usize).unwrap(),
+            settings,
+        }
+    }
+
+    /// Checks the last two swings of `kind` for a regular divergence.
+    ///
+    /// Returns the price of the newest swing if a divergence was confirmed on the current bar.
+    fn divergence(&self, prices: &[f64], rsi_values: &[f64], kind: SwingKind) -> Option<f64> {
+        let width = self.settings.lookback_swings as usize;
+        let warm_up = self.settings.rsi_period as usize;
+
+        let swings: Vec<usize> = find_swings(prices, width, kind)
+            .into_iter()
+            .filter(|&i| i >= warm_up) // Ignore swings where RSI is not yet meaningful
+            .collect();
+        let (previous, latest) = match swings.as_slice() {
+            [.., previous, latest] => (*previous, *latest),
+            _ => return None,
+        };
+
+        // Only act on the bar that confirms the newest swing.
+        if latest + width != prices.len() - 1 {
+            return None;
+        }
+
+        let is_divergence = match kind {
+            SwingKind::Low => prices[latest] < prices[previous] && rsi_values[latest] > rsi_values[previous],
+            SwingKind::High => prices[latest] > prices[previous] && rsi_values[latest] < rsi_values[previous],
+        };
+
+        is_divergence.then(|| prices[latest])
+    }
+}
+
+impl Strategy for RsiDivergence {
+    fn name(&self) -> &'static str {
+        "RsiDivergence"
+    }
+
+    /// RSI warm-up plus at least two full swing windows.
+    fn required_history(&self) -> usize {
+        let width = self.settings.lookback_swings as usize;
+        self.settings.rsi_period as usize + (width * 2 + 1) * 2
+    }
+
+    fn assess(&mut self, klines: &[Kline]) -> Signal {
+        if klines.len() < self.required_history() {
+            return Signal::Hold;
+        }
+
+        // --- Data Preparation & Indicator Calculation ---
+        let klines = prepare_klines(klines, self.settings.use_heikin_ashi);
+        let klines = klines.as_ref();
+
+        let closes: Vec<f64> = klines.iter().map(|k| k.close.to_f64().unwrap_or(0.0)).collect();
+        let highs: Vec<f64> = klines.iter().map(|k| k.high.to_f64().unwrap_or(0.0)).collect();
+        let lows: Vec<f64> = klines.iter().map(|k| k.low.to_f64().unwrap_or(0.0)).collect();
+
+        let mut rsi = self.rsi.clone();
+        let rsi_values: Vec<f64> = closes.iter().map(|c| rsi.next(*c)).collect();
+
+        // --- Divergence Checks ---
+
+        // Bullish: price makes a lower low while RSI makes a higher low.
+        if let Some(swing_low) = self.divergence(&lows, &rsi_values, SwingKind::Low) {
+            return Signal::GoLong {
+                confidence: self.settings.confidence,
+                // The divergent swing low invalidates the setup if broken.
+                suggested_sl: Decimal::from_f64(swing_low),
+                suggested_tp: None,
+            };
+        }
+
+        // Bearish: price makes a higher high while RSI makes a lower high.
+        if let Some(swing_high) = self.divergence(&highs, &rsi_values, SwingKind::High) {
+            return Signal::GoShort {
+                confidence: self.settings.confidence,
+                // The divergent swing high invalidates the setup if broken.
+                suggested_sl: Decimal::from_f64(swing_high),
+                suggested_tp: None,
+            };
+        }
+
+        Signal::Hold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::kline;
+
+    fn settings() -> RsiDivergenceSettings {
+        RsiDivergenceSettings {
+            rsi_period: 5,
+            lookback_swings: 2,
+            confidence: 0.65,
+            use_heikin_ashi: false,
+        }
+    }
+
+    /// Klines closing at `closes`, each with a fixed 0.5 range above and below the close so
+    /// the swing points of the lows and highs are exactly the swing points of the closes.
+    fn klines(closes: &[f64]) -> Vec<Kline> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| kline(i as i64 * 60_000, close, close + 0.5, close - 0.5, close))
+            .collect()
+    }
+
+    #[test]
+    fn lower_price_low_with_higher_rsi_low_goes_long() {
+        let mut closes: Vec<f64> = (0..12).map(|i| if i % 2 == 0 { 100.0 } else { 101.0 }).collect();
+        // A sharp sell-off into the first low, then a gentle drift to a slightly lower second
+        // low: price makes a lower low while RSI makes a higher one.
+        closes.extend([95.0, 88.0, 80.0, 84.0, 87.0, 85.5, 83.0, 81.0, 79.5, 80.5, 81.5]);
+        let klines = klines(&closes);
+
+        let signal = RsiDivergence::new(settings()).assess(&klines);
+
+        assert_eq!(
+            signal,
+            Signal::GoLong { confidence: 0.65, suggested_sl: Decimal::from_f64(79.0), suggested_tp: None }
+        );
+    }
+
+    #[test]
+    fn higher_price_high_with_lower_rsi_high_goes_short() {
+        let mut closes: Vec<f64> = (0..12).map(|i| if i % 2 == 0 { 100.0 } else { 99.0 }).collect();
+        closes.extend([105.0, 112.0, 120.0, 116.0, 113.0, 114.5, 117.0, 119.0, 120.5, 119.5, 118.5]);
+        let klines = klines(&closes);
+
+        let signal = RsiDivergence::new(settings()).assess(&klines);
+
+        assert_eq!(
+            signal,
+            Signal::GoShort { confidence: 0.65, suggested_sl: Decimal::from_f64(121.0), suggested_tp: None }
+        );
+    }
+
+    #[test]
+    fn divergence_is_only_signalled_on_the_confirming_bar() {
+        let mut closes: Vec<f64> = (0..12).map(|i| if i % 2 == 0 { 100.0 } else { 101.0 }).collect();
+        closes.extend([95.0, 88.0, 80.0, 84.0, 87.0, 85.5, 83.0, 81.0, 79.5, 80.5, 81.5, 82.5]);
+        let klines = klines(&closes);
+
+        assert_eq!(RsiDivergence::new(settings()).assess(&klines), Signal::Hold);
+    }
+}
@@ -0,0 +1,169 @@
+// In crates/strategies/src/stochastic.rs
+
+use crate::types::StochasticSettings;
+use crate::{prepare_klines, Signal, Strategy};
+use core_types::{Kline, Side};
+use rust_decimal::prelude::*;
+use ta::indicators::{FastStochastic, SimpleMovingAverage as Sma};
+use ta::{DataItem, Next};
+
+/// The stochastic level that separates the bullish half of the range from the bearish one.
+const MIDLINE: f64 = 50.0;
+
+/// The struct for the Stochastic oscillator mean-reversion strategy.
+///
+/// %K is the fast stochastic of the last `k_period` bars, smoothed by an SMA of `smoothing`
+/// bars, and %D is the SMA of %K over `d_period` bars. The strategy goes long when %K
+/// crosses above %D while %D is still oversold, and short on the symmetric cross in
+/// overbought territory. With `exit_at_midline` set, the position it entered is closed once
+/// %K crosses back through 50.
+#[derive(Debug)]
+pub struct Stochastic {
+    settings: StochasticSettings,
+    stochastic: FastStochastic,
+    k_sma: Sma,
+    d_sma: Sma,
+    // The side of the last entry signal, used for the midline exit
+    position: Option<Side>,
+}
+
+impl Stochastic {
+    /// Creates a new `Stochastic` strategy instance.
+    pub fn new(settings: StochasticSettings) -> Self {
+        Self {
+            stochastic: FastStochastic::new(settings.k_period as usize).unwrap(),
+            k_sma: Sma::new(settings.smoothing as usize).unwrap(),
+            d_sma: Sma::new(settings.d_period as usize).unwrap(),
+            settings,
+            position: None,
+        }
+    }
+
+    /// Returns the smoothed %K and %D lines for every kline.
+    fn lines(&self, klines: &[Kline]) -> (Vec<f64>, Vec<f64>) {
+        let mut stochastic = self.stochastic.clone();
+        let mut k_sma = self.k_sma.clone();
+        let mut d_sma = self.d_sma.clone();
+
+        klines
+            .iter()
+            .map(|kline| {
+                let close = kline.close.to_f64().unwrap_or(0.0);
+                let data_item = DataItem::builder()
+                    .high(kline.high.to_f64().unwrap_or(0.0))
+                    .low(kline.low.to_f64().unwrap_or(0.0))
+                    .close(close)
+                    .open(close)
+                    .volume(0.0)
+                    .build()
+                    .unwrap();
+                let k = k_sma.next(stochastic.next(&data_item));
+                (k, d_sma.next(k))
+            })
+            .unzip()
+    }
+}
+
+impl Strategy for Stochastic {
+    fn name(&self) -> &'static str {
+        "Stochastic"
+    }
+
+    fn reset(&mut self) {
+        self.position = None;
+    }
+
+    /// Both lines need a full window each, plus one bar to detect a cross.
+    fn required_history(&self) -> usize {
+        (self.settings.k_period + self.settings.smoothing + self.settings.d_period) as usize
+    }
+
+    fn assess(&mut self, klines: &[Kline]) -> Signal {
+        if klines.len() < self.required_history() {
+            return Signal::Hold;
+        }
+
+        // --- Data Preparation & Indicator Calculation ---
+        let klines = prepare_klines(klines, self.settings.use_heikin_ashi);
+        let (k_values, d_values) = self.lines(klines.as_ref());
+
+        let (prev_k, k) = (k_values[k_values.len() - 2], k_values[k_values.len() - 1]);
+        let (prev_d, d) = (d_values[d_values.len() - 2], d_values[d_values.len() - 1]);
+
+        // --- Exit Logic ---
+        if self.settings.exit_at_midline {
+            let reached_midline = match self.position {
+                Some(Side::Long) => prev_k < MIDLINE && k >= MIDLINE,
+                Some(Side::Short) => prev_k > MIDLINE && k <= MIDLINE,
+                None => false,
+            };
+            if reached_midline {
+                self.position = None;
+                return Signal::Close;
+            }
+        }
+
+        // --- Entry Logic ---
+        if prev_k <= prev_d && k > d && d < self.settings.oversold {
+            self.position = Some(Side::Long);
+            return Signal::GoLong {
+                confidence: self.settings.confidence,
+                suggested_sl: None,
+                suggested_tp: None,
+            };
+        }
+
+        if prev_k >= prev_d && k < d && d > self.settings.overbought {
+            self.position = Some(Side::Short);
+            return Signal::GoShort {
+                confidence: self.settings.confidence,
+                suggested_sl: None,
+                suggested_tp: None,
+            };
+        }
+
+        Signal::Hold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::klines_from_closes;
+
+    fn settings() -> StochasticSettings {
+        StochasticSettings {
+            k_period: 5,
+            d_period: 3,
+            smoothing: 1,
+            oversold: 20.0,
+            overbought: 80.0,
+            confidence: 0.7,
+            exit_at_midline: false,
+            use_heikin_ashi: false,
+        }
+    }
+
+    #[test]
+    fn oversold_bullish_cross_goes_long() {
+        // A steady decline pins %K and %D near 0, then one up bar lifts %K above %D
+        // while %D is still oversold.
+        let mut closes: Vec<f64> = (0..12).map(|i| 100.0 - i as f64 * 2.0).collect();
+        closes.push(77.5);
+        let klines = klines_from_closes(&closes);
+
+        let signal = Stochastic::new(settings()).assess(&klines);
+
+        assert_eq!(signal, Signal::GoLong { confidence: 0.7, suggested_sl: None, suggested_tp: None });
+    }
+
+    #[test]
+    fn the_same_cross_above_the_oversold_level_holds() {
+        let mut closes: Vec<f64> = (0..12).map(|i| 100.0 - i as f64 * 2.0).collect();
+        closes.push(77.5);
+        let klines = klines_from_closes(&closes);
+        let settings = StochasticSettings { oversold: 0.0, ..settings() };
+
+        assert_eq!(Stochastic::new(settings).assess(&klines), Signal::Hold);
+    }
+}
@@ -1,7 +1,7 @@
 // In crates/strategies/src/supertrend.rs
 
 use crate::types::SuperTrendSettings; // We will define this next
-use crate::{Signal, Strategy};
+use crate::{prepare_klines, Signal, Strategy};
 use core_types::{Kline, Side};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
@@ -34,7 +34,6 @@ struct StState {
 pub struct SuperTrend {
     settings: SuperTrendSettings,
     atr_indicator: AverageTrueRange,
-    ema_confirm: Ema,
     // We only need to store the history of states for calculation.
     states: Vec<StState>,
     // Tracks the current position side to generate correct exit signals.
@@ -56,7 +55,6 @@ impl SuperTrend {
 
         Self {
             atr_indicator: AverageTrueRange::new(settings.period as usize).unwrap(),
-            ema_confirm: Ema::new(settings.ema_confirmation_period as usize).unwrap(),
             settings,
             states: Vec::new(),
             last_signal_side: None,
@@ -69,15 +67,26 @@ impl Strategy for SuperTrend {
         "EnhancedSuperTrend"
     }
 
-    fn assess(&mut self, klines: &[Kline]) -> Signal {
-        let required_bars = (self.settings.period as usize)
-            .max(self.settings.ema_confirmation_period as usize);
+    fn reset(&mut self) {
+        self.states.clear();
+        self.last_signal_side = None;
+    }
 
-        if klines.len() < required_bars {
+    /// The longer of the ATR and EMA periods, plus the bars needed to confirm a trend.
+    fn required_history(&self) -> usize {
+        (self.settings.period as usize).max(self.settings.ema_confirmation_period as usize)
+            + self.settings.confirmation_bars as usize
+    }
+
+    fn assess(&mut self, klines: &[Kline]) -> Signal {
+        if klines.len() < self.required_history() {
             return Signal::Hold;
         }
 
         // --- State Calculation Loop ---
+        let klines = prepare_klines(klines, self.settings.use_heikin_ashi);
+        let klines = klines.as_ref();
+
         // We recalculate the state history based on the provided klines.
         // This makes the strategy stateless between `assess` calls, which is robust.
         self.states.clear();
@@ -159,7 +168,12 @@ impl Strategy for SuperTrend {
             let ema_val: f64 = klines.iter().map(|k| k.close.to_f64().unwrap()).collect::<Vec<f64>>().as_slice().ema(self.settings.ema_confirmation_period as usize).unwrap_or(0.0);
             if current_kline.close.to_f64().unwrap() > ema_val {
                 self.last_signal_side = Some(Side::Long);
-                return Signal::GoLong { confidence: self.settings.confidence };
+                // The lower SuperTrend band is the natural invalidation level for a long.
+                return Signal::GoLong {
+                    confidence: self.settings.confidence,
+                    suggested_sl: Decimal::from_f64(current_state.final_lower_band),
+                    suggested_tp: None,
+                };
             }
         }
 
@@ -167,7 +181,12 @@ impl Strategy for SuperTrend {
              let ema_val: f64 = klines.iter().map(|k| k.close.to_f64().unwrap()).collect::<Vec<f64>>().as_slice().ema(self.settings.ema_confirmation_period as usize).unwrap_or(0.0);
             if current_kline.close.to_f64().unwrap() < ema_val {
                 self.last_signal_side = Some(Side::Short);
-                return Signal::GoShort { confidence: self.settings.confidence };
+                // The upper SuperTrend band is the natural invalidation level for a short.
+                return Signal::GoShort {
+                    confidence: self.settings.confidence,
+                    suggested_sl: Decimal::from_f64(current_state.final_upper_band),
+                    suggested_tp: None,
+                };
             }
         }
 
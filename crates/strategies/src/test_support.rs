@@ -0,0 +1,39 @@
+// In crates/strategies/src/test_support.rs
+
+//! Kline builders shared by the strategy tests.
+
+use core_types::Kline;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+
+/// The spacing between the builders' klines, in milliseconds (one minute).
+const BAR_MS: i64 = 60_000;
+
+/// Builds one kline per close. Each bar opens at the previous close and its high and low
+/// reach 0.5% beyond the body, so range-based indicators see some volatility.
+pub(crate) fn klines_from_closes(closes: &[f64]) -> Vec<Kline> {
+    let mut previous_close = closes.first().copied().unwrap_or_default();
+    closes
+        .iter()
+        .enumerate()
+        .map(|(i, &close)| {
+            let open = previous_close;
+            previous_close = close;
+            kline(i as i64 * BAR_MS, open, open.max(close) * 1.005, open.min(close) * 0.995, close)
+        })
+        .collect()
+}
+
+/// Builds a single kline with the given prices.
+pub(crate) fn kline(open_time: i64, open: f64, high: f64, low: f64, close: f64) -> Kline {
+    let price = |value: f64| Decimal::from_f64(value).unwrap();
+    Kline {
+        open_time,
+        open: price(open),
+        high: price(high),
+        low: price(low),
+        close: price(close),
+        volume: Decimal::ONE_HUNDRED,
+        close_time: open_time + BAR_MS - 1,
+    }
+}
@@ -1,5 +1,6 @@
 // In crates/strategies/src/types.rs
 
+use crate::registry::EnsembleMember;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,6 +15,9 @@ pub struct MACrossoverSettings {
 
     // The confidence score to assign to signals from this strategy
     pub confidence: f64,
+    // Compute indicators on Heikin-Ashi candles instead of the raw klines
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)] // Clone is needed for the optimizer
@@ -25,6 +29,9 @@ pub struct SuperTrendSettings {
     pub confirmation_bars: u32,
     pub ema_confirmation_period: u32,
     pub confidence: f64,
+    // Compute indicators on Heikin-Ashi candles instead of the raw klines
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,4 +44,70 @@ pub struct ProbReversionSettings {
     pub rsi_oversold: f64,
     pub rsi_smoothing: u32,
     pub confidence: f64,
-}
\ No newline at end of file
+    // Compute indicators on Heikin-Ashi candles instead of the raw klines
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RsiDivergenceSettings {
+    pub rsi_period: u32,
+    // The number of bars on each side of a pivot required to confirm it as a swing.
+    pub lookback_swings: u32,
+    pub confidence: f64,
+    // Compute indicators on Heikin-Ashi candles instead of the raw klines
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StochasticSettings {
+    // The number of bars the fast stochastic looks back over.
+    pub k_period: u32,
+    // The SMA period of %D, the signal line.
+    pub d_period: u32,
+    // The SMA period applied to the fast stochastic to get %K (1 disables smoothing).
+    pub smoothing: u32,
+    pub oversold: f64,
+    pub overbought: f64,
+    pub confidence: f64,
+    // Close the position once %K crosses back through 50
+    #[serde(default)]
+    pub exit_at_midline: bool,
+    // Compute indicators on Heikin-Ashi candles instead of the raw klines
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeltnerSettings {
+    // The EMA period of the channel midline.
+    pub ema_period: u32,
+    pub atr_period: u32,
+    // The channel half-width in ATRs.
+    pub multiplier: f64,
+    pub confidence: f64,
+    // Compute indicators on Heikin-Ashi candles instead of the raw klines
+    #[serde(default)]
+    pub use_heikin_ashi: bool,
+}
+
+/// The rule used by `EnsembleStrategy` to combine the signals of its sub-strategies.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnsembleRule {
+    /// Enter when more than half of the sub-strategies agree on a direction.
+    MajorityVote,
+    /// Enter only when every sub-strategy agrees on a direction.
+    Unanimous,
+    /// Enter in the signalled direction with the average confidence across all
+    /// sub-strategies, where abstaining strategies count as zero.
+    ConfidenceWeighted,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnsembleSettings {
+    pub rule: EnsembleRule,
+    // The sub-strategies whose signals are combined, each with its own settings.
+    pub members: Vec<EnsembleMember>,
+}
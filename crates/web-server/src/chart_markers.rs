@@ -0,0 +1,119 @@
+// In crates/web-server/src/chart_markers.rs
+
+use database::ApiTrade;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+const LONG_COLOR: &str = "#26a69a";
+const SHORT_COLOR: &str = "#ef5350";
+
+/// A series marker in the format TradingView lightweight-charts expects from `setMarkers`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ChartMarker {
+    /// The bar time as a UTC Unix timestamp in seconds.
+    pub time: i64,
+    /// `"aboveBar"` or `"belowBar"`.
+    pub position: &'static str,
+    /// `"arrowUp"` or `"arrowDown"`.
+    pub shape: &'static str,
+    pub color: &'static str,
+    pub text: String,
+}
+
+/// Converts trades into entry and exit markers, sorted by time as lightweight-charts requires.
+///
+/// Buys (long entries, short exits) are drawn as an up arrow below the bar and sells as a
+/// down arrow above it. Markers are coloured by the trade's direction, and exit markers carry
+/// the trade's P&L.
+pub fn trades_to_markers(trades: &[ApiTrade]) -> Vec<ChartMarker> {
+    let mut markers = Vec::with_capacity(trades.len() * 2);
+    for trade in trades {
+        let is_long = trade.side == "Long";
+        let color = if is_long { LONG_COLOR } else { SHORT_COLOR };
+
+        let (position, shape) = buy_or_sell(is_long);
+        markers.push(ChartMarker {
+            time: trade.entry_time.timestamp(),
+            position,
+            shape,
+            color,
+            text: format!("{} @ {}", trade.side, trade.entry_price.normalize()),
+        });
+        let (position, shape) = buy_or_sell(!is_long);
+        markers.push(ChartMarker {
+            time: trade.exit_time.timestamp(),
+            position,
+            shape,
+            color,
+            text: format!("Exit {} ({})", trade.side, format_pnl(trade.pnl)),
+        });
+    }
+    markers.sort_by_key(|marker| marker.time);
+    markers
+}
+
+/// The `(position, shape)` of a buy (`true`) or sell (`false`) marker.
+fn buy_or_sell(is_buy: bool) -> (&'static str, &'static str) {
+    if is_buy { ("belowBar", "arrowUp") } else { ("aboveBar", "arrowDown") }
+}
+
+fn format_pnl(pnl: Decimal) -> String {
+    let pnl = pnl.round_dp(2);
+    if pnl.is_sign_negative() { format!("-${}", pnl.abs()) } else { format!("+${}", pnl) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn trade(side: &str, entry_secs: i64, exit_secs: i64, pnl: Decimal) -> ApiTrade {
+        ApiTrade {
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            entry_time: Utc.timestamp_opt(entry_secs, 0).unwrap(),
+            exit_time: Utc.timestamp_opt(exit_secs, 0).unwrap(),
+            entry_price: dec!(100.50),
+            exit_price: dec!(110),
+            quantity: dec!(1),
+            pnl,
+            fees: dec!(0),
+            signal_confidence: 1.0,
+            leverage: 1,
+            max_adverse_excursion: dec!(0),
+            max_favorable_excursion: dec!(0),
+            entry_reason: "long_signal".to_string(),
+            exit_reason: "signal".to_string(),
+        }
+    }
+
+    #[test]
+    fn long_entry_is_an_up_arrow_at_the_entry_time_in_seconds() {
+        let markers = trades_to_markers(&[trade("Long", 1_700_000_000, 1_700_000_600, dec!(9.5))]);
+
+        assert_eq!(markers.len(), 2);
+        let entry = &markers[0];
+        assert_eq!(entry.time, 1_700_000_000);
+        assert_eq!((entry.position, entry.shape, entry.color), ("belowBar", "arrowUp", LONG_COLOR));
+        assert_eq!(entry.text, "Long @ 100.5");
+        let exit = &markers[1];
+        assert_eq!(exit.time, 1_700_000_600);
+        assert_eq!((exit.position, exit.shape), ("aboveBar", "arrowDown"));
+        assert_eq!(exit.text, "Exit Long (+$9.5)");
+    }
+
+    #[test]
+    fn short_markers_are_mirrored_and_sorted_by_time() {
+        let markers = trades_to_markers(&[
+            trade("Long", 300, 400, dec!(1)),
+            trade("Short", 100, 200, dec!(-3.456)),
+        ]);
+
+        let times: Vec<i64> = markers.iter().map(|m| m.time).collect();
+        assert_eq!(times, vec![100, 200, 300, 400]);
+        assert_eq!((markers[0].shape, markers[0].color), ("arrowDown", SHORT_COLOR));
+        assert_eq!((markers[1].shape, markers[1].text.as_str()), ("arrowUp", "Exit Short (-$3.46)"));
+    }
+}
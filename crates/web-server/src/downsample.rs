@@ -0,0 +1,89 @@
+// In crates/web-server/src/downsample.rs
+
+use analytics::types::EquityPoint;
+
+/// Reduces an equity curve to at most `max_points` points for charting.
+///
+/// The first and last points are always kept. The points in between are split into
+/// equally-sized buckets, and each bucket contributes only its minimum and maximum (in
+/// time order), so peaks and troughs — including the global max and min — survive.
+/// `max_points` is clamped to at least 4. Curves that already fit are returned unchanged.
+pub fn downsample_equity_curve(points: Vec<EquityPoint>, max_points: usize) -> Vec<EquityPoint> {
+    let max_points = max_points.max(4);
+    if points.len() <= max_points {
+        return points;
+    }
+
+    let interior = &points[1..points.len() - 1];
+    let bucket_count = (max_points - 2) / 2;
+    let bucket_size = interior.len().div_ceil(bucket_count);
+
+    let mut sampled = Vec::with_capacity(max_points);
+    sampled.push(points[0].clone());
+    for bucket in interior.chunks(bucket_size) {
+        let (mut min_idx, mut max_idx) = (0, 0);
+        for (i, point) in bucket.iter().enumerate() {
+            if point.value < bucket[min_idx].value {
+                min_idx = i;
+            }
+            if point.value > bucket[max_idx].value {
+                max_idx = i;
+            }
+        }
+
+        let (first, second) = if min_idx <= max_idx { (min_idx, max_idx) } else { (max_idx, min_idx) };
+        sampled.push(bucket[first].clone());
+        if second != first {
+            sampled.push(bucket[second].clone());
+        }
+    }
+    sampled.push(points[points.len() - 1].clone());
+
+    sampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal::Decimal;
+
+    /// A 10,000-point curve oscillating around 10,000, with a single global peak and trough
+    /// in the middle.
+    fn curve() -> Vec<EquityPoint> {
+        (0..10_000)
+            .map(|i| {
+                let value = match i {
+                    3_217 => 15_000,
+                    6_781 => 5_000,
+                    _ => 10_000 + (i * 37 % 200) as i64 - 100,
+                };
+                EquityPoint {
+                    timestamp: Utc.timestamp_opt(i as i64 * 60, 0).unwrap(),
+                    value: Decimal::from(value),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn long_curve_is_reduced_keeping_the_global_extremes() {
+        let points = curve();
+        let sampled = downsample_equity_curve(points.clone(), 500);
+
+        assert!(sampled.len() <= 500, "{} points returned", sampled.len());
+        assert_eq!(sampled.first().unwrap().timestamp, points.first().unwrap().timestamp);
+        assert_eq!(sampled.last().unwrap().timestamp, points.last().unwrap().timestamp);
+        let max = sampled.iter().map(|p| p.value).max().unwrap();
+        let min = sampled.iter().map(|p| p.value).min().unwrap();
+        assert_eq!(max, Decimal::from(15_000));
+        assert_eq!(min, Decimal::from(5_000));
+        assert!(sampled.windows(2).all(|pair| pair[0].timestamp < pair[1].timestamp));
+    }
+
+    #[test]
+    fn short_curve_is_returned_unchanged() {
+        let points: Vec<_> = curve().into_iter().take(300).collect();
+        assert_eq!(downsample_equity_curve(points.clone(), 500).len(), points.len());
+    }
+}
@@ -19,14 +19,47 @@ pub enum Error {
     #[error("Resource not found: {0}")]
     NotFound(String),
 
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Exchange error: {0}")]
+    BadGateway(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+
     // Add other web-specific errors here in the future
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// The stable, machine-readable code of the error, sent as `error.code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::DatabaseError(_) => "DATABASE_ERROR",
+            Error::ServerBindError(_) => "SERVER_BIND_ERROR",
+            Error::NotFound(_) => "NOT_FOUND",
+            Error::BadRequest(_) => "BAD_REQUEST",
+            Error::Unauthorized(_) => "UNAUTHORIZED",
+            Error::Conflict(_) => "CONFLICT",
+            Error::BadGateway(_) => "BAD_GATEWAY",
+            Error::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+}
+
 // This allows us to convert our custom Error into a proper HTTP response.
+// Every error is sent as `{ "error": { "code": "...", "message": "..." } }`.
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
+        let code = self.code();
         let (status, error_message) = match self {
             Error::DatabaseError(e) => {
                 // Log the full error for debugging
@@ -45,9 +78,69 @@ impl IntoResponse for Error {
                 )
             }
             Error::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            Error::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            Error::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            Error::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            Error::BadGateway(msg) => (StatusCode::BAD_GATEWAY, msg),
+            Error::Internal(msg) => {
+                tracing::error!("Internal error occurred: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "An internal error occurred".to_string(),
+                )
+            }
         };
 
-        let body = Json(json!({ "error": error_message }));
+        let body = Json(json!({ "error": { "code": code, "message": error_message } }));
         (status, body).into_response()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders `error` and returns the status and the JSON body.
+    async fn render(error: Error) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn every_variant_maps_to_its_status_code_and_message() {
+        let cases = [
+            (Error::NotFound("run 7".to_string()), StatusCode::NOT_FOUND, "NOT_FOUND", "run 7"),
+            (Error::BadRequest("bad window".to_string()), StatusCode::BAD_REQUEST, "BAD_REQUEST", "bad window"),
+            (Error::Unauthorized("no token".to_string()), StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "no token"),
+            (Error::Conflict("running".to_string()), StatusCode::CONFLICT, "CONFLICT", "running"),
+            (Error::BadGateway("timeout".to_string()), StatusCode::BAD_GATEWAY, "BAD_GATEWAY", "timeout"),
+            // Internal details are logged, never sent.
+            (
+                Error::Internal("secret detail".to_string()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INTERNAL_ERROR",
+                "An internal error occurred",
+            ),
+            (
+                Error::DatabaseError(database::Error::InvalidInterval("7m".to_string())),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                "An internal database error occurred",
+            ),
+            (
+                Error::ServerBindError(std::io::Error::from(std::io::ErrorKind::AddrInUse)),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "SERVER_BIND_ERROR",
+                "Failed to bind server to address",
+            ),
+        ];
+
+        for (error, status, code, message) in cases {
+            let (actual_status, body) = render(error).await;
+            assert_eq!(actual_status, status, "{code}");
+            assert_eq!(body, json!({ "error": { "code": code, "message": message } }));
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// In crates/web-server/src/heatmap.rs
+
+use analytics::scoring::{calculate_score, ScoringWeights};
+use analytics::types::PerformanceReport;
+use database::FullReport;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::types::HeatmapMetric;
+
+/// One cell of a parameter-sensitivity heatmap.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapCell {
+    pub x: f64,
+    pub y: f64,
+    /// The mean of the metric over every run with these two parameter values.
+    pub metric: f64,
+    /// The number of runs averaged into `metric`.
+    pub runs: usize,
+}
+
+/// Returns the value of `metric` for `report`.
+pub fn metric_value(metric: HeatmapMetric, report: &PerformanceReport) -> f64 {
+    match metric {
+        HeatmapMetric::Score => calculate_score(report, &ScoringWeights::default()),
+        HeatmapMetric::NetPnl => report.net_pnl_percentage,
+        HeatmapMetric::SharpeRatio => report.sharpe_ratio,
+        HeatmapMetric::SortinoRatio => report.sortino_ratio,
+        HeatmapMetric::CalmarRatio => report.calmar_ratio,
+        HeatmapMetric::MaxDrawdown => report.max_drawdown_percentage,
+        HeatmapMetric::ProfitFactor => report.profit_factor,
+        HeatmapMetric::WinRate => report.win_rate,
+    }
+}
+
+/// Groups runs by the values of their `x` and `y` parameters, sorted by `x` then `y`.
+///
+/// Runs lacking either parameter, or where it is not a number, are skipped. When other
+/// parameters were optimized too, several runs share a cell and their metric is averaged.
+pub fn build_heatmap(reports: &[FullReport], x: &str, y: &str, metric: HeatmapMetric) -> Vec<HeatmapCell> {
+    let mut sums: HashMap<(u64, u64), (f64, f64, f64, usize)> = HashMap::new();
+    for full in reports {
+        let param = |name: &str| full.parameters.get(name).and_then(serde_json::Value::as_f64);
+        let (Some(x_value), Some(y_value)) = (param(x), param(y)) else {
+            continue;
+        };
+        let cell = sums
+            .entry((x_value.to_bits(), y_value.to_bits()))
+            .or_insert((x_value, y_value, 0.0, 0));
+        cell.2 += metric_value(metric, &full.report);
+        cell.3 += 1;
+    }
+
+    let mut cells: Vec<HeatmapCell> = sums
+        .into_values()
+        .map(|(x, y, sum, runs)| HeatmapCell { x, y, metric: sum / runs as f64, runs })
+        .collect();
+    cells.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn run(run_id: i64, parameters: Value, sharpe_ratio: f64) -> FullReport {
+        FullReport { run_id, parameters, report: PerformanceReport { sharpe_ratio, ..PerformanceReport::new() } }
+    }
+
+    #[test]
+    fn a_two_by_two_grid_yields_one_cell_per_parameter_pair() {
+        let reports = vec![
+            run(1, json!({ "fast": 10, "slow": 50, "confidence": 0.5 }), 1.0),
+            run(2, json!({ "fast": 10, "slow": 100 }), 0.5),
+            run(3, json!({ "fast": 20, "slow": 50 }), -0.25),
+            run(4, json!({ "fast": 20, "slow": 100 }), 2.0),
+            // Shares the first cell, so the two Sharpe ratios are averaged.
+            run(5, json!({ "fast": 10, "slow": 50, "confidence": 0.9 }), 2.0),
+            // Missing or non-numeric parameters are skipped.
+            run(6, json!({ "fast": 30 }), 9.0),
+            run(7, json!({ "fast": "30", "slow": 50 }), 9.0),
+        ];
+
+        let cells = build_heatmap(&reports, "fast", "slow", HeatmapMetric::SharpeRatio);
+
+        let cells: Vec<_> = cells.iter().map(|c| (c.x, c.y, c.metric, c.runs)).collect();
+        assert_eq!(
+            cells,
+            vec![(10.0, 50.0, 1.5, 2), (10.0, 100.0, 0.5, 1), (20.0, 50.0, -0.25, 1), (20.0, 100.0, 2.0, 1)]
+        );
+    }
+}
@@ -1,33 +1,48 @@
 // In crates/web-server/src/lib.rs (REPLACE ENTIRE FILE)
 
 use axum::{
+    body::Body,
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{Message, WebSocket},
         State, Query, Path
     },
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Router,
     response::Json,
-    Extension,
 };
-use futures::{sink::SinkExt, stream::StreamExt}; // for websocket send/receive
-use database::{Db, BacktestRun, OptimizationJob, ApiTrade};
-use std::collections::VecDeque;
-use std::sync::{Arc, Mutex};
+use futures::stream::StreamExt; // for websocket send/receive
+use rust_decimal::prelude::ToPrimitive;
+use subtle::ConstantTimeEq;
+use database::{Db, BacktestRun, OptimizationJob, ApiTrade, KlineSeries, LiveTrade, FullBacktestExport, GlobalStats};
+use std::sync::Arc;
 use tokio::sync::broadcast;
-use types::{PaginatedResponse, PaginationParams};
-use analytics::types::EquityPoint;
+use types::{
+    AttributionParams, DistributionParams, EquityCurveParams, HeatmapParams, KeyMetrics, LeaderboardEntry, LeaderboardParams, LeaderboardSortKey,
+    MonteCarloParams, PaginatedResponse, PaginationParams, MAX_MONTE_CARLO_ITERATIONS,
+    MAX_ATTRIBUTION_TOP, MAX_DISTRIBUTION_BUCKETS, RollingParams, TradeDistributions, TradeFilterParams, WsMessage, WsPortfolioUpdate,
+};
+use analytics::scoring::{rank_reports, ScoringWeights};
+use analytics::attribution::{attribute_trades, AttributionReport, TradeOutcome};
+use analytics::distribution::histogram;
+use analytics::engine::AnalyticsEngine;
+use analytics::types::{EquityPoint, MonteCarloReport, RollingPoint};
 use app_config::types::ServerSettings; // Import the new settings
 use tokio::net::TcpListener;
-use events::WsMessage;
+use events::ReplayCache;
+use execution::Portfolio;
+use api_client::types::{AccountState, ApiClient, FlattenReport};
+use utoipa::OpenApi;
+use chart_markers::{trades_to_markers, ChartMarker};
+use heatmap::{build_heatmap, HeatmapCell};
 
+pub mod chart_markers;
+pub mod downsample;
 pub mod error;
+pub mod heatmap;
 pub mod types;
 
-// WebSocket message replay cache type
-type WsCache = Arc<Mutex<VecDeque<WsMessage>>>;
-
 // Re-export our custom error type for convenience.
 pub use error::{Error, Result};
 
@@ -38,10 +53,40 @@ pub use error::{Error, Result};
 pub struct AppState {
     pub db: Db,
     pub ws_tx: broadcast::Sender<WsMessage>, // For broadcasting live messages
-    pub ws_cache: WsCache,                   // For replaying recent messages
+    pub ws_cache: ReplayCache,               // For replaying recent messages
+    pub portfolio: Arc<tokio::sync::Mutex<Portfolio>>, // The engine's live portfolio
+    pub api_client: Option<ApiClient>,       // Set when trading live, for privileged actions
+    pub admin_token: Option<String>,         // Bearer token of the privileged endpoints
 }
 
-const WS_CACHE_SIZE: usize = 200; // The maximum number of messages to keep in the replay cache.
+/// The OpenAPI description of the REST API, served at `GET /api/openapi.json`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Atlas API"),
+    paths(
+        get_backtest_runs_handler,
+        get_optimizations_handler,
+        get_backtest_details_handler,
+        get_backtest_trades_handler,
+        get_backtest_equity_curve_handler,
+        get_backtest_chart_markers_handler,
+        get_stats_overview_handler,
+    ),
+    components(schemas(
+        BacktestRun,
+        OptimizationJob,
+        ApiTrade,
+        EquityPoint,
+        ChartMarker,
+        GlobalStats,
+        database::RunPnlSummary,
+        analytics::types::PerformanceReport,
+        PaginatedResponse<BacktestRun>,
+        PaginatedResponse<OptimizationJob>,
+        PaginatedResponse<ApiTrade>,
+    ))
+)]
+pub struct ApiDoc;
 
 // We will add the `create_router` and `run` functions in the next tasks.
 
@@ -68,10 +113,29 @@ pub fn create_router(app_state: AppState) -> Router {
         // Add the new optimization routes
         .route("/optimizations", get(get_optimizations_handler))
         .route("/optimizations/{jobId}", get(get_optimization_details_handler))
+        .route("/optimizations/{jobId}/leaderboard", get(get_optimization_leaderboard_handler))
+        .route("/optimizations/{jobId}/heatmap", get(get_optimization_heatmap_handler))
         // Add the new backtest detail routes
+        .route("/backtests/cancel/{jobId}", post(post_cancel_optimization_handler))
         .route("/backtests/{runId}", get(get_backtest_details_handler))
         .route("/backtests/{runId}/trades", get(get_backtest_trades_handler))
-        .route("/backtests/{runId}/equity-curve", get(get_backtest_equity_curve_handler));
+        .route("/backtests/{runId}/trades.ndjson", get(get_backtest_trades_ndjson_handler))
+        .route("/backtests/{runId}/equity-curve", get(get_backtest_equity_curve_handler))
+        .route("/backtests/{runId}/monte-carlo", get(get_backtest_monte_carlo_handler))
+        .route("/backtests/{runId}/distributions", get(get_backtest_distributions_handler))
+        .route("/backtests/{runId}/attribution", get(get_backtest_attribution_handler))
+        .route("/backtests/{runId}/rolling", get(get_backtest_rolling_handler))
+        .route("/backtests/{runId}/export", get(get_backtest_export_handler))
+        .route("/backtests/{runId}/chart-markers", get(get_backtest_chart_markers_handler))
+        .route("/market-data/series", get(get_available_series_handler))
+        .route("/stats/overview", get(get_stats_overview_handler))
+        .route("/portfolio", get(get_portfolio_handler))
+        .route("/portfolio/reset", post(post_portfolio_reset_handler))
+        .route("/live/trades", get(get_live_trades_handler))
+        .route("/live/equity-curve", get(get_live_equity_curve_handler))
+        .route("/live/flatten", post(post_live_flatten_handler))
+        .route("/live/account", get(get_live_account_handler))
+        .route("/openapi.json", get(get_openapi_handler));
 
     // The main router.
     Router::new()
@@ -84,6 +148,22 @@ pub fn create_router(app_state: AppState) -> Router {
         .with_state(app_state)
 }
 
+/// Handler for `GET /api/portfolio`
+/// Returns a snapshot of the live portfolio, so freshly-connected clients don't have to
+/// wait for the next `PortfolioUpdate` event.
+async fn get_portfolio_handler(
+    State(state): State<AppState>,
+) -> Json<WsPortfolioUpdate> {
+    let portfolio = state.portfolio.lock().await;
+    Json(portfolio.snapshot())
+}
+
+/// Handler for `GET /api/openapi.json`
+/// Serves the generated OpenAPI spec of the REST API.
+async fn get_openapi_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
 /// A simple health check handler.
 /// Responds with a 200 OK and a JSON body.
 async fn health_check_handler() -> &'static str {
@@ -92,10 +172,20 @@ async fn health_check_handler() -> &'static str {
 
 /// The handler for `GET /api/backtest-runs`.
 /// Fetches a paginated list of backtest runs from the database.
+#[utoipa::path(
+    get,
+    path = "/api/backtest-runs",
+    params(PaginationParams),
+    responses(
+        (status = 200, description = "A page of backtest runs", body = PaginatedResponse<BacktestRun>),
+        (status = 400, description = "Invalid pagination parameters"),
+    )
+)]
 async fn get_backtest_runs_handler(
     State(state): State<AppState>,
     Query(params): Query<PaginationParams>,
 ) -> Result<Json<PaginatedResponse<BacktestRun>>> {
+    let params = params.validate()?;
     // Pass the optional job_id to the database function
     let (runs, total_items) = state.db
         .get_backtest_runs_paginated(params.page, params.page_size, params.job_id)
@@ -113,10 +203,20 @@ async fn get_backtest_runs_handler(
 }
 
 /// Handler for `GET /api/optimizations`
+#[utoipa::path(
+    get,
+    path = "/api/optimizations",
+    params(PaginationParams),
+    responses(
+        (status = 200, description = "A page of optimization jobs", body = PaginatedResponse<OptimizationJob>),
+        (status = 400, description = "Invalid pagination parameters"),
+    )
+)]
 async fn get_optimizations_handler(
     State(state): State<AppState>,
     Query(params): Query<PaginationParams>,
 ) -> Result<Json<PaginatedResponse<OptimizationJob>>> {
+    let params = params.validate()?;
     let (jobs, total_items) = state.db
         .get_optimization_jobs_paginated(params.page, params.page_size)
         .await?;
@@ -150,7 +250,96 @@ async fn get_optimization_details_handler(
     }
 }
 
+/// Handler for `GET /api/optimizations/:jobId/leaderboard`
+/// Scores every run of the job, drops runs with too few trades, and returns a sorted page.
+async fn get_optimization_leaderboard_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<i64>,
+    Query(params): Query<LeaderboardParams>,
+) -> Result<Json<PaginatedResponse<LeaderboardEntry>>> {
+    let pagination = PaginationParams { page: params.page, page_size: params.page_size, job_id: None }.validate()?;
+
+    let reports = state.db.get_reports_for_job(job_id).await?;
+    let weights = ScoringWeights::default();
+    let mut entries: Vec<LeaderboardEntry> = rank_reports(reports, &weights)
+        .into_iter()
+        .map(|ranked| {
+            let full = ranked.report;
+            let report = &full.report;
+            LeaderboardEntry {
+                run_id: full.run_id,
+                score: ranked.score,
+                key_metrics: KeyMetrics {
+                    net_pnl_percentage: report.net_pnl_percentage,
+                    max_drawdown_percentage: report.max_drawdown_percentage,
+                    sharpe_ratio: report.sharpe_ratio,
+                    profit_factor: report.profit_factor,
+                    win_rate: report.win_rate,
+                    calmar_ratio: report.calmar_ratio,
+                    total_trades: report.total_trades,
+                },
+                parameters: full.parameters,
+            }
+        })
+        .collect();
+
+    let sort_value = |entry: &LeaderboardEntry| match params.sort_by {
+        LeaderboardSortKey::Score => entry.score,
+        LeaderboardSortKey::NetPnl => entry.key_metrics.net_pnl_percentage,
+        LeaderboardSortKey::SharpeRatio => entry.key_metrics.sharpe_ratio,
+        LeaderboardSortKey::MaxDrawdown => entry.key_metrics.max_drawdown_percentage,
+        LeaderboardSortKey::ProfitFactor => entry.key_metrics.profit_factor,
+        LeaderboardSortKey::WinRate => entry.key_metrics.win_rate,
+    };
+    entries.sort_by(|a, b| {
+        let ordering = sort_value(b).partial_cmp(&sort_value(a)).unwrap_or(std::cmp::Ordering::Equal);
+        if params.ascending { ordering.reverse() } else { ordering }
+    });
+
+    let total_items = entries.len() as i64;
+    let offset = ((pagination.page - 1) * pagination.page_size) as usize;
+    let items = entries.into_iter().skip(offset).take(pagination.page_size as usize).collect();
+
+    Ok(Json(PaginatedResponse {
+        items,
+        total_items,
+        page: pagination.page,
+        page_size: pagination.page_size,
+    }))
+}
+
+/// Handler for `GET /api/optimizations/:jobId/heatmap`
+/// Plots a metric of every run of the job over the values of two of its parameters.
+async fn get_optimization_heatmap_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<i64>,
+    Query(params): Query<HeatmapParams>,
+) -> Result<Json<Vec<HeatmapCell>>> {
+    let reports = state.db.get_reports_for_job(job_id).await?;
+    if reports.is_empty() {
+        return Err(Error::NotFound(format!("Optimization job {} has no runs", job_id)));
+    }
+
+    let cells = build_heatmap(&reports, &params.x, &params.y, params.metric);
+    if cells.is_empty() {
+        return Err(Error::BadRequest(format!(
+            "No run of optimization job {} has numeric parameters '{}' and '{}'",
+            job_id, params.x, params.y
+        )));
+    }
+    Ok(Json(cells))
+}
+
 /// Handler for `GET /api/backtests/:runId`
+#[utoipa::path(
+    get,
+    path = "/api/backtests/{runId}",
+    params(("runId" = i64, Path, description = "The backtest run ID")),
+    responses(
+        (status = 200, description = "The run's performance report", body = analytics::types::PerformanceReport),
+        (status = 404, description = "Backtest run not found"),
+    )
+)]
 async fn get_backtest_details_handler(
     State(state): State<AppState>,
     Path(run_id): Path<i64>,
@@ -162,13 +351,25 @@ async fn get_backtest_details_handler(
 }
 
 /// Handler for `GET /api/backtests/:runId/trades`
+#[utoipa::path(
+    get,
+    path = "/api/backtests/{runId}/trades",
+    params(("runId" = i64, Path, description = "The backtest run ID"), PaginationParams, TradeFilterParams),
+    responses(
+        (status = 200, description = "A page of the run's trades", body = PaginatedResponse<ApiTrade>),
+        (status = 400, description = "Invalid pagination or filter parameters"),
+    )
+)]
 async fn get_backtest_trades_handler(
     State(state): State<AppState>,
     Path(run_id): Path<i64>,
     Query(params): Query<PaginationParams>,
+    Query(filter): Query<TradeFilterParams>,
 ) -> Result<Json<PaginatedResponse<ApiTrade>>> {
+    let params = params.validate()?;
+    let filter = filter.validate()?;
     let (trades, total_items) = state.db
-        .get_trades_for_run_paginated(run_id, params.page, params.page_size)
+        .get_trades_for_run_paginated(run_id, params.page, params.page_size, &filter)
         .await?;
     
     let response = PaginatedResponse {
@@ -181,14 +382,359 @@ async fn get_backtest_trades_handler(
 }
 
 /// Handler for `GET /api/backtests/:runId/equity-curve`
+/// If `max_points` is given, long curves are downsampled to roughly that many points.
+#[utoipa::path(
+    get,
+    path = "/api/backtests/{runId}/equity-curve",
+    params(("runId" = i64, Path, description = "The backtest run ID"), EquityCurveParams),
+    responses(
+        (status = 200, description = "The run's equity curve", body = Vec<EquityPoint>),
+    )
+)]
 async fn get_backtest_equity_curve_handler(
     State(state): State<AppState>,
     Path(run_id): Path<i64>,
+    Query(params): Query<EquityCurveParams>,
 ) -> Result<Json<Vec<EquityPoint>>> {
     let curve = state.db.get_equity_curve_for_run(run_id).await?;
+    let curve = match params.max_points {
+        Some(max_points) => downsample::downsample_equity_curve(curve, max_points),
+        None => curve,
+    };
+    Ok(Json(curve))
+}
+
+/// Handler for `GET /api/backtests/:runId/monte-carlo`
+/// Resamples the run's trade sequence; the CPU-bound work runs on a blocking task.
+async fn get_backtest_monte_carlo_handler(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+    Query(params): Query<MonteCarloParams>,
+) -> Result<Json<MonteCarloReport>> {
+    if params.iterations < 1 || params.iterations > MAX_MONTE_CARLO_ITERATIONS {
+        return Err(Error::BadRequest(format!(
+            "iterations must be between 1 and {}",
+            MAX_MONTE_CARLO_ITERATIONS
+        )));
+    }
+    if state.db.get_performance_report(run_id).await?.is_none() {
+        return Err(Error::NotFound(format!("Backtest run {} not found", run_id)));
+    }
+
+    let pnls = state.db.get_trade_pnls_for_run(run_id).await?;
+    let report = tokio::task::spawn_blocking(move || {
+        AnalyticsEngine::new().monte_carlo(&pnls, params.iterations, params.seed)
+    })
+    .await
+    .map_err(|e| Error::Internal(format!("Monte Carlo task failed: {}", e)))?;
+    Ok(Json(report))
+}
+
+/// Handler for `GET /api/backtests/:runId/distributions`
+/// Returns histograms of trade P&L and trade duration with `buckets` buckets each.
+async fn get_backtest_distributions_handler(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+    Query(params): Query<DistributionParams>,
+) -> Result<Json<TradeDistributions>> {
+    if params.buckets < 1 || params.buckets > MAX_DISTRIBUTION_BUCKETS {
+        return Err(Error::BadRequest(format!(
+            "buckets must be between 1 and {}",
+            MAX_DISTRIBUTION_BUCKETS
+        )));
+    }
+    if state.db.get_performance_report(run_id).await?.is_none() {
+        return Err(Error::NotFound(format!("Backtest run {} not found", run_id)));
+    }
+
+    let trades = state.db.get_all_trades_for_run(run_id).await?;
+    let pnls: Vec<f64> = trades.iter().map(|t| t.pnl.to_f64().unwrap_or(0.0)).collect();
+    let durations: Vec<f64> = trades
+        .iter()
+        .map(|t| (t.exit_time - t.entry_time).num_seconds() as f64)
+        .collect();
+
+    Ok(Json(TradeDistributions {
+        total_trades: trades.len(),
+        pnl: histogram(&pnls, params.buckets),
+        duration_secs: histogram(&durations, params.buckets),
+    }))
+}
+
+/// Handler for `GET /api/backtests/:runId/attribution`
+/// Returns the `top` trades that added the most to and took the most from the run's equity.
+async fn get_backtest_attribution_handler(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+    Query(params): Query<AttributionParams>,
+) -> Result<Json<AttributionReport>> {
+    if params.top < 1 || params.top > MAX_ATTRIBUTION_TOP {
+        return Err(Error::BadRequest(format!(
+            "top must be between 1 and {}",
+            MAX_ATTRIBUTION_TOP
+        )));
+    }
+    if state.db.get_performance_report(run_id).await?.is_none() {
+        return Err(Error::NotFound(format!("Backtest run {} not found", run_id)));
+    }
+
+    let trades: Vec<TradeOutcome> = state
+        .db
+        .get_all_trades_for_run(run_id)
+        .await?
+        .into_iter()
+        .map(|t| TradeOutcome { entry_time: t.entry_time, exit_time: t.exit_time, pnl: t.pnl })
+        .collect();
+    let equity_curve = state.db.get_equity_curve_for_run(run_id).await?;
+
+    Ok(Json(attribute_trades(&trades, &equity_curve, params.top)))
+}
+
+/// Handler for `GET /api/backtests/:runId/rolling`
+/// Returns `metric` computed over each sliding window of `window` equity points.
+async fn get_backtest_rolling_handler(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+    Query(params): Query<RollingParams>,
+) -> Result<Json<Vec<RollingPoint>>> {
+    if params.window < 2 {
+        return Err(Error::BadRequest(format!("window must be at least 2, got {}", params.window)));
+    }
+    if state.db.get_performance_report(run_id).await?.is_none() {
+        return Err(Error::NotFound(format!("Backtest run {} not found", run_id)));
+    }
+
+    let equity_curve = state.db.get_equity_curve_for_run(run_id).await?;
+    Ok(Json(AnalyticsEngine::new().rolling(&equity_curve, params.metric, params.window)))
+}
+
+/// Handler for `GET /api/backtests/:runId/export`
+/// Bundles the run's metadata, report, trades and equity curve into one JSON document.
+async fn get_backtest_export_handler(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+) -> Result<Json<FullBacktestExport>> {
+    match state.db.export_backtest(run_id).await? {
+        Some(export) => Ok(Json(export)),
+        None => Err(Error::NotFound(format!("Backtest run {} not found", run_id))),
+    }
+}
+
+/// The number of serialized trades buffered between the database cursor and the response.
+const NDJSON_BUFFERED_TRADES: usize = 256;
+
+/// Handler for `GET /api/backtests/:runId/trades.ndjson`
+/// Streams every trade of the run as newline-delimited JSON, one `ApiTrade` per line.
+async fn get_backtest_trades_ndjson_handler(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+) -> Result<impl IntoResponse> {
+    if state.db.get_performance_report(run_id).await?.is_none() {
+        return Err(Error::NotFound(format!("Backtest run {} not found", run_id)));
+    }
+
+    // The cursor borrows the pool, so it is drained on its own task and the lines are
+    // handed to the response through a bounded channel, which also applies backpressure.
+    let (tx, rx) = tokio::sync::mpsc::channel::<std::result::Result<String, Error>>(NDJSON_BUFFERED_TRADES);
+    let db = state.db.clone();
+    tokio::spawn(async move {
+        let mut trades = db.stream_trades_for_run(run_id);
+        while let Some(trade) = trades.next().await {
+            let line = trade.map_err(Error::from).and_then(|trade| {
+                serde_json::to_string(&trade)
+                    .map(|json| json + "\n")
+                    .map_err(|e| Error::Internal(e.to_string()))
+            });
+            let failed = line.is_err();
+            if tx.send(line).await.is_err() || failed {
+                break; // The client disconnected, or the stream is already failed.
+            }
+        }
+    });
+
+    let lines = futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|line| (line, rx)) });
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)))
+}
+
+/// Handler for `GET /api/backtests/:runId/chart-markers`
+/// Returns the run's trade entries and exits as TradingView lightweight-charts markers.
+#[utoipa::path(
+    get,
+    path = "/api/backtests/{runId}/chart-markers",
+    params(("runId" = i64, Path, description = "The backtest run ID")),
+    responses(
+        (status = 200, description = "Entry and exit markers, sorted by time", body = Vec<ChartMarker>),
+        (status = 404, description = "Backtest run not found"),
+    )
+)]
+async fn get_backtest_chart_markers_handler(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+) -> Result<Json<Vec<ChartMarker>>> {
+    if state.db.get_performance_report(run_id).await?.is_none() {
+        return Err(Error::NotFound(format!("Backtest run {} not found", run_id)));
+    }
+    let trades = state.db.get_all_trades_for_run(run_id).await?;
+    Ok(Json(trades_to_markers(&trades)))
+}
+
+/// Checks the request's `Authorization: Bearer <token>` header against `server.admin_token`.
+fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<()> {
+    let Some(expected) = &state.admin_token else {
+        return Err(Error::Unauthorized("Privileged endpoints are disabled: server.admin_token is not set".to_string()));
+    };
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    // Compared in constant time, so response timing doesn't reveal how much of a guess matched.
+    let matches = provided.is_some_and(|provided| bool::from(provided.as_bytes().ct_eq(expected.as_bytes())));
+    if !matches {
+        return Err(Error::Unauthorized("Missing or invalid admin token".to_string()));
+    }
+    Ok(())
+}
+
+/// Handler for `POST /api/live/flatten`
+/// Closes every open position on the exchange and drops the closed ones from the live
+/// portfolio. Symbols that could not be closed are listed in the report's `failed`.
+async fn post_live_flatten_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<FlattenReport>> {
+    require_admin(&state, &headers)?;
+    let Some(api_client) = &state.api_client else {
+        return Err(Error::Conflict("Flattening is only available when trading live".to_string()));
+    };
+
+    tracing::warn!("Flattening all live positions on request.");
+    let report = api_client
+        .flatten_positions()
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to flatten positions: {}", e)))?;
+
+    let mut portfolio = state.portfolio.lock().await;
+    for symbol in report.closed.iter().filter_map(|s| core_types::Symbol::new(s).ok()) {
+        portfolio.open_positions.remove(&symbol);
+    }
+    let _ = state.ws_tx.send(WsMessage::PortfolioUpdate(portfolio.snapshot()));
+    Ok(Json(report))
+}
+
+/// Handler for `POST /api/backtests/cancel/{jobId}`
+/// Requests cancellation of a running optimization job. The optimizer picks the request up
+/// within a few seconds, stops its in-flight backtests and skips the remaining ones.
+async fn post_cancel_optimization_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(job_id): Path<i64>,
+) -> Result<StatusCode> {
+    require_admin(&state, &headers)?;
+    if !state.db.request_optimization_cancel(job_id).await? {
+        return Err(Error::NotFound(format!("Optimization job {} not found", job_id)));
+    }
+    tracing::warn!(job_id, "Cancellation of optimization job requested.");
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Handler for `POST /api/portfolio/reset`
+/// Resets the paper-trading portfolio to its initial capital, dropping all open positions,
+/// and returns the new snapshot.
+async fn post_portfolio_reset_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<WsPortfolioUpdate>> {
+    require_admin(&state, &headers)?;
+    if state.api_client.is_some() {
+        return Err(Error::Conflict("The portfolio can only be reset when paper trading".to_string()));
+    }
+
+    let mut portfolio = state.portfolio.lock().await;
+    tracing::warn!(initial_capital = %portfolio.initial_capital, "Resetting the paper portfolio on request.");
+    portfolio.reset();
+    let snapshot = portfolio.snapshot();
+    let _ = state.ws_tx.send(WsMessage::PortfolioUpdate(snapshot.clone()));
+    Ok(Json(snapshot))
+}
+
+/// Handler for `GET /api/live/account`
+/// Returns the exchange's view of the account (balances, positions and totals), as opposed
+/// to the engine's internal portfolio.
+async fn get_live_account_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<AccountState>> {
+    require_admin(&state, &headers)?;
+    let Some(api_client) = &state.api_client else {
+        return Err(Error::Conflict("The exchange account is only available when trading live".to_string()));
+    };
+
+    let account = api_client
+        .get_account_balance()
+        .await
+        .map_err(|e| Error::BadGateway(format!("Failed to fetch the account from the exchange: {}", e)))?;
+    Ok(Json(account))
+}
+
+/// Handler for `GET /api/live/trades`
+/// Lists the executions made by the live engine, newest first.
+async fn get_live_trades_handler(
+    State(state): State<AppState>,
+    Query(params): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<LiveTrade>>> {
+    let params = params.validate()?;
+    let (trades, total_items) = state.db
+        .get_live_trades_paginated(params.page, params.page_size)
+        .await?;
+
+    let response = PaginatedResponse {
+        items: trades,
+        total_items,
+        page: params.page,
+        page_size: params.page_size,
+    };
+    Ok(Json(response))
+}
+
+/// Handler for `GET /api/live/equity-curve`
+/// Returns the persisted equity snapshots of the live portfolio, oldest first.
+/// If `max_points` is given, long curves are downsampled to roughly that many points.
+async fn get_live_equity_curve_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EquityCurveParams>,
+) -> Result<Json<Vec<EquityPoint>>> {
+    let curve = state.db.get_live_equity_curve().await?;
+    let curve = match params.max_points {
+        Some(max_points) => downsample::downsample_equity_curve(curve, max_points),
+        None => curve,
+    };
     Ok(Json(curve))
 }
 
+/// Handler for `GET /api/stats/overview`
+/// Returns aggregate statistics across all backtest runs.
+#[utoipa::path(
+    get,
+    path = "/api/stats/overview",
+    responses(
+        (status = 200, description = "Aggregate statistics across all runs", body = GlobalStats),
+    )
+)]
+async fn get_stats_overview_handler(
+    State(state): State<AppState>,
+) -> Result<Json<GlobalStats>> {
+    Ok(Json(state.db.get_global_stats().await?))
+}
+
+/// Handler for `GET /api/market-data/series`
+/// Lists the symbol/interval combinations that have backfilled kline data.
+async fn get_available_series_handler(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<KlineSeries>>> {
+    let series = state.db.get_available_series().await?;
+    Ok(Json(series))
+}
+
 /// The handler for `GET /ws`.
 /// Upgrades the connection to a WebSocket and handles the real-time communication.
 async fn ws_handler(
@@ -203,11 +749,8 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
     tracing::info!("New WebSocket client connected.");
 
     // --- 1. The "Replay" ---
-    // Get a lock on the cache and clone all historical messages to a local vector.
-    let replay_msgs: Vec<_> = {
-        let cache = state.ws_cache.lock().unwrap();
-        cache.iter().cloned().collect()
-    };
+    // Copy all cached historical messages to a local vector.
+    let replay_msgs = state.ws_cache.snapshot();
     for msg in replay_msgs {
         let json_msg = serde_json::to_string(&msg).unwrap();
         if socket.send(Message::Text(json_msg.into())).await.is_err() {
@@ -260,14 +803,17 @@ pub async fn run(
     settings: ServerSettings,
     db_pool: Db,
     ws_tx: broadcast::Sender<events::WsMessage>, // <-- Add this
+    ws_cache: ReplayCache, // Shared with the tracing layer that fills it
+    portfolio: Arc<tokio::sync::Mutex<Portfolio>>,
+    api_client: Option<ApiClient>, // Only given when trading live
 ) -> Result<()> {
-    // The ws_cache should also be created here and passed into the AppState
-    let ws_cache = Arc::new(Mutex::new(VecDeque::with_capacity(200)));
-    
     let app_state = AppState {
         db: db_pool,
         ws_tx,
         ws_cache,
+        portfolio,
+        api_client,
+        admin_token: settings.admin_token.clone(),
     };
     
     // 4. Create and run the router.
@@ -283,4 +829,340 @@ pub async fn run(
         .unwrap();
 
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use app_config::types::DatabaseSettings;
+    use axum::http::Request;
+    use core_types::{Position, Side, Symbol};
+    use rust_decimal::Decimal;
+    use tower::ServiceExt;
+
+    /// Builds a state whose database pool only connects when a handler queries it.
+    fn app_state() -> AppState {
+        let db = database::connect_lazy(&DatabaseSettings {
+            url: "postgres://localhost/unused".to_string(),
+            max_connections: None,
+            acquire_timeout_secs: None,
+            idle_timeout_secs: None,
+        })
+        .unwrap();
+        AppState {
+            db,
+            ws_tx: broadcast::channel(16).0,
+            ws_cache: ReplayCache::new(16),
+            portfolio: Arc::new(tokio::sync::Mutex::new(Portfolio::new(Decimal::from(10_000)))),
+            api_client: None,
+            admin_token: None,
+        }
+    }
+
+    /// Sends `request` through the router and returns the status and the body as JSON.
+    async fn send(state: AppState, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = create_router(state).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    fn get(uri: &str) -> Request<Body> {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn portfolio_endpoint_reflects_an_added_position() {
+        let state = app_state();
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        {
+            let mut portfolio = state.portfolio.lock().await;
+            portfolio.open_positions.insert(
+                symbol.clone(),
+                Position {
+                    symbol: symbol.clone(),
+                    side: Side::Long,
+                    quantity: Decimal::from(2),
+                    entry_price: Decimal::from(100),
+                    leverage: 1,
+                    sl_price: Decimal::from(90),
+                    tp_price: None,
+                    entry_time: 0,
+                    entry_fee: Decimal::ZERO,
+                },
+            );
+            portfolio.update_mark_price(symbol, Decimal::from(110));
+        }
+
+        let (status, body) = send(state, get("/api/portfolio")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let snapshot: WsPortfolioUpdate = serde_json::from_value(body).unwrap();
+        assert_eq!(snapshot.cash, Decimal::from(10_000));
+        // Marked to market: 2 x (110 - 100) of unrealized P&L.
+        assert_eq!(snapshot.total_value, Decimal::from(10_020));
+        let position = &snapshot.open_positions["BTCUSDT"];
+        assert_eq!(position.position.quantity, Decimal::from(2));
+        assert_eq!(position.mark_price, Decimal::from(110));
+        assert_eq!(position.unrealized_pnl, Decimal::from(20));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn live_equity_curve_returns_the_snapshots_oldest_first(pool: sqlx::PgPool) {
+        let state = AppState { db: Db::from(pool), ..app_state() };
+        let snapshot = |minute: i64, value: i64| EquityPoint {
+            timestamp: chrono::DateTime::from_timestamp(1_704_067_200 + minute * 60, 0).unwrap(),
+            value: Decimal::from(value),
+        };
+        // Saved out of order, as a trade snapshot can land between two periodic ones.
+        for point in [snapshot(2, 10_050), snapshot(0, 10_000), snapshot(1, 9_980)] {
+            state.db.save_live_equity_point(&point).await.unwrap();
+        }
+
+        let (status, body) = send(state, get("/api/live/equity-curve")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let curve: Vec<EquityPoint> = serde_json::from_value(body).unwrap();
+        let values: Vec<_> = curve.iter().map(|point| point.value).collect();
+        assert_eq!(values, [Decimal::from(10_000), Decimal::from(9_980), Decimal::from(10_050)]);
+        assert!(curve.windows(2).all(|pair| pair[0].timestamp < pair[1].timestamp));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn ndjson_export_streams_one_trade_per_line(pool: sqlx::PgPool) {
+        use analytics::types::{EntryReason, ExitReason, PerformanceReport, Trade};
+
+        let state = AppState { db: Db::from(pool), ..app_state() };
+        let symbol = Symbol::new("BTCUSDT").unwrap();
+        let at = |minute: i64| chrono::DateTime::from_timestamp(1_704_067_200 + minute * 60, 0).unwrap();
+        let trades: Vec<Trade> = (0..5)
+            .map(|i| Trade {
+                symbol: symbol.clone(),
+                side: if i % 2 == 0 { Side::Long } else { Side::Short },
+                entry_time: at(i * 10),
+                exit_time: at(i * 10 + 5),
+                entry_price: Decimal::from(100),
+                exit_price: Decimal::from(100 + i),
+                quantity: Decimal::ONE,
+                pnl: Decimal::from(i),
+                fees: Decimal::ZERO,
+                signal_confidence: 0.5,
+                leverage: 1,
+                max_adverse_excursion: Decimal::ZERO,
+                max_favorable_excursion: Decimal::from(i),
+                entry_reason: EntryReason::LongSignal,
+                exit_reason: ExitReason::Signal,
+            })
+            .collect();
+        let meta = database::BacktestRunMeta {
+            strategy_name: "MACrossover",
+            symbol: &symbol,
+            interval: "1m",
+            start_date: at(0),
+            end_date: at(60),
+            parameters: &serde_json::json!({}),
+            config: None,
+        };
+        let run_id = state
+            .db
+            .save_full_backtest(None, &meta, &PerformanceReport::new(), &trades, &[])
+            .await
+            .unwrap();
+
+        let response = create_router(state)
+            .oneshot(get(&format!("/api/backtests/{}/trades.ndjson", run_id)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let lines: Vec<ApiTrade> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), trades.len());
+        let pnls: Vec<Decimal> = lines.iter().map(|trade| trade.pnl).collect();
+        assert_eq!(pnls, (0..5).map(Decimal::from).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn page_zero_is_rejected_with_400() {
+        let (status, body) = send(app_state(), get("/api/backtest-runs?page=0")).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "BAD_REQUEST");
+        assert_eq!(body["error"]["message"], "page must be at least 1, got 0");
+    }
+
+    #[tokio::test]
+    async fn openapi_spec_describes_the_backtest_runs_endpoint() {
+        let (status, spec) = send(app_state(), get("/api/openapi.json")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let operation = &spec["paths"]["/api/backtest-runs"]["get"];
+        assert!(operation.is_object(), "missing /api/backtest-runs: {}", spec["paths"]);
+        let page_param = operation["parameters"].as_array().unwrap().iter().any(|p| p["name"] == "page");
+        assert!(page_param);
+
+        let schema_ref = operation["responses"]["200"]["content"]["application/json"]["schema"]["$ref"]
+            .as_str()
+            .expect("the 200 response should reference a schema");
+        let schema_name = schema_ref.rsplit('/').next().unwrap();
+        let schema = &spec["components"]["schemas"][schema_name];
+        assert!(schema["properties"]["items"].is_object(), "unexpected schema {}", schema);
+        assert!(schema["properties"]["total_items"].is_object());
+        assert!(spec["components"]["schemas"]["BacktestRun"].is_object());
+    }
+
+    fn post_reset() -> Request<Body> {
+        Request::post("/api/portfolio/reset")
+            .header("Authorization", "Bearer secret")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn portfolio_reset_restores_the_initial_capital_and_broadcasts_it() {
+        let state = AppState { admin_token: Some("secret".to_string()), ..app_state() };
+        let mut events = state.ws_tx.subscribe();
+        let symbol = Symbol::new("ETHUSDT").unwrap();
+        {
+            let mut portfolio = state.portfolio.lock().await;
+            portfolio.cash = Decimal::from(7_500);
+            portfolio.open_positions.insert(
+                symbol.clone(),
+                Position {
+                    symbol: symbol.clone(),
+                    side: Side::Short,
+                    quantity: Decimal::ONE,
+                    entry_price: Decimal::from(2_000),
+                    leverage: 2,
+                    sl_price: Decimal::from(2_200),
+                    tp_price: None,
+                    entry_time: 0,
+                    entry_fee: Decimal::ZERO,
+                },
+            );
+            portfolio.update_mark_price(symbol, Decimal::from(2_100));
+        }
+
+        let (status, body) = send(state.clone(), post_reset()).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let snapshot: WsPortfolioUpdate = serde_json::from_value(body).unwrap();
+        assert_eq!(snapshot.cash, Decimal::from(10_000));
+        assert_eq!(snapshot.total_value, Decimal::from(10_000));
+        assert!(snapshot.open_positions.is_empty());
+        let portfolio = state.portfolio.lock().await;
+        assert_eq!(portfolio.cash, Decimal::from(10_000));
+        assert!(portfolio.open_positions.is_empty());
+        match events.try_recv().unwrap() {
+            WsMessage::PortfolioUpdate(update) => {
+                assert_eq!(update.cash, Decimal::from(10_000));
+                assert!(update.open_positions.is_empty());
+            }
+            other => panic!("expected a portfolio update, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn portfolio_reset_is_rejected_with_a_wrong_token() {
+        let state = AppState { admin_token: Some("secret".to_string()), ..app_state() };
+        state.portfolio.lock().await.cash = Decimal::from(7_500);
+
+        for token in ["Bearer secreT", "Bearer secret2", "Bearer ", "secret"] {
+            let request = Request::post("/api/portfolio/reset")
+                .header("Authorization", token)
+                .body(Body::empty())
+                .unwrap();
+
+            let (status, _) = send(state.clone(), request).await;
+
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{token}");
+        }
+        assert_eq!(state.portfolio.lock().await.cash, Decimal::from(7_500));
+    }
+
+    #[tokio::test]
+    async fn portfolio_reset_is_rejected_when_trading_live() {
+        let api_client = ApiClient::with_base_url("http://127.0.0.1:9");
+        let state = AppState { admin_token: Some("secret".to_string()), api_client: Some(api_client), ..app_state() };
+        state.portfolio.lock().await.cash = Decimal::from(7_500);
+
+        let (status, _) = send(state.clone(), post_reset()).await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(state.portfolio.lock().await.cash, Decimal::from(7_500));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn an_unknown_backtest_run_is_a_404_with_the_message(pool: sqlx::PgPool) {
+        let state = AppState { db: Db::from(pool), ..app_state() };
+
+        let (status, body) = send(state, get("/api/backtests/4242")).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "NOT_FOUND");
+        assert_eq!(body["error"]["message"], "Backtest run 4242 not found");
+    }
+
+    /// An `ApiClient` for a mock exchange serving `/fapi/v2/account` with `status` and `body`.
+    async fn mock_exchange(status: StatusCode, body: serde_json::Value) -> ApiClient {
+        let route = axum::routing::get(move || async move { (status, Json(body)) });
+        api_client::test_support::mock_exchange(Router::new().route("/fapi/v2/account", route)).await
+    }
+
+    fn get_live_account() -> Request<Body> {
+        Request::get("/api/live/account")
+            .header("Authorization", "Bearer secret")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn live_account_returns_the_exchange_account() {
+        let account = serde_json::json!({
+            "assets": [],
+            "positions": [{
+                "symbol": "BTCUSDT",
+                "positionAmt": "-0.5",
+                "entryPrice": "40000",
+                "markPrice": "39800",
+                "unrealizedProfit": "100",
+                "leverage": "5",
+                "positionSide": "BOTH"
+            }],
+            "totalWalletBalance": "10500",
+            "totalUnrealizedProfit": "100",
+            "totalMarginBalance": "10600",
+            "totalAvailableBalance": "6600"
+        });
+        let api_client = mock_exchange(StatusCode::OK, account).await;
+        let state = AppState { admin_token: Some("secret".to_string()), api_client: Some(api_client), ..app_state() };
+
+        let (status, body) = send(state, get_live_account()).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let account: AccountState = serde_json::from_value(body).unwrap();
+        assert_eq!(account.total_wallet_balance, Decimal::from(10_500));
+        assert_eq!(account.total_available_balance, Some(Decimal::from(6_600)));
+        assert_eq!(account.positions.len(), 1);
+        assert_eq!(account.positions[0].symbol, "BTCUSDT");
+    }
+
+    #[tokio::test]
+    async fn an_exchange_error_is_a_502() {
+        let error = serde_json::json!({ "code": -2015, "msg": "Invalid API-key, IP, or permissions for action." });
+        let api_client = mock_exchange(StatusCode::UNAUTHORIZED, error).await;
+        let state = AppState { admin_token: Some("secret".to_string()), api_client: Some(api_client), ..app_state() };
+
+        let (status, body) = send(state, get_live_account()).await;
+
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["error"]["code"], "BAD_GATEWAY");
+        let message = body["error"]["message"].as_str().unwrap();
+        assert!(message.starts_with("Failed to fetch the account from the exchange"), "{}", message);
+        assert!(message.contains("Invalid API-key"), "{}", message);
+    }
+}
@@ -1,10 +1,15 @@
 // In crates/web-server/src/types.rs
 
+use analytics::distribution::HistogramBucket;
+use analytics::types::RollingMetric;
+use core_types::Side;
+use database::TradeFilter;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 /// Represents a paginated list of items.
 /// This is a generic struct that can be used for any paginated API response.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PaginatedResponse<T> {
     pub items: Vec<T>,
     pub total_items: i64,
@@ -13,7 +18,8 @@ pub struct PaginatedResponse<T> {
 }
 
 /// Represents the pagination query parameters from the URL (e.g., ?page=1&pageSize=50).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PaginationParams {
     // `serde(default = ...)` provides a default value if the param is missing.
     #[serde(default = "default_page")]
@@ -24,32 +30,262 @@ pub struct PaginationParams {
     pub job_id: Option<i64>,
 }
 
+/// Represents the trade filter query parameters (e.g., ?side=Short&only_losers=true).
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TradeFilterParams {
+    /// `Long` or `Short` (case-insensitive).
+    pub side: Option<String>,
+    #[serde(default)]
+    pub only_winners: bool,
+    #[serde(default)]
+    pub only_losers: bool,
+}
+
+impl TradeFilterParams {
+    /// Parses the parameters into a `TradeFilter`, rejecting an unknown `side` and
+    /// `only_winners` combined with `only_losers`.
+    pub fn validate(self) -> crate::Result<TradeFilter> {
+        let side = match self.side.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None => None,
+            Some("long") => Some(Side::Long),
+            Some("short") => Some(Side::Short),
+            Some(_) => {
+                return Err(crate::Error::BadRequest(format!(
+                    "side must be Long or Short, got '{}'",
+                    self.side.unwrap_or_default()
+                )));
+            }
+        };
+        if self.only_winners && self.only_losers {
+            return Err(crate::Error::BadRequest(
+                "only_winners and only_losers are mutually exclusive".to_string(),
+            ));
+        }
+        Ok(TradeFilter { side, only_winners: self.only_winners, only_losers: self.only_losers })
+    }
+}
+
+/// Represents the query parameters for the equity-curve endpoint (e.g., ?max_points=500).
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EquityCurveParams {
+    /// If set, the curve is downsampled to at most this many points.
+    pub max_points: Option<usize>,
+}
+
+/// Represents the query parameters for the Monte Carlo endpoint (e.g., ?iterations=1000&seed=7).
+#[derive(Debug, Deserialize)]
+pub struct MonteCarloParams {
+    #[serde(default = "default_monte_carlo_iterations")]
+    pub iterations: usize,
+    /// The resampling seed. Defaults to `analytics::engine::DEFAULT_MONTE_CARLO_SEED`.
+    pub seed: Option<u64>,
+}
+
+fn default_monte_carlo_iterations() -> usize {
+    1000
+}
+
+/// The largest number of Monte Carlo iterations a client may request.
+pub const MAX_MONTE_CARLO_ITERATIONS: usize = 100_000;
+
+/// Represents the query parameters for the distributions endpoint (e.g., ?buckets=20).
+#[derive(Debug, Deserialize)]
+pub struct DistributionParams {
+    #[serde(default = "default_distribution_buckets")]
+    pub buckets: usize,
+}
+
+fn default_distribution_buckets() -> usize {
+    20
+}
+
+/// The largest number of histogram buckets a client may request.
+pub const MAX_DISTRIBUTION_BUCKETS: usize = 500;
+
+/// The trade P&L and duration histograms of a backtest run.
+#[derive(Debug, Serialize)]
+pub struct TradeDistributions {
+    pub total_trades: usize,
+    pub pnl: Vec<HistogramBucket>,
+    pub duration_secs: Vec<HistogramBucket>,
+}
+
+/// Represents the query parameters for the rolling-metrics endpoint (e.g., ?metric=sharpe&window=30).
+#[derive(Debug, Deserialize)]
+pub struct RollingParams {
+    #[serde(default)]
+    pub metric: RollingMetric,
+    /// The number of equity points in each window.
+    #[serde(default = "default_rolling_window")]
+    pub window: usize,
+}
+
+fn default_rolling_window() -> usize {
+    30
+}
+
+/// Represents the query parameters for the attribution endpoint (e.g., ?top=5).
+#[derive(Debug, Deserialize)]
+pub struct AttributionParams {
+    #[serde(default = "default_attribution_top")]
+    pub top: usize,
+}
+
+fn default_attribution_top() -> usize {
+    10
+}
+
+/// The largest number of contributors and detractors a client may request.
+pub const MAX_ATTRIBUTION_TOP: usize = 1_000;
+
+/// The largest page size a client may request; larger values are clamped to this.
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+impl PaginationParams {
+    /// Validates the parameters, rejecting `page < 1` and `page_size < 1`, and clamping
+    /// `page_size` to `MAX_PAGE_SIZE`.
+    pub fn validate(mut self) -> crate::Result<Self> {
+        if self.page < 1 {
+            return Err(crate::Error::BadRequest(format!("page must be at least 1, got {}", self.page)));
+        }
+        if self.page_size < 1 {
+            return Err(crate::Error::BadRequest(format!("page_size must be at least 1, got {}", self.page_size)));
+        }
+        self.page_size = self.page_size.min(MAX_PAGE_SIZE);
+        Ok(self)
+    }
+}
+
+/// The metric a leaderboard can be sorted by.
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardSortKey {
+    #[default]
+    Score,
+    NetPnl,
+    SharpeRatio,
+    MaxDrawdown,
+    ProfitFactor,
+    WinRate,
+}
+
+/// Represents the query parameters for the leaderboard endpoint
+/// (e.g., ?page=1&page_size=50&sort_by=sharpe_ratio&ascending=false).
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardParams {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+    #[serde(default)]
+    pub sort_by: LeaderboardSortKey,
+    /// Sorts from lowest to highest instead of the default highest first.
+    #[serde(default)]
+    pub ascending: bool,
+}
+
+/// The metric a parameter heatmap can plot.
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapMetric {
+    #[default]
+    Score,
+    NetPnl,
+    #[serde(alias = "sharpe")]
+    SharpeRatio,
+    #[serde(alias = "sortino")]
+    SortinoRatio,
+    #[serde(alias = "calmar")]
+    CalmarRatio,
+    MaxDrawdown,
+    ProfitFactor,
+    WinRate,
+}
+
+/// Represents the query parameters for the heatmap endpoint (e.g., ?x=period&y=multiplier&metric=sharpe).
+#[derive(Debug, Deserialize)]
+pub struct HeatmapParams {
+    /// The parameter plotted on the x axis.
+    pub x: String,
+    /// The parameter plotted on the y axis.
+    pub y: String,
+    #[serde(default)]
+    pub metric: HeatmapMetric,
+}
+
+/// The headline metrics shown for each leaderboard entry.
+#[derive(Debug, Serialize)]
+pub struct KeyMetrics {
+    pub net_pnl_percentage: f64,
+    pub max_drawdown_percentage: f64,
+    pub sharpe_ratio: f64,
+    pub profit_factor: f64,
+    pub win_rate: f64,
+    pub calmar_ratio: f64,
+    pub total_trades: u32,
+}
+
+/// A single ranked run of an optimization job.
+#[derive(Debug, Serialize)]
+pub struct LeaderboardEntry {
+    pub run_id: i64,
+    pub score: f64,
+    pub key_metrics: KeyMetrics,
+    pub parameters: serde_json::Value,
+}
+
 // Helper functions for serde defaults.
 fn default_page() -> u32 { 1 }
 fn default_page_size() -> u32 { 50 }
 
-use analytics::types::{PerformanceReport, Trade}; // For future use
-use chrono::{DateTime, Utc};
-use core_types::{Execution, Position};
-use rust_decimal::Decimal;
-use std::collections::HashMap;
+// --- WebSocket Message Structures ---
+
+// The WebSocket messages are defined once in the `events` crate, which every producer
+// (executors, engine, tracing layer) shares; they are re-exported here for API consumers.
+pub use events::{WsLogMessage, WsMessage, WsPortfolioUpdate};
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    fn pagination(page: u32, page_size: u32) -> PaginationParams {
+        PaginationParams { page, page_size, job_id: None }
+    }
 
-// --- WebSocket Message Structures ---
+    #[test]
+    fn oversized_page_size_is_clamped() {
+        let params = pagination(3, 100_000).validate().unwrap();
+        assert_eq!(params.page, 3);
+        assert_eq!(params.page_size, MAX_PAGE_SIZE);
+    }
 
-/// Represents a log message event to be sent to the UI.
-#[derive(Debug, Clone, Serialize)]
-pub struct WsLogMessage {
-    pub timestamp: DateTime<Utc>,
-    pub level: String,
-    pub message: String,
-}
-
-/// Represents the full, updated state of the portfolio.
-#[derive(Debug, Clone, Serialize)]
-pub struct WsPortfolioUpdate {
-    pub cash: Decimal,
-    pub total_value: Decimal, // cash + value of open positions
-    pub open_positions: HashMap<String, Position>, // Keyed by symbol string for easy JS access
-}
\ No newline at end of file
+    #[test]
+    fn page_zero_and_empty_pages_are_rejected() {
+        assert!(matches!(pagination(0, 50).validate(), Err(crate::Error::BadRequest(_))));
+        assert!(matches!(pagination(1, 0).validate(), Err(crate::Error::BadRequest(_))));
+        assert_eq!(pagination(1, 50).validate().unwrap().page_size, 50);
+    }
+
+    #[test]
+    fn websocket_types_are_the_events_types() {
+        // These coercions compile only while the types are re-exports, not look-alike copies.
+        fn identity<T>(value: T) -> T {
+            value
+        }
+        let _: fn(events::WsLogMessage) -> WsLogMessage = identity;
+        let _: fn(events::WsPortfolioUpdate) -> WsPortfolioUpdate = identity;
+        let _: fn(events::WsMessage) -> WsMessage = identity;
+
+        // The frontend relies on the tag/content layout.
+        let message = WsMessage::Log(WsLogMessage {
+            timestamp: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+            level: "INFO".to_string(),
+            message: "hello".to_string(),
+        });
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["type"], "Log");
+        assert_eq!(json["payload"]["message"], "hello");
+    }
+}
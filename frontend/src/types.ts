@@ -31,7 +31,14 @@ export interface Position {
   export interface WsPortfolioUpdatePayload {
     cash: string;
     total_value: string;
-    open_positions: Record<string, Position>; // A map of symbol strings to Position objects
+    open_positions: Record<string, PositionView>; // A map of symbol strings to valued positions
+  }
+
+  // A position valued at the latest known mark price.
+  export interface PositionView extends Position {
+    mark_price: string;
+    unrealized_pnl: string;
+    unrealized_pnl_percent: number;
   }
   
   // --- Top-Level WebSocket Message ---
@@ -0,0 +1,3 @@
+-- Add down migration script here
+-- In down.sql
+DROP TABLE funding_rates;
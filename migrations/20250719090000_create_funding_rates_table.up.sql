@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- In up.sql
+-- Create the funding_rates table
+CREATE TABLE funding_rates (
+    symbol TEXT NOT NULL,
+    funding_time BIGINT NOT NULL,
+    funding_rate NUMERIC(30, 15) NOT NULL,
+    PRIMARY KEY (symbol, funding_time)
+);
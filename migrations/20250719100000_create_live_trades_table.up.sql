@@ -0,0 +1,19 @@
+-- Add up migration script here
+-- In up.sql
+-- Every execution made by the live engine, for auditing.
+CREATE TABLE live_trades (
+    id BIGSERIAL PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    side TEXT NOT NULL,
+    price NUMERIC(30, 15) NOT NULL,
+    quantity NUMERIC(30, 15) NOT NULL,
+    fee NUMERIC(30, 15) NOT NULL,
+    leverage INT NOT NULL,
+    -- The exchange's order ID; NULL for simulated (paper) fills.
+    order_id BIGINT,
+    originating_signal JSONB NOT NULL,
+    portfolio_snapshot JSONB NOT NULL,
+    executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_live_trades_executed_at ON live_trades(executed_at);
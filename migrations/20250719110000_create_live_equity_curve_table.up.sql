@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- In up.sql
+-- Periodic and per-trade snapshots of the live portfolio's marked-to-market equity.
+CREATE TABLE live_equity_curve (
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMPTZ NOT NULL,
+    equity NUMERIC(30, 15) NOT NULL
+);
+
+CREATE INDEX idx_live_equity_curve_timestamp ON live_equity_curve(timestamp);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE performance_reports
+    DROP COLUMN omega_ratio,
+    DROP COLUMN tail_ratio;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- In up.sql
+-- Reports saved before these metrics existed default to 0.
+ALTER TABLE performance_reports
+    ADD COLUMN omega_ratio DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN tail_ratio DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE trades
+    DROP COLUMN max_adverse_excursion,
+    DROP COLUMN max_favorable_excursion;
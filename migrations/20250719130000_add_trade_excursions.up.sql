@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- In up.sql
+-- Trades saved before excursions were tracked default to 0.
+ALTER TABLE trades
+    ADD COLUMN max_adverse_excursion NUMERIC(30, 15) NOT NULL DEFAULT 0,
+    ADD COLUMN max_favorable_excursion NUMERIC(30, 15) NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE performance_reports
+    DROP COLUMN aborted;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- In up.sql
+-- Marks runs stopped early by the optimizer's drawdown abort.
+ALTER TABLE performance_reports
+    ADD COLUMN aborted BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE performance_reports
+    DROP COLUMN value_at_risk_95,
+    DROP COLUMN conditional_var_95;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- In up.sql
+-- Reports saved before these metrics existed default to 0.
+ALTER TABLE performance_reports
+    ADD COLUMN value_at_risk_95 DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN conditional_var_95 DOUBLE PRECISION NOT NULL DEFAULT 0;
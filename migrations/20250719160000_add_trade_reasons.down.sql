@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE trades
+    DROP COLUMN entry_reason,
+    DROP COLUMN exit_reason;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- In up.sql
+-- Trades saved before reasons were tracked default to 'unknown'.
+ALTER TABLE trades
+    ADD COLUMN entry_reason TEXT NOT NULL DEFAULT 'unknown',
+    ADD COLUMN exit_reason TEXT NOT NULL DEFAULT 'unknown';
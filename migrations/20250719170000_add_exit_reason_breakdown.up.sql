@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- In up.sql
+ALTER TABLE performance_reports
+    ADD COLUMN by_exit_reason JSONB; -- Stored as JSON
@@ -0,0 +1,4 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE optimization_jobs
+    DROP COLUMN cancel_requested;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- In up.sql
+-- Set by `POST /api/backtests/cancel/{jobId}` and polled by the running optimizer.
+ALTER TABLE optimization_jobs
+    ADD COLUMN cancel_requested BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,7 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE performance_reports
+    DROP COLUMN cancelled;
+
+ALTER TABLE optimization_jobs
+    DROP COLUMN status;
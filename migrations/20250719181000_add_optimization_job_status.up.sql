@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- In up.sql
+-- How a job ended: 'running' until the optimizer finishes it as 'completed' or 'cancelled'.
+ALTER TABLE optimization_jobs
+    ADD COLUMN status TEXT NOT NULL DEFAULT 'running';
+
+-- Marks runs stopped part-way by a job cancellation.
+ALTER TABLE performance_reports
+    ADD COLUMN cancelled BOOLEAN NOT NULL DEFAULT false;
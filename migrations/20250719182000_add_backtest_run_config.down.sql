@@ -0,0 +1,4 @@
+-- Add down migration script here
+-- In down.sql
+ALTER TABLE backtest_runs
+    DROP COLUMN config;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- In up.sql
+-- The fees, risk settings and seed a run was produced with, so it can be re-run faithfully.
+-- Runs saved before this column existed have none.
+ALTER TABLE backtest_runs
+    ADD COLUMN config JSONB;